@@ -1,9 +1,10 @@
 //! Physics benchmarks.
 
-use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use criterion::{Criterion, criterion_group, criterion_main};
 use par_particle_life::simulation::{
     InteractionMatrix, Particle, RadiusMatrix, SimulationConfig, compute_forces_cpu,
 };
+use std::hint::black_box;
 
 fn make_particles(n: usize, num_types: usize) -> Vec<Particle> {
     use rand::Rng;
@@ -0,0 +1,149 @@
+//! Bin prefix sum benchmarks: tiled Blelloch scan vs the Hillis-Steele scan
+//! it replaced, at increasing bin counts.
+//!
+//! Needs a GPU adapter; prints a notice and does nothing without one.
+//! Software adapters such as llvmpipe make workgroup barriers very expensive
+//! and favor the barrier-free baseline, so measure on real hardware.
+
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use par_particle_life::renderer::gpu::{
+    DEFAULT_SPATIAL_WORKGROUP_SIZE, SpatialHashPipelines, create_prefix_scan_block_sums,
+    prefix_scan_levels,
+};
+use wgpu::util::DeviceExt;
+
+/// The previous ping-pong Hillis-Steele scan, one dispatch per step.
+const HILLIS_STEELE_WGSL: &str = r#"
+@group(0) @binding(0) var<storage, read> source: array<u32>;
+@group(0) @binding(1) var<storage, read_write> destination: array<u32>;
+@group(0) @binding(2) var<uniform> step_size: u32;
+
+@compute @workgroup_size(256)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let idx = id.x;
+    if (idx >= arrayLength(&source)) {
+        return;
+    }
+    if (idx < step_size) {
+        destination[idx] = source[idx];
+    } else {
+        destination[idx] = source[idx - step_size] + source[idx];
+    }
+}
+"#;
+
+const BIN_COUNTS: [u32; 3] = [1 << 16, 1 << 20, 1 << 22];
+
+fn create_device() -> Option<(wgpu::Device, wgpu::Queue)> {
+    let instance = wgpu::Instance::default();
+    let adapter =
+        pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+            .ok()?;
+    pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default())).ok()
+}
+
+fn storage_buffer(device: &wgpu::Device, len: u32) -> wgpu::Buffer {
+    let data: Vec<u32> = (0..len).map(|i| i % 5).collect();
+    device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        label: Some("Bench Bin Counts"),
+        contents: bytemuck::cast_slice(&data),
+        usage: wgpu::BufferUsages::STORAGE,
+    })
+}
+
+fn wait(device: &wgpu::Device, queue: &wgpu::Queue, encoder: wgpu::CommandEncoder) {
+    queue.submit(Some(encoder.finish()));
+    device
+        .poll(wgpu::PollType::wait_indefinitely())
+        .expect("Device poll failed");
+}
+
+fn benchmark_prefix_sum(c: &mut Criterion) {
+    let Some((device, queue)) = create_device() else {
+        eprintln!("No GPU adapter available; skipping prefix sum benchmarks");
+        return;
+    };
+
+    let tiled = SpatialHashPipelines::new(&device, DEFAULT_SPATIAL_WORKGROUP_SIZE);
+
+    let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("Hillis-Steele Scan"),
+        source: wgpu::ShaderSource::Wgsl(HILLIS_STEELE_WGSL.into()),
+    });
+    let hillis_steele = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("Hillis-Steele Pipeline"),
+        layout: None,
+        module: &module,
+        entry_point: Some("main"),
+        compilation_options: Default::default(),
+        cache: None,
+    });
+    let layout = hillis_steele.get_bind_group_layout(0);
+
+    let mut group = c.benchmark_group("bin_prefix_sum");
+    group.sample_size(10);
+
+    for len in BIN_COUNTS {
+        // Tiled Blelloch scan, in place
+        let data = storage_buffer(&device, len);
+        let block_sums = create_prefix_scan_block_sums(&device, len);
+        let bind_groups = tiled.create_prefix_sum_bind_groups(&device, &data, &block_sums);
+        let levels = prefix_scan_levels(len);
+        group.bench_with_input(BenchmarkId::new("tiled_blelloch", len), &len, |b, _| {
+            b.iter(|| {
+                let mut encoder = device.create_command_encoder(&Default::default());
+                tiled.encode_prefix_sum(&mut encoder, &bind_groups, &levels);
+                wait(&device, &queue, encoder);
+            })
+        });
+
+        // Hillis-Steele, ping-ponging between two buffers
+        let buffers = [storage_buffer(&device, len), storage_buffer(&device, len)];
+        let passes = 32 - len.leading_zeros();
+        let step_groups: Vec<wgpu::BindGroup> = (0..passes)
+            .map(|pass| {
+                let step = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: Some("Bench Step Size"),
+                    contents: bytemuck::bytes_of(&(1u32 << pass)),
+                    usage: wgpu::BufferUsages::UNIFORM,
+                });
+                let (src, dst) = (&buffers[pass as usize % 2], &buffers[1 - pass as usize % 2]);
+                device.create_bind_group(&wgpu::BindGroupDescriptor {
+                    label: Some("Bench Step Bind Group"),
+                    layout: &layout,
+                    entries: &[
+                        wgpu::BindGroupEntry {
+                            binding: 0,
+                            resource: src.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 1,
+                            resource: dst.as_entire_binding(),
+                        },
+                        wgpu::BindGroupEntry {
+                            binding: 2,
+                            resource: step.as_entire_binding(),
+                        },
+                    ],
+                })
+            })
+            .collect();
+        group.bench_with_input(BenchmarkId::new("hillis_steele", len), &len, |b, _| {
+            b.iter(|| {
+                let mut encoder = device.create_command_encoder(&Default::default());
+                for bind_group in &step_groups {
+                    let mut pass = encoder.begin_compute_pass(&Default::default());
+                    pass.set_pipeline(&hillis_steele);
+                    pass.set_bind_group(0, bind_group, &[]);
+                    pass.dispatch_workgroups(len.div_ceil(256), 1, 1);
+                }
+                wait(&device, &queue, encoder);
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, benchmark_prefix_sum);
+criterion_main!(benches);
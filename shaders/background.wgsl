@@ -0,0 +1,37 @@
+// Background gradient shader.
+// Draws a two-color vertical gradient with the fullscreen quad before the
+// particles. Alpha is 1 when clearing and `trail_fade` when fading trails.
+
+struct BackgroundParams {
+    // Top color (rgb) and pass alpha (a)
+    top: vec4<f32>,
+    // Bottom color (rgb); a is unused
+    bottom: vec4<f32>,
+}
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@group(0) @binding(0) var<uniform> params: BackgroundParams;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(in.position, 0.0, 1.0);
+    out.uv = in.uv;
+    return out;
+}
+
+// uv.y runs from 0 at the top of the screen to 1 at the bottom
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let rgb = mix(params.top.rgb, params.bottom.rgb, in.uv.y);
+    return vec4<f32>(rgb, params.top.a);
+}
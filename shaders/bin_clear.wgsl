@@ -4,7 +4,7 @@
 @group(0) @binding(0) var<storage, read_write> bin_counts: array<atomic<u32>>;
 @group(0) @binding(1) var<uniform> total_bins: u32;
 
-@compute @workgroup_size(256)
+@compute @workgroup_size(WORKGROUP_SIZE) // substituted by SpatialHashPipelines::new
 fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     if (id.x >= total_bins) {
         return;
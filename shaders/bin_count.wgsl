@@ -5,7 +5,7 @@ struct PosType {
     x: POS_FLOAT,
     y: POS_FLOAT,
     particle_type: u32,
-    _padding: u32,
+    spin: u32, // pack2x16float(orientation, angular velocity)
 }
 
 struct SpatialParams {
@@ -33,7 +33,7 @@ fn get_bin_index(pos: vec2<f32>) -> u32 {
     return bin_y * params.grid_width + bin_x;
 }
 
-@compute @workgroup_size(256)
+@compute @workgroup_size(WORKGROUP_SIZE) // substituted by SpatialHashPipelines::new
 fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     if (id.x >= params.num_particles) {
         return;
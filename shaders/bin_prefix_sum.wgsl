@@ -1,23 +1,129 @@
-// Prefix sum step for computing bin offsets.
-// Performs one step of the Hillis-Steele parallel prefix sum algorithm.
-// Multiple passes are needed: step_size = 1, 2, 4, 8, ... until step_size >= total_bins
+// Tiled prefix sum for computing bin offsets.
+// Work-efficient Blelloch (up-sweep/down-sweep) scan in two kernels:
+//
+// - scan_blocks: each workgroup scans a 2048-element block of `data` in place
+//   (inclusive) and writes the block total to `block_sums`. Every thread scans
+//   8 consecutive elements serially; the Blelloch scan then runs over the 256
+//   per-thread totals, which keeps the number of barriers per element low.
+// - add_block_offsets: after `block_sums` has itself been scanned, adds the
+//   total of all preceding blocks to every element of a block.
+//
+// Large arrays recurse: the block sums of level k are the data of level k+1.
+// bin_counts[0] is always 0 and bin_counts[i+1] holds the count of bin i, so
+// the inclusive scan yields exclusive bin offsets.
 
-@group(0) @binding(0) var<storage, read> source: array<u32>;
-@group(0) @binding(1) var<storage, read_write> destination: array<u32>;
-@group(0) @binding(2) var<uniform> step_size: u32;
+const WORKGROUP_SIZE: u32 = 256u;
+const ITEMS_PER_THREAD: u32 = 8u;
+const BLOCK_SIZE: u32 = 2048u; // WORKGROUP_SIZE * ITEMS_PER_THREAD
+
+@group(0) @binding(0) var<storage, read_write> data: array<u32>;
+@group(0) @binding(1) var<storage, read_write> block_sums: array<u32>;
+
+// One padding slot per 32 elements avoids shared-memory bank conflicts when
+// threads walk their 8-element runs.
+var<workgroup> tile: array<u32, 2112>;
+var<workgroup> thread_sums: array<u32, 256>;
+
+fn padded(i: u32) -> u32 {
+    return i + (i >> 5u);
+}
+
+@compute @workgroup_size(256)
+fn scan_blocks(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+) {
+    let n = arrayLength(&data);
+    let t = local_id.x;
+    let base = group_id.x * BLOCK_SIZE;
+
+    // Coalesced load into shared memory
+    for (var k = 0u; k < ITEMS_PER_THREAD; k++) {
+        let i = k * WORKGROUP_SIZE + t;
+        var value = 0u;
+        if (base + i < n) {
+            value = data[base + i];
+        }
+        tile[padded(i)] = value;
+    }
+    workgroupBarrier();
+
+    // Serial inclusive scan of this thread's run
+    let run = t * ITEMS_PER_THREAD;
+    var total = 0u;
+    for (var k = 0u; k < ITEMS_PER_THREAD; k++) {
+        total += tile[padded(run + k)];
+        tile[padded(run + k)] = total;
+    }
+    thread_sums[t] = total;
+
+    // Up-sweep over thread totals
+    var offset = 1u;
+    for (var d = WORKGROUP_SIZE >> 1u; d > 0u; d = d >> 1u) {
+        workgroupBarrier();
+        if (t < d) {
+            let i = offset * (2u * t + 1u) - 1u;
+            let j = offset * (2u * t + 2u) - 1u;
+            thread_sums[j] += thread_sums[i];
+        }
+        offset = offset << 1u;
+    }
+
+    // Root holds the block total; clear it for the exclusive down-sweep
+    workgroupBarrier();
+    if (t == 0u) {
+        block_sums[group_id.x] = thread_sums[WORKGROUP_SIZE - 1u];
+        thread_sums[WORKGROUP_SIZE - 1u] = 0u;
+    }
+
+    // Down-sweep: distribute partial sums
+    for (var d = 1u; d < WORKGROUP_SIZE; d = d << 1u) {
+        offset = offset >> 1u;
+        workgroupBarrier();
+        if (t < d) {
+            let i = offset * (2u * t + 1u) - 1u;
+            let j = offset * (2u * t + 2u) - 1u;
+            let left = thread_sums[i];
+            thread_sums[i] = thread_sums[j];
+            thread_sums[j] += left;
+        }
+    }
+    workgroupBarrier();
+
+    // Add the total of all earlier runs in the block
+    let carry = thread_sums[t];
+    for (var k = 0u; k < ITEMS_PER_THREAD; k++) {
+        tile[padded(run + k)] += carry;
+    }
+    workgroupBarrier();
+
+    // Coalesced store
+    for (var k = 0u; k < ITEMS_PER_THREAD; k++) {
+        let i = k * WORKGROUP_SIZE + t;
+        if (base + i < n) {
+            data[base + i] = tile[padded(i)];
+        }
+    }
+}
 
 @compute @workgroup_size(256)
-fn main(@builtin(global_invocation_id) id: vec3<u32>) {
-    let idx = id.x;
-    if (idx >= arrayLength(&source)) {
+fn add_block_offsets(
+    @builtin(local_invocation_id) local_id: vec3<u32>,
+    @builtin(workgroup_id) group_id: vec3<u32>,
+) {
+    // The first block has nothing before it
+    if (group_id.x == 0u) {
         return;
     }
 
-    if (idx < step_size) {
-        // Elements before step_size just copy their value
-        destination[idx] = source[idx];
-    } else {
-        // Add the element step_size positions back
-        destination[idx] = source[idx - step_size] + source[idx];
+    let n = arrayLength(&data);
+    let carry = block_sums[group_id.x - 1u];
+    let base = group_id.x * BLOCK_SIZE;
+
+    for (var k = 0u; k < ITEMS_PER_THREAD; k++) {
+        let i = base + k * WORKGROUP_SIZE + local_id.x;
+        if (i < n) {
+            data[i] += carry;
+        }
     }
 }
@@ -1,12 +1,13 @@
 // Sort particles by their spatial bin.
 // Uses bin_offsets from prefix sum and atomic counters to place particles.
 // Particles are reordered so particles in the same bin are contiguous.
+// Velocities and 3D depths are moved with their particles.
 
 struct PosType {
     x: POS_FLOAT,
     y: POS_FLOAT,
     particle_type: u32,
-    _padding: u32,
+    spin: u32, // pack2x16float(orientation, angular velocity)
 }
 
 struct SpatialParams {
@@ -23,6 +24,8 @@ struct SpatialParams {
 @group(0) @binding(4) var<storage, read> bin_offsets: array<u32>;
 @group(0) @binding(5) var<storage, read_write> bin_counts: array<atomic<u32>>;
 @group(0) @binding(6) var<uniform> params: SpatialParams;
+@group(0) @binding(7) var<storage, read> depth_in: array<f32>;
+@group(0) @binding(8) var<storage, read_write> depth_out: array<f32>;
 
 fn get_bin_index(pos: vec2<f32>) -> u32 {
     let bin_x = clamp(
@@ -38,7 +41,7 @@ fn get_bin_index(pos: vec2<f32>) -> u32 {
     return bin_y * params.grid_width + bin_x;
 }
 
-@compute @workgroup_size(256)
+@compute @workgroup_size(WORKGROUP_SIZE) // substituted by SpatialHashPipelines::new
 fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     if (id.x >= params.num_particles) {
         return;
@@ -59,5 +62,6 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
 
     particles_out[new_index] = particle;
     vel_out[new_index] = vel;
+    depth_out[new_index] = depth_in[id.x];
 }
 
@@ -20,8 +20,12 @@ struct BrushRenderParams {
     camera_zoom: f32,
     camera_offset_x: f32,
     camera_offset_y: f32,
+    // Appearance
+    line_width: f32,  // Outline width in world units
+    fill_alpha: f32,  // Filled disk opacity (0 = outline only)
+    contrast: u32,    // Dark halo + brightened outline (0 = off)
     // Padding
-    _padding: vec3<f32>,
+    _padding: vec4<f32>,
 }
 
 struct VertexOutput {
@@ -31,14 +35,20 @@ struct VertexOutput {
 
 @group(0) @binding(0) var<uniform> brush: BrushRenderParams;
 
-// Circle line thickness in world units
-const LINE_THICKNESS: f32 = 2.0;
+// Composite `src` over `dst` (straight alpha).
+fn over(dst: vec4<f32>, src: vec4<f32>) -> vec4<f32> {
+    let a = src.a + dst.a * (1.0 - src.a);
+    if (a <= 0.0) {
+        return vec4<f32>(0.0);
+    }
+    let rgb = (src.rgb * src.a + dst.rgb * dst.a * (1.0 - src.a)) / a;
+    return vec4<f32>(rgb, a);
+}
 
 @vertex
 fn vs_main(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
-    // Generate a quad that covers the brush circle with some padding
-    let padding = LINE_THICKNESS * 2.0;
-    let size = (brush.radius + padding) * 2.0;
+    // Generate a quad that covers the brush circle plus outline and halo
+    let padding = brush.line_width * 3.0;
 
     // Quad vertices (0,1,2,3 -> triangle strip)
     let x = f32(vertex_index & 1u);
@@ -74,31 +84,32 @@ fn fs_main(input: VertexOutput) -> @location(0) vec4<f32> {
         discard;
     }
 
-    // Calculate distance from center in UV space (-1 to 1)
-    let dist = length(input.uv);
+    // Distance from center in world units
+    let extent = brush.radius + brush.line_width * 3.0;
+    let dist = length(input.uv) * extent;
+    let edge_dist = abs(dist - brush.radius);
+    var color = vec3<f32>(brush.color_r, brush.color_g, brush.color_b);
+    var result = vec4<f32>(0.0);
 
-    // Normalized distance relative to radius
-    let radius_normalized = brush.radius / (brush.radius + LINE_THICKNESS * 2.0);
+    // Optional translucent fill
+    if (dist < brush.radius && brush.fill_alpha > 0.0) {
+        result = vec4<f32>(color, brush.fill_alpha);
+    }
 
-    // Line thickness in normalized space
-    let thickness = LINE_THICKNESS / (brush.radius + LINE_THICKNESS * 2.0);
+    // Contrast mode: dark halo either side of a brightened outline, so the
+    // circle reads against both light and dark backdrops
+    if (brush.contrast != 0u) {
+        let halo = 1.0 - smoothstep(brush.line_width, brush.line_width * 2.0, edge_dist);
+        result = over(result, vec4<f32>(0.0, 0.0, 0.0, halo * 0.6 * brush.color_a));
+        color = mix(color, vec3<f32>(1.0), 0.35);
+    }
 
-    // Check if we're on the circle edge
-    let inner = radius_normalized - thickness;
-    let outer = radius_normalized + thickness;
+    // Outline with smoothed edge
+    let line = 1.0 - smoothstep(brush.line_width * 0.5, brush.line_width, edge_dist);
+    result = over(result, vec4<f32>(color, brush.color_a * line));
 
-    if (dist < inner || dist > outer) {
+    if (result.a < 0.001) {
         discard;
     }
-
-    // Smooth the circle edge
-    let edge_dist = abs(dist - radius_normalized) / thickness;
-    let alpha = 1.0 - smoothstep(0.5, 1.0, edge_dist);
-
-    return vec4<f32>(
-        brush.color_r,
-        brush.color_g,
-        brush.color_b,
-        brush.color_a * alpha
-    );
+    return result;
 }
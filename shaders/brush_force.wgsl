@@ -1,12 +1,14 @@
 // Brush force compute shader.
 // Applies attraction/repulsion forces from user brush interaction.
 // Supports attract and repel modes with smooth falloff.
+// While the simulation is paused (sculpt_dt > 0) the velocity change moves
+// particles directly instead, so the brush can shape a stopped scene.
 
 struct PosType {
     x: POS_FLOAT,
     y: POS_FLOAT,
     particle_type: u32,
-    _padding: u32,
+    spin: u32, // pack2x16float(orientation, angular velocity)
 }
 
 struct BrushParams {
@@ -28,75 +30,121 @@ struct BrushParams {
     num_particles: u32,
     // Target particle type (-1 for all)
     target_type: i32,
+    // Bitmask of locked particle types (bit t = type t)
+    locked_mask: u32,
+    // Ignore radius and pull across the whole world (1 = gravity well)
+    infinite_range: u32,
+    // Distance falloff exponent for infinite-range points
+    falloff_exponent: f32,
+    // Paused sculpting: move positions by the velocity change times this dt
+    // and leave velocities alone (0 = normal velocity mode)
+    sculpt_dt: f32,
+    // World size for keeping sculpted particles inside the world
+    world_width: f32,
+    world_height: f32,
+    // Wrap sculpted particles around the edges (1) instead of clamping (0)
+    sculpt_wrap: u32,
     // Padding
-    _padding: vec2<u32>,
+    _padding1: u32,
+    _padding2: u32,
+    _padding3: u32,
 }
 
 // Force scaling constants
 // Multiplied by UI force value (0-100) to get actual velocity change per frame
 const BRUSH_FORCE_MULTIPLIER: f32 = 50.0;
 const BRUSH_DIRECTIONAL_STRENGTH: f32 = 0.5;
+// Number of brush points in the brush buffer (must match MAX_BRUSH_POINTS in Rust)
+const MAX_BRUSH_POINTS: u32 = 5u;
+// High bit of particle_type marks a static particle (must match STATIC_PARTICLE_FLAG in Rust)
+const STATIC_FLAG: u32 = 0x80000000u;
+const TYPE_MASK: u32 = 0x7fffffffu;
 
-@group(0) @binding(0) var<storage, read> pos_type: array<PosType>;
+@group(0) @binding(0) var<storage, read_write> pos_type: array<PosType>;
 @group(0) @binding(1) var<storage, read_write> velocities: array<vec2<VEL_FLOAT>>;
-@group(0) @binding(2) var<uniform> brush: BrushParams;
+@group(0) @binding(2) var<uniform> brushes: array<BrushParams, MAX_BRUSH_POINTS>;
 
 @compute @workgroup_size(256)
 fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     let i = id.x;
-    if (i >= brush.num_particles) {
-        return;
-    }
-
-    // Skip if brush is not active
-    if (brush.is_active == 0u) {
+    // Every brush point carries the same particle count; point 0 is the mouse
+    if (i >= brushes[0].num_particles) {
         return;
     }
 
     let particle_pos_data = pos_type[i];
-
-    // Check target type filter
-    if (brush.target_type >= 0 && i32(particle_pos_data.particle_type) != brush.target_type) {
+    // Static particles don't respond to the brush
+    if ((particle_pos_data.particle_type & STATIC_FLAG) != 0u) {
         return;
     }
-
-    let brush_pos = vec2<f32>(brush.pos_x, brush.pos_y);
+    let particle_type = particle_pos_data.particle_type & TYPE_MASK;
     let particle_pos = vec2<f32>(f32(particle_pos_data.x), f32(particle_pos_data.y));
-
-    // Calculate distance to brush
-    let delta = particle_pos - brush_pos;
-    let dist = length(delta);
-
-    // Skip if outside brush radius
-    if (dist >= brush.radius) {
-        return;
+    let start_vel = vec2<f32>(velocities[i]);
+    var vel = start_vel;
+
+    for (var b = 0u; b < MAX_BRUSH_POINTS; b = b + 1u) {
+        let brush = brushes[b];
+
+        // Skip if brush is not active. Gravity wells need the world size to
+        // pick the nearest cursor image, so only the advance shader applies them.
+        if (brush.is_active == 0u || brush.infinite_range != 0u) {
+            continue;
+        }
+
+        // Check target type filter
+        if (brush.target_type >= 0 && i32(particle_type) != brush.target_type) {
+            continue;
+        }
+
+        // Locked types ignore the brush entirely
+        let type_bit = 1u << min(particle_type, 31u);
+        if ((brush.locked_mask & type_bit) != 0u) {
+            continue;
+        }
+
+        // Calculate distance to brush
+        let brush_pos = vec2<f32>(brush.pos_x, brush.pos_y);
+        let delta = particle_pos - brush_pos;
+        let dist = length(delta);
+
+        // Skip if outside brush radius or at the center (avoid division by zero)
+        if (dist >= brush.radius || dist < 0.1) {
+            continue;
+        }
+
+        // Calculate normalized distance (0 at center, 1 at edge)
+        let normalized_dist = dist / brush.radius;
+
+        // Smooth falloff using smoothstep (stronger at center, weaker at edge)
+        let force_magnitude = 1.0 - smoothstep(0.0, 1.0, normalized_dist);
+
+        // Calculate radial force (toward or away from brush)
+        let direction = delta / dist;  // Unit vector from brush to particle
+        let radial_force = brush.force * force_magnitude * BRUSH_FORCE_MULTIPLIER;
+
+        // Calculate directional force from brush movement
+        let brush_vel = vec2<f32>(brush.vel_x, brush.vel_y);
+        let directional_strength = force_magnitude * brush.directional_force * BRUSH_DIRECTIONAL_STRENGTH;
+
+        // Positive force = attract = move toward brush = negative direction
+        // Negative force = repel = move away from brush = positive direction
+        vel = vel - direction * radial_force + brush_vel * directional_strength;
     }
 
-    // Avoid division by zero at center
-    if (dist < 0.1) {
+    // Every brush point carries the same sculpt settings as the mouse
+    let sculpt = brushes[0];
+    if (sculpt.sculpt_dt > 0.0) {
+        let world = vec2<f32>(sculpt.world_width, sculpt.world_height);
+        var pos = particle_pos + (vel - start_vel) * sculpt.sculpt_dt;
+        if (sculpt.sculpt_wrap != 0u) {
+            pos = pos - floor(pos / world) * world;
+        } else {
+            pos = clamp(pos, vec2<f32>(0.0), world);
+        }
+        pos_type[i].x = POS_FLOAT(pos.x);
+        pos_type[i].y = POS_FLOAT(pos.y);
         return;
     }
 
-    // Calculate normalized distance (0 at center, 1 at edge)
-    let normalized_dist = dist / brush.radius;
-
-    // Smooth falloff using smoothstep (stronger at center, weaker at edge)
-    let force_magnitude = 1.0 - smoothstep(0.0, 1.0, normalized_dist);
-
-    // Calculate radial force (toward or away from brush)
-    let direction = delta / dist;  // Unit vector from brush to particle
-    let radial_force = brush.force * force_magnitude * BRUSH_FORCE_MULTIPLIER;
-
-    // Calculate directional force from brush movement
-    let brush_vel = vec2<f32>(brush.vel_x, brush.vel_y);
-    let directional_strength = force_magnitude * brush.directional_force * BRUSH_DIRECTIONAL_STRENGTH;
-
-    // Apply forces to particle velocity
-    var vel = vec2<f32>(velocities[i]);
-    // Positive force = attract = move toward brush = negative direction
-    // Negative force = repel = move away from brush = positive direction
-    vel.x = vel.x - direction.x * radial_force + brush_vel.x * directional_strength;
-    vel.y = vel.y - direction.y * radial_force + brush_vel.y * directional_strength;
-
     velocities[i] = vec2<VEL_FLOAT>(vel);
 }
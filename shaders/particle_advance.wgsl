@@ -5,7 +5,7 @@ struct PosType {
     x: POS_FLOAT,
     y: POS_FLOAT,
     particle_type: u32,
-    _padding: u32,
+    spin: u32, // pack2x16float(orientation, angular velocity)
 }
 
 struct SimParams {
@@ -23,12 +23,16 @@ struct SimParams {
     dt: f32,
     max_bin_density: f32,
     neighbor_budget: u32, // Max neighbors to check per particle (0 = unlimited)
-    _padding0: u32,
-    _padding1: u32,
-    _padding2: u32,
-    _padding3: u32,
-    _padding4: u32,
-    _padding5: u32,
+    matrix_strength: f32, // Multiplier on interaction matrix values
+    world_shape: u32, // 0 = Rectangle, 1 = Circle (inscribed)
+    spin_coupling: f32, // Torque from tangential velocity (0 = spin off)
+    spin_friction: f32, // Rotational friction per step
+    sample_seed: u32, // Per-step seed for fair neighbor sampling
+    fair_budget: u32, // 1 = sample budgeted neighbors spread across each bin
+    velocity_clamp_mode: u32, // 0 = Hard, 1 = SoftTanh (max_velocity * tanh(speed / max_velocity))
+    particle_shape: u32, // 0 = HardDisk, 1 = SoftDisk, 2 = Square, 3 = Gaussian
+    velocity_scale: f32, // Thermostat multiplier applied before clamping (1 = off)
+    repel_radius: f32, // Repulsion core as a fraction of min radius (0.05 - 1)
 }
 
 struct BrushParams {
@@ -50,18 +54,40 @@ struct BrushParams {
     num_particles: u32,
     // Target particle type (-1 for all)
     target_type: i32,
+    // Bitmask of locked particle types (bit t = type t)
+    locked_mask: u32,
+    // Ignore radius and pull across the whole world (1 = gravity well)
+    infinite_range: u32,
+    // Distance falloff exponent for infinite-range points
+    falloff_exponent: f32,
+    // Paused sculpting fields, used by brush_force.wgsl only
+    sculpt_dt: f32,
+    world_width: f32,
+    world_height: f32,
+    sculpt_wrap: u32,
     // Padding
-    _padding: vec2<u32>,
+    _padding1: u32,
+    _padding2: u32,
+    _padding3: u32,
 }
 
 // Force scaling constants (matched to reference implementation)
 const BRUSH_FORCE_MULTIPLIER: f32 = 50.0;
 const BRUSH_DIRECTIONAL_STRENGTH: f32 = 40.0;
+// Spin limits
+const TAU: f32 = 6.283185307;
+const MAX_SPIN: f32 = 100.0; // rad/s
+// Number of brush points in the brush buffer (must match MAX_BRUSH_POINTS in Rust)
+const MAX_BRUSH_POINTS: u32 = 5u;
+// High bit of particle_type marks a static particle (must match STATIC_PARTICLE_FLAG in Rust)
+const STATIC_FLAG: u32 = 0x80000000u;
+const TYPE_MASK: u32 = 0x7fffffffu;
 
 @group(0) @binding(0) var<storage, read_write> pos: array<PosType>;
 @group(0) @binding(1) var<storage, read_write> vel: array<vec2<VEL_FLOAT>>;
 @group(0) @binding(2) var<uniform> params: SimParams;
-@group(0) @binding(3) var<uniform> brush: BrushParams;
+@group(0) @binding(3) var<uniform> brushes: array<BrushParams, MAX_BRUSH_POINTS>;
+@group(0) @binding(4) var<storage, read> type_physics: array<vec2<f32>>; // per type: (friction, force_factor)
 
 @compute @workgroup_size(256)
 fn main(@builtin(global_invocation_id) id: vec3<u32>) {
@@ -71,64 +97,102 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     }
 
     var particle_pos_data = pos[i];
+
+    // Static particles never integrate; they only act as force sources
+    if ((particle_pos_data.particle_type & STATIC_FLAG) != 0u) {
+        vel[i] = vec2<VEL_FLOAT>(0.0);
+        return;
+    }
+    let particle_type = particle_pos_data.particle_type & TYPE_MASK;
+
     var particle_pos = vec2<f32>(f32(particle_pos_data.x), f32(particle_pos_data.y));
     var particle_vel = vec2<f32>(vel[i]);
     let width = params.world_width;
     let height = params.world_height;
 
-    // Apply brush force if active
-    if (brush.is_active == 1u) {
+    // Apply forces from every active brush point (mouse, touches, pinned)
+    for (var b = 0u; b < MAX_BRUSH_POINTS; b = b + 1u) {
+        let brush = brushes[b];
+        if (brush.is_active != 1u) {
+            continue;
+        }
+
         // Check target type filter
-        var should_apply = true;
-        if (brush.target_type >= 0 && i32(particle_pos_data.particle_type) != brush.target_type) {
-            should_apply = false;
+        if (brush.target_type >= 0 && i32(particle_type) != brush.target_type) {
+            continue;
         }
 
-        if (should_apply) {
-            let brush_pos = vec2<f32>(brush.pos_x, brush.pos_y);
-            
-            var dist_vec = particle_pos - brush_pos;
+        // Locked types ignore the brush entirely
+        let type_bit = 1u << min(particle_type, 31u);
+        if ((brush.locked_mask & type_bit) != 0u) {
+            continue;
+        }
 
-            // Handle wrapping distance calculation for wrap modes
-            if (params.boundary_mode != 0u) {
-                dist_vec.x = dist_vec.x - width * round(dist_vec.x / width);
-                dist_vec.y = dist_vec.y - height * round(dist_vec.y / height);
-            }
+        let brush_pos = vec2<f32>(brush.pos_x, brush.pos_y);
 
-            let dist_sq = dot(dist_vec, dist_vec);
-            let radius_sq = brush.radius * brush.radius;
+        var dist_vec = particle_pos - brush_pos;
 
-            if (dist_sq < radius_sq && dist_sq > 0.1) {
+        // Handle wrapping distance calculation for wrap modes (1, 2, 3)
+        if (params.boundary_mode >= 1u && params.boundary_mode <= 3u && params.world_shape == 0u) {
+            dist_vec.x = dist_vec.x - width * round(dist_vec.x / width);
+            dist_vec.y = dist_vec.y - height * round(dist_vec.y / height);
+        }
+
+        let dist_sq = dot(dist_vec, dist_vec);
+
+        // Gravity well: pull toward the (nearest image of the) cursor from
+        // anywhere in the world, constant inside the brush radius and falling
+        // off as 1 / dist^falloff_exponent beyond it
+        if (brush.infinite_range != 0u) {
+            if (dist_sq > 0.1) {
                 let dist = sqrt(dist_sq);
-                let normalized_dist = dist / brush.radius;
+                let falloff = pow(max(dist / brush.radius, 1.0), -brush.falloff_exponent);
+                let pull = -dist_vec / dist * brush.force * falloff * BRUSH_FORCE_MULTIPLIER;
+                particle_vel = particle_vel + pull * params.dt;
+            }
+            continue;
+        }
 
-                // Smooth falloff using smoothstep
-                let force_magnitude = 1.0 - smoothstep(0.0, 1.0, normalized_dist);
+        let radius_sq = brush.radius * brush.radius;
 
-                // Radial force - positive brush.force = attract (toward brush)
-                // Negate dist_vec so positive force pulls particles toward brush
-                let radial_force = brush.force * force_magnitude * BRUSH_FORCE_MULTIPLIER;
-                let radial_dir = -dist_vec / dist;
+        if (dist_sq < radius_sq && dist_sq > 0.1) {
+            let dist = sqrt(dist_sq);
+            let normalized_dist = dist / brush.radius;
 
-                // Directional force from brush movement
-                let directional_force = force_magnitude * brush.directional_force * params.friction;
-                let brush_vel = vec2<f32>(brush.vel_x, brush.vel_y);
+            // Smooth falloff using smoothstep
+            let force_magnitude = 1.0 - smoothstep(0.0, 1.0, normalized_dist);
 
-                // Apply forces (multiply by dt for frame-independent movement)
-                let total_force = (radial_dir * radial_force) + (brush_vel * directional_force);
-                particle_vel.x = particle_vel.x + total_force.x * params.dt;
-                particle_vel.y = particle_vel.y + total_force.y * params.dt;
-            }
+            // Radial force - positive brush.force = attract (toward brush)
+            // Negate dist_vec so positive force pulls particles toward brush
+            let radial_force = brush.force * force_magnitude * BRUSH_FORCE_MULTIPLIER;
+            let radial_dir = -dist_vec / dist;
+
+            // Directional force from brush movement
+            let directional_force = force_magnitude * brush.directional_force * params.friction;
+            let brush_vel = vec2<f32>(brush.vel_x, brush.vel_y);
+
+            // Apply forces (multiply by dt for frame-independent movement)
+            let total_force = (radial_dir * radial_force) + (brush_vel * directional_force);
+            particle_vel.x = particle_vel.x + total_force.x * params.dt;
+            particle_vel.y = particle_vel.y + total_force.y * params.dt;
         }
     }
 
-    // Apply friction
-    let friction_factor = 1.0 - params.friction;
+    // Apply per-type friction
+    let friction_factor = 1.0 - type_physics[particle_type].x;
     particle_vel = particle_vel * friction_factor;
 
+    // Thermostat scaling goes before the clamp so the two never fight
+    particle_vel = particle_vel * params.velocity_scale;
+
     // Clamp velocity
     let speed = length(particle_vel);
-    if (speed > params.max_velocity) {
+    if (params.velocity_clamp_mode == 1u) {
+        // Soft clamp - near-identity when slow, approaches the cap smoothly
+        if (speed > 0.0 && params.max_velocity > 0.0) {
+            particle_vel = particle_vel * (params.max_velocity * tanh(speed / params.max_velocity) / speed);
+        }
+    } else if (speed > params.max_velocity) {
         let scale = params.max_velocity / speed;
         particle_vel = particle_vel * scale;
     }
@@ -140,7 +204,34 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     let margin = params.particle_size;
 
     // Handle boundaries
-    if (params.boundary_mode == 0u) {
+    if (params.world_shape == 1u) {
+        // Circular world - radial check against the inscribed circle
+        let center = vec2<f32>(width, height) * 0.5;
+        let limit = max(min(width, height) * 0.5 - margin, 0.0);
+        let offset = particle_pos - center;
+        let dist = length(offset);
+        if (dist > limit && dist > 0.0) {
+            let dir = offset / dist;
+            if (params.boundary_mode == 0u) {
+                // Repel - clamp to the edge and reflect the outward velocity
+                particle_pos = center + dir * limit;
+                let outward = dot(particle_vel, dir);
+                if (outward > 0.0) {
+                    particle_vel = particle_vel - dir * (2.0 * outward);
+                }
+            } else if (params.boundary_mode == 4u) {
+                // Sticky - clamp to the edge and drop the outward velocity
+                particle_pos = center + dir * limit;
+                let outward = dot(particle_vel, dir);
+                if (outward > 0.0) {
+                    particle_vel = particle_vel - dir * outward;
+                }
+            } else {
+                // Wrap - reappear on the opposite side of the circle
+                particle_pos = center - dir * limit;
+            }
+        }
+    } else if (params.boundary_mode == 0u) {
         // Repel mode - bounce off walls
         if (particle_pos.x < margin) {
             particle_pos.x = margin;
@@ -157,6 +248,24 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
             particle_pos.y = height - margin;
             particle_vel.y = -abs(particle_vel.y);
         }
+    } else if (params.boundary_mode == 4u) {
+        // Sticky mode - stop at walls, zeroing the velocity into each wall
+        // hit (both components in a corner)
+        if (particle_pos.x < margin) {
+            particle_pos.x = margin;
+            particle_vel.x = max(particle_vel.x, 0.0);
+        } else if (particle_pos.x > width - margin) {
+            particle_pos.x = width - margin;
+            particle_vel.x = min(particle_vel.x, 0.0);
+        }
+
+        if (particle_pos.y < margin) {
+            particle_pos.y = margin;
+            particle_vel.y = max(particle_vel.y, 0.0);
+        } else if (particle_pos.y > height - margin) {
+            particle_pos.y = height - margin;
+            particle_vel.y = min(particle_vel.y, 0.0);
+        }
     } else {
         // Wrap mode (modes 1, 2, 3) - teleport to opposite side
         if (particle_pos.x < 0.0) {
@@ -172,6 +281,22 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         }
     }
 
+    // Spin: the tangential component of velocity (relative to the particle's
+    // orientation axis) torques the orientation toward the direction of motion,
+    // damped by rotational friction. Visual only; it does not feed back into forces.
+    if (params.spin_coupling > 0.0) {
+        let spin_state = unpack2x16float(particle_pos_data.spin);
+        var angle = spin_state.x;
+        var omega = spin_state.y;
+        let axis_perp = vec2<f32>(-sin(angle), cos(angle));
+        let torque = params.spin_coupling * dot(particle_vel, axis_perp);
+        omega = clamp((omega + torque * params.dt) * (1.0 - params.spin_friction), -MAX_SPIN, MAX_SPIN);
+        angle = angle + omega * params.dt;
+        // Keep the angle in [-PI, PI] so it stays precise as f16
+        angle = angle - TAU * round(angle / TAU);
+        particle_pos_data.spin = pack2x16float(vec2<f32>(angle, omega));
+    }
+
     // Write back to buffers
     particle_pos_data.x = POS_FLOAT(particle_pos.x);
     particle_pos_data.y = POS_FLOAT(particle_pos.y);
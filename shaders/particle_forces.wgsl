@@ -6,7 +6,7 @@ struct PosType {
     x: POS_FLOAT,
     y: POS_FLOAT,
     particle_type: u32,
-    _padding: u32,
+    spin: u32, // pack2x16float(orientation, angular velocity)
 }
 
 struct SimParams {
@@ -24,14 +24,22 @@ struct SimParams {
     dt: f32,
     max_bin_density: f32,
     neighbor_budget: u32, // Max neighbors to check per particle (0 = unlimited)
-    _padding0: u32,
-    _padding1: u32,
-    _padding2: u32,
-    _padding3: u32,
-    _padding4: u32,
-    _padding5: u32,
+    matrix_strength: f32, // Multiplier on interaction matrix values
+    world_shape: u32, // 0 = Rectangle, 1 = Circle (inscribed)
+    spin_coupling: f32, // Torque from tangential velocity (0 = spin off)
+    spin_friction: f32, // Rotational friction per step
+    sample_seed: u32, // Per-step seed for fair neighbor sampling
+    fair_budget: u32, // 1 = sample budgeted neighbors spread across each bin
+    velocity_clamp_mode: u32, // 0 = Hard, 1 = SoftTanh (max_velocity * tanh(speed / max_velocity))
+    particle_shape: u32, // 0 = HardDisk, 1 = SoftDisk, 2 = Square, 3 = Gaussian
+    velocity_scale: f32, // Thermostat multiplier applied before clamping (1 = off)
+    repel_radius: f32, // Repulsion core as a fraction of min radius (0.05 - 1)
 }
 
+// High bit of particle_type marks a static particle; static particles still
+// act as force sources, so forces only mask it off the type index
+const TYPE_MASK: u32 = 0x7fffffffu;
+
 @group(0) @binding(0) var<storage, read> pos_type_in: array<PosType>;
 @group(0) @binding(1) var<storage, read> vel_in: array<vec2<VEL_FLOAT>>;
 @group(0) @binding(2) var<storage, read_write> vel_out: array<vec2<VEL_FLOAT>>;
@@ -39,6 +47,19 @@ struct SimParams {
 @group(0) @binding(4) var<storage, read> interaction_matrix: array<f32>;
 @group(0) @binding(5) var<storage, read> min_radius: array<f32>;
 @group(0) @binding(6) var<storage, read> max_radius: array<f32>;
+@group(0) @binding(7) var<storage, read> anisotropy: array<vec4<f32>>; // per type: (cos, sin, aspect, 0)
+@group(0) @binding(8) var<storage, read> type_physics: array<vec2<f32>>; // per type: (friction, force_factor)
+
+// Distance from `my_type`'s point of view. Anisotropic types stretch the
+// cross-axis component so their interaction region becomes an ellipse.
+fn effective_distance(my_aniso: vec4<f32>, delta: vec2<f32>, dist: f32) -> f32 {
+    if (my_aniso.z <= 1.0) {
+        return dist;
+    }
+    let along = delta.x * my_aniso.x + delta.y * my_aniso.y;
+    let across = (delta.y * my_aniso.x - delta.x * my_aniso.y) * my_aniso.z;
+    return sqrt(along * along + across * across);
+}
 
 @compute @workgroup_size(256)
 fn main(@builtin(global_invocation_id) id: vec3<u32>) {
@@ -49,13 +70,16 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
 
     let particle = pos_type_in[i];
     let my_vel = vec2<f32>(vel_in[i]);
-    let my_type = particle.particle_type;
+    let my_type = particle.particle_type & TYPE_MASK;
+    let my_aniso = anisotropy[my_type];
     let my_pos = vec2<f32>(f32(particle.x), f32(particle.y));
 
     let half_width = params.world_width * 0.5;
     let half_height = params.world_height * 0.5;
-    // Modes 1, 2, 3 all use wrapped distance calculation (Wrap, MirrorWrap, InfiniteWrap)
-    let is_wrap = params.boundary_mode != 0u;
+    // Modes 1, 2, 3 all use wrapped distance calculation (Wrap, MirrorWrap, InfiniteWrap);
+    // Repel (0) and Sticky (4) don't. A circular world never wraps distances
+    let is_wrap = params.boundary_mode >= 1u && params.boundary_mode <= 3u
+        && params.world_shape == 0u;
 
     var total_force = vec2<f32>(0.0, 0.0);
 
@@ -65,7 +89,7 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         }
 
         let other = pos_type_in[j];
-        let other_type = other.particle_type;
+        let other_type = other.particle_type & TYPE_MASK;
         let other_pos = vec2<f32>(f32(other.x), f32(other.y));
 
         var delta = other_pos - my_pos;
@@ -88,7 +112,8 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         }
 
         let idx = my_type * params.num_types + other_type;
-        let min_r = min_radius[idx];
+        // Attraction flips to repulsion at the core radius
+        let min_r = min_radius[idx] * params.repel_radius;
         let max_r = max_radius[idx];
         let max_r_sq = max_r * max_r;
 
@@ -97,8 +122,12 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
             continue;
         }
 
-        let dist = sqrt(dist_sq);
-        let direction = delta / dist;
+        let true_dist = sqrt(dist_sq);
+        let direction = delta / true_dist;
+        let dist = effective_distance(my_aniso, delta, true_dist);
+        if (dist >= max_r) {
+            continue;
+        }
 
         var force_magnitude = 0.0;
 
@@ -107,7 +136,7 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
             force_magnitude = (dist / min_r - 1.0) * params.repel_strength;
         } else {
             // Attraction/repulsion based on interaction matrix
-            let strength = interaction_matrix[idx];
+            let strength = interaction_matrix[idx] * params.matrix_strength;
             let mid = (min_r + max_r) * 0.5;
             let half_range = mid - min_r;
 
@@ -122,7 +151,21 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
 
     // Apply wall repulsion for Repel mode (configurable strength 0-100)
     // Uses cubic falloff for strong near-wall repulsion
-    if (params.boundary_mode == 0u && params.wall_repel_strength > 0.0) {
+    if (params.boundary_mode == 0u && params.wall_repel_strength > 0.0 && params.world_shape == 1u) {
+        // Circular wall - same cubic falloff, applied radially toward the center
+        let wall_margin = 100.0;
+        let wall_base_strength = params.wall_repel_strength * 0.2;
+        let center = vec2<f32>(params.world_width, params.world_height) * 0.5;
+        let radius = min(params.world_width, params.world_height) * 0.5;
+        let offset = my_pos - center;
+        let dist_from_center = length(offset);
+        let dist_from_wall = radius - dist_from_center;
+        if (dist_from_wall < wall_margin && dist_from_center > 0.0) {
+            let t = clamp(1.0 - dist_from_wall / wall_margin, 0.0, 1.0);
+            let force = wall_base_strength * t * t * t;
+            total_force = total_force - (offset / dist_from_center) * force;
+        }
+    } else if (params.boundary_mode == 0u && params.wall_repel_strength > 0.0) {
         let wall_margin = 100.0; // Distance from wall where repulsion starts
         let wall_base_strength = params.wall_repel_strength * 0.2; // Scale 0-100 to 0-20 force
 
@@ -154,10 +197,11 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         }
     }
 
-    // Apply force scaled by force factor
+    // Apply force scaled by this type's force factor
+    let force_factor = type_physics[my_type].y;
     var updated_vel = my_vel;
-    updated_vel.x = updated_vel.x + total_force.x * params.force_factor;
-    updated_vel.y = updated_vel.y + total_force.y * params.force_factor;
+    updated_vel.x = updated_vel.x + total_force.x * force_factor;
+    updated_vel.y = updated_vel.y + total_force.y * force_factor;
 
     vel_out[i] = vec2<VEL_FLOAT>(updated_vel);
 }
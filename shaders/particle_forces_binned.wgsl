@@ -6,7 +6,7 @@ struct PosType {
     x: POS_FLOAT,
     y: POS_FLOAT,
     particle_type: u32,
-    _padding: u32,
+    spin: u32, // pack2x16float(orientation, angular velocity)
 }
 
 struct SimParams {
@@ -24,12 +24,16 @@ struct SimParams {
     dt: f32,
     max_bin_density: f32,
     neighbor_budget: u32, // Max neighbors to check per particle (0 = unlimited)
-    _padding0: u32,
-    _padding1: u32,
-    _padding2: u32,
-    _padding3: u32,
-    _padding4: u32,
-    _padding5: u32,
+    matrix_strength: f32, // Multiplier on interaction matrix values
+    world_shape: u32, // 0 = Rectangle, 1 = Circle (inscribed)
+    spin_coupling: f32, // Torque from tangential velocity (0 = spin off)
+    spin_friction: f32, // Rotational friction per step
+    sample_seed: u32, // Per-step seed for fair neighbor sampling
+    fair_budget: u32, // 1 = sample budgeted neighbors spread across each bin
+    velocity_clamp_mode: u32, // 0 = Hard, 1 = SoftTanh (max_velocity * tanh(speed / max_velocity))
+    particle_shape: u32, // 0 = HardDisk, 1 = SoftDisk, 2 = Square, 3 = Gaussian
+    velocity_scale: f32, // Thermostat multiplier applied before clamping (1 = off)
+    repel_radius: f32, // Repulsion core as a fraction of min radius (0.05 - 1)
 }
 
 struct SpatialParams {
@@ -39,6 +43,10 @@ struct SpatialParams {
     grid_height: u32,
 }
 
+// High bit of particle_type marks a static particle; static particles still
+// act as force sources, so forces only mask it off the type index
+const TYPE_MASK: u32 = 0x7fffffffu;
+
 @group(0) @binding(1) var<storage, read_write> velocities: array<vec2<VEL_FLOAT>>;
 @group(0) @binding(2) var<uniform> params: SimParams;
 @group(0) @binding(3) var<storage, read> interaction_matrix: array<f32>;
@@ -47,6 +55,26 @@ struct SpatialParams {
 @group(0) @binding(6) var<storage, read> bin_offsets: array<u32>;
 @group(0) @binding(7) var<uniform> spatial: SpatialParams;
 @group(0) @binding(8) var<storage, read> sorted_pos_type: array<PosType>;
+@group(0) @binding(9) var<storage, read> anisotropy: array<vec4<f32>>; // per type: (cos, sin, aspect, 0)
+@group(0) @binding(10) var<storage, read> type_physics: array<vec2<f32>>; // per type: (friction, force_factor)
+
+// Distance from `my_type`'s point of view. Anisotropic types stretch the
+// cross-axis component so their interaction region becomes an ellipse.
+fn effective_distance(my_aniso: vec4<f32>, delta: vec2<f32>, dist: f32) -> f32 {
+    if (my_aniso.z <= 1.0) {
+        return dist;
+    }
+    let along = delta.x * my_aniso.x + delta.y * my_aniso.y;
+    let across = (delta.y * my_aniso.x - delta.x * my_aniso.y) * my_aniso.z;
+    return sqrt(along * along + across * across);
+}
+
+// PCG hash for picking where budgeted sampling starts within a bin
+fn hash_u32(value: u32) -> u32 {
+    let state = value * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
 
 fn get_bin_coords(pos: vec2<f32>) -> vec2<i32> {
     return vec2<i32>(
@@ -55,7 +83,7 @@ fn get_bin_coords(pos: vec2<f32>) -> vec2<i32> {
     );
 }
 
-@compute @workgroup_size(256)
+@compute @workgroup_size(WORKGROUP_SIZE) // substituted by SpatialHashPipelines::new
 fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     // Iterate over SORTED particles to ensure memory coherence for position/bin lookup
     let sorted_idx = id.x;
@@ -64,7 +92,8 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
     }
 
     let particle = sorted_pos_type[sorted_idx];
-    let my_type = particle.particle_type;
+    let my_type = particle.particle_type & TYPE_MASK;
+    let my_aniso = anisotropy[my_type];
     let my_pos = vec2<f32>(f32(particle.x), f32(particle.y));
 
     // Velocity buffer is now sorted, so we can access it directly
@@ -72,8 +101,10 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
 
     let half_width = params.world_width * 0.5;
     let half_height = params.world_height * 0.5;
-    // Modes 1, 2, 3 all use wrapped distance calculation (Wrap, MirrorWrap, InfiniteWrap)
-    let is_wrap = params.boundary_mode != 0u;
+    // Modes 1, 2, 3 all use wrapped distance calculation (Wrap, MirrorWrap, InfiniteWrap);
+    // Repel (0) and Sticky (4) don't. A circular world never wraps distances
+    let is_wrap = params.boundary_mode >= 1u && params.boundary_mode <= 3u
+        && params.world_shape == 0u;
 
     // Get this particle's bin coordinates
     let my_bin = get_bin_coords(my_pos);
@@ -120,8 +151,20 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
             // Track neighbors checked for this bin (reset per bin for fair sampling)
             var bin_neighbors_checked = 0u;
 
+            // By default the budget takes the first particles in the bin. Fair
+            // sampling instead strides across the whole bin from a hashed start,
+            // so the sampled subset is spread out and changes every frame.
+            let bin_count = bin_end - bin_start;
+            var sample_start = 0u;
+            var sample_stride = 1u;
+            if (params.fair_budget != 0u && per_bin_budget > 0u && bin_count > per_bin_budget) {
+                sample_stride = bin_count / per_bin_budget;
+                sample_start = hash_u32(sorted_idx ^ hash_u32(params.sample_seed ^ bin_index)) % bin_count;
+            }
+
             // Iterate over particles in this bin
-            for (var j = bin_start; j < bin_end; j = j + 1u) {
+            for (var k = 0u; k < bin_count; k = k + 1u) {
+                let j = bin_start + (sample_start + k * sample_stride) % bin_count;
                 // Early exit if we've hit the per-bin budget (ensures symmetric sampling)
                 if (per_bin_budget > 0u && bin_neighbors_checked >= per_bin_budget) {
                     break;
@@ -137,7 +180,7 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
                 bin_neighbors_checked = bin_neighbors_checked + 1u;
                 neighbors_checked = neighbors_checked + 1u;
                 let other = sorted_pos_type[j];
-                let other_type = other.particle_type;
+                let other_type = other.particle_type & TYPE_MASK;
                 let other_pos = vec2<f32>(f32(other.x), f32(other.y));
 
                 var delta = other_pos - my_pos;
@@ -161,7 +204,8 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
                 }
 
                 let idx = my_type * params.num_types + other_type;
-                let min_r = min_radius[idx];
+                // Attraction flips to repulsion at the core radius
+                let min_r = min_radius[idx] * params.repel_radius;
                 let max_r = max_radius[idx];
                 let max_r_sq = max_r * max_r;
 
@@ -170,8 +214,12 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
                     continue;
                 }
 
-                let dist = sqrt(dist_sq);
-                let direction = delta / dist;
+                let true_dist = sqrt(dist_sq);
+                let direction = delta / true_dist;
+                let dist = effective_distance(my_aniso, delta, true_dist);
+                if (dist >= max_r) {
+                    continue;
+                }
 
                 var force_magnitude = 0.0;
 
@@ -180,7 +228,7 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
                     force_magnitude = (dist / min_r - 1.0) * params.repel_strength;
                 } else {
                     // Attraction/repulsion based on interaction matrix
-                    let strength = interaction_matrix[idx];
+                    let strength = interaction_matrix[idx] * params.matrix_strength;
                     let mid = (min_r + max_r) * 0.5;
                     let half_range = mid - min_r;
 
@@ -197,7 +245,21 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
 
     // Apply wall repulsion for Repel mode (configurable strength 0-100)
     // Uses cubic falloff for strong near-wall repulsion
-    if (params.boundary_mode == 0u && params.wall_repel_strength > 0.0) {
+    if (params.boundary_mode == 0u && params.wall_repel_strength > 0.0 && params.world_shape == 1u) {
+        // Circular wall - same cubic falloff, applied radially toward the center
+        let wall_margin = 100.0;
+        let wall_base_strength = params.wall_repel_strength * 0.2;
+        let center = vec2<f32>(params.world_width, params.world_height) * 0.5;
+        let radius = min(params.world_width, params.world_height) * 0.5;
+        let offset = my_pos - center;
+        let dist_from_center = length(offset);
+        let dist_from_wall = radius - dist_from_center;
+        if (dist_from_wall < wall_margin && dist_from_center > 0.0) {
+            let t = clamp(1.0 - dist_from_wall / wall_margin, 0.0, 1.0);
+            let force = wall_base_strength * t * t * t;
+            total_force = total_force - (offset / dist_from_center) * force;
+        }
+    } else if (params.boundary_mode == 0u && params.wall_repel_strength > 0.0) {
         let wall_margin = 100.0; // Distance from wall where repulsion starts
         let wall_base_strength = params.wall_repel_strength * 0.2; // Scale 0-100 to 0-20 force
 
@@ -229,8 +291,8 @@ fn main(@builtin(global_invocation_id) id: vec3<u32>) {
         }
     }
 
-    // Apply force scaled by force factor
-    var final_force = total_force * params.force_factor;
+    // Apply force scaled by this type's force factor
+    var final_force = total_force * type_physics[my_type].y;
 
     // Apply density-based force scaling if max_bin_density is set (non-zero)
     // This reduces forces in very dense clusters to prevent explosions and stabilize performance
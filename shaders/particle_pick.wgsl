@@ -0,0 +1,109 @@
+// Particle picking shader.
+// Renders particle indices into a tiny integer target centered on the cursor.
+// Depth is the squared distance from the sprite center, so the particle whose
+// center is nearest to the cursor wins when sprites overlap.
+
+struct PosType {
+    x: POS_FLOAT,
+    y: POS_FLOAT,
+    particle_type: u32,
+    spin: u32, // pack2x16float(orientation, angular velocity)
+}
+
+struct SimParams {
+    num_particles: u32,
+    num_types: u32,
+    force_factor: f32,
+    friction: f32,
+    repel_strength: f32,
+    max_velocity: f32,
+    world_width: f32,
+    world_height: f32,
+    boundary_mode: u32,
+    wall_repel_strength: f32,
+    particle_size: f32,
+    dt: f32,
+    max_bin_density: f32,
+    neighbor_budget: u32, // Max neighbors to check per particle (0 = unlimited)
+    matrix_strength: f32, // Multiplier on interaction matrix values
+    world_shape: u32, // 0 = Rectangle, 1 = Circle (inscribed)
+    spin_coupling: f32, // Torque from tangential velocity (0 = spin off)
+    spin_friction: f32, // Rotational friction per step
+    sample_seed: u32, // Per-step seed for fair neighbor sampling
+    fair_budget: u32, // 1 = sample budgeted neighbors spread across each bin
+    velocity_clamp_mode: u32, // 0 = Hard, 1 = SoftTanh (max_velocity * tanh(speed / max_velocity))
+    particle_shape: u32, // 0 = HardDisk, 1 = SoftDisk, 2 = Square, 3 = Gaussian
+    velocity_scale: f32, // Thermostat multiplier applied before clamping (1 = off)
+    repel_radius: f32, // Repulsion core as a fraction of min radius (0.05 - 1)
+}
+
+struct Camera {
+    // Cursor position in world coordinates
+    center_x: f32,
+    center_y: f32,
+    // Scale factors (one screen pixel spans the whole target)
+    scale_x: f32,
+    scale_y: f32,
+}
+
+// Quad vertices for instanced rendering
+const QUAD_VERTICES = array<vec2<f32>, 4>(
+    vec2<f32>(-1.0, -1.0),
+    vec2<f32>( 1.0, -1.0),
+    vec2<f32>(-1.0,  1.0),
+    vec2<f32>( 1.0,  1.0)
+);
+
+@group(0) @binding(0) var<storage, read> particles: array<PosType>;
+@group(0) @binding(1) var<uniform> params: SimParams;
+@group(0) @binding(2) var<uniform> camera: Camera;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) offset: vec2<f32>,
+    @location(1) @interpolate(flat) id: u32,
+}
+
+struct FragmentOutput {
+    @location(0) id: u32,
+    @builtin(frag_depth) depth: f32,
+}
+
+@vertex
+fn vs_main(
+    @builtin(instance_index) instance_index: u32,
+    @builtin(vertex_index) vertex_index: u32
+) -> VertexOutput {
+    let particle = particles[instance_index];
+    let particle_pos = vec2<f32>(f32(particle.x), f32(particle.y));
+
+    // Same transform as particle_render.wgsl, with the pick camera
+    let camera_scale = vec2<f32>(camera.scale_x, -camera.scale_y);
+    let camera_center = vec2<f32>(camera.center_x, camera.center_y);
+    let transformed_pos = (particle_pos - camera_center) * camera_scale;
+
+    let quad_offset = QUAD_VERTICES[vertex_index];
+    let vertex_offset = quad_offset * params.particle_size * camera_scale;
+
+    var output: VertexOutput;
+    output.position = vec4<f32>(transformed_pos + vertex_offset, 0.0, 1.0);
+    output.offset = quad_offset;
+    // 0 is reserved for "no particle"
+    output.id = instance_index + 1u;
+    return output;
+}
+
+@fragment
+fn fs_main(in: VertexOutput) -> FragmentOutput {
+    let dist_sq = dot(in.offset, in.offset);
+
+    // Only the visible disk is pickable
+    if (dist_sq > 1.0) {
+        discard;
+    }
+
+    var output: FragmentOutput;
+    output.id = in.id;
+    output.depth = dist_sq;
+    return output;
+}
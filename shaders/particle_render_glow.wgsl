@@ -6,7 +6,7 @@ struct PosType {
     x: POS_FLOAT,
     y: POS_FLOAT,
     particle_type: u32,
-    _padding: u32,
+    spin: u32, // pack2x16float(orientation, angular velocity)
 }
 
 struct SimParams {
@@ -24,12 +24,16 @@ struct SimParams {
     dt: f32,
     max_bin_density: f32,
     neighbor_budget: u32, // Max neighbors to check per particle (0 = unlimited)
-    _padding0: u32,
-    _padding1: u32,
-    _padding2: u32,
-    _padding3: u32,
-    _padding4: u32,
-    _padding5: u32,
+    matrix_strength: f32, // Multiplier on interaction matrix values
+    world_shape: u32, // 0 = Rectangle, 1 = Circle (inscribed)
+    spin_coupling: f32, // Torque from tangential velocity (0 = spin off)
+    spin_friction: f32, // Rotational friction per step
+    sample_seed: u32, // Per-step seed for fair neighbor sampling
+    fair_budget: u32, // 1 = sample budgeted neighbors spread across each bin
+    velocity_clamp_mode: u32, // 0 = Hard, 1 = SoftTanh (max_velocity * tanh(speed / max_velocity))
+    particle_shape: u32, // 0 = HardDisk, 1 = SoftDisk, 2 = Square, 3 = Gaussian
+    velocity_scale: f32, // Thermostat multiplier applied before clamping (1 = off)
+    repel_radius: f32, // Repulsion core as a fraction of min radius (0.05 - 1)
 }
 
 struct Camera {
@@ -46,7 +50,8 @@ struct GlowParams {
     glow_intensity: f32,
     /// Steepness of falloff (higher = sharper edge, 1.0-4.0).
     glow_steepness: f32,
-    _padding: f32,
+    /// Factor applied to glow_size (1/zoom with zoom compensation, else 1).
+    zoom_scale: f32,
 }
 
 // Quad vertices for instanced rendering
@@ -63,6 +68,89 @@ const QUAD_VERTICES = array<vec2<f32>, 4>(
 @group(0) @binding(3) var<uniform> camera: Camera;
 @group(0) @binding(4) var<uniform> glow: GlowParams;
 
+// Color mode selectors (must match ColorMode::shader_id)
+const COLOR_MODE_BY_TYPE: u32 = 0u;
+const COLOR_MODE_BY_SPEED: u32 = 1u;
+const COLOR_MODE_BY_DIRECTION: u32 = 2u;
+
+// Masks the static-particle flag off particle_type
+const TYPE_MASK: u32 = 0x7fffffffu;
+
+struct ColorParams {
+    mode: u32,
+    colormap_size: u32,
+    speed_range: f32,
+    streak_length: f32,
+    hue_offset: f32,
+    seam_fade: f32,
+    depth_limit: f32, // 3D depth range (0 = 3D off)
+    _padding: f32,
+}
+
+@group(0) @binding(5) var<storage, read> colormap: array<vec4<f32>>;
+@group(0) @binding(6) var<uniform> color_params: ColorParams;
+@group(0) @binding(7) var<storage, read> velocities: array<vec2<VEL_FLOAT>>;
+@group(0) @binding(8) var<storage, read> depths: array<f32>;
+
+// Perspective scale of a particle in 3D mode: depth 0 (nearest) draws at
+// full size and brightness, depth_limit at half. 1.0 with 3D off.
+fn depth_scale(index: u32) -> f32 {
+    if (color_params.depth_limit <= 0.0) {
+        return 1.0;
+    }
+    return color_params.depth_limit / (color_params.depth_limit + depths[index]);
+}
+
+// Sample the colormap at t in [0, 1]
+fn sample_colormap(t: f32) -> vec4<f32> {
+    let last = color_params.colormap_size - 1u;
+    let idx = min(u32(clamp(t, 0.0, 1.0) * f32(last) + 0.5), last);
+    return colormap[idx];
+}
+
+// Fully saturated color for a hue in turns (wraps outside [0, 1))
+fn hue_to_rgb(hue: f32) -> vec3<f32> {
+    let rgb = abs(fract(vec3<f32>(hue) + vec3<f32>(0.0, 2.0 / 3.0, 1.0 / 3.0)) * 6.0 - 3.0) - 1.0;
+    return clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Rotate an RGB color's hue by `angle` radians around the gray axis
+fn rotate_hue(color: vec3<f32>, angle: f32) -> vec3<f32> {
+    let k = vec3<f32>(0.57735027);
+    let c = cos(angle);
+    let rotated = color * c + cross(k, color) * sin(angle) + k * dot(k, color) * (1.0 - c);
+    return clamp(rotated, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Fade particles near the world edges (Wrap mode) so the seam reads as
+// continuous. seam_fade is the faded fraction of each axis (0 = off).
+fn seam_alpha(pos: vec2<f32>) -> f32 {
+    if (color_params.seam_fade <= 0.0) {
+        return 1.0;
+    }
+    let world = vec2<f32>(params.world_width, params.world_height);
+    let edge = min(pos, world - pos) / world;
+    return smoothstep(0.0, color_params.seam_fade, min(edge.x, edge.y));
+}
+
+// Resolve a particle's color for the active color mode
+fn particle_color(index: u32, particle_type: u32) -> vec4<f32> {
+    if (color_params.mode == COLOR_MODE_BY_SPEED) {
+        let speed = length(vec2<f32>(velocities[index]));
+        return sample_colormap(speed / color_params.speed_range);
+    }
+    if (color_params.mode == COLOR_MODE_BY_DIRECTION) {
+        let vel = vec2<f32>(velocities[index]);
+        let heading = atan2(vel.y, vel.x) + color_params.hue_offset;
+        return vec4<f32>(hue_to_rgb(heading / 6.283185307), 1.0);
+    }
+    let color = colors[particle_type];
+    if (color_params.hue_offset != 0.0) {
+        return vec4<f32>(rotate_hue(color.rgb, color_params.hue_offset), color.a);
+    }
+    return color;
+}
+
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) offset: vec2<f32>,
@@ -75,7 +163,7 @@ fn vs_main(
     @builtin(vertex_index) vertex_index: u32
 ) -> VertexOutput {
     let particle = particles[instance_index];
-    let color = colors[particle.particle_type];
+    let color = particle_color(instance_index, particle.particle_type & TYPE_MASK);
     let particle_pos = vec2<f32>(f32(particle.x), f32(particle.y));
 
     // Transform particle position to clip space
@@ -83,16 +171,19 @@ fn vs_main(
     let camera_center = vec2<f32>(camera.center_x, camera.center_y);
     let transformed_pos = (particle_pos - camera_center) * camera_scale;
 
-    // Get quad vertex offset - scaled by glow_size for larger glow effect
+    // Get quad vertex offset - scaled by glow_size for larger glow effect.
+    // With zoom compensation the halo never shrinks below the particle itself.
     let quad_offset = QUAD_VERTICES[vertex_index];
-    let glow_particle_size = params.particle_size * glow.glow_size;
-    let vertex_offset = quad_offset * glow_particle_size * camera_scale;
+    let glow_particle_size = params.particle_size * max(glow.glow_size * glow.zoom_scale, 1.0);
+    // The halo follows its particle's 3D size and brightness
+    let scale = depth_scale(instance_index);
+    let vertex_offset = quad_offset * glow_particle_size * scale * camera_scale;
     let final_pos = transformed_pos + vertex_offset;
 
     var output: VertexOutput;
     output.position = vec4<f32>(final_pos, 0.0, 1.0);
     output.offset = quad_offset;
-    output.color = color;
+    output.color = vec4<f32>(color.rgb * seam_alpha(particle_pos) * scale, color.a);
     return output;
 }
 
@@ -6,7 +6,7 @@ struct PosType {
     x: POS_FLOAT,
     y: POS_FLOAT,
     particle_type: u32,
-    _padding: u32,
+    spin: u32, // pack2x16float(orientation, angular velocity)
 }
 
 struct SimParams {
@@ -24,12 +24,16 @@ struct SimParams {
     dt: f32,
     max_bin_density: f32,
     neighbor_budget: u32, // Max neighbors to check per particle (0 = unlimited)
-    _padding0: u32,
-    _padding1: u32,
-    _padding2: u32,
-    _padding3: u32,
-    _padding4: u32,
-    _padding5: u32,
+    matrix_strength: f32, // Multiplier on interaction matrix values
+    world_shape: u32, // 0 = Rectangle, 1 = Circle (inscribed)
+    spin_coupling: f32, // Torque from tangential velocity (0 = spin off)
+    spin_friction: f32, // Rotational friction per step
+    sample_seed: u32, // Per-step seed for fair neighbor sampling
+    fair_budget: u32, // 1 = sample budgeted neighbors spread across each bin
+    velocity_clamp_mode: u32, // 0 = Hard, 1 = SoftTanh (max_velocity * tanh(speed / max_velocity))
+    particle_shape: u32, // 0 = HardDisk, 1 = SoftDisk, 2 = Square, 3 = Gaussian
+    velocity_scale: f32, // Thermostat multiplier applied before clamping (1 = off)
+    repel_radius: f32, // Repulsion core as a fraction of min radius (0.05 - 1)
 }
 
 struct Camera {
@@ -82,11 +86,154 @@ const QUAD_VERTICES = array<vec2<f32>, 4>(
 @group(0) @binding(3) var<uniform> camera: Camera;
 @group(0) @binding(4) var<uniform> mirror: MirrorParams;
 
+// Color mode selectors (must match ColorMode::shader_id)
+const COLOR_MODE_BY_TYPE: u32 = 0u;
+const COLOR_MODE_BY_SPEED: u32 = 1u;
+const COLOR_MODE_BY_DIRECTION: u32 = 2u;
+
+// Masks the static-particle flag off particle_type
+const TYPE_MASK: u32 = 0x7fffffffu;
+
+struct ColorParams {
+    mode: u32,
+    colormap_size: u32,
+    speed_range: f32,
+    streak_length: f32,
+    hue_offset: f32,
+    seam_fade: f32,
+    depth_limit: f32, // 3D depth range (0 = 3D off)
+    _padding: f32,
+}
+
+@group(0) @binding(5) var<storage, read> colormap: array<vec4<f32>>;
+@group(0) @binding(6) var<uniform> color_params: ColorParams;
+@group(0) @binding(7) var<storage, read> velocities: array<vec2<VEL_FLOAT>>;
+@group(0) @binding(8) var<storage, read> depths: array<f32>;
+
+// Perspective scale of a particle in 3D mode: depth 0 (nearest) draws at
+// full size and brightness, depth_limit at half. 1.0 with 3D off.
+fn depth_scale(index: u32) -> f32 {
+    if (color_params.depth_limit <= 0.0) {
+        return 1.0;
+    }
+    return color_params.depth_limit / (color_params.depth_limit + depths[index]);
+}
+
+// Clip-space depth in [0, 1) for the 3D depth test (0.0 with 3D off)
+fn clip_depth(index: u32) -> f32 {
+    if (color_params.depth_limit <= 0.0) {
+        return 0.0;
+    }
+    return depths[index] / color_params.depth_limit;
+}
+
+// Sample the colormap at t in [0, 1]
+fn sample_colormap(t: f32) -> vec4<f32> {
+    let last = color_params.colormap_size - 1u;
+    let idx = min(u32(clamp(t, 0.0, 1.0) * f32(last) + 0.5), last);
+    return colormap[idx];
+}
+
+// Fully saturated color for a hue in turns (wraps outside [0, 1))
+fn hue_to_rgb(hue: f32) -> vec3<f32> {
+    let rgb = abs(fract(vec3<f32>(hue) + vec3<f32>(0.0, 2.0 / 3.0, 1.0 / 3.0)) * 6.0 - 3.0) - 1.0;
+    return clamp(rgb, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Rotate an RGB color's hue by `angle` radians around the gray axis
+fn rotate_hue(color: vec3<f32>, angle: f32) -> vec3<f32> {
+    let k = vec3<f32>(0.57735027);
+    let c = cos(angle);
+    let rotated = color * c + cross(k, color) * sin(angle) + k * dot(k, color) * (1.0 - c);
+    return clamp(rotated, vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+// Resolve a particle's color for the active color mode
+fn particle_color(index: u32, particle_type: u32) -> vec4<f32> {
+    if (color_params.mode == COLOR_MODE_BY_SPEED) {
+        let speed = length(vec2<f32>(velocities[index]));
+        return sample_colormap(speed / color_params.speed_range);
+    }
+    if (color_params.mode == COLOR_MODE_BY_DIRECTION) {
+        let vel = vec2<f32>(velocities[index]);
+        let heading = atan2(vel.y, vel.x) + color_params.hue_offset;
+        return vec4<f32>(hue_to_rgb(heading / 6.283185307), 1.0);
+    }
+    let color = colors[particle_type];
+    if (color_params.hue_offset != 0.0) {
+        return vec4<f32>(rotate_hue(color.rgb, color_params.hue_offset), color.a);
+    }
+    return color;
+}
+
+// Quad vertex offset in world units. With streaks enabled the quad is
+// stretched along the velocity by streak_length * speed, trailing behind the
+// particle; slow particles fall back to a round dot.
+fn sprite_offset(index: u32, quad_offset: vec2<f32>) -> vec2<f32> {
+    let size = params.particle_size;
+    if (color_params.streak_length > 0.0) {
+        let vel = vec2<f32>(velocities[index]);
+        let speed = length(vel);
+        if (speed > 1e-3) {
+            let dir = vel / speed;
+            let perp = vec2<f32>(-dir.y, dir.x);
+            let half_len = size + 0.5 * color_params.streak_length * speed;
+            let along = quad_offset.x * half_len - (half_len - size);
+            return dir * along + perp * (quad_offset.y * size);
+        }
+    }
+    return quad_offset * size;
+}
+
+// Darken a notch from the center toward the particle's orientation so spin
+// is visible (only when spin is enabled)
+fn orientation_shade(offset: vec2<f32>, angle: f32) -> f32 {
+    if (params.spin_coupling <= 0.0) {
+        return 1.0;
+    }
+    let axis = vec2<f32>(cos(angle), sin(angle));
+    let along = dot(offset, axis);
+    let across = abs(offset.x * axis.y - offset.y * axis.x);
+    if (along > 0.0 && across < 0.2) {
+        return 0.4;
+    }
+    return 1.0;
+}
+
+// Particle shape selectors (must match ParticleShape::shader_id)
+const SHAPE_HARD_DISK: u32 = 0u;
+const SHAPE_SOFT_DISK: u32 = 1u;
+const SHAPE_SQUARE: u32 = 2u;
+const SHAPE_GAUSSIAN: u32 = 3u;
+
+// Sprite coverage at a quad offset in [-1, 1]^2 for the selected shape
+fn shape_alpha(offset: vec2<f32>) -> f32 {
+    let dist_sq = dot(offset, offset);
+    switch params.particle_shape {
+        case SHAPE_SOFT_DISK: {
+            return 1.0 - smoothstep(0.25, 1.0, dist_sq);
+        }
+        case SHAPE_SQUARE: {
+            return 1.0;
+        }
+        case SHAPE_GAUSSIAN: {
+            // Falls to ~1% at the quad edge
+            return exp(-4.6 * dist_sq);
+        }
+        default: {
+            // Hard disk: smooth edge using derivative-based anti-aliasing
+            let edge_width = fwidth(dist_sq);
+            return 1.0 - smoothstep(max(0.0, 1.0 - edge_width), 1.0, dist_sq);
+        }
+    }
+}
+
 struct VertexOutput {
     @builtin(position) position: vec4<f32>,
     @location(0) offset: vec2<f32>,
     @location(1) color: vec4<f32>,
     @location(2) is_mirror: f32,
+    @location(3) angle: f32,
 }
 
 @vertex
@@ -101,7 +248,7 @@ fn vs_main(
     let mirror_index = instance_index % num_copies;
 
     let particle = particles[particle_index];
-    let base_color = colors[particle.particle_type];
+    let base_color = particle_color(particle_index, particle.particle_type & TYPE_MASK);
     let particle_pos = vec2<f32>(f32(particle.x), f32(particle.y));
 
     // Get mirror offset based on copy count
@@ -122,8 +269,10 @@ fn vs_main(
     let transformed_pos = (world_pos - camera_center) * camera_scale;
 
     // Get quad vertex offset
+    // Nearer particles draw bigger and brighter in 3D mode
     let quad_offset = QUAD_VERTICES[vertex_index];
-    let vertex_offset = quad_offset * params.particle_size * camera_scale;
+    let scale = depth_scale(particle_index);
+    let vertex_offset = sprite_offset(particle_index, quad_offset) * scale * camera_scale;
     let final_pos = transformed_pos + vertex_offset;
 
     // Determine if this is a mirror copy (not the original)
@@ -133,23 +282,21 @@ fn vs_main(
     let final_alpha = mix(base_color.a, base_color.a * 0.75, is_mirror);
 
     var output: VertexOutput;
-    output.position = vec4<f32>(final_pos, 0.0, 1.0);
+    output.position = vec4<f32>(final_pos, clip_depth(particle_index), 1.0);
     output.offset = quad_offset;
-    output.color = vec4<f32>(base_color.rgb, final_alpha);
+    output.angle = unpack2x16float(particle.spin).x;
+    output.color = vec4<f32>(base_color.rgb * scale, final_alpha);
     output.is_mirror = is_mirror;
     return output;
 }
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    let dist_sq = dot(in.offset, in.offset);
-
-    let edge_width = fwidth(dist_sq);
-    let alpha = 1.0 - smoothstep(max(0.0, 1.0 - edge_width), 1.0, dist_sq);
-
+    let alpha = shape_alpha(in.offset);
     if (alpha < 0.01) {
         discard;
     }
 
-    return vec4<f32>(in.color.rgb, in.color.a * alpha);
+    let shade = orientation_shade(in.offset, in.angle);
+    return vec4<f32>(in.color.rgb * shade, in.color.a * alpha);
 }
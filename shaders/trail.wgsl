@@ -0,0 +1,46 @@
+// Trail (motion blur) shader.
+// Fades the persistent trail texture toward the background color, then
+// copies the result to the screen. Both passes draw the fullscreen quad.
+
+struct TrailParams {
+    // Background color (rgb) and fade alpha per frame (a)
+    fade_color: vec4<f32>,
+}
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+// Fade pass bindings
+@group(0) @binding(0) var<uniform> params: TrailParams;
+
+// Blit pass bindings
+@group(0) @binding(1) var trail_texture: texture_2d<f32>;
+@group(0) @binding(2) var trail_sampler: sampler;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(in.position, 0.0, 1.0);
+    out.uv = in.uv;
+    return out;
+}
+
+// Translucent background quad, alpha blended over the previous frame
+@fragment
+fn fs_fade(in: VertexOutput) -> @location(0) vec4<f32> {
+    return params.fade_color;
+}
+
+// Opaque copy of the trail texture to the surface
+@fragment
+fn fs_blit(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = textureSample(trail_texture, trail_sampler, in.uv);
+    return vec4<f32>(color.rgb, 1.0);
+}
@@ -0,0 +1,41 @@
+// Vignette shader.
+// Darkens the screen toward its corners with the fullscreen quad, after the
+// scene reaches the surface. Outputs black with the darkening as alpha, so
+// alpha blending scales the scene color by (1 - darkening).
+
+struct VignetteParams {
+    // Darkening at the corners (0 = none, 1 = black)
+    intensity: f32,
+    _padding0: f32,
+    _padding1: f32,
+    _padding2: f32,
+}
+
+struct VertexInput {
+    @location(0) position: vec2<f32>,
+    @location(1) uv: vec2<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+}
+
+@group(0) @binding(0) var<uniform> params: VignetteParams;
+
+@vertex
+fn vs_main(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+    out.position = vec4<f32>(in.position, 0.0, 1.0);
+    out.uv = in.uv;
+    return out;
+}
+
+// Distance from the center is 1 at the edge midpoints and sqrt(2) in the
+// corners; darkening starts halfway out and peaks in the corners
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    let r = length(in.uv * 2.0 - 1.0);
+    let darkening = params.intensity * smoothstep(0.5, 1.41421356, r);
+    return vec4<f32>(0.0, 0.0, 0.0, darkening);
+}
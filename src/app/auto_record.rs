@@ -0,0 +1,36 @@
+//! Record-on-startup automation mode.
+
+use crate::video_recorder::VideoFormat;
+
+/// Record a fixed span of simulated time right after startup, then quit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoRecord {
+    /// Seconds of simulated time to record.
+    pub seconds: f32,
+    /// Output format of the recording.
+    pub format: VideoFormat,
+}
+
+impl AutoRecord {
+    /// Frames to capture at `fps` so the video spans `seconds` (at least one).
+    pub fn frame_count(&self, fps: u32) -> u32 {
+        ((self.seconds.max(0.0) * fps as f32).ceil() as u32).max(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_count_covers_duration() {
+        let auto = |seconds| AutoRecord {
+            seconds,
+            format: VideoFormat::MP4,
+        };
+        assert_eq!(auto(10.0).frame_count(30), 300);
+        assert_eq!(auto(0.5).frame_count(30), 15);
+        assert_eq!(auto(0.01).frame_count(30), 1);
+        assert_eq!(auto(0.0).frame_count(30), 1);
+    }
+}
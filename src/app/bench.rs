@@ -0,0 +1,471 @@
+//! Headless GPU benchmark behind the `bench` subcommand.
+//!
+//! Steps the spatial-hash compute passes on an off-screen device, using the
+//! same pipelines and buffers as the app, and reports wall-clock step times
+//! plus per-pass GPU timings when timestamp queries are available.
+
+use std::fmt;
+use std::time::Instant;
+
+use anyhow::{Result, bail};
+use bytemuck::cast_slice;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::app::BrushState;
+use crate::app::gpu_state::{MAX_TIMESTAMP_QUERIES, SpatialBindGroupCache};
+use crate::generators::{
+    PaletteType, PositionPattern, RuleType, SpawnConfig, colors::generate_colors_with_rng,
+    positions::generate_positions_with_rng, rules::generate_rules_with_rng,
+};
+use crate::renderer::gpu::{
+    BrushPipelines, ComputePipelines, GpuContext, PrefixScanPass, SimulationBuffers,
+    SpatialHashBuffers, SpatialHashPipelines, fair_sample_seed,
+};
+use crate::simulation::{RadiusMatrix, SimulationConfig};
+
+/// Seed for the generated scene so runs are comparable.
+const BENCH_SEED: u64 = 0x5EED;
+/// Steps run before timing starts (pipeline warm-up, first-use allocations).
+const WARMUP_STEPS: u32 = 10;
+/// Fixed timestep uploaded for every step.
+const BENCH_DT: f32 = 1.0 / 60.0;
+
+/// Benchmark settings.
+#[derive(Debug, Clone, Copy)]
+pub struct BenchOptions {
+    /// Number of particles to simulate.
+    pub particles: u32,
+    /// Number of particle types.
+    pub types: u32,
+    /// Number of timed steps.
+    pub steps: u32,
+    /// Allow half-precision velocity storage when the GPU supports it.
+    pub allow_f16: bool,
+    /// Workgroup size of the spatial hash passes.
+    pub workgroup_size: u32,
+}
+
+/// Summary of a set of timing samples, in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingStats {
+    pub min: f32,
+    pub median: f32,
+    pub p99: f32,
+    pub max: f32,
+    pub mean: f32,
+}
+
+impl TimingStats {
+    /// Summarize samples using nearest-rank percentiles.
+    ///
+    /// Returns `None` when there are no samples.
+    pub fn from_samples(samples: &[f32]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(f32::total_cmp);
+        let rank = |p: f32| {
+            let idx = (p * sorted.len() as f32).ceil() as usize;
+            sorted[idx.clamp(1, sorted.len()) - 1]
+        };
+        Some(Self {
+            min: sorted[0],
+            median: rank(0.5),
+            p99: rank(0.99),
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<f32>() / sorted.len() as f32,
+        })
+    }
+}
+
+/// Results of a benchmark run.
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    /// Settings the run used.
+    pub options: BenchOptions,
+    /// Adapter name and backend.
+    pub adapter: String,
+    /// Wall-clock time per step (submit to completion).
+    pub step_ms: TimingStats,
+    /// Total wall-clock time of the timed steps, in seconds.
+    pub total_secs: f64,
+    /// GPU time per pass, in dispatch order. Empty without timestamp support.
+    pub gpu_pass_ms: Vec<(String, TimingStats)>,
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let o = &self.options;
+        writeln!(f, "Adapter: {}", self.adapter)?;
+        writeln!(
+            f,
+            "{} particles, {} types, {} steps, spatial workgroup {}",
+            o.particles, o.types, o.steps, o.workgroup_size
+        )?;
+        let s = &self.step_ms;
+        writeln!(
+            f,
+            "Step ms: min {:.3}  median {:.3}  p99 {:.3}  max {:.3}",
+            s.min, s.median, s.p99, s.max
+        )?;
+        writeln!(
+            f,
+            "Total: {:.3} s ({:.1} steps/s)",
+            self.total_secs,
+            o.steps as f64 / self.total_secs.max(f64::EPSILON)
+        )?;
+        if self.gpu_pass_ms.is_empty() {
+            writeln!(f, "GPU pass timings: unavailable (no timestamp queries)")?;
+        } else {
+            writeln!(f, "GPU pass ms (mean / median / max):")?;
+            for (label, t) in &self.gpu_pass_ms {
+                writeln!(
+                    f,
+                    "  {:<14} {:>8.3} {:>8.3} {:>8.3}",
+                    label, t.mean, t.median, t.max
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Timestamp queries written around each compute pass.
+struct PassTimer {
+    query_set: wgpu::QuerySet,
+    resolve: wgpu::Buffer,
+    readback: wgpu::Buffer,
+    period: f32,
+}
+
+impl PassTimer {
+    fn new(device: &wgpu::Device, queue: &wgpu::Queue) -> Option<Self> {
+        let features =
+            wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::TIMESTAMP_QUERY_INSIDE_PASSES;
+        if !device.features().contains(features) {
+            return None;
+        }
+        let size = MAX_TIMESTAMP_QUERIES as u64 * 8;
+        Some(Self {
+            query_set: device.create_query_set(&wgpu::QuerySetDescriptor {
+                label: Some("Bench Timestamp Query Set"),
+                ty: wgpu::QueryType::Timestamp,
+                count: MAX_TIMESTAMP_QUERIES,
+            }),
+            resolve: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Bench Timestamp Resolve Buffer"),
+                size,
+                usage: wgpu::BufferUsages::QUERY_RESOLVE | wgpu::BufferUsages::COPY_SRC,
+                mapped_at_creation: false,
+            }),
+            readback: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Bench Timestamp Readback Buffer"),
+                size,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }),
+            period: queue.get_timestamp_period(),
+        })
+    }
+
+    /// Read back `count` resolved timestamps as per-pass durations.
+    fn read(&self, device: &wgpu::Device, count: u32) -> Vec<f32> {
+        let slice = self.readback.slice(..count as u64 * 8);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |res| {
+            let _ = tx.send(res);
+        });
+        let _ = device.poll(wgpu::PollType::wait_indefinitely());
+        if !matches!(rx.recv(), Ok(Ok(()))) {
+            return Vec::new();
+        }
+        let durations = {
+            let data = slice.get_mapped_range();
+            let timestamps: &[u64] = cast_slice(&data);
+            timestamps
+                .chunks_exact(2)
+                .map(|pair| pair[1].saturating_sub(pair[0]) as f32 * self.period / 1_000_000.0)
+                .collect()
+        };
+        self.readback.unmap();
+        durations
+    }
+}
+
+/// Record one compute pass, bracketed by timestamps when available.
+#[allow(clippy::too_many_arguments)]
+fn dispatch(
+    encoder: &mut wgpu::CommandEncoder,
+    timer: Option<&PassTimer>,
+    query_index: &mut u32,
+    labels: &mut Vec<String>,
+    label: String,
+    pipeline: &wgpu::ComputePipeline,
+    bind_group: &wgpu::BindGroup,
+    workgroups: u32,
+) {
+    let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+        label: Some(&label),
+        timestamp_writes: None,
+    });
+    if let Some(t) = timer {
+        pass.write_timestamp(&t.query_set, *query_index);
+    }
+    pass.set_pipeline(pipeline);
+    pass.set_bind_group(0, bind_group, &[]);
+    pass.dispatch_workgroups(workgroups, 1, 1);
+    if let Some(t) = timer {
+        pass.write_timestamp(&t.query_set, *query_index + 1);
+        *query_index += 2;
+    }
+    labels.push(label);
+}
+
+/// Run the benchmark and collect timings.
+///
+/// Fails if no GPU adapter is available or the options are degenerate.
+pub fn run(options: BenchOptions) -> Result<BenchReport> {
+    if options.particles == 0 || options.types == 0 || options.steps == 0 {
+        bail!("particles, types and steps must all be greater than zero");
+    }
+
+    let (adapter, device, queue) = pollster::block_on(GpuContext::new_headless(options.allow_f16))?;
+    let info = adapter.get_info();
+    let adapter_name = format!("{} ({:?})", info.name, info.backend);
+
+    // Same default scene the app starts with, generated from a fixed seed.
+    let mut config = SimulationConfig {
+        num_particles: options.particles,
+        num_types: options.types,
+        ..SimulationConfig::default()
+    };
+    let num_types = options.types as usize;
+    let mut rng = StdRng::seed_from_u64(BENCH_SEED);
+    let interaction_matrix = generate_rules_with_rng(RuleType::Random, num_types, &mut rng);
+    let radius_matrix = RadiusMatrix::default_for_size(num_types);
+    let colors = generate_colors_with_rng(PaletteType::Rainbow, num_types, &mut rng);
+    let spawn = SpawnConfig {
+        num_particles: options.particles as usize,
+        num_types,
+        width: config.world_size.x,
+        height: config.world_size.y,
+        velocity: Default::default(),
+        sampling: Default::default(),
+        type_weights: Vec::new(),
+    };
+    let particles = generate_positions_with_rng(PositionPattern::Random, &spawn, &mut rng);
+
+    let max_radius = radius_matrix.max_interaction_radius();
+    config.spatial_hash_cell_size = config.spatial_hash_cell_size.max(max_radius);
+
+    let mut buffers = SimulationBuffers::new(
+        &device,
+        &particles,
+        &interaction_matrix,
+        &radius_matrix,
+        &colors,
+        &config,
+    );
+    let compute = ComputePipelines::new(&device);
+    let spatial_pipelines = SpatialHashPipelines::new(&device, options.workgroup_size);
+    let mut spatial_buffers = SpatialHashBuffers::new(&device, &config, max_radius);
+    // Only the brush uniform is needed; the advance shader reads it.
+    let brush_pipelines = BrushPipelines::new(&device, wgpu::TextureFormat::Rgba8Unorm);
+    brush_pipelines.update_brush(&queue, &BrushState::default(), options.particles);
+    spatial_buffers.update_params(&queue, &config, max_radius);
+
+    let mut bind_groups = SpatialBindGroupCache::new();
+    let timer = PassTimer::new(&device, &queue);
+    let particle_workgroups = options.particles.div_ceil(256);
+    let spatial_workgroups = spatial_pipelines.workgroups(options.particles);
+
+    let mut step_ms = Vec::with_capacity(options.steps as usize);
+    let mut pass_samples: Vec<(String, Vec<f32>)> = Vec::new();
+    let mut total_secs = 0.0f64;
+
+    for frame in 0..WARMUP_STEPS + options.steps {
+        buffers.update_params(
+            &queue,
+            &config,
+            BENCH_DT,
+            fair_sample_seed(config.seed, frame, 0),
+            1.0,
+        );
+        bind_groups.ensure(&device, &buffers, &spatial_buffers, &spatial_pipelines);
+
+        let start = Instant::now();
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Bench Compute Encoder"),
+        });
+        let mut query_index = 0u32;
+        let mut labels = Vec::new();
+        let bin_workgroups = spatial_pipelines.workgroups(spatial_buffers.total_bins_with_end());
+
+        dispatch(
+            &mut encoder,
+            timer.as_ref(),
+            &mut query_index,
+            &mut labels,
+            "clear".to_string(),
+            &spatial_pipelines.clear_pipeline,
+            bind_groups.clear(true),
+            bin_workgroups,
+        );
+        dispatch(
+            &mut encoder,
+            timer.as_ref(),
+            &mut query_index,
+            &mut labels,
+            "count".to_string(),
+            &spatial_pipelines.count_pipeline,
+            bind_groups.count_for_current(&buffers),
+            spatial_workgroups,
+        );
+        let prefix_groups = bind_groups.prefix_groups();
+        for &prefix_pass in &bind_groups.prefix_passes {
+            let label = match prefix_pass {
+                PrefixScanPass::Scan { level, .. } => format!("prefix scan {}", level),
+                PrefixScanPass::AddOffsets { level, .. } => format!("prefix add {}", level),
+            };
+            dispatch(
+                &mut encoder,
+                timer.as_ref(),
+                &mut query_index,
+                &mut labels,
+                label,
+                spatial_pipelines.prefix_pipeline(prefix_pass),
+                &prefix_groups[prefix_pass.level()],
+                prefix_pass.workgroups(),
+            );
+        }
+        dispatch(
+            &mut encoder,
+            timer.as_ref(),
+            &mut query_index,
+            &mut labels,
+            "clear_sort".to_string(),
+            &spatial_pipelines.clear_pipeline,
+            bind_groups.clear(false),
+            bin_workgroups,
+        );
+        dispatch(
+            &mut encoder,
+            timer.as_ref(),
+            &mut query_index,
+            &mut labels,
+            "sort".to_string(),
+            &spatial_pipelines.sort_pipeline,
+            bind_groups.sort_for_current(&buffers),
+            spatial_workgroups,
+        );
+        dispatch(
+            &mut encoder,
+            timer.as_ref(),
+            &mut query_index,
+            &mut labels,
+            "forces".to_string(),
+            &spatial_pipelines.forces_pipeline,
+            bind_groups.forces_for_current(&buffers),
+            spatial_workgroups,
+        );
+        let advance_bind_group = compute.create_advance_bind_group(
+            &device,
+            buffers.next_pos_type(),
+            buffers.next_velocities(),
+            &buffers,
+            &brush_pipelines.brush_buffer,
+        );
+        dispatch(
+            &mut encoder,
+            timer.as_ref(),
+            &mut query_index,
+            &mut labels,
+            "advance".to_string(),
+            &compute.advance_pipeline,
+            &advance_bind_group,
+            particle_workgroups,
+        );
+
+        if let Some(t) = timer.as_ref() {
+            encoder.resolve_query_set(&t.query_set, 0..query_index, &t.resolve, 0);
+            encoder.copy_buffer_to_buffer(&t.resolve, 0, &t.readback, 0, query_index as u64 * 8);
+        }
+
+        queue.submit(std::iter::once(encoder.finish()));
+        let _ = device.poll(wgpu::PollType::wait_indefinitely());
+        let elapsed = start.elapsed();
+        buffers.swap_buffers();
+
+        if frame < WARMUP_STEPS {
+            continue;
+        }
+        total_secs += elapsed.as_secs_f64();
+        step_ms.push(elapsed.as_secs_f32() * 1000.0);
+
+        if let Some(t) = timer.as_ref() {
+            for (label, ms) in labels.into_iter().zip(t.read(&device, query_index)) {
+                match pass_samples.iter_mut().find(|(l, _)| *l == label) {
+                    Some((_, samples)) => samples.push(ms),
+                    None => pass_samples.push((label, vec![ms])),
+                }
+            }
+        }
+    }
+
+    Ok(BenchReport {
+        options: BenchOptions {
+            workgroup_size: spatial_pipelines.workgroup_size,
+            ..options
+        },
+        adapter: adapter_name,
+        step_ms: TimingStats::from_samples(&step_ms).expect("at least one timed step"),
+        total_secs,
+        gpu_pass_ms: pass_samples
+            .into_iter()
+            .filter_map(|(label, samples)| Some((label, TimingStats::from_samples(&samples)?)))
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_stats_percentiles() {
+        let samples: Vec<f32> = (1..=100).rev().map(|v| v as f32).collect();
+        let stats = TimingStats::from_samples(&samples).unwrap();
+        assert_eq!(stats.min, 1.0);
+        assert_eq!(stats.median, 50.0);
+        assert_eq!(stats.p99, 99.0);
+        assert_eq!(stats.max, 100.0);
+        assert!((stats.mean - 50.5).abs() < 1e-4);
+
+        let single = TimingStats::from_samples(&[2.5]).unwrap();
+        assert_eq!((single.min, single.median, single.p99), (2.5, 2.5, 2.5));
+        assert!(TimingStats::from_samples(&[]).is_none());
+    }
+
+    #[test]
+    fn test_gpu_bench_runs_headless() {
+        let options = BenchOptions {
+            particles: 1000,
+            types: 4,
+            steps: 5,
+            allow_f16: true,
+            workgroup_size: 64,
+        };
+        let report = match run(options) {
+            Ok(report) => report,
+            Err(e) => {
+                eprintln!("Skipping GPU test, no adapter: {e}");
+                return;
+            }
+        };
+        assert!(report.step_ms.min > 0.0);
+        assert!(report.step_ms.min <= report.step_ms.max);
+        assert!(report.to_string().contains("1000 particles"));
+    }
+}
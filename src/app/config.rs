@@ -2,8 +2,16 @@
 
 use serde::{Deserialize, Serialize};
 
-use crate::generators::{colors::PaletteType, positions::PositionPattern, rules::RuleType};
-use crate::simulation::{BoundaryMode, SimulationConfig};
+use super::StagnationAction;
+use crate::generators::{
+    colors::PaletteType,
+    positions::{PositionPattern, SpawnSampling, SpawnVelocity},
+    rules::RuleType,
+};
+use crate::simulation::{
+    BlendMode, BoundaryMode, ColorMode, ParticleShape, SimulationConfig, VelocityClampMode,
+    WorldShape,
+};
 
 /// Application-level configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,10 +22,20 @@ pub struct AppConfig {
     pub window_width: u32,
     /// Initial window height.
     pub window_height: u32,
+    /// Monitor to open the window on (None = primary).
+    #[serde(default)]
+    pub window_monitor: Option<usize>,
     /// Target frames per second.
     pub target_fps: u32,
     /// Enable VSync.
     pub vsync: bool,
+    /// Frame-rate cap applied while VSync is off (`None` = uncapped).
+    #[serde(default)]
+    pub fps_cap: Option<u32>,
+    /// MSAA samples for particle edges: 1 (off), 2 or 4. Unsupported counts
+    /// fall back to 1.
+    #[serde(default = "default_msaa_samples")]
+    pub msaa_samples: u32,
     /// UI: Is Simulation section open?
     pub ui_simulation_open: bool,
     /// UI: Is Physics section open?
@@ -34,22 +52,55 @@ pub struct AppConfig {
     pub ui_presets_open: bool,
     /// UI: Is Keyboard Shortcuts section open?
     pub ui_keyboard_shortcuts_open: bool,
+    /// UI: Is Debug section open?
+    #[serde(default)]
+    pub ui_debug_open: bool,
+    /// UI: Show the floating stats overlay (independent of the sidebar).
+    #[serde(default)]
+    pub show_stats_overlay: bool,
 
     /// Physics: force factor.
     #[serde(default = "default_phys_force_factor")]
     pub phys_force_factor: f32,
+    /// Physics: interaction matrix strength multiplier.
+    #[serde(default = "default_phys_matrix_strength")]
+    pub phys_matrix_strength: f32,
+    /// Physics: spin coupling (0 = spin disabled).
+    #[serde(default)]
+    pub phys_spin_coupling: f32,
+    /// Physics: rotational friction for spin.
+    #[serde(default = "default_phys_spin_friction")]
+    pub phys_spin_friction: f32,
+    /// Physics: thermostat target temperature (`None` = thermostat off).
+    #[serde(default)]
+    pub phys_target_temperature: Option<f32>,
     /// Physics: friction.
     #[serde(default = "default_phys_friction")]
     pub phys_friction: f32,
     /// Physics: repel strength.
     #[serde(default = "default_phys_repel_strength")]
     pub phys_repel_strength: f32,
+    /// Physics: repulsion core radius as a fraction of min radius.
+    #[serde(default = "default_phys_repel_radius")]
+    pub phys_repel_radius: f32,
     /// Physics: max velocity.
     #[serde(default = "default_phys_max_velocity")]
     pub phys_max_velocity: f32,
+    /// Physics: how speed is limited to max velocity.
+    #[serde(default)]
+    pub phys_velocity_clamp: VelocityClampMode,
+    /// Physics: fixed timestep in seconds (`None` = follow frame time).
+    #[serde(default)]
+    pub phys_fixed_dt: Option<f32>,
+    /// Physics: steps per frame with a fixed timestep.
+    #[serde(default = "default_phys_substeps")]
+    pub phys_substeps: u32,
     /// Physics: boundary mode.
     #[serde(default = "default_phys_boundary_mode")]
     pub phys_boundary_mode: BoundaryMode,
+    /// Physics: world boundary shape.
+    #[serde(default)]
+    pub phys_world_shape: WorldShape,
     /// Physics: wall repel strength.
     #[serde(default = "default_phys_wall_repel_strength")]
     pub phys_wall_repel_strength: f32,
@@ -63,6 +114,12 @@ pub struct AppConfig {
     /// Simulation: number of types.
     #[serde(default = "default_sim_num_types")]
     pub sim_num_types: u32,
+    /// Simulation: locked generation seed (`None` = random each run).
+    #[serde(default)]
+    pub sim_seed: Option<u64>,
+    /// Simulation: optional display names per particle type.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sim_type_names: Vec<String>,
 
     /// Generators: current rule type.
     #[serde(default = "default_gen_rule")]
@@ -73,13 +130,41 @@ pub struct AppConfig {
     /// Generators: current spawn pattern.
     #[serde(default = "default_gen_pattern")]
     pub gen_pattern: PositionPattern,
-
+    /// Generators: initial velocity field for spawned particles.
+    #[serde(default)]
+    pub gen_spawn_velocity: SpawnVelocity,
+    /// Generators: coordinate sampling for spawn patterns.
+    #[serde(default)]
+    pub gen_spawn_sampling: SpawnSampling,
+    /// Generators: relative particle share per type (empty = uniform).
+    #[serde(default)]
+    pub gen_type_weights: Vec<f32>,
+    /// Generators: shuffle spawned particles with the generator RNG so draw
+    /// and memory order don't follow type.
+    #[serde(default)]
+    pub gen_shuffle_particles: bool,
+    /// Generators: standard deviation of the Mutate Matrix noise.
+    #[serde(default = "default_gen_mutation_sigma")]
+    pub gen_mutation_sigma: f32,
+    /// Generators: hue/value jitter applied to palette colors.
+    #[serde(default)]
+    pub gen_palette_jitter: f32,
+
+    /// Rendering: 3D depth mode (size/brightness attenuation and depth test).
+    #[serde(default)]
+    pub render_enable_3d: bool,
+    /// Rendering: depth range of 3D mode.
+    #[serde(default = "default_depth_limit")]
+    pub render_depth_limit: f32,
     /// Rendering: particle size.
     #[serde(default = "default_particle_size")]
     pub render_particle_size: f32,
     /// Rendering: background color.
     #[serde(default = "default_background_color")]
     pub render_background_color: [f32; 3],
+    /// Rendering: vertical background gradient `[top, bottom]` (`None` = solid).
+    #[serde(default)]
+    pub render_background_gradient: Option<[[f32; 3]; 2]>,
     /// Rendering: glow enabled.
     #[serde(default = "default_glow_enabled")]
     pub render_glow_enabled: bool,
@@ -92,6 +177,48 @@ pub struct AppConfig {
     /// Rendering: glow steepness.
     #[serde(default = "default_glow_steepness")]
     pub render_glow_steepness: f32,
+    /// Rendering: keep the glow size constant on screen across zoom levels.
+    #[serde(default)]
+    pub render_glow_zoom_compensate: bool,
+    /// Rendering: motion trails enabled.
+    #[serde(default)]
+    pub render_trails_enabled: bool,
+    /// Rendering: per-frame trail fade alpha.
+    #[serde(default = "default_trail_fade")]
+    pub render_trail_fade: f32,
+    /// Rendering: vignette darkening toward the corners (0 = off).
+    #[serde(default)]
+    pub render_vignette: f32,
+    /// Rendering: particle color mode.
+    #[serde(default)]
+    pub render_color_mode: ColorMode,
+    /// Rendering: particle sprite shape.
+    #[serde(default)]
+    pub render_particle_shape: ParticleShape,
+    /// Rendering: blending of the base particle pass.
+    #[serde(default)]
+    pub render_blend_mode: BlendMode,
+    /// Rendering: colormap for scalar color modes.
+    #[serde(default = "default_colormap")]
+    pub render_colormap: PaletteType,
+    /// Rendering: speed at the top of the colormap.
+    #[serde(default = "default_color_speed_range")]
+    pub render_color_speed_range: f32,
+    /// Rendering: rotate palette hues over time.
+    #[serde(default)]
+    pub render_animate_palette: bool,
+    /// Rendering: palette hue rotation speed (degrees per second).
+    #[serde(default = "default_palette_hue_speed")]
+    pub render_palette_hue_speed: f32,
+    /// Rendering: edge fade width in Wrap mode (fraction of each axis).
+    #[serde(default)]
+    pub render_seam_fade: f32,
+    /// Rendering: draw particles as velocity streaks.
+    #[serde(default)]
+    pub render_velocity_streaks: bool,
+    /// Rendering: streak length per unit of speed.
+    #[serde(default = "default_streak_length")]
+    pub render_streak_length: f32,
     /// Rendering: spatial hash cell size.
     #[serde(default = "default_spatial_hash_cell_size")]
     pub render_spatial_hash_cell_size: f32,
@@ -99,6 +226,48 @@ pub struct AppConfig {
     /// Simulation: auto-scale radii with particle density.
     #[serde(default = "default_auto_scale_radii")]
     pub auto_scale_radii: bool,
+
+    /// Simulation: express default radii as fractions of the shorter world
+    /// side and rescale them (and the cell size) when the world is resized.
+    #[serde(default)]
+    pub radii_relative_to_world: bool,
+
+    /// Simulation: resize the world to the window surface whenever the
+    /// window is resized, instead of keeping the fixed world size.
+    #[serde(default)]
+    pub match_world_to_window: bool,
+
+    /// Simulation: action taken when the scene stagnates.
+    #[serde(default)]
+    pub stagnation_action: StagnationAction,
+    /// Simulation: mean speed at or below which the scene counts as stagnant.
+    #[serde(default = "default_stagnation_speed_threshold")]
+    pub stagnation_speed_threshold: f32,
+    /// Simulation: seconds the scene must stay stagnant before acting.
+    #[serde(default = "default_stagnation_seconds")]
+    pub stagnation_seconds: f32,
+
+    /// Autosave: minutes between recovery autosaves (0 = disabled).
+    #[serde(default = "default_autosave_interval_minutes")]
+    pub autosave_interval_minutes: u32,
+    /// Autosave: include particle positions (requires a GPU readback).
+    #[serde(default)]
+    pub autosave_particles: bool,
+
+    /// Presets: restore the saved camera zoom/pan when loading a preset.
+    #[serde(default = "default_restore_preset_camera")]
+    pub restore_preset_camera: bool,
+
+    /// GPU: force f32 velocity storage even when the device supports f16.
+    #[serde(default)]
+    pub force_f32: bool,
+    /// GPU (dev): workgroup size of the spatial hash passes, for profiling.
+    #[serde(default = "default_spatial_workgroup_size")]
+    pub spatial_workgroup_size: u32,
+
+    /// Adaptive quality: drop effects when the FPS falls below `target_fps`.
+    #[serde(default)]
+    pub adaptive_quality: bool,
 }
 
 impl Default for AppConfig {
@@ -107,8 +276,11 @@ impl Default for AppConfig {
             title: "Par Particle Life".to_string(),
             window_width: 1920,
             window_height: 1080,
+            window_monitor: None,
             target_fps: 60,
             vsync: false,
+            fps_cap: None,
+            msaa_samples: default_msaa_samples(),
             // UI section open/closed state
             ui_simulation_open: true,
             ui_physics_open: true,
@@ -118,40 +290,97 @@ impl Default for AppConfig {
             ui_rendering_open: false,          // Default false as per UI
             ui_presets_open: false,            // Default false as per UI
             ui_keyboard_shortcuts_open: false, // Default false as per UI
+            ui_debug_open: false,              // Default false as per UI
+            show_stats_overlay: false,
 
             // Physics defaults
             phys_force_factor: default_phys_force_factor(),
+            phys_matrix_strength: default_phys_matrix_strength(),
+            phys_spin_coupling: SimulationConfig::default().spin_coupling,
+            phys_spin_friction: default_phys_spin_friction(),
+            phys_target_temperature: None,
             phys_friction: default_phys_friction(),
             phys_repel_strength: default_phys_repel_strength(),
+            phys_repel_radius: default_phys_repel_radius(),
             phys_max_velocity: default_phys_max_velocity(),
+            phys_velocity_clamp: VelocityClampMode::default(),
+            phys_fixed_dt: None,
+            phys_substeps: default_phys_substeps(),
             phys_boundary_mode: default_phys_boundary_mode(),
+            phys_world_shape: WorldShape::default(),
             phys_wall_repel_strength: default_phys_wall_repel_strength(),
             phys_mirror_wrap_count: default_phys_mirror_wrap_count(),
 
             // Simulation defaults (mirror SimulationConfig::default)
             sim_num_particles: default_sim_num_particles(),
             sim_num_types: default_sim_num_types(),
+            sim_seed: None,
+            sim_type_names: Vec::new(),
 
             // Generator defaults
             gen_rule: default_gen_rule(),
             gen_palette: default_gen_palette(),
             gen_pattern: default_gen_pattern(),
+            gen_spawn_velocity: SpawnVelocity::Zero,
+            gen_spawn_sampling: SpawnSampling::Uniform,
+            gen_type_weights: Vec::new(),
+            gen_shuffle_particles: false,
+            gen_mutation_sigma: default_gen_mutation_sigma(),
+            gen_palette_jitter: 0.0,
 
             // Rendering defaults (mirror SimulationConfig::default)
+            render_enable_3d: false,
+            render_depth_limit: default_depth_limit(),
             render_particle_size: default_particle_size(),
             render_background_color: default_background_color(),
+            render_background_gradient: None,
             render_glow_enabled: default_glow_enabled(),
             render_glow_intensity: default_glow_intensity(),
             render_glow_size: default_glow_size(),
             render_glow_steepness: default_glow_steepness(),
+            render_glow_zoom_compensate: false,
+            render_trails_enabled: false,
+            render_trail_fade: default_trail_fade(),
+            render_vignette: 0.0,
+            render_color_mode: ColorMode::default(),
+            render_particle_shape: ParticleShape::default(),
+            render_blend_mode: BlendMode::default(),
+            render_colormap: default_colormap(),
+            render_color_speed_range: default_color_speed_range(),
+            render_animate_palette: false,
+            render_palette_hue_speed: default_palette_hue_speed(),
+            render_seam_fade: 0.0,
+            render_velocity_streaks: false,
+            render_streak_length: default_streak_length(),
             render_spatial_hash_cell_size: default_spatial_hash_cell_size(),
 
             // Density scaling
             auto_scale_radii: default_auto_scale_radii(),
+            radii_relative_to_world: false,
+            match_world_to_window: false,
+
+            // Stagnation watchdog
+            stagnation_action: StagnationAction::default(),
+            stagnation_speed_threshold: default_stagnation_speed_threshold(),
+            stagnation_seconds: default_stagnation_seconds(),
+
+            // Crash recovery
+            autosave_interval_minutes: default_autosave_interval_minutes(),
+            autosave_particles: false,
+            restore_preset_camera: default_restore_preset_camera(),
+
+            // GPU precision
+            force_f32: false,
+            spatial_workgroup_size: default_spatial_workgroup_size(),
+            adaptive_quality: false,
         }
     }
 }
 
+fn default_msaa_samples() -> u32 {
+    1
+}
+
 fn default_sim_num_particles() -> u32 {
     SimulationConfig::default().num_particles
 }
@@ -172,6 +401,14 @@ fn default_gen_pattern() -> PositionPattern {
     PositionPattern::Disk
 }
 
+fn default_gen_mutation_sigma() -> f32 {
+    0.1
+}
+
+fn default_depth_limit() -> f32 {
+    SimulationConfig::default().depth_limit
+}
+
 fn default_particle_size() -> f32 {
     SimulationConfig::default().particle_size
 }
@@ -196,6 +433,26 @@ fn default_glow_steepness() -> f32 {
     SimulationConfig::default().glow_steepness
 }
 
+fn default_trail_fade() -> f32 {
+    SimulationConfig::default().trail_fade
+}
+
+fn default_colormap() -> PaletteType {
+    SimulationConfig::default().colormap
+}
+
+fn default_color_speed_range() -> f32 {
+    SimulationConfig::default().color_speed_range
+}
+
+fn default_palette_hue_speed() -> f32 {
+    SimulationConfig::default().palette_hue_speed
+}
+
+fn default_streak_length() -> f32 {
+    SimulationConfig::default().streak_length
+}
+
 fn default_spatial_hash_cell_size() -> f32 {
     SimulationConfig::default().spatial_hash_cell_size
 }
@@ -204,6 +461,14 @@ fn default_phys_force_factor() -> f32 {
     SimulationConfig::default().force_factor
 }
 
+fn default_phys_matrix_strength() -> f32 {
+    SimulationConfig::default().matrix_strength
+}
+
+fn default_phys_spin_friction() -> f32 {
+    SimulationConfig::default().spin_friction
+}
+
 fn default_phys_friction() -> f32 {
     SimulationConfig::default().friction
 }
@@ -212,10 +477,18 @@ fn default_phys_repel_strength() -> f32 {
     SimulationConfig::default().repel_strength
 }
 
+fn default_phys_repel_radius() -> f32 {
+    SimulationConfig::default().repel_radius
+}
+
 fn default_phys_max_velocity() -> f32 {
     500.0
 }
 
+fn default_phys_substeps() -> u32 {
+    SimulationConfig::default().substeps
+}
+
 fn default_phys_boundary_mode() -> BoundaryMode {
     SimulationConfig::default().boundary_mode
 }
@@ -232,6 +505,26 @@ fn default_auto_scale_radii() -> bool {
     true
 }
 
+fn default_restore_preset_camera() -> bool {
+    true
+}
+
+fn default_spatial_workgroup_size() -> u32 {
+    crate::renderer::gpu::DEFAULT_SPATIAL_WORKGROUP_SIZE
+}
+
+fn default_stagnation_speed_threshold() -> f32 {
+    0.5
+}
+
+fn default_stagnation_seconds() -> f32 {
+    10.0
+}
+
+fn default_autosave_interval_minutes() -> u32 {
+    5
+}
+
 impl AppConfig {
     /// Get the application's configuration directory.
     pub fn config_dir() -> anyhow::Result<std::path::PathBuf> {
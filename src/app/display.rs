@@ -0,0 +1,115 @@
+//! Monitor selection and exclusive fullscreen.
+
+use winit::{
+    dpi::{PhysicalPosition, PhysicalSize},
+    monitor::{MonitorHandle, VideoModeHandle},
+    window::Fullscreen,
+};
+
+/// Where and how the window is shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WindowOptions {
+    /// Monitor index in enumeration order (None = primary).
+    pub monitor: Option<usize>,
+    /// Exclusive fullscreen on the selected monitor.
+    pub fullscreen: bool,
+}
+
+/// Validate a requested monitor index against `available` monitors.
+///
+/// Returns `None` (use the primary monitor) when nothing was requested or
+/// the index no longer exists.
+pub fn resolve_monitor_index(requested: Option<usize>, available: usize) -> Option<usize> {
+    match requested {
+        Some(index) if index < available => Some(index),
+        Some(index) => {
+            log::warn!(
+                "Monitor {} not found ({} available); using the primary monitor",
+                index,
+                available
+            );
+            None
+        }
+        None => None,
+    }
+}
+
+/// Pick the monitor for `options` from `monitors`, falling back to `primary`.
+pub fn select_monitor(
+    monitors: &[MonitorHandle],
+    primary: Option<MonitorHandle>,
+    options: &WindowOptions,
+) -> Option<MonitorHandle> {
+    resolve_monitor_index(options.monitor, monitors.len())
+        .map(|index| monitors[index].clone())
+        .or(primary)
+        .or_else(|| monitors.first().cloned())
+}
+
+/// The monitor's native video mode: its current resolution at the highest
+/// refresh rate, or the largest mode if none matches.
+pub fn native_video_mode(monitor: &MonitorHandle) -> Option<VideoModeHandle> {
+    let size = monitor.size();
+    let key = |mode: &VideoModeHandle| {
+        let mode_size = mode.size();
+        (
+            mode_size == size,
+            mode_size.width * mode_size.height,
+            mode.refresh_rate_millihertz(),
+            mode.bit_depth(),
+        )
+    };
+    monitor.video_modes().max_by_key(key)
+}
+
+/// Fullscreen mode for `monitor`: exclusive at the native video mode, or
+/// borderless where the platform reports no video modes.
+pub fn exclusive_fullscreen(monitor: &MonitorHandle) -> Fullscreen {
+    match native_video_mode(monitor) {
+        Some(mode) => Fullscreen::Exclusive(mode),
+        None => Fullscreen::Borderless(Some(monitor.clone())),
+    }
+}
+
+/// Top-left position that centers a window of `window_size` on `monitor`.
+pub fn centered_position(
+    monitor: &MonitorHandle,
+    window_size: PhysicalSize<u32>,
+) -> PhysicalPosition<i32> {
+    let origin = monitor.position();
+    let size = monitor.size();
+    PhysicalPosition::new(
+        origin.x + (size.width as i32 - window_size.width as i32).max(0) / 2,
+        origin.y + (size.height as i32 - window_size.height as i32).max(0) / 2,
+    )
+}
+
+/// Human-readable monitor label for selectors and logs.
+pub fn monitor_label(index: usize, monitor: &MonitorHandle) -> String {
+    let size = monitor.size();
+    let refresh = monitor
+        .refresh_rate_millihertz()
+        .map(|mhz| format!(" @ {:.0} Hz", mhz as f32 / 1000.0))
+        .unwrap_or_default();
+    format!(
+        "{}: {} ({}x{}{})",
+        index,
+        monitor.name().unwrap_or_else(|| "Unknown".to_string()),
+        size.width,
+        size.height,
+        refresh
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_monitor_index() {
+        assert_eq!(resolve_monitor_index(None, 2), None);
+        assert_eq!(resolve_monitor_index(Some(1), 2), Some(1));
+        // Missing monitor falls back to primary
+        assert_eq!(resolve_monitor_index(Some(2), 2), None);
+    }
+}
@@ -0,0 +1,41 @@
+//! Frame-rate cap for running without vsync.
+//!
+//! With vsync off nothing throttles the redraw loop, so the GPU renders as
+//! fast as it can. The cap sleeps before each frame until the frame time
+//! since the previous update has reached `1 / fps_cap`. Vsync already paces
+//! presentation, so with vsync on the cap is ignored.
+
+use std::time::Duration;
+
+/// Lowest accepted cap; smaller values are raised to it.
+pub const MIN_FPS_CAP: u32 = 10;
+
+/// How long to sleep before the next frame, given the time `elapsed` since
+/// the previous update. `None` when no wait is needed.
+pub fn frame_cap_wait(fps_cap: Option<u32>, vsync: bool, elapsed: Duration) -> Option<Duration> {
+    let cap = fps_cap.filter(|_| !vsync)?.max(MIN_FPS_CAP);
+    let frame_time = Duration::from_secs_f64(1.0 / f64::from(cap));
+    frame_time
+        .checked_sub(elapsed)
+        .filter(|wait| !wait.is_zero())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_cap_wait() {
+        let elapsed = Duration::from_millis(5);
+        let wait = frame_cap_wait(Some(50), false, elapsed).unwrap();
+        assert_eq!(wait, Duration::from_millis(15));
+
+        // Slow frames, no cap and vsync never wait
+        assert_eq!(
+            frame_cap_wait(Some(50), false, Duration::from_millis(30)),
+            None
+        );
+        assert_eq!(frame_cap_wait(None, false, elapsed), None);
+        assert_eq!(frame_cap_wait(Some(50), true, elapsed), None);
+    }
+}
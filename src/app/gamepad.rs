@@ -0,0 +1,280 @@
+//! Optional gamepad controls for kiosk and exhibit setups.
+//!
+//! The left stick pans the camera and the right stick zooms it; the triggers
+//! nudge the force factor and the bumpers the friction; face buttons
+//! regenerate particles, randomize rules or reset the view. Controller
+//! polling uses `gilrs` and is only built with the `gamepad` feature; the
+//! mapping in [`GamepadAxes`] is plain math over the same [`CameraState`]
+//! and config values the keyboard and mouse drive, so both work together.
+//!
+//! When no controller backend is available [`GamepadPoller::new`] returns
+//! `None` and gamepad input is off. Without the feature the app never polls
+//! (`AppHandler::poll_gamepad` compiles to a no-op).
+
+use glam::Vec2;
+
+use super::CameraState;
+
+/// Stick and trigger values below this are treated as rest.
+pub const GAMEPAD_DEADZONE: f32 = 0.15;
+
+/// Pan speed at full stick, in visible screens per second.
+const PAN_SPEED: f32 = 0.75;
+
+/// Zoom factor per second at full stick.
+const ZOOM_SPEED: f32 = 2.0;
+
+/// Force factor change per second at full trigger.
+const FORCE_FACTOR_RATE: f32 = 1.0;
+
+/// Friction change per second while a bumper is held.
+const FRICTION_RATE: f32 = 0.25;
+
+/// Force factor range (matches the Physics slider).
+const FORCE_FACTOR_RANGE: (f32, f32) = (0.1, 5.0);
+
+/// Friction range (matches the Physics slider).
+const FRICTION_RANGE: (f32, f32) = (0.0, 1.0);
+
+/// One-shot actions bound to gamepad buttons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadAction {
+    /// Respawn particles (South / A, like R).
+    Regenerate,
+    /// Generate new rules (West / X, like M).
+    RandomizeRules,
+    /// Return to the default view (North / Y, like 0).
+    ResetView,
+    /// Pause or resume (Start, like Space).
+    TogglePause,
+}
+
+/// Continuous controller state read each frame.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct GamepadAxes {
+    /// Left stick, -1..1 per axis with up positive.
+    pub left_stick: Vec2,
+    /// Right stick, -1..1 per axis with up positive.
+    pub right_stick: Vec2,
+    /// Left analog trigger, 0..1.
+    pub left_trigger: f32,
+    /// Right analog trigger, 0..1.
+    pub right_trigger: f32,
+    /// Left bumper held.
+    pub left_bumper: bool,
+    /// Right bumper held.
+    pub right_bumper: bool,
+}
+
+/// Zero `value` inside the deadzone and rescale the rest to keep the full
+/// range, so movement starts smoothly at the deadzone edge.
+pub fn apply_deadzone(value: f32) -> f32 {
+    let magnitude = value.abs();
+    if !magnitude.is_finite() || magnitude <= GAMEPAD_DEADZONE {
+        return 0.0;
+    }
+    value.signum() * ((magnitude - GAMEPAD_DEADZONE) / (1.0 - GAMEPAD_DEADZONE)).min(1.0)
+}
+
+impl GamepadAxes {
+    /// Copy with the deadzone applied to every analog input.
+    pub fn filtered(&self) -> Self {
+        Self {
+            left_stick: self.left_stick.map(apply_deadzone),
+            right_stick: self.right_stick.map(apply_deadzone),
+            left_trigger: apply_deadzone(self.left_trigger),
+            right_trigger: apply_deadzone(self.right_trigger),
+            ..*self
+        }
+    }
+
+    /// Pan and zoom `camera` for `dt` seconds of stick input. Returns
+    /// whether the camera changed.
+    ///
+    /// Pan speed is relative to the visible area, so it feels the same at
+    /// any zoom. Expects [`Self::filtered`] values.
+    pub fn move_camera(&self, camera: &mut CameraState, world_size: Vec2, dt: f32) -> bool {
+        let mut changed = false;
+        if self.left_stick != Vec2::ZERO {
+            // Stick up moves the view up; world Y grows downward on screen
+            let direction = Vec2::new(self.left_stick.x, -self.left_stick.y);
+            camera.pan(direction * world_size * PAN_SPEED * dt / camera.zoom);
+            changed = true;
+        }
+        if self.right_stick.y != 0.0 {
+            camera.zoom_center(ZOOM_SPEED.powf(self.right_stick.y * dt));
+            changed = true;
+        }
+        changed
+    }
+
+    /// Nudge `force_factor` (triggers) and `friction` (bumpers) for `dt`
+    /// seconds of input, within their slider ranges. Returns whether either
+    /// value changed.
+    ///
+    /// Expects [`Self::filtered`] values.
+    pub fn nudge_physics(&self, force_factor: &mut f32, friction: &mut f32, dt: f32) -> bool {
+        let before = (*force_factor, *friction);
+        let force_delta = (self.right_trigger - self.left_trigger) * FORCE_FACTOR_RATE * dt;
+        if force_delta != 0.0 {
+            *force_factor =
+                (*force_factor + force_delta).clamp(FORCE_FACTOR_RANGE.0, FORCE_FACTOR_RANGE.1);
+        }
+        let friction_dir = match (self.left_bumper, self.right_bumper) {
+            (true, false) => -1.0,
+            (false, true) => 1.0,
+            _ => 0.0,
+        };
+        if friction_dir != 0.0 {
+            *friction = (*friction + friction_dir * FRICTION_RATE * dt)
+                .clamp(FRICTION_RANGE.0, FRICTION_RANGE.1);
+        }
+        before != (*force_factor, *friction)
+    }
+}
+
+/// Reads the first connected controller through `gilrs`.
+#[cfg(feature = "gamepad")]
+pub struct GamepadPoller {
+    /// Controller backend.
+    gilrs: gilrs::Gilrs,
+    /// Controller currently in use; the most recently active one.
+    active: Option<gilrs::GamepadId>,
+}
+
+#[cfg(feature = "gamepad")]
+impl GamepadPoller {
+    /// Open the controller backend, or `None` (logged) when the platform
+    /// has none. Controllers may be connected later.
+    pub fn new() -> Option<Self> {
+        match gilrs::Gilrs::new() {
+            Ok(gilrs) => {
+                let active = gilrs.gamepads().next().map(|(id, gamepad)| {
+                    log::info!("Gamepad connected: {}", gamepad.name());
+                    id
+                });
+                Some(Self { gilrs, active })
+            }
+            Err(e) => {
+                log::warn!("Gamepad input disabled: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Drain pending controller events into `actions` and return the
+    /// current (unfiltered) axes; all zero when no controller is connected.
+    pub fn poll(&mut self, actions: &mut Vec<GamepadAction>) -> GamepadAxes {
+        use gilrs::{Button, EventType};
+
+        while let Some(event) = self.gilrs.next_event() {
+            match event.event {
+                EventType::Connected => {
+                    log::info!("Gamepad connected: {}", self.gilrs.gamepad(event.id).name());
+                    if self.active.is_none() {
+                        self.active = Some(event.id);
+                    }
+                }
+                EventType::Disconnected => {
+                    log::info!("Gamepad disconnected");
+                    if self.active == Some(event.id) {
+                        self.active = self.gilrs.gamepads().next().map(|(id, _)| id);
+                    }
+                }
+                EventType::ButtonPressed(button, _) => {
+                    self.active = Some(event.id);
+                    let action = match button {
+                        Button::South => GamepadAction::Regenerate,
+                        Button::West => GamepadAction::RandomizeRules,
+                        Button::North => GamepadAction::ResetView,
+                        Button::Start => GamepadAction::TogglePause,
+                        _ => continue,
+                    };
+                    actions.push(action);
+                }
+                _ => {}
+            }
+        }
+
+        let Some(gamepad) = self.active.and_then(|id| self.gilrs.connected_gamepad(id)) else {
+            return GamepadAxes::default();
+        };
+        let trigger = |button| gamepad.button_data(button).map_or(0.0, |d| d.value());
+        GamepadAxes {
+            left_stick: Vec2::new(
+                gamepad.value(gilrs::Axis::LeftStickX),
+                gamepad.value(gilrs::Axis::LeftStickY),
+            ),
+            right_stick: Vec2::new(
+                gamepad.value(gilrs::Axis::RightStickX),
+                gamepad.value(gilrs::Axis::RightStickY),
+            ),
+            left_trigger: trigger(Button::LeftTrigger2),
+            right_trigger: trigger(Button::RightTrigger2),
+            left_bumper: gamepad.is_pressed(Button::LeftTrigger),
+            right_bumper: gamepad.is_pressed(Button::RightTrigger),
+        }
+    }
+}
+
+/// Stand-in when built without the `gamepad` feature; never constructed.
+#[cfg(not(feature = "gamepad"))]
+pub struct GamepadPoller;
+
+#[cfg(not(feature = "gamepad"))]
+impl GamepadPoller {
+    /// Gamepad support is not compiled in.
+    pub fn new() -> Option<Self> {
+        None
+    }
+
+    /// Never called; there is no poller without the feature.
+    pub fn poll(&mut self, _actions: &mut Vec<GamepadAction>) -> GamepadAxes {
+        GamepadAxes::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gamepad_axes_drive_camera_and_physics() {
+        assert_eq!(apply_deadzone(0.1), 0.0);
+        assert_eq!(apply_deadzone(-1.0), -1.0);
+        assert!((apply_deadzone(0.575) - 0.5).abs() < 1e-6);
+
+        // Drift inside the deadzone leaves everything alone
+        let world = Vec2::new(1000.0, 500.0);
+        let mut camera = CameraState::default();
+        let (mut force, mut friction) = (1.0, 0.5);
+        let drift = GamepadAxes {
+            left_stick: Vec2::new(0.1, -0.1),
+            right_trigger: 0.05,
+            ..GamepadAxes::default()
+        }
+        .filtered();
+        assert!(!drift.move_camera(&mut camera, world, 1.0));
+        assert!(!drift.nudge_physics(&mut force, &mut friction, 1.0));
+
+        // Full right + up pans right and up, scaled by zoom; stick up zooms in
+        camera.zoom = 2.0;
+        let axes = GamepadAxes {
+            left_stick: Vec2::new(1.0, 1.0),
+            right_stick: Vec2::new(0.0, 1.0),
+            right_trigger: 1.0,
+            left_bumper: true,
+            ..GamepadAxes::default()
+        }
+        .filtered();
+        assert!(axes.move_camera(&mut camera, world, 0.5));
+        assert!((camera.offset - Vec2::new(187.5, -93.75)).length() < 1e-3);
+        assert!(camera.zoom > 2.0);
+
+        // Triggers and bumpers stay within the slider ranges
+        force = 4.9;
+        friction = 0.01;
+        assert!(axes.nudge_physics(&mut force, &mut friction, 1.0));
+        assert_eq!((force, friction), (5.0, 0.0));
+    }
+}
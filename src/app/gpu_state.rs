@@ -3,24 +3,66 @@
 use bytemuck::cast_slice;
 
 use crate::renderer::gpu::{
-    BrushPipelines, ComputePipelines, GpuContext, RenderPipelines, SimulationBuffers,
-    SpatialHashBuffers, SpatialHashPipelines,
+    BackgroundPipeline, BrushPipelines, ComputePipelines, DepthTarget, GpuContext, MsaaTarget,
+    PickPipeline, PrefixScanPass, RenderBuffers, RenderPipelines, SimulationBuffers,
+    SpatialHashBuffers, SpatialHashPipelines, TrailPipelines, VignettePipeline, prefix_scan_passes,
+    supported_sample_count,
 };
 
-// Maximum prefix-sum passes the spatial hash can issue (matches buffer allocation).
-pub(crate) const MAX_PREFIX_PASSES: u32 = 32;
+// Maximum prefix-sum passes the spatial hash can issue: 2048^3 exceeds any u32
+// bin count, so at most 3 scan levels plus 2 offset passes.
+pub(crate) const MAX_PREFIX_PASSES: u32 = 5;
 // Clear + count + prefix passes + clear-sort + sort + forces + advance (each with start/end).
 pub(crate) const MAX_TIMESTAMP_QUERIES: u32 = (MAX_PREFIX_PASSES + 6) * 2;
 
+/// Debug toggles for individual compute and render passes.
+///
+/// Used to bisect GPU cost alongside the per-pass timings. Everything is
+/// enabled by default. Disabling binning or the sort leaves stale bin data,
+/// so neighbor lookups (and the simulation) become incorrect; that is expected.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PassToggles {
+    /// Bin clear, count and prefix-sum passes.
+    pub(crate) binning: bool,
+    /// Pre-sort clear and bin sort passes.
+    pub(crate) sort: bool,
+    /// Force computation pass.
+    pub(crate) forces: bool,
+    /// Advance (integration) pass.
+    pub(crate) advance: bool,
+    /// Master switch for all particle rendering (off = compute only).
+    pub(crate) render: bool,
+    /// Glow render pass.
+    pub(crate) glow: bool,
+    /// Solid particle render pass.
+    pub(crate) particles: bool,
+    /// Brush circle overlay pass.
+    pub(crate) brush_circle: bool,
+}
+
+impl Default for PassToggles {
+    fn default() -> Self {
+        Self {
+            binning: true,
+            sort: true,
+            forces: true,
+            advance: true,
+            render: true,
+            glow: true,
+            particles: true,
+            brush_circle: true,
+        }
+    }
+}
+
 /// Cached bind groups for the spatial hash compute passes.
 ///
-/// These groups are rebuilt when buffer handles change or the number of
-/// prefix-sum passes changes (driven by the current spatial grid size).
+/// These groups are rebuilt when buffer handles change. The prefix sum runs
+/// in place, so bin offsets always end up in buffer A and buffer B holds the
+/// sort counters.
 pub(crate) struct SpatialBindGroupCache {
-    /// Number of prefix-sum passes the cache was built for.
-    pub(crate) pass_count: u32,
-    /// True when the final prefix-sum result lives in buffer A.
-    pub(crate) offsets_in_a: bool,
+    /// Prefix-sum dispatches for the current spatial buffers.
+    pub(crate) prefix_passes: Vec<PrefixScanPass>,
     clear_a: Option<wgpu::BindGroup>,
     clear_b: Option<wgpu::BindGroup>,
     prefix: Vec<wgpu::BindGroup>,
@@ -35,8 +77,7 @@ pub(crate) struct SpatialBindGroupCache {
 impl SpatialBindGroupCache {
     pub(crate) fn new() -> Self {
         Self {
-            pass_count: 0,
-            offsets_in_a: true,
+            prefix_passes: Vec::new(),
             clear_a: None,
             clear_b: None,
             prefix: Vec::new(),
@@ -51,7 +92,7 @@ impl SpatialBindGroupCache {
 
     /// Drop cached bind groups so they will be rebuilt on next use.
     pub(crate) fn invalidate(&mut self) {
-        self.pass_count = 0;
+        self.prefix_passes.clear();
         self.prefix.clear();
         self.clear_a = None;
         self.clear_b = None;
@@ -68,11 +109,9 @@ impl SpatialBindGroupCache {
         &mut self,
         device: &wgpu::Device,
         sim_buffers: &SimulationBuffers,
-        spatial_buffers: &mut SpatialHashBuffers,
+        spatial_buffers: &SpatialHashBuffers,
         spatial_pipelines: &SpatialHashPipelines,
     ) {
-        let pass_count = spatial_buffers.prefix_sum_passes();
-
         let needs_rebuild = self.clear_a.is_none()
             || self.clear_b.is_none()
             || self.count_from_a.is_none()
@@ -81,16 +120,13 @@ impl SpatialBindGroupCache {
             || self.sort_from_b.is_none()
             || self.forces_into_a.is_none()
             || self.forces_into_b.is_none()
-            || self.pass_count != pass_count
-            || self.prefix.len() as u32 != pass_count;
+            || self.prefix.is_empty();
 
         if !needs_rebuild {
             return;
         }
 
-        self.pass_count = pass_count;
-        self.offsets_in_a = pass_count.is_multiple_of(2);
-        spatial_buffers.current_offset_buffer = if self.offsets_in_a { 0 } else { 1 };
+        self.prefix_passes = prefix_scan_passes(&spatial_buffers.prefix_scan_levels());
 
         self.clear_a =
             Some(spatial_pipelines.create_clear_bind_group(device, spatial_buffers, true));
@@ -109,27 +145,16 @@ impl SpatialBindGroupCache {
             spatial_buffers,
         ));
 
-        // Prefix-sum bind groups alternate between A and B each pass.
-        self.prefix.clear();
-        let mut src_is_a = true;
-        for pass_idx in 0..pass_count {
-            let (source, dest) = if src_is_a {
-                (&spatial_buffers.bin_counts_a, &spatial_buffers.bin_counts_b)
-            } else {
-                (&spatial_buffers.bin_counts_b, &spatial_buffers.bin_counts_a)
-            };
-
-            let step_size = &spatial_buffers.step_size_uniforms[pass_idx as usize];
-            self.prefix.push(
-                spatial_pipelines.create_prefix_sum_bind_group(device, source, dest, step_size),
-            );
-
-            src_is_a = !src_is_a;
-        }
+        // One prefix-sum bind group per scan level, scanning bin_counts_a in place.
+        self.prefix = spatial_pipelines.create_prefix_sum_bind_groups(
+            device,
+            &spatial_buffers.bin_counts_a,
+            &spatial_buffers.scan_block_sums,
+        );
 
-        // Offset buffer is where the prefix sum finished; count buffer is the other one.
-        let offset_in_a = self.offsets_in_a;
-        let count_in_a = !offset_in_a;
+        // Offsets are in A after the scan; sort counters use B.
+        let offset_in_a = true;
+        let count_in_a = false;
 
         // Sort: Current (Source) -> Next (Dest)
         // sort_from_a: 0 -> 1
@@ -139,6 +164,8 @@ impl SpatialBindGroupCache {
             &sim_buffers.pos_type[1],   // Pos Out
             &sim_buffers.velocities[0], // Vel In
             &sim_buffers.velocities[1], // Vel Out
+            &sim_buffers.depths[0],     // Depth In
+            &sim_buffers.depths[1],     // Depth Out
             spatial_buffers,
             offset_in_a,
             count_in_a,
@@ -150,6 +177,8 @@ impl SpatialBindGroupCache {
             &sim_buffers.pos_type[0],   // Pos Out
             &sim_buffers.velocities[1], // Vel In
             &sim_buffers.velocities[0], // Vel Out
+            &sim_buffers.depths[1],     // Depth In
+            &sim_buffers.depths[0],     // Depth Out
             spatial_buffers,
             offset_in_a,
             count_in_a,
@@ -251,12 +280,26 @@ pub(crate) struct GpuState {
     pub(crate) timestamps_supported: bool,
     /// Brush pipelines.
     pub(crate) brush_pipelines: BrushPipelines,
+    /// Particle pick pipeline.
+    pub(crate) pick: PickPipeline,
+    /// Trail fade/blit pipelines and persistent trail texture.
+    pub(crate) trail: TrailPipelines,
+    /// Gradient background pipeline.
+    pub(crate) background: BackgroundPipeline,
+    /// Vignette post pass pipeline.
+    pub(crate) vignette: VignettePipeline,
+    /// Multisampled scene target (unused while MSAA is off).
+    pub(crate) msaa: MsaaTarget,
+    /// Particle depth target (only allocated in 3D mode).
+    pub(crate) depth: DepthTarget,
+    /// Render-only buffers (fullscreen quad).
+    pub(crate) render_buffers: RenderBuffers,
     /// Brush force bind group (for future brush circle rendering).
     pub(crate) _brush_bind_group: wgpu::BindGroup,
     /// Render bind group.
     pub(crate) render_bind_group: wgpu::BindGroup,
-    /// Glow render bind group.
-    pub(crate) glow_bind_group: wgpu::BindGroup,
+    /// Glow render bind group; only kept while glow is enabled.
+    pub(crate) glow_bind_group: Option<wgpu::BindGroup>,
     /// Mirror wrap render bind group.
     pub(crate) mirror_bind_group: wgpu::BindGroup,
     /// Infinite wrap render bind group.
@@ -269,7 +312,68 @@ pub(crate) struct GpuState {
     pub(crate) egui_renderer: egui_wgpu::Renderer,
 }
 
+/// Estimated GPU memory usage broken down by category, in bytes.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct VramUsage {
+    /// Particle position/velocity ping-pong buffers.
+    pub(crate) particles: u64,
+    /// Spatial hash bin count/offset buffers.
+    pub(crate) spatial_bins: u64,
+    /// Interaction and radius matrices.
+    pub(crate) matrices: u64,
+    /// Uniforms, colors and timestamp readback.
+    pub(crate) other: u64,
+}
+
+impl VramUsage {
+    /// Total estimated bytes across all categories.
+    pub(crate) fn total(&self) -> u64 {
+        self.particles + self.spatial_bins + self.matrices + self.other
+    }
+}
+
 impl GpuState {
+    /// Estimate GPU buffer memory from the known allocation sizes.
+    ///
+    /// Textures (surface, egui) and driver overhead are not included.
+    pub(crate) fn vram_usage(&self) -> VramUsage {
+        let particles = self.buffers.particle_bytes();
+        let matrices = self.buffers.matrix_bytes();
+        let spatial_bins = self.spatial_buffers.bin_bytes();
+        let other = (self.buffers.estimated_bytes() - particles - matrices)
+            + (self.spatial_buffers.estimated_bytes() - spatial_bins)
+            + self.render.estimated_bytes()
+            + self.brush_pipelines.estimated_bytes()
+            + self.pick.estimated_bytes()
+            + self.trail.estimated_bytes()
+            + self.background.estimated_bytes()
+            + self.vignette.estimated_bytes()
+            + self.render_buffers.fullscreen_quad.size()
+            + self
+                .timestamp_resolve_buffer
+                .as_ref()
+                .map_or(0, wgpu::Buffer::size);
+        VramUsage {
+            particles,
+            spatial_bins,
+            matrices,
+            other,
+        }
+    }
+
+    /// Switch the scene passes to `requested` MSAA samples, falling back to
+    /// 1 when the device does not support it, and rebuild their pipelines.
+    pub(crate) fn set_msaa_samples(&mut self, requested: u32) {
+        let format = self.context.surface_format();
+        let sample_count = supported_sample_count(&self.context.adapter, format, requested);
+        let device = &self.context.device;
+        self.render.set_sample_count(device, sample_count);
+        self.trail.set_sample_count(device, sample_count);
+        self.background.set_sample_count(device, sample_count);
+        self.msaa = MsaaTarget::new(format, requested, sample_count);
+        log::info!("MSAA set to {}x", sample_count);
+    }
+
     /// Read back resolved timestamp queries and compute per-pass durations.
     pub(crate) fn fetch_gpu_timings(&mut self) {
         if self.timestamp_last_count < 2 {
@@ -0,0 +1,88 @@
+//! Periodic crash-recovery autosave and restore.
+
+use std::time::Instant;
+
+use super::AppHandler;
+use crate::app::{Preset, RecoveryState};
+
+impl AppHandler {
+    /// Autosave the scene if the configured interval has elapsed.
+    pub(crate) fn maybe_autosave(&mut self, now: Instant) {
+        let interval_minutes = self.app.config.autosave_interval_minutes;
+        // Don't overwrite an unanswered recovery file from the previous session
+        if interval_minutes == 0 || self.pending_recovery.is_some() {
+            return;
+        }
+        if now.duration_since(self.last_autosave).as_secs() >= u64::from(interval_minutes) * 60 {
+            self.autosave_scene();
+            self.last_autosave = now;
+        }
+    }
+
+    /// Write the current config, matrices and generator selections to the
+    /// recovery slot. Particle positions are only included when enabled,
+    /// since they require a blocking GPU readback.
+    pub(crate) fn autosave_scene(&mut self) {
+        if self.app.config.autosave_particles {
+            self.sync_particles_from_gpu();
+        }
+
+        let mut preset = Preset::new(
+            "recovery",
+            &self.app.sim_config,
+            &self.app.interaction_matrix,
+            &self.app.radius_matrix,
+            self.app.current_rule,
+            self.app.current_palette,
+            self.app.current_pattern,
+        );
+        preset.camera = Some(self.camera);
+        let particles = self
+            .app
+            .config
+            .autosave_particles
+            .then_some(self.app.particles.as_slice());
+
+        match RecoveryState::new(preset, particles).save() {
+            Ok(()) => log::info!(
+                "Autosaved scene to {}",
+                RecoveryState::recovery_path().display()
+            ),
+            Err(e) => log::error!("Failed to autosave scene: {}", e),
+        }
+    }
+
+    /// Restore the scene left behind by an unclean exit.
+    pub(crate) fn restore_recovery(&mut self) {
+        let Some(state) = self.pending_recovery.take() else {
+            return;
+        };
+
+        let particles = state.to_particles();
+        self.apply_preset(state.preset);
+
+        if let Some(particles) = particles
+            && !particles.is_empty()
+        {
+            self.app.sim_config.num_particles = particles.len() as u32;
+            self.app.config.sim_num_particles = self.app.sim_config.num_particles;
+            self.app.particles = particles;
+            // Snapshots don't keep depths; spread them afresh in 3D mode
+            self.app.assign_depths(0);
+            self.app.physics.resize(self.app.particles.len());
+            self.app.sim_frame = 0;
+            self.sync_buffers();
+        }
+
+        self.preset_status = format!("Recovered scene from {}", state.saved_at);
+        log::info!("Recovered scene autosaved at {}", state.saved_at);
+    }
+
+    /// Discard the recovery file from the previous session.
+    pub(crate) fn discard_recovery(&mut self) {
+        self.pending_recovery = None;
+        if let Err(e) = RecoveryState::clear() {
+            log::error!("Failed to remove recovery file: {}", e);
+        }
+    }
+}
@@ -4,12 +4,29 @@ use rand::Rng;
 
 use super::AppHandler;
 use crate::app::BrushTool;
-use crate::simulation::{BoundaryMode, Particle};
+use crate::simulation::{Particle, uses_wrapped_distance};
 
 impl AppHandler {
     /// Draw particles at the brush position.
     /// Adds new particles within the brush radius with random offset.
     pub(crate) fn draw_particles(&mut self) {
+        let count = self.brush.draw_intensity as usize;
+        self.spawn_brush_particles(count, self.brush.radius);
+    }
+
+    /// Emit particles at the cursor at the Emitter's rate, up to its cap.
+    pub(crate) fn emit_particles(&mut self, dt: f32) {
+        let count = self.brush.take_emit_count(dt, self.app.particles.len());
+        if count > 0 {
+            // Small jitter so emitted particles don't start exactly on top of each other
+            let jitter = self.app.sim_config.particle_size.max(1.0);
+            self.spawn_brush_particles(count, jitter);
+        }
+    }
+
+    /// Spawn `spawn_count` particles of the brush draw type within `spread`
+    /// of the brush position.
+    fn spawn_brush_particles(&mut self, spawn_count: usize, spread: f32) {
         // Sync with GPU first to get current positions
         self.sync_particles_from_gpu();
 
@@ -18,26 +35,38 @@ impl AppHandler {
         let world_width = self.app.sim_config.world_size.x;
         let world_height = self.app.sim_config.world_size.y;
 
-        // Determine how many particles to spawn this frame
-        let spawn_count = self.brush.draw_intensity as usize;
+        // Locked types are never drawn
+        let unlocked: Vec<u32> = (0..num_types)
+            .filter(|&t| !self.brush.is_locked(t))
+            .collect();
+        if unlocked.is_empty() {
+            return;
+        }
+        if self.brush.draw_type >= 0 && self.brush.is_locked(self.brush.draw_type as u32) {
+            return;
+        }
 
         for _ in 0..spawn_count {
             // Random position within brush radius
             let angle = rng.random::<f32>() * std::f32::consts::TAU;
-            let radius = rng.random::<f32>().sqrt() * self.brush.radius;
+            let radius = rng.random::<f32>().sqrt() * spread;
             let x = self.brush.position.x + angle.cos() * radius;
             let y = self.brush.position.y + angle.sin() * radius;
 
             // Determine particle type
             let particle_type = if self.brush.draw_type < 0 {
-                // Random type
-                rng.random_range(0..num_types)
+                // Random unlocked type
+                unlocked[rng.random_range(0..unlocked.len())]
             } else {
                 (self.brush.draw_type as u32).min(num_types - 1)
             };
 
             // Create new particle
-            let particle = Particle::new(x, y, particle_type);
+            let mut particle = Particle::new(x, y, particle_type);
+            particle.set_static(self.brush.draw_static);
+            if self.app.sim_config.enable_3d {
+                particle.z = rng.random::<f32>() * self.app.sim_config.depth_limit.max(1.0);
+            }
 
             // Add to particles list (will grow buffer on sync)
             self.app.particles.push(particle);
@@ -50,10 +79,7 @@ impl AppHandler {
         self.needs_sync = true;
 
         // Apply boundary wrapping to newly added particles
-        if self.app.sim_config.boundary_mode == BoundaryMode::Wrap
-            || self.app.sim_config.boundary_mode == BoundaryMode::MirrorWrap
-            || self.app.sim_config.boundary_mode == BoundaryMode::InfiniteWrap
-        {
+        if uses_wrapped_distance(&self.app.sim_config) {
             // Calculate skip offset before borrowing
             let skip_offset = self.app.particles.len() - spawn_count;
 
@@ -74,12 +100,10 @@ impl AppHandler {
         let brush_pos = self.brush.position;
         let brush_radius_sq = self.brush.radius * self.brush.radius;
         let target_type = self.brush.target_type;
+        let brush = &self.brush;
         let world_width = self.app.sim_config.world_size.x;
         let world_height = self.app.sim_config.world_size.y;
-        let use_wrap = matches!(
-            self.app.sim_config.boundary_mode,
-            BoundaryMode::Wrap | BoundaryMode::MirrorWrap | BoundaryMode::InfiniteWrap
-        );
+        let use_wrap = uses_wrapped_distance(&self.app.sim_config);
 
         let initial_count = self.app.particles.len();
 
@@ -90,6 +114,11 @@ impl AppHandler {
                 return true; // Keep particle (doesn't match target type)
             }
 
+            // Locked types survive even when erasing all types
+            if brush.is_locked(particle.particle_type) {
+                return true;
+            }
+
             // Calculate distance to brush center
             let mut dx = particle.x - brush_pos.x;
             let mut dy = particle.y - brush_pos.y;
@@ -122,15 +151,31 @@ impl AppHandler {
         }
     }
 
+    /// Release every static particle so it moves freely again.
+    pub(crate) fn release_static_particles(&mut self) {
+        self.sync_particles_from_gpu();
+
+        let mut released = 0;
+        for particle in self.app.particles.iter_mut().filter(|p| p.is_static()) {
+            particle.set_static(false);
+            released += 1;
+        }
+        if released > 0 {
+            self.needs_sync = true;
+        }
+        self.show_hud_message(format!("Released {released} static particles"));
+    }
+
     /// Process brush tools during active use.
     /// Called each frame when brush is active.
-    pub(crate) fn process_brush_tools(&mut self) {
+    pub(crate) fn process_brush_tools(&mut self, dt: f32) {
         if !self.brush.is_active {
             return;
         }
 
         match self.brush.tool {
             BrushTool::Draw => self.draw_particles(),
+            BrushTool::Emitter => self.emit_particles(dt),
             BrushTool::Erase => self.erase_particles(),
             BrushTool::Attract | BrushTool::Repel => {
                 // These are handled by the GPU compute shader
@@ -4,11 +4,11 @@ use super::AppHandler;
 use crate::app::AppConfig;
 use crate::generators::{
     colors::{PaletteType, generate_colors},
-    positions::{PositionPattern, SpawnConfig, generate_positions},
-    rules::{RuleType, generate_rules},
+    positions::{PositionPattern, SpawnSampling, SpawnVelocity},
+    rules::RuleType,
 };
 use crate::renderer::gpu::{SimulationBuffers, SpatialHashBuffers};
-use crate::simulation::{BoundaryMode, RadiusMatrix};
+use crate::simulation::{BoundaryMode, WorldShape, world_circle};
 
 impl AppHandler {
     /// Update camera uniform buffer with current zoom and pan.
@@ -26,6 +26,9 @@ impl AppHandler {
     }
 
     pub(crate) fn sync_buffers(&mut self) {
+        // The type count may have changed; keep per-type overrides in step
+        self.app.sim_config.resize_type_overrides();
+
         if let Some(gpu) = &self.gpu {
             // Recreate buffers with new particle count
             let colors_rgba = self.app.colors_as_rgba();
@@ -47,21 +50,31 @@ impl AppHandler {
             let new_bind_group = gpu.render.create_render_bind_group(
                 &gpu.context.device,
                 new_buffers.current_pos_type(),
+                new_buffers.current_velocities(),
+                new_buffers.current_depths(),
                 &new_buffers,
             );
-            let new_glow_bind_group = gpu.render.create_glow_bind_group(
-                &gpu.context.device,
-                new_buffers.current_pos_type(),
-                &new_buffers,
-            );
+            let new_glow_bind_group = self.app.sim_config.enable_glow.then(|| {
+                gpu.render.create_glow_bind_group(
+                    &gpu.context.device,
+                    new_buffers.current_pos_type(),
+                    new_buffers.current_velocities(),
+                    new_buffers.current_depths(),
+                    &new_buffers,
+                )
+            });
             let new_mirror_bind_group = gpu.render.create_mirror_bind_group(
                 &gpu.context.device,
                 new_buffers.current_pos_type(),
+                new_buffers.current_velocities(),
+                new_buffers.current_depths(),
                 &new_buffers,
             );
             let new_infinite_bind_group = gpu.render.create_infinite_bind_group(
                 &gpu.context.device,
                 new_buffers.current_pos_type(),
+                new_buffers.current_velocities(),
+                new_buffers.current_depths(),
                 &new_buffers,
             );
 
@@ -89,7 +102,7 @@ impl AppHandler {
                 gpu.spatial_bind_groups.ensure(
                     &gpu.context.device,
                     &gpu.buffers,
-                    &mut gpu.spatial_buffers,
+                    &gpu.spatial_buffers,
                     &gpu.spatial_pipelines,
                 );
             }
@@ -110,7 +123,7 @@ impl AppHandler {
             gpu.spatial_bind_groups.ensure(
                 &gpu.context.device,
                 &gpu.buffers,
-                &mut gpu.spatial_buffers,
+                &gpu.spatial_buffers,
                 &gpu.spatial_pipelines,
             );
 
@@ -122,6 +135,52 @@ impl AppHandler {
         }
     }
 
+    /// Grow or shrink the particle count while keeping the current state.
+    ///
+    /// Existing particles are read back from the GPU; shrinking truncates
+    /// and growing appends particles spawned with the current pattern.
+    /// When the new count fits the allocated buffers only the appended
+    /// tail is uploaded, otherwise the buffers are recreated.
+    pub(crate) fn resize_particle_count(&mut self, count: u32) {
+        self.sync_particles_from_gpu();
+        let keep = self.app.particles.len().min(count as usize);
+        self.app.resize_particles(count as usize);
+
+        let in_place = self.gpu.as_mut().is_some_and(|gpu| {
+            gpu.buffers
+                .resize_particles(&gpu.context.queue, &self.app.particles, keep)
+        });
+        if in_place {
+            // Density rebalancing may have changed the radii and cell size
+            self.sync_radius_matrix();
+            self.sync_spatial_buffers();
+        } else {
+            self.sync_buffers();
+        }
+    }
+
+    /// Remove all particles for a blank canvas. The GPU buffers keep their
+    /// allocation, so drawing back up to the old count refills them in place.
+    pub(crate) fn clear_particles(&mut self) {
+        self.app.clear_particles();
+        if let Some(gpu) = &mut self.gpu {
+            gpu.buffers
+                .resize_particles(&gpu.context.queue, &self.app.particles, 0);
+        }
+        self.show_hud_message("Cleared all particles".to_string());
+    }
+
+    /// Spread every particle over a fresh random depth for 3D mode (far to
+    /// near) and upload them, e.g. when 3D is turned on.
+    pub(crate) fn assign_depths(&mut self) {
+        self.sync_particles_from_gpu();
+        self.app.assign_depths(0);
+        if let Some(gpu) = &self.gpu {
+            gpu.buffers
+                .update_particles(&gpu.context.queue, &self.app.particles);
+        }
+    }
+
     /// Read particles back from GPU to CPU to ensure we have the latest state
     /// before modifying them (e.g. for brush tools).
     pub(crate) fn sync_particles_from_gpu(&mut self) {
@@ -139,9 +198,24 @@ impl AppHandler {
         let height = self.app.sim_config.world_size.y;
         let margin = self.app.sim_config.particle_size;
 
+        if self.app.sim_config.world_shape == WorldShape::Circle {
+            // Pull stray particles onto the inside edge of the circle
+            let (center, radius) = world_circle(self.app.sim_config.world_size);
+            let limit = (radius - margin).max(0.0);
+            for particle in &mut self.app.particles {
+                let offset = glam::Vec2::new(particle.x, particle.y) - center;
+                if offset.length() > limit {
+                    let pos = center + offset.normalize_or_zero() * limit;
+                    particle.x = pos.x;
+                    particle.y = pos.y;
+                }
+            }
+            return;
+        }
+
         for particle in &mut self.app.particles {
             match self.app.sim_config.boundary_mode {
-                BoundaryMode::Repel => {
+                BoundaryMode::Repel | BoundaryMode::Sticky => {
                     // Clamp to valid bounds with margin
                     particle.x = particle.x.clamp(margin, width - margin);
                     particle.y = particle.y.clamp(margin, height - margin);
@@ -162,6 +236,13 @@ impl AppHandler {
         }
     }
 
+    pub(crate) fn sync_radius_matrix(&mut self) {
+        if let Some(gpu) = &self.gpu {
+            gpu.buffers
+                .update_radius_matrix(&gpu.context.queue, &self.app.radius_matrix);
+        }
+    }
+
     pub(crate) fn sync_colors(&mut self) {
         if let Some(gpu) = &self.gpu {
             let colors_rgba = self.app.colors_as_rgba();
@@ -169,8 +250,20 @@ impl AppHandler {
         }
     }
 
+    /// Regenerate the colormap used by scalar color modes and upload it.
+    pub(crate) fn sync_colormap(&mut self) {
+        if let Some(gpu) = &self.gpu {
+            let colormap = generate_colors(
+                self.app.sim_config.colormap,
+                crate::renderer::gpu::COLORMAP_SIZE as usize,
+            );
+            gpu.render.update_colormap(&gpu.context.queue, &colormap);
+        }
+    }
+
     /// Resets all application settings and simulation state to their default values.
     pub(crate) fn reset_to_defaults(&mut self) {
+        self.restore_quality();
         // Reset AppConfig to default
         self.app.config = AppConfig::default();
         // Save the default config to overwrite the old one
@@ -190,25 +283,19 @@ impl AppHandler {
         self.ui_rendering_open = self.app.config.ui_rendering_open;
         self.ui_presets_open = self.app.config.ui_presets_open;
         self.ui_keyboard_shortcuts_open = self.app.config.ui_keyboard_shortcuts_open;
+        self.ui_debug_open = self.app.config.ui_debug_open;
 
         // Reset simulation parameters
         let num_types = self.app.sim_config.num_types as usize;
-        self.app.interaction_matrix = generate_rules(RuleType::Random, num_types);
-        self.app.radius_matrix = RadiusMatrix::default_for_size(num_types);
+        self.app.radius_matrix = self.app.default_radius_matrix(num_types);
         self.app.current_rule = RuleType::Random;
         self.app.current_palette = PaletteType::Rainbow;
-        self.app.colors = generate_colors(PaletteType::Rainbow, num_types);
         self.app.current_pattern = PositionPattern::Disk;
+        self.app.current_spawn_velocity = SpawnVelocity::Zero;
+        self.app.current_spawn_sampling = SpawnSampling::Uniform;
 
-        // Regenerate particles with default settings
-        let spawn_config = SpawnConfig {
-            num_particles: self.app.sim_config.num_particles as usize,
-            num_types,
-            width: self.app.sim_config.world_size.x,
-            height: self.app.sim_config.world_size.y,
-        };
-        self.app.particles = generate_positions(self.app.current_pattern, &spawn_config);
-        self.app.physics.resize(self.app.particles.len());
+        // Regenerate rules, colors and particles with default settings
+        self.app.regenerate_from_seed(false);
 
         // Reset camera and brush state
         self.camera = crate::app::CameraState::default();
@@ -219,6 +306,7 @@ impl AppHandler {
         self.update_camera();
         self.sync_interaction_matrix();
         self.sync_colors();
+        self.sync_colormap();
 
         self.preset_status = "All settings reset to defaults.".to_string();
         log::info!("All settings reset to defaults.");
@@ -0,0 +1,176 @@
+//! Live GPU vs CPU divergence diagnostic.
+
+use std::time::Instant;
+
+use super::AppHandler;
+use crate::renderer::gpu::fair_sample_seed;
+use crate::simulation::{Particle, PhysicsEngine, step_parity, trajectory_divergence};
+
+/// Seconds between comparisons (each needs two blocking GPU readbacks and a
+/// full CPU physics step).
+const CPU_COMPARE_INTERVAL_SECS: f32 = 1.0;
+
+/// Minimum radius used to match GPU particles to CPU particles.
+const CPU_COMPARE_MIN_SEARCH: f32 = 8.0;
+
+/// Largest particle count [`AppHandler::verify_step`] runs on; the CPU
+/// reference step is slow and pairing gets ambiguous in dense scenes.
+pub(crate) const PARITY_MAX_PARTICLES: u32 = 4096;
+
+/// Timestep for parity verification.
+const PARITY_DT: f32 = 1.0 / 60.0;
+
+/// Allowed velocity error as a fraction of `max_velocity` with f32 storage
+/// (force summation order and fused multiply-adds differ from the CPU).
+const PARITY_F32_TOLERANCE: f32 = 1e-4;
+
+/// Allowed velocity error as a fraction of `max_velocity` with f16 storage.
+/// Half precision keeps 11 significant bits, so storing a velocity alone
+/// rounds it by up to 2^-11; this allows two such roundings.
+const PARITY_F16_TOLERANCE: f32 = 1.0 / 1024.0;
+
+/// Allowed position error on top of the velocity tolerance times `dt`.
+const PARITY_POSITION_SLACK: f32 = 1e-3;
+
+impl AppHandler {
+    /// Read the pre-step GPU state if a comparison is due this frame.
+    pub(crate) fn begin_cpu_compare(&mut self, now: Instant) -> Option<Vec<Particle>> {
+        if !self.cpu_compare
+            || now.duration_since(self.last_cpu_compare).as_secs_f32() < CPU_COMPARE_INTERVAL_SECS
+        {
+            return None;
+        }
+        self.last_cpu_compare = now;
+
+        let gpu = self.gpu.as_ref()?;
+        Some(
+            gpu.buffers
+                .read_particles(&gpu.context.device, &gpu.context.queue),
+        )
+    }
+
+    /// Step `before` on the CPU `steps` times and compare it with the GPU
+    /// result of the same steps.
+    pub(crate) fn finish_cpu_compare(
+        &mut self,
+        before: Option<Vec<Particle>>,
+        dt: f32,
+        steps: u32,
+    ) {
+        let Some(mut reference) = before else {
+            return;
+        };
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+        let observed = gpu
+            .buffers
+            .read_particles(&gpu.context.device, &gpu.context.queue);
+
+        let config = &self.app.sim_config;
+        let mut engine = PhysicsEngine::new(reference.len());
+        for _ in 0..steps {
+            engine.step(
+                &mut reference,
+                &self.app.interaction_matrix,
+                &self.app.radius_matrix,
+                config,
+                dt,
+            );
+        }
+
+        let search_radius =
+            (config.max_velocity * dt * steps as f32 * 2.0).max(CPU_COMPARE_MIN_SEARCH);
+        let divergence = trajectory_divergence(&reference, &observed, config, search_radius);
+        log::debug!(
+            "GPU vs CPU divergence: max {:.4}, mean {:.4}, unmatched {}",
+            divergence.max,
+            divergence.mean,
+            divergence.unmatched
+        );
+        self.cpu_compare_result = Some(divergence);
+    }
+
+    /// Run one GPU step and one CPU step from the same state and log the
+    /// largest per-particle position and velocity discrepancy.
+    ///
+    /// Returns the worse of the two discrepancies divided by its tolerance,
+    /// so values above 1.0 indicate a shader regression (infinite if a GPU
+    /// particle has no CPU counterpart). The tolerance is wider with f16
+    /// velocity storage. Only runs up to [`PARITY_MAX_PARTICLES`]; the step
+    /// advances the simulation like a single step would.
+    pub(crate) fn verify_step(&mut self) -> Option<f32> {
+        let gpu = self.gpu.as_ref()?;
+        if gpu.buffers.num_particles > PARITY_MAX_PARTICLES {
+            log::warn!(
+                "Parity check skipped: {} particles (limit {})",
+                gpu.buffers.num_particles,
+                PARITY_MAX_PARTICLES
+            );
+            return None;
+        }
+        let mut reference = gpu
+            .buffers
+            .read_particles(&gpu.context.device, &gpu.context.queue);
+        let use_f16 = gpu.buffers.use_f16;
+        gpu.buffers.update_params(
+            &gpu.context.queue,
+            &self.app.sim_config,
+            PARITY_DT,
+            fair_sample_seed(self.app.sim_config.seed, self.app.sim_frame, 0),
+            1.0,
+        );
+
+        // Brush input would show up as a discrepancy
+        let brush_active = std::mem::replace(&mut self.brush.is_active, false);
+        self.run_gpu_compute(1);
+        self.brush.is_active = brush_active;
+
+        let gpu = self.gpu.as_ref()?;
+        let observed = gpu
+            .buffers
+            .read_particles(&gpu.context.device, &gpu.context.queue);
+
+        let config = &self.app.sim_config;
+        PhysicsEngine::new(reference.len()).step(
+            &mut reference,
+            &self.app.interaction_matrix,
+            &self.app.radius_matrix,
+            config,
+            PARITY_DT,
+        );
+
+        let search_radius = (config.max_velocity * PARITY_DT * 2.0).max(CPU_COMPARE_MIN_SEARCH);
+        let parity = step_parity(&reference, &observed, config, search_radius);
+        let relative = if use_f16 {
+            PARITY_F16_TOLERANCE
+        } else {
+            PARITY_F32_TOLERANCE
+        };
+        let velocity_tolerance = relative * config.max_velocity;
+        let position_tolerance = velocity_tolerance * PARITY_DT + PARITY_POSITION_SLACK;
+        let score = if parity.unmatched > 0 {
+            f32::INFINITY
+        } else {
+            (parity.max_position / position_tolerance).max(parity.max_velocity / velocity_tolerance)
+        };
+
+        let message = format!(
+            "GPU/CPU parity ({}): max position {:.5} (tol {:.5}), max velocity {:.5} \
+             (tol {:.5}), unmatched {}",
+            if use_f16 { "f16" } else { "f32" },
+            parity.max_position,
+            position_tolerance,
+            parity.max_velocity,
+            velocity_tolerance,
+            parity.unmatched
+        );
+        if score <= 1.0 {
+            log::info!("{}", message);
+        } else {
+            log::warn!("{} - exceeds tolerance", message);
+        }
+        self.step_parity = Some((parity, score));
+        Some(score)
+    }
+}
@@ -0,0 +1,61 @@
+//! Keyboard cycling through palettes and rule types.
+
+use std::time::Instant;
+
+use super::AppHandler;
+use crate::generators::{PaletteType, RuleType};
+
+impl AppHandler {
+    /// Step the color palette forward (`step > 0`) or backward, wrapping
+    /// around the palette list.
+    pub(crate) fn cycle_palette(&mut self, step: isize) {
+        self.push_undo();
+        let palette = step_wrapped(PaletteType::all(), self.app.current_palette, step);
+        self.app.current_palette = palette;
+        self.app.config.gen_palette = palette;
+        self.app.regenerate_colors();
+        self.sync_colors();
+        self.record_exploration();
+        self.show_hud_message(format!("Palette: {}", palette.display_name()));
+    }
+
+    /// Step the rule generator forward (`step > 0`) or backward, wrapping
+    /// around the rule list.
+    pub(crate) fn cycle_rule(&mut self, step: isize) {
+        self.push_undo();
+        let rule = step_wrapped(RuleType::all(), self.app.current_rule, step);
+        self.app.current_rule = rule;
+        self.app.config.gen_rule = rule;
+        self.app.regenerate_rules();
+        self.sync_interaction_matrix();
+        self.record_exploration();
+        self.show_hud_message(format!("Rules: {}", rule.display_name()));
+    }
+
+    /// Show a short-lived message in the center of the HUD.
+    pub(crate) fn show_hud_message(&mut self, message: String) {
+        self.hud_message = Some((message, Instant::now()));
+    }
+}
+
+/// Item `step` positions away from `current` in `items`, wrapping at both
+/// ends. Falls back to the first item if `current` is not listed.
+fn step_wrapped<T: Copy + PartialEq>(items: &[T], current: T, step: isize) -> T {
+    let len = items.len() as isize;
+    let index = items.iter().position(|&item| item == current).unwrap_or(0) as isize;
+    items[(index + step).rem_euclid(len) as usize]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_wrapped() {
+        let items = [1, 2, 3];
+        assert_eq!(step_wrapped(&items, 1, 1), 2);
+        assert_eq!(step_wrapped(&items, 3, 1), 1);
+        assert_eq!(step_wrapped(&items, 1, -1), 3);
+        assert_eq!(step_wrapped(&items, 9, 1), 2);
+    }
+}
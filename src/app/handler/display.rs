@@ -0,0 +1,105 @@
+//! Runtime monitor and fullscreen switching, and world-to-window matching.
+
+use super::AppHandler;
+use crate::app::display::{centered_position, exclusive_fullscreen, monitor_label, select_monitor};
+use crate::simulation::rescale_positions;
+
+impl AppHandler {
+    /// Labels for the available monitors, in enumeration order.
+    pub(crate) fn monitor_labels(&self) -> Vec<String> {
+        self.gpu
+            .as_ref()
+            .map(|gpu| {
+                gpu.context
+                    .window
+                    .available_monitors()
+                    .enumerate()
+                    .map(|(i, monitor)| monitor_label(i, &monitor))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Move the window to the selected monitor and enter or leave exclusive
+    /// fullscreen there.
+    pub(crate) fn apply_window_options(&mut self) {
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+        let window = &gpu.context.window;
+        let monitors: Vec<_> = window.available_monitors().collect();
+        let Some(monitor) =
+            select_monitor(&monitors, window.primary_monitor(), &self.window_options)
+        else {
+            log::warn!("No monitors reported; leaving window placement unchanged");
+            return;
+        };
+
+        if self.window_options.fullscreen {
+            window.set_fullscreen(Some(exclusive_fullscreen(&monitor)));
+        } else {
+            window.set_fullscreen(None);
+            window.set_outer_position(centered_position(&monitor, window.outer_size()));
+        }
+        log::info!(
+            "Window on monitor {:?}{}",
+            monitor.name(),
+            if self.window_options.fullscreen {
+                " (exclusive fullscreen)"
+            } else {
+                ""
+            }
+        );
+    }
+
+    /// Resize the world to the window surface when `match_world_to_window`
+    /// is enabled.
+    ///
+    /// Particles and the camera pan are rescaled proportionally so the
+    /// pattern keeps its layout, and the spatial hash grid is rebuilt for
+    /// the new world size.
+    pub(crate) fn match_world_to_window(&mut self) {
+        if !self.app.config.match_world_to_window {
+            return;
+        }
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+        let (width, height) = gpu.context.surface_size();
+        if width == 0 || height == 0 {
+            return;
+        }
+        let new_size = glam::Vec2::new(width as f32, height as f32);
+        if let Err(e) = self.resize_world(new_size) {
+            log::warn!("Cannot match world to window: {}", e);
+        }
+    }
+
+    /// Resize the world to `new_size`, rescaling particles and the camera pan
+    /// proportionally so the pattern keeps its layout.
+    ///
+    /// Particles are then wrapped or clamped into the new bounds and the
+    /// particle and spatial hash buffers rebuilt for the new grid.
+    pub(crate) fn resize_world(&mut self, new_size: glam::Vec2) -> Result<(), String> {
+        let old_size = self.app.sim_config.world_size;
+        if new_size == old_size {
+            return Ok(());
+        }
+        let mut resized = self.app.sim_config.clone();
+        resized.world_size = new_size;
+        resized.validate()?;
+
+        self.sync_particles_from_gpu();
+        rescale_positions(&mut self.app.particles, old_size, new_size);
+        self.app.sim_config.world_size = new_size;
+        // Rounding can land a particle exactly on the far edge
+        self.normalize_particle_positions();
+        self.camera.offset *= new_size / old_size;
+        self.app.rescale_radii_for_world(old_size);
+        self.needs_sync_spatial_buffers = true;
+        self.sync_buffers();
+        self.update_camera();
+        log::info!("World resized to {}x{}", new_size.x, new_size.y);
+        Ok(())
+    }
+}
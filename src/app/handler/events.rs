@@ -12,6 +12,8 @@ use winit::{
 
 use super::AppHandler;
 use crate::app::BrushTool;
+use crate::app::display::{centered_position, exclusive_fullscreen, select_monitor};
+use crate::renderer::gpu::MAX_EXTRA_BRUSH_POINTS;
 
 impl ApplicationHandler for AppHandler {
     fn resumed(&mut self, event_loop: &ActiveEventLoop) {
@@ -31,6 +33,26 @@ impl ApplicationHandler for AppHandler {
                 window_attrs = window_attrs.with_window_icon(Some(icon));
             }
 
+            // Monitor placement and exclusive fullscreen
+            let monitors: Vec<_> = event_loop.available_monitors().collect();
+            if let Some(monitor) = select_monitor(
+                &monitors,
+                event_loop.primary_monitor(),
+                &self.window_options,
+            ) {
+                if self.window_options.fullscreen {
+                    window_attrs =
+                        window_attrs.with_fullscreen(Some(exclusive_fullscreen(&monitor)));
+                } else if self.window_options.monitor.is_some() {
+                    let size = LogicalSize::new(
+                        self.app.config.window_width,
+                        self.app.config.window_height,
+                    )
+                    .to_physical(monitor.scale_factor());
+                    window_attrs = window_attrs.with_position(centered_position(&monitor, size));
+                }
+            }
+
             let window = Arc::new(
                 event_loop
                     .create_window(window_attrs)
@@ -38,6 +60,16 @@ impl ApplicationHandler for AppHandler {
             );
 
             self.init_gpu(window);
+            self.match_world_to_window();
+
+            if let Some((name, preset)) = self.startup_preset.take() {
+                log::info!("Applying startup preset: {}", name);
+                self.apply_preset(preset);
+                self.preset_status = format!("Loaded: {}", name);
+                self.selected_preset = name;
+            }
+
+            self.start_auto_recording();
         }
     }
 
@@ -66,42 +98,7 @@ impl ApplicationHandler for AppHandler {
         match event {
             WindowEvent::CloseRequested => {
                 log::info!("Close requested, exiting...");
-                // Save UI states to app.config before saving the config
-                self.app.config.ui_simulation_open = self.ui_simulation_open;
-                self.app.config.ui_physics_open = self.ui_physics_open;
-                self.app.config.ui_generators_open = self.ui_generators_open;
-                self.app.config.ui_interaction_matrix_open = self.ui_interaction_matrix_open;
-                self.app.config.ui_brush_tools_open = self.ui_brush_tools_open;
-                self.app.config.ui_rendering_open = self.ui_rendering_open;
-                self.app.config.ui_presets_open = self.ui_presets_open;
-                self.app.config.ui_keyboard_shortcuts_open = self.ui_keyboard_shortcuts_open;
-
-                // Persist current settings
-                self.app.config.sim_num_particles = self.app.sim_config.num_particles;
-                self.app.config.sim_num_types = self.app.sim_config.num_types;
-                self.app.config.phys_force_factor = self.app.sim_config.force_factor;
-                self.app.config.phys_friction = self.app.sim_config.friction;
-                self.app.config.phys_repel_strength = self.app.sim_config.repel_strength;
-                self.app.config.phys_max_velocity = self.app.sim_config.max_velocity;
-                self.app.config.phys_boundary_mode = self.app.sim_config.boundary_mode;
-                self.app.config.phys_wall_repel_strength = self.app.sim_config.wall_repel_strength;
-                self.app.config.phys_mirror_wrap_count = self.app.sim_config.mirror_wrap_count;
-                self.app.config.gen_rule = self.app.current_rule;
-                self.app.config.gen_palette = self.app.current_palette;
-                self.app.config.gen_pattern = self.app.current_pattern;
-                self.app.config.render_particle_size = self.app.sim_config.particle_size;
-                self.app.config.render_background_color = self.app.sim_config.background_color;
-                self.app.config.render_glow_enabled = self.app.sim_config.enable_glow;
-                self.app.config.render_glow_intensity = self.app.sim_config.glow_intensity;
-                self.app.config.render_glow_size = self.app.sim_config.glow_size;
-                self.app.config.render_glow_steepness = self.app.sim_config.glow_steepness;
-                self.app.config.render_spatial_hash_cell_size =
-                    self.app.sim_config.spatial_hash_cell_size;
-
-                if let Err(e) = self.app.config.save() {
-                    log::error!("Failed to save app config: {}", e);
-                }
-                event_loop.exit();
+                self.clean_exit(event_loop);
             }
             WindowEvent::Resized(new_size) => {
                 if let Some(gpu) = &mut self.gpu {
@@ -114,8 +111,12 @@ impl ApplicationHandler for AppHandler {
                         new_size.height as f32,
                     );
                 }
+                self.match_world_to_window();
             }
+            // Auto-record drives its own frames from `about_to_wait`
+            WindowEvent::RedrawRequested if self.auto_recording => {}
             WindowEvent::RedrawRequested => {
+                self.wait_for_frame_cap();
                 self.update();
                 self.render();
 
@@ -124,41 +125,98 @@ impl ApplicationHandler for AppHandler {
                     gpu.context.window.request_redraw();
                 }
             }
-            WindowEvent::KeyboardInput { event, .. } => {
-                if event.state.is_pressed() {
-                    use winit::keyboard::{KeyCode, PhysicalKey};
-                    match event.physical_key {
-                        PhysicalKey::Code(KeyCode::Space) => {
-                            self.app.toggle_running();
-                        }
-                        PhysicalKey::Code(KeyCode::KeyR) => {
-                            self.app.regenerate_particles();
-                            self.sync_buffers();
-                        }
-                        PhysicalKey::Code(KeyCode::KeyM) => {
-                            self.app.regenerate_rules();
-                            self.sync_interaction_matrix();
-                        }
-                        PhysicalKey::Code(KeyCode::KeyH) => {
-                            self.show_ui = !self.show_ui;
-                        }
-                        PhysicalKey::Code(KeyCode::KeyC) => {
-                            // Reset camera
-                            self.camera.reset();
-                            self.update_camera();
-                        }
-                        PhysicalKey::Code(KeyCode::F11) => {
-                            self.toggle_recording();
-                        }
-                        PhysicalKey::Code(KeyCode::F12) => {
-                            self.screenshot_requested = true;
-                            log::info!("Screenshot requested");
+            WindowEvent::KeyboardInput { event, .. } if event.state.is_pressed() => {
+                use winit::keyboard::{KeyCode, PhysicalKey};
+                if self.modifiers.alt_key()
+                    && let PhysicalKey::Code(code) = event.physical_key
+                    && let Some(slot) = favorite_slot(code)
+                {
+                    self.load_favorite(slot);
+                    return;
+                }
+                if self.modifiers.control_key()
+                    && event.physical_key == PhysicalKey::Code(KeyCode::KeyZ)
+                {
+                    if self.modifiers.shift_key() {
+                        self.redo();
+                    } else {
+                        self.undo();
+                    }
+                    return;
+                }
+                match event.physical_key {
+                    PhysicalKey::Code(KeyCode::Space) => {
+                        self.app.toggle_running();
+                    }
+                    PhysicalKey::Code(KeyCode::Period) => {
+                        self.request_single_step();
+                    }
+                    PhysicalKey::Code(KeyCode::KeyR) => {
+                        self.app.regenerate_particles();
+                        self.sync_buffers();
+                    }
+                    PhysicalKey::Code(KeyCode::KeyM) => {
+                        self.push_undo();
+                        self.app.regenerate_rules();
+                        self.sync_interaction_matrix();
+                        self.record_exploration();
+                    }
+                    PhysicalKey::Code(KeyCode::BracketLeft) => {
+                        if self.modifiers.shift_key() {
+                            self.cycle_rule(-1);
+                        } else {
+                            self.cycle_palette(-1);
                         }
-                        PhysicalKey::Code(KeyCode::Escape) => {
-                            event_loop.exit();
+                    }
+                    PhysicalKey::Code(KeyCode::BracketRight) => {
+                        if self.modifiers.shift_key() {
+                            self.cycle_rule(1);
+                        } else {
+                            self.cycle_palette(1);
                         }
-                        _ => {}
                     }
+                    PhysicalKey::Code(KeyCode::KeyN) => {
+                        self.push_undo();
+                        self.app.mutate_rules();
+                        self.sync_interaction_matrix();
+                        self.record_exploration();
+                    }
+                    PhysicalKey::Code(KeyCode::KeyH) => {
+                        self.show_ui = !self.show_ui;
+                    }
+                    PhysicalKey::Code(KeyCode::F3) => {
+                        self.app.config.show_stats_overlay = !self.app.config.show_stats_overlay;
+                    }
+                    PhysicalKey::Code(KeyCode::KeyT) => {
+                        self.reverse_velocities();
+                    }
+                    PhysicalKey::Code(KeyCode::KeyG) => {
+                        self.brush.gravity_well = !self.brush.gravity_well;
+                    }
+                    PhysicalKey::Code(KeyCode::KeyC)
+                    | PhysicalKey::Code(KeyCode::Digit0)
+                    | PhysicalKey::Code(KeyCode::Numpad0) => {
+                        self.reset_view();
+                    }
+                    PhysicalKey::Code(KeyCode::KeyF) => {
+                        self.fit_view();
+                    }
+                    PhysicalKey::Code(KeyCode::F11) => {
+                        self.toggle_recording();
+                    }
+                    PhysicalKey::Code(KeyCode::F10) => {
+                        self.clipboard_requested = true;
+                        log::info!("Clipboard screenshot requested");
+                    }
+                    PhysicalKey::Code(KeyCode::F12) => {
+                        self.screenshot_requested = true;
+                        log::info!("Screenshot requested");
+                    }
+                    PhysicalKey::Code(KeyCode::Escape) => {
+                        log::info!("Escape pressed, exiting...");
+                        self.clean_exit(event_loop);
+                    }
+                    _ => {}
                 }
             }
             WindowEvent::MouseInput { state, button, .. } => {
@@ -173,7 +231,13 @@ impl ApplicationHandler for AppHandler {
                 }
                 // Left mouse button for brush interaction
                 if button == MouseButton::Left && self.brush.tool != BrushTool::None {
-                    if state == ElementState::Pressed && !egui_wants_pointer {
+                    if state == ElementState::Pressed
+                        && !egui_wants_pointer
+                        && self.modifiers.control_key()
+                    {
+                        // Ctrl+click pins a persistent attract/repel point
+                        self.brush.pin_point(MAX_EXTRA_BRUSH_POINTS);
+                    } else if state == ElementState::Pressed && !egui_wants_pointer {
                         self.brush.is_active = true;
                     } else if state == ElementState::Released {
                         self.brush.is_active = false;
@@ -215,6 +279,34 @@ impl ApplicationHandler for AppHandler {
                 }
                 self.camera.last_mouse_pos = new_pos;
             }
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers.state();
+            }
+            WindowEvent::Touch(touch) => {
+                use winit::event::TouchPhase;
+                if let Some(gpu) = &self.gpu {
+                    let screen_pos =
+                        glam::Vec2::new(touch.location.x as f32, touch.location.y as f32);
+                    let world_pos = self.camera.screen_to_world(
+                        screen_pos,
+                        glam::Vec2::new(
+                            gpu.context.surface_config.width as f32,
+                            gpu.context.surface_config.height as f32,
+                        ),
+                        self.app.sim_config.world_size,
+                    );
+                    match touch.phase {
+                        TouchPhase::Started | TouchPhase::Moved => {
+                            if !egui_wants_pointer {
+                                self.brush.set_touch_point(touch.id, world_pos);
+                            }
+                        }
+                        TouchPhase::Ended | TouchPhase::Cancelled => {
+                            self.brush.remove_touch_point(touch.id);
+                        }
+                    }
+                }
+            }
             WindowEvent::MouseWheel { delta, .. } => {
                 use winit::event::MouseScrollDelta;
                 let scroll_amount = match delta {
@@ -231,10 +323,39 @@ impl ApplicationHandler for AppHandler {
         }
     }
 
-    fn about_to_wait(&mut self, _event_loop: &ActiveEventLoop) {
+    fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
+        if self.auto_recording {
+            if self.is_recording {
+                self.update();
+                self.render();
+            } else {
+                // Saved (or failed to start); the status holds the outcome
+                log::info!("Auto-record finished: {}", self.preset_status);
+                self.clean_exit(event_loop);
+            }
+            return;
+        }
+
         // Request redraw for continuous rendering
         if let Some(gpu) = &self.gpu {
             gpu.context.window.request_redraw();
         }
     }
 }
+
+/// Hotbar slot (0-based) for the digit keys 1..9.
+fn favorite_slot(code: winit::keyboard::KeyCode) -> Option<usize> {
+    use winit::keyboard::KeyCode;
+    match code {
+        KeyCode::Digit1 => Some(0),
+        KeyCode::Digit2 => Some(1),
+        KeyCode::Digit3 => Some(2),
+        KeyCode::Digit4 => Some(3),
+        KeyCode::Digit5 => Some(4),
+        KeyCode::Digit6 => Some(5),
+        KeyCode::Digit7 => Some(6),
+        KeyCode::Digit8 => Some(7),
+        KeyCode::Digit9 => Some(8),
+        _ => None,
+    }
+}
@@ -0,0 +1,95 @@
+//! Clean shutdown: persist settings and drop the recovery file.
+
+use winit::event_loop::ActiveEventLoop;
+
+use super::AppHandler;
+use crate::app::RecoveryState;
+
+impl AppHandler {
+    /// Save the config, remove the crash-recovery file and exit.
+    ///
+    /// Every way of quitting (window close, Escape, end of an auto-record)
+    /// goes through here, so a normal exit never leaves a recovery file
+    /// behind to trigger the restore prompt on the next launch.
+    pub(crate) fn clean_exit(&mut self, event_loop: &ActiveEventLoop) {
+        self.persist_config();
+        // Keep a recovery file that is still awaiting a decision
+        if let Err(e) = RecoveryState::clear_on_exit(self.pending_recovery.is_some()) {
+            log::error!("Failed to remove recovery file: {}", e);
+        }
+        event_loop.exit();
+    }
+
+    /// Copy the current session settings into the config and save it.
+    fn persist_config(&mut self) {
+        // Persist the user's settings, not adaptive quality overrides
+        self.restore_quality();
+        // Save UI states to app.config before saving the config
+        self.app.config.ui_simulation_open = self.ui_simulation_open;
+        self.app.config.ui_physics_open = self.ui_physics_open;
+        self.app.config.ui_generators_open = self.ui_generators_open;
+        self.app.config.ui_interaction_matrix_open = self.ui_interaction_matrix_open;
+        self.app.config.ui_brush_tools_open = self.ui_brush_tools_open;
+        self.app.config.ui_rendering_open = self.ui_rendering_open;
+        self.app.config.ui_presets_open = self.ui_presets_open;
+        self.app.config.ui_keyboard_shortcuts_open = self.ui_keyboard_shortcuts_open;
+        self.app.config.ui_debug_open = self.ui_debug_open;
+
+        // Persist current settings
+        self.app.config.sim_num_particles = self.app.sim_config.num_particles;
+        self.app.config.sim_num_types = self.app.sim_config.num_types;
+        self.app.config.sim_seed = self.app.sim_config.seed;
+        self.app.config.sim_type_names = self.app.sim_config.type_names.clone();
+        self.app.config.phys_force_factor = self.app.sim_config.force_factor;
+        self.app.config.phys_matrix_strength = self.app.sim_config.matrix_strength;
+        self.app.config.phys_spin_coupling = self.app.sim_config.spin_coupling;
+        self.app.config.phys_spin_friction = self.app.sim_config.spin_friction;
+        self.app.config.phys_target_temperature = self.app.sim_config.target_temperature;
+        self.app.config.phys_friction = self.app.sim_config.friction;
+        self.app.config.phys_repel_strength = self.app.sim_config.repel_strength;
+        self.app.config.phys_repel_radius = self.app.sim_config.repel_radius;
+        self.app.config.phys_max_velocity = self.app.sim_config.max_velocity;
+        self.app.config.phys_velocity_clamp = self.app.sim_config.velocity_clamp;
+        self.app.config.phys_fixed_dt = self.app.sim_config.fixed_dt;
+        self.app.config.phys_substeps = self.app.sim_config.substeps;
+        self.app.config.phys_boundary_mode = self.app.sim_config.boundary_mode;
+        self.app.config.phys_world_shape = self.app.sim_config.world_shape;
+        self.app.config.phys_wall_repel_strength = self.app.sim_config.wall_repel_strength;
+        self.app.config.phys_mirror_wrap_count = self.app.sim_config.mirror_wrap_count;
+        self.app.config.gen_rule = self.app.current_rule;
+        self.app.config.gen_palette = self.app.current_palette;
+        self.app.config.gen_palette_jitter = self.app.sim_config.palette_jitter;
+        self.app.config.gen_pattern = self.app.current_pattern;
+        self.app.config.gen_spawn_velocity = self.app.current_spawn_velocity;
+        self.app.config.gen_spawn_sampling = self.app.current_spawn_sampling;
+        self.app.config.gen_type_weights = self.app.current_type_weights.clone();
+        self.app.config.render_enable_3d = self.app.sim_config.enable_3d;
+        self.app.config.render_depth_limit = self.app.sim_config.depth_limit;
+        self.app.config.render_particle_size = self.app.sim_config.particle_size;
+        self.app.config.render_background_color = self.app.sim_config.background_color;
+        self.app.config.render_background_gradient = self.app.sim_config.background_gradient;
+        self.app.config.render_glow_enabled = self.app.sim_config.enable_glow;
+        self.app.config.render_glow_intensity = self.app.sim_config.glow_intensity;
+        self.app.config.render_glow_size = self.app.sim_config.glow_size;
+        self.app.config.render_glow_steepness = self.app.sim_config.glow_steepness;
+        self.app.config.render_glow_zoom_compensate = self.app.sim_config.glow_zoom_compensate;
+        self.app.config.render_trails_enabled = self.app.sim_config.enable_trails;
+        self.app.config.render_trail_fade = self.app.sim_config.trail_fade;
+        self.app.config.render_vignette = self.app.sim_config.vignette;
+        self.app.config.render_color_mode = self.app.sim_config.color_mode;
+        self.app.config.render_particle_shape = self.app.sim_config.particle_shape;
+        self.app.config.render_blend_mode = self.app.sim_config.blend_mode;
+        self.app.config.render_colormap = self.app.sim_config.colormap;
+        self.app.config.render_color_speed_range = self.app.sim_config.color_speed_range;
+        self.app.config.render_animate_palette = self.app.sim_config.animate_palette;
+        self.app.config.render_palette_hue_speed = self.app.sim_config.palette_hue_speed;
+        self.app.config.render_seam_fade = self.app.sim_config.seam_fade;
+        self.app.config.render_velocity_streaks = self.app.sim_config.velocity_streaks;
+        self.app.config.render_streak_length = self.app.sim_config.streak_length;
+        self.app.config.render_spatial_hash_cell_size = self.app.sim_config.spatial_hash_cell_size;
+
+        if let Err(e) = self.app.config.save() {
+            log::error!("Failed to save app config: {}", e);
+        }
+    }
+}
@@ -0,0 +1,57 @@
+//! Game controller input: camera, physics nudges and button actions.
+
+use super::AppHandler;
+#[cfg(feature = "gamepad")]
+use crate::app::GamepadAction;
+
+impl AppHandler {
+    /// Apply one frame of controller input.
+    ///
+    /// Runs alongside keyboard and mouse input, which drive the same camera
+    /// and config values. Does nothing when no controller backend exists;
+    /// with no controller connected the axes read as rest.
+    #[cfg(feature = "gamepad")]
+    pub(crate) fn poll_gamepad(&mut self, dt: f32) {
+        let Some(gamepad) = self.gamepad.as_mut() else {
+            return;
+        };
+        let mut actions = Vec::new();
+        let axes = gamepad.poll(&mut actions).filtered();
+
+        if axes.move_camera(&mut self.camera, self.app.sim_config.world_size, dt) {
+            self.update_camera();
+        }
+
+        let sim = &mut self.app.sim_config;
+        if axes.nudge_physics(&mut sim.force_factor, &mut sim.friction, dt) {
+            self.app.config.phys_force_factor = sim.force_factor;
+            self.app.config.phys_friction = sim.friction;
+            let message = format!(
+                "Force {:.2}, Friction {:.2}",
+                sim.force_factor, sim.friction
+            );
+            self.show_hud_message(message);
+        }
+
+        for action in actions {
+            match action {
+                GamepadAction::Regenerate => {
+                    self.app.regenerate_particles();
+                    self.sync_buffers();
+                }
+                GamepadAction::RandomizeRules => {
+                    self.push_undo();
+                    self.app.regenerate_rules();
+                    self.sync_interaction_matrix();
+                    self.record_exploration();
+                }
+                GamepadAction::ResetView => self.reset_view(),
+                GamepadAction::TogglePause => self.app.toggle_running(),
+            }
+        }
+    }
+
+    /// Controller support is not compiled in without the `gamepad` feature.
+    #[cfg(not(feature = "gamepad"))]
+    pub(crate) fn poll_gamepad(&mut self, _dt: f32) {}
+}
@@ -1,13 +1,22 @@
 //! GPU compute operations for particle physics simulation.
 
 use super::AppHandler;
-use crate::app::BrushTool;
-use crate::app::gpu_state::GpuState;
+use crate::app::gpu_state::{GpuState, PassToggles};
+use crate::app::{App, BrushTool};
+use crate::renderer::gpu::{PrefixScanPass, fair_sample_seed};
 use crate::simulation::SimulationConfig;
 
 impl AppHandler {
-    pub(crate) fn run_gpu_compute(&mut self, _dt: f32) {
+    /// Run `substeps` physics steps (force + advance + swap each), all with
+    /// the timestep already uploaded by `update()`. Each substep after the
+    /// first gets its own fair sampling seed.
+    pub(crate) fn run_gpu_compute(&mut self, substeps: u32) {
         let Some(gpu) = &mut self.gpu else { return };
+        // Nothing to simulate on a cleared canvas, and every particle
+        // dispatch below would be zero-sized
+        if gpu.buffers.num_particles == 0 {
+            return;
+        }
 
         // Params already updated in update() - no need to duplicate
 
@@ -33,42 +42,136 @@ impl AppHandler {
             );
         }
 
+        let substeps = substeps.max(1);
+        for substep in 0..substeps {
+            // Brush forces apply once per frame: deactivate every point
+            // (mouse, touches, pins and the gravity well) after the first
+            // substep. Each substep is its own submit, so the queued write
+            // lands between them.
+            if substep == 1 {
+                gpu.brush_pipelines.update_brush_inactive(
+                    &gpu.context.queue,
+                    &self.brush,
+                    gpu.buffers.num_particles,
+                );
+            }
+            if substep > 0 {
+                gpu.buffers.update_sample_seed(
+                    &gpu.context.queue,
+                    fair_sample_seed(self.app.sim_config.seed, self.app.sim_frame, substep),
+                );
+            }
+            Self::run_gpu_compute_step(gpu, &self.app, workgroup_count, &self.pass_toggles);
+
+            // Render the final substep's output
+            if substep + 1 == substeps {
+                Self::create_render_bind_groups_for_next(gpu, self.app.sim_config.enable_glow);
+            }
+
+            // Swap so the next step reads what was just computed
+            gpu.buffers.swap_buffers();
+        }
+    }
+
+    /// Apply an Attract/Repel brush while paused by dispatching only the brush
+    /// pass, which moves the particles under it in place (no forces, advance
+    /// or buffer swap), so stopped scenes can be sculpted.
+    ///
+    /// Draw and Erase edit the CPU particles and sync, so they already work
+    /// while paused.
+    pub(crate) fn run_paused_brush(&mut self, dt: f32) {
+        if !self.brush.is_active
+            || !matches!(self.brush.tool, BrushTool::Attract | BrushTool::Repel)
+        {
+            return;
+        }
+        let Some(gpu) = &mut self.gpu else { return };
+        let num_particles = gpu.buffers.num_particles;
+        if num_particles == 0 {
+            return;
+        }
+
+        gpu.brush_pipelines.update_brush_sculpt(
+            &gpu.context.queue,
+            &self.brush,
+            num_particles,
+            dt,
+            &self.app.sim_config,
+        );
+        let bind_group = gpu.brush_pipelines.create_force_bind_group(
+            &gpu.context.device,
+            gpu.buffers.current_pos_type(),
+            gpu.buffers.current_velocities(),
+        );
+
+        let mut encoder = gpu.context.create_encoder("Paused Brush Encoder");
+        {
+            let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Paused Brush Pass"),
+                timestamp_writes: None,
+            });
+            compute_pass.set_pipeline(&gpu.brush_pipelines.force_pipeline);
+            compute_pass.set_bind_group(0, &bind_group, &[]);
+            compute_pass.dispatch_workgroups(num_particles.div_ceil(256), 1, 1);
+        }
+        gpu.context.submit(encoder.finish());
+    }
+
+    /// One physics step: forces and advance from current into next.
+    fn run_gpu_compute_step(
+        gpu: &mut GpuState,
+        app: &App,
+        workgroup_count: u32,
+        toggles: &PassToggles,
+    ) {
         // Run compute passes on the shared encoder (no individual submits).
         // Compute reads from current_particles(), writes to next_particles().
         // Brush force is now integrated into the advance shader.
-        if self.app.sim_config.use_spatial_hash {
+        if app.sim_config.use_spatial_hash {
             // Spatial hash optimized path - uses separate submissions for barrier correctness.
             // The spatial hash requires transitioning buffers between atomic and non-atomic access,
             // which needs explicit barriers via separate encoder submissions.
-            let max_radius = self.app.radius_matrix.max_interaction_radius();
+            let max_radius = app.radius_matrix.max_interaction_radius();
             Self::run_gpu_compute_spatial_with_barriers(
                 gpu,
-                &self.app.sim_config,
+                &app.sim_config,
                 workgroup_count,
                 max_radius,
+                toggles,
             );
         } else {
             // Brute force O(n²) path - single encoder, no blocking wait
             let mut encoder = gpu.context.create_encoder("GPU Compute Encoder");
-            Self::run_gpu_compute_brute_force_on_encoder(&mut encoder, gpu, workgroup_count);
+            Self::run_gpu_compute_brute_force_on_encoder(
+                &mut encoder,
+                gpu,
+                workgroup_count,
+                toggles,
+            );
             gpu.context.submit(encoder.finish());
         }
+    }
 
-        // Create render bind groups pointing to next_particles() (the OUTPUT of compute).
-        // Compute read from current, wrote to next - so render needs to use next.
+    /// Point the render bind groups at next_particles() (the OUTPUT of compute).
+    /// Compute read from current, wrote to next - so render needs to use next.
+    /// The glow group is skipped (and dropped) while glow is disabled.
+    fn create_render_bind_groups_for_next(gpu: &mut GpuState, glow: bool) {
         gpu.render_bind_group = gpu.render.create_render_bind_group(
             &gpu.context.device,
             gpu.buffers.next_pos_type(),
+            gpu.buffers.next_velocities(),
+            gpu.buffers.next_depths(),
             &gpu.buffers,
         );
-        gpu.glow_bind_group = gpu.render.create_glow_bind_group(
-            &gpu.context.device,
-            gpu.buffers.next_pos_type(),
-            &gpu.buffers,
-        );
-
-        // Swap so next frame's compute reads from what we just rendered (the computed output)
-        gpu.buffers.swap_buffers();
+        gpu.glow_bind_group = glow.then(|| {
+            gpu.render.create_glow_bind_group(
+                &gpu.context.device,
+                gpu.buffers.next_pos_type(),
+                gpu.buffers.next_velocities(),
+                gpu.buffers.next_depths(),
+                &gpu.buffers,
+            )
+        });
     }
 
     /// Run GPU compute using brute force O(n²) algorithm on a shared encoder.
@@ -77,6 +180,7 @@ impl AppHandler {
         encoder: &mut wgpu::CommandEncoder,
         gpu: &mut GpuState,
         workgroup_count: u32,
+        toggles: &PassToggles,
     ) {
         // Read from current (input), write to next (output)
         let pos_in = gpu.buffers.current_pos_type();
@@ -84,6 +188,10 @@ impl AppHandler {
         let pos_out = gpu.buffers.next_pos_type();
         let vel_out = gpu.buffers.next_velocities();
 
+        // Particles keep their slots here, so carry the depths across as-is
+        let depth_in = gpu.buffers.current_depths();
+        encoder.copy_buffer_to_buffer(depth_in, 0, gpu.buffers.next_depths(), 0, depth_in.size());
+
         // Create bind groups for compute passes
         let force_bind_group = gpu.compute.create_force_bind_group(
             &gpu.context.device,
@@ -97,12 +205,12 @@ impl AppHandler {
             &gpu.context.device,
             pos_out, // Write new positions
             vel_out, // Read/Write velocities
-            &gpu.buffers.params,
+            &gpu.buffers,
             &gpu.brush_pipelines.brush_buffer,
         );
 
         // Force computation pass
-        {
+        if toggles.forces {
             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Force Compute Pass"),
                 timestamp_writes: None,
@@ -113,7 +221,7 @@ impl AppHandler {
         }
 
         // Advance pass (integrate velocities, apply boundaries)
-        {
+        if toggles.advance {
             let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Advance Pass"),
                 timestamp_writes: None,
@@ -133,6 +241,7 @@ impl AppHandler {
         sim_config: &SimulationConfig,
         particle_workgroups: u32,
         max_radius: f32,
+        toggles: &PassToggles,
     ) {
         // Debug flag - set to true to enable logging (first frame only)
         static DEBUG_ONCE: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(true);
@@ -146,14 +255,14 @@ impl AppHandler {
         gpu.spatial_bind_groups.ensure(
             &gpu.context.device,
             &gpu.buffers,
-            &mut gpu.spatial_buffers,
+            &gpu.spatial_buffers,
             &gpu.spatial_pipelines,
         );
 
         let total_bins = gpu.spatial_buffers.total_bins_with_end();
-        let bin_workgroups = total_bins.div_ceil(256);
-        let num_passes = gpu.spatial_bind_groups.pass_count;
-        let offsets_in_a = gpu.spatial_bind_groups.offsets_in_a;
+        let bin_workgroups = gpu.spatial_pipelines.workgroups(total_bins);
+        let spatial_workgroups = gpu.spatial_pipelines.workgroups(gpu.buffers.num_particles);
+        let num_passes = gpu.spatial_bind_groups.prefix_passes.len();
 
         if should_debug {
             log::info!(
@@ -173,7 +282,7 @@ impl AppHandler {
         let mut timestamp_labels: Vec<String> = Vec::new();
         let mut query_index: u32 = 0;
 
-        {
+        if toggles.binning {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Bin Clear Pass"),
                 timestamp_writes: None,
@@ -193,13 +302,13 @@ impl AppHandler {
                 pass.write_timestamp(qs, query_index);
                 query_index += 1;
             }
+            timestamp_labels.push("clear".to_string());
         }
-        timestamp_labels.push("clear".to_string());
 
         // Count particles per bin
         let count_bind_group = gpu.spatial_bind_groups.count_for_current(&gpu.buffers);
 
-        {
+        if toggles.binning {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Bin Count Pass"),
                 timestamp_writes: None,
@@ -210,16 +319,24 @@ impl AppHandler {
             }
             pass.set_pipeline(&gpu.spatial_pipelines.count_pipeline);
             pass.set_bind_group(0, count_bind_group, &[]);
-            pass.dispatch_workgroups(particle_workgroups, 1, 1);
+            pass.dispatch_workgroups(spatial_workgroups, 1, 1);
             if let Some(qs) = gpu.timestamp_query_set.as_ref() {
                 pass.write_timestamp(qs, query_index);
                 query_index += 1;
             }
+            timestamp_labels.push("count".to_string());
         }
-        timestamp_labels.push("count".to_string());
 
         // ============ PHASE 2: Prefix Sum ============
-        for (idx, bind_group) in gpu.spatial_bind_groups.prefix_groups().iter().enumerate() {
+        // Tiled Blelloch scan, in place in buffer A: scan each level's
+        // blocks bottom-up, then add block offsets back top-down.
+        let prefix_passes: &[PrefixScanPass] = if toggles.binning {
+            &gpu.spatial_bind_groups.prefix_passes
+        } else {
+            &[]
+        };
+        let prefix_groups = gpu.spatial_bind_groups.prefix_groups();
+        for &prefix_pass in prefix_passes {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Prefix Sum Pass"),
                 timestamp_writes: None,
@@ -228,24 +345,24 @@ impl AppHandler {
                 pass.write_timestamp(qs, query_index);
                 query_index += 1;
             }
-            pass.set_pipeline(&gpu.spatial_pipelines.prefix_sum_pipeline);
-            pass.set_bind_group(0, bind_group, &[]);
-            pass.dispatch_workgroups(bin_workgroups, 1, 1);
+            pass.set_pipeline(gpu.spatial_pipelines.prefix_pipeline(prefix_pass));
+            pass.set_bind_group(0, &prefix_groups[prefix_pass.level()], &[]);
+            pass.dispatch_workgroups(prefix_pass.workgroups(), 1, 1);
             if let Some(qs) = gpu.timestamp_query_set.as_ref() {
                 pass.write_timestamp(qs, query_index);
                 query_index += 1;
             }
-            timestamp_labels.push(format!("prefix {}", idx));
+            timestamp_labels.push(match prefix_pass {
+                PrefixScanPass::Scan { level, .. } => format!("prefix scan {}", level),
+                PrefixScanPass::AddOffsets { level, .. } => format!("prefix add {}", level),
+            });
         }
 
-        // Track which buffer has the final prefix sum result
-        gpu.spatial_buffers.current_offset_buffer = if offsets_in_a { 0 } else { 1 };
-
         // ============ PHASE 3: Clear for Sort + Sort ============
-        // Clear the OTHER buffer for sort atomic counters
-        let clear_for_sort_bind_group = gpu.spatial_bind_groups.clear(!offsets_in_a);
+        // Clear buffer B for sort atomic counters (offsets stay in A)
+        let clear_for_sort_bind_group = gpu.spatial_bind_groups.clear(false);
 
-        {
+        if toggles.sort {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Pre-Sort Clear Pass"),
                 timestamp_writes: None,
@@ -261,13 +378,13 @@ impl AppHandler {
                 pass.write_timestamp(qs, query_index);
                 query_index += 1;
             }
+            timestamp_labels.push("clear_sort".to_string());
         }
-        timestamp_labels.push("clear_sort".to_string());
 
         // Sort particles by bin
         let sort_bind_group = gpu.spatial_bind_groups.sort_for_current(&gpu.buffers);
 
-        {
+        if toggles.sort {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Bin Sort Pass"),
                 timestamp_writes: None,
@@ -278,13 +395,13 @@ impl AppHandler {
             }
             pass.set_pipeline(&gpu.spatial_pipelines.sort_pipeline);
             pass.set_bind_group(0, sort_bind_group, &[]);
-            pass.dispatch_workgroups(particle_workgroups, 1, 1);
+            pass.dispatch_workgroups(spatial_workgroups, 1, 1);
             if let Some(qs) = gpu.timestamp_query_set.as_ref() {
                 pass.write_timestamp(qs, query_index);
                 query_index += 1;
             }
+            timestamp_labels.push("sort".to_string());
         }
-        timestamp_labels.push("sort".to_string());
 
         // ============ PHASE 4: Forces + Advance ============
         let forces_bind_group = gpu.spatial_bind_groups.forces_for_current(&gpu.buffers);
@@ -296,12 +413,12 @@ impl AppHandler {
             &gpu.context.device,
             pos_out, // In-place update
             vel_out, // In-place update (after force pass wrote to it)
-            &gpu.buffers.params,
+            &gpu.buffers,
             &gpu.brush_pipelines.brush_buffer,
         );
 
         // Binned force computation
-        {
+        if toggles.forces {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Binned Forces Pass"),
                 timestamp_writes: None,
@@ -312,16 +429,16 @@ impl AppHandler {
             }
             pass.set_pipeline(&gpu.spatial_pipelines.forces_pipeline);
             pass.set_bind_group(0, forces_bind_group, &[]);
-            pass.dispatch_workgroups(particle_workgroups, 1, 1);
+            pass.dispatch_workgroups(spatial_workgroups, 1, 1);
             if let Some(qs) = gpu.timestamp_query_set.as_ref() {
                 pass.write_timestamp(qs, query_index);
                 query_index += 1;
             }
+            timestamp_labels.push("forces".to_string());
         }
-        timestamp_labels.push("forces".to_string());
 
         // Advance pass
-        {
+        if toggles.advance {
             let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
                 label: Some("Advance Pass"),
                 timestamp_writes: None,
@@ -341,8 +458,8 @@ impl AppHandler {
                 pass.write_timestamp(qs, query_index);
                 query_index += 1;
             }
+            timestamp_labels.push("advance".to_string());
         }
-        timestamp_labels.push("advance".to_string());
 
         if gpu.timestamps_supported {
             if let (Some(qs), Some(resolve)) = (
@@ -386,8 +503,8 @@ impl AppHandler {
             .update_params(&gpu.context.queue, sim_config, max_radius);
 
         let total_bins = gpu.spatial_buffers.total_bins_with_end();
-        let bin_workgroups = total_bins.div_ceil(256);
-        let num_passes = gpu.spatial_buffers.prefix_sum_passes();
+        let bin_workgroups = gpu.spatial_pipelines.workgroups(total_bins);
+        let spatial_workgroups = gpu.spatial_pipelines.workgroups(gpu.buffers.num_particles);
 
         // Read from current_particles (input)
         let pos_in = gpu.buffers.current_pos_type();
@@ -425,64 +542,26 @@ impl AppHandler {
             });
             pass.set_pipeline(&gpu.spatial_pipelines.count_pipeline);
             pass.set_bind_group(0, &count_bind_group, &[]);
-            pass.dispatch_workgroups(particle_workgroups, 1, 1);
+            pass.dispatch_workgroups(spatial_workgroups, 1, 1);
         }
 
-        // Phase 3: Prefix sum (multiple passes) - all in same encoder
-        // We ping-pong between buffer A and B
-        let mut src_is_a = true;
-
-        // Use pre-allocated step size uniform buffers for each pass.
-        assert!(
-            (num_passes as usize) <= gpu.spatial_buffers.step_size_uniforms.len(),
-            "Not enough step_size_uniforms: need {} but have {}",
-            num_passes,
-            gpu.spatial_buffers.step_size_uniforms.len()
+        // Phase 3: Prefix sum (in place in buffer A) - all in same encoder
+        let prefix_bind_groups = gpu.spatial_pipelines.create_prefix_sum_bind_groups(
+            &gpu.context.device,
+            &gpu.spatial_buffers.bin_counts_a,
+            &gpu.spatial_buffers.scan_block_sums,
+        );
+        gpu.spatial_pipelines.encode_prefix_sum(
+            encoder,
+            &prefix_bind_groups,
+            &gpu.spatial_buffers.prefix_scan_levels(),
         );
 
-        for pass_idx in 0..num_passes {
-            let (source, dest) = if src_is_a {
-                (
-                    &gpu.spatial_buffers.bin_counts_a,
-                    &gpu.spatial_buffers.bin_counts_b,
-                )
-            } else {
-                (
-                    &gpu.spatial_buffers.bin_counts_b,
-                    &gpu.spatial_buffers.bin_counts_a,
-                )
-            };
-
-            let step_size_buffer = &gpu.spatial_buffers.step_size_uniforms[pass_idx as usize];
-
-            let prefix_bind_group = gpu.spatial_pipelines.create_prefix_sum_bind_group(
-                &gpu.context.device,
-                source,
-                dest,
-                step_size_buffer,
-            );
-
-            {
-                let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-                    label: Some("Prefix Sum Pass"),
-                    timestamp_writes: None,
-                });
-                pass.set_pipeline(&gpu.spatial_pipelines.prefix_sum_pipeline);
-                pass.set_bind_group(0, &prefix_bind_group, &[]);
-                pass.dispatch_workgroups(bin_workgroups, 1, 1);
-            }
-
-            src_is_a = !src_is_a;
-        }
-
-        // Track which buffer has the final prefix sum result
-        gpu.spatial_buffers.current_offset_buffer = if src_is_a { 0 } else { 1 };
-
-        // Phase 4: Clear bin counts for sort (in the OTHER buffer from offsets)
+        // Phase 4: Clear bin counts for sort (buffer B)
         let clear_for_sort_bind_group = gpu.spatial_pipelines.create_clear_bind_group(
             &gpu.context.device,
             &gpu.spatial_buffers,
-            !src_is_a, // The OTHER buffer
+            false, // buffer B
         );
 
         {
@@ -502,9 +581,11 @@ impl AppHandler {
             gpu.buffers.next_pos_type(),
             gpu.buffers.current_velocities(),
             gpu.buffers.next_velocities(),
+            gpu.buffers.current_depths(),
+            gpu.buffers.next_depths(),
             &gpu.spatial_buffers,
-            src_is_a,  // offset buffer
-            !src_is_a, // count buffer (cleared above)
+            true,  // offset buffer
+            false, // count buffer (cleared above)
         );
 
         {
@@ -514,7 +595,7 @@ impl AppHandler {
             });
             pass.set_pipeline(&gpu.spatial_pipelines.sort_pipeline);
             pass.set_bind_group(0, &sort_bind_group, &[]);
-            pass.dispatch_workgroups(particle_workgroups, 1, 1);
+            pass.dispatch_workgroups(spatial_workgroups, 1, 1);
         }
 
         // Phase 6: Compute forces using binned approach
@@ -533,7 +614,7 @@ impl AppHandler {
             &gpu.context.device,
             pos_out,
             vel_out,
-            &gpu.buffers.params,
+            &gpu.buffers,
             &gpu.brush_pipelines.brush_buffer,
         );
 
@@ -545,7 +626,7 @@ impl AppHandler {
             });
             pass.set_pipeline(&gpu.spatial_pipelines.forces_pipeline);
             pass.set_bind_group(0, &forces_bind_group, &[]);
-            pass.dispatch_workgroups(particle_workgroups, 1, 1);
+            pass.dispatch_workgroups(spatial_workgroups, 1, 1);
         }
 
         // Advance pass
@@ -0,0 +1,31 @@
+//! Seeding particles from an image file.
+
+use super::AppHandler;
+use crate::generators::image_seed::load_seed_image;
+
+impl AppHandler {
+    /// Respawn the particles from the image at `image_seed_path`, keeping
+    /// the current rules, palette and particle count.
+    pub(crate) fn spawn_from_image(&mut self) {
+        let path = self.image_seed_path.trim().to_string();
+        let image = match load_seed_image(&path) {
+            Ok(image) => image,
+            Err(e) => {
+                log::error!("Failed to seed from image: {:#}", e);
+                self.preset_status = format!("Image seed failed: {:#}", e);
+                return;
+            }
+        };
+
+        self.app.spawn_from_image(&image);
+        self.sync_buffers();
+        log::info!(
+            "Spawned {} particles from {} ({}x{})",
+            self.app.particles.len(),
+            path,
+            image.width(),
+            image.height()
+        );
+        self.preset_status = format!("Spawned particles from {}", path);
+    }
+}
@@ -6,15 +6,20 @@ use winit::window::{Icon, Window};
 use super::AppHandler;
 use crate::app::gpu_state::{GpuState, MAX_TIMESTAMP_QUERIES, SpatialBindGroupCache};
 use crate::renderer::gpu::{
-    BrushPipelines, ComputePipelines, GpuContext, RenderPipelines, SimulationBuffers,
-    SpatialHashBuffers, SpatialHashPipelines,
+    BackgroundPipeline, BrushPipelines, ComputePipelines, DepthTarget, GpuContext, MsaaTarget,
+    PickPipeline, RenderBuffers, RenderPipelines, SimulationBuffers, SpatialHashBuffers,
+    SpatialHashPipelines, TrailPipelines, VignettePipeline, supported_sample_count,
 };
 
 impl AppHandler {
     pub(crate) fn init_gpu(&mut self, window: Arc<Window>) {
-        // Initialize GPU context using vsync preference from config
-        let context = pollster::block_on(GpuContext::new(window.clone(), self.app.config.vsync))
-            .expect("Failed to create GPU context");
+        // Initialize GPU context using vsync and precision preferences from config
+        let context = pollster::block_on(GpuContext::new(
+            window.clone(),
+            self.app.config.vsync,
+            !self.force_f32,
+        ))
+        .expect("Failed to create GPU context");
 
         // Create simulation buffers
         let colors_rgba = self.app.colors_as_rgba();
@@ -27,10 +32,23 @@ impl AppHandler {
             &self.app.sim_config,
         );
 
+        // Scene passes draw multisampled when MSAA is on and supported
+        let msaa_samples = self.app.config.msaa_samples;
+        let sample_count =
+            supported_sample_count(&context.adapter, context.surface_format(), msaa_samples);
+        let msaa = MsaaTarget::new(context.surface_format(), msaa_samples, sample_count);
+
         // Create pipelines
         let compute = ComputePipelines::new(&context.device);
-        let render = RenderPipelines::new(&context.device, context.surface_format());
-        let spatial_pipelines = SpatialHashPipelines::new(&context.device);
+        let render = RenderPipelines::new(&context.device, context.surface_format(), sample_count);
+        let spatial_pipelines =
+            SpatialHashPipelines::new(&context.device, self.app.config.spatial_workgroup_size);
+        let pick = PickPipeline::new(&context.device);
+        let trail = TrailPipelines::new(&context.device, context.surface_format(), sample_count);
+        let background =
+            BackgroundPipeline::new(&context.device, context.surface_format(), sample_count);
+        let vignette = VignettePipeline::new(&context.device, context.surface_format());
+        let render_buffers = RenderBuffers::new(&context.device);
 
         // Create spatial hash buffers (cell size clamped to max interaction radius)
         let max_radius = self.app.radius_matrix.max_interaction_radius();
@@ -46,15 +64,25 @@ impl AppHandler {
         );
 
         // Create initial render bind groups (will be recreated each frame for GPU compute)
-        let render_bind_group =
-            render.create_render_bind_group(&context.device, buffers.current_pos_type(), &buffers);
-        let glow_bind_group =
-            render.create_glow_bind_group(&context.device, buffers.current_pos_type(), &buffers);
-        let mirror_bind_group =
-            render.create_mirror_bind_group(&context.device, buffers.current_pos_type(), &buffers);
+        let render_bind_group = render.create_render_bind_group(
+            &context.device,
+            buffers.current_pos_type(),
+            buffers.current_velocities(),
+            buffers.current_depths(),
+            &buffers,
+        );
+        let mirror_bind_group = render.create_mirror_bind_group(
+            &context.device,
+            buffers.current_pos_type(),
+            buffers.current_velocities(),
+            buffers.current_depths(),
+            &buffers,
+        );
         let infinite_bind_group = render.create_infinite_bind_group(
             &context.device,
             buffers.current_pos_type(),
+            buffers.current_velocities(),
+            buffers.current_depths(),
             &buffers,
         );
 
@@ -67,6 +95,14 @@ impl AppHandler {
             context.surface_config.height as f32,
         );
 
+        render.update_colormap(
+            &context.queue,
+            &crate::generators::colors::generate_colors(
+                self.app.sim_config.colormap,
+                crate::renderer::gpu::COLORMAP_SIZE as usize,
+            ),
+        );
+
         // Initialize egui
         let egui_ctx = egui::Context::default();
 
@@ -133,9 +169,16 @@ impl AppHandler {
             timestamp_labels: Vec::new(),
             timestamps_supported,
             brush_pipelines,
+            pick,
+            trail,
+            background,
+            vignette,
+            msaa,
+            depth: DepthTarget::default(),
+            render_buffers,
             _brush_bind_group: brush_bind_group,
             render_bind_group,
-            glow_bind_group,
+            glow_bind_group: None,
             mirror_bind_group,
             infinite_bind_group,
             egui_ctx,
@@ -148,7 +191,7 @@ impl AppHandler {
             gpu.spatial_bind_groups.ensure(
                 &gpu.context.device,
                 &gpu.buffers,
-                &mut gpu.spatial_buffers,
+                &gpu.spatial_buffers,
                 &gpu.spatial_pipelines,
             );
         }
@@ -0,0 +1,72 @@
+//! Exploration lineage navigation: record and restore randomized states.
+
+use super::AppHandler;
+use crate::app::ExplorationState;
+
+impl AppHandler {
+    /// Push the current generator state onto the exploration lineage.
+    ///
+    /// Call after every randomize (rules, palette, pattern, type count).
+    pub(crate) fn record_exploration(&mut self) {
+        self.lineage.record(ExplorationState::capture(&self.app));
+    }
+
+    /// Step back to the previous exploration.
+    pub(crate) fn exploration_back(&mut self) {
+        if let Some(state) = self.lineage.back().cloned() {
+            self.restore_exploration(state);
+        }
+    }
+
+    /// Step forward to the next exploration.
+    pub(crate) fn exploration_forward(&mut self) {
+        if let Some(state) = self.lineage.forward().cloned() {
+            self.restore_exploration(state);
+        }
+    }
+
+    /// Jump to the exploration at `index` in the lineage.
+    pub(crate) fn exploration_go_to(&mut self, index: usize) {
+        if let Some(state) = self.lineage.go_to(index).cloned() {
+            self.restore_exploration(state);
+        }
+    }
+
+    /// Restore a recorded exploration exactly, respawning particles only
+    /// when the pattern or type count changed.
+    pub(crate) fn restore_exploration(&mut self, state: ExplorationState) {
+        let old_max_radius = self.app.radius_matrix.max_interaction_radius();
+        let respawn = state.pattern != self.app.current_pattern
+            || state.num_types != self.app.sim_config.num_types;
+
+        self.app.current_rule = state.rule;
+        self.app.current_palette = state.palette;
+        self.app.current_pattern = state.pattern;
+        self.app.config.gen_rule = state.rule;
+        self.app.config.gen_palette = state.palette;
+        self.app.config.gen_pattern = state.pattern;
+        self.app.sim_config.num_types = state.num_types;
+        self.app.config.sim_num_types = state.num_types;
+        self.app.interaction_matrix = state.interaction_matrix;
+        self.app.radius_matrix = state.radius_matrix;
+        self.app.palette_colors = state.colors;
+        self.app.apply_palette_jitter();
+
+        if respawn {
+            self.app.regenerate_particles();
+            self.sync_buffers();
+        } else {
+            self.sync_interaction_matrix();
+            self.sync_radius_matrix();
+            self.sync_colors();
+
+            // The spatial hash cell must cover the largest interaction range
+            let max_r = self.app.radius_matrix.max_interaction_radius();
+            if max_r != old_max_radius {
+                self.app.sim_config.spatial_hash_cell_size =
+                    self.app.config.render_spatial_hash_cell_size.max(max_r);
+                self.sync_spatial_buffers();
+            }
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! CSV metrics logging of per-type population and kinetic energy.
+
+use std::path::PathBuf;
+
+use super::AppHandler;
+use crate::metrics_logger::MetricsLogger;
+
+impl AppHandler {
+    /// Start or stop metrics logging to match the UI checkbox.
+    pub(crate) fn set_metrics_logging(&mut self, enabled: bool) {
+        if enabled && self.metrics_logger.is_none() {
+            self.start_metrics_logging();
+        } else if !enabled {
+            self.stop_metrics_logging();
+        }
+    }
+
+    /// Open the CSV file: the chosen path, or a timestamped file in the
+    /// videos directory when none is set.
+    fn start_metrics_logging(&mut self) {
+        let chosen = self.metrics_log_path.trim();
+        let path = if chosen.is_empty() {
+            match Self::ensure_videos_dir() {
+                Ok(dir) => {
+                    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+                    dir.join(format!("metrics_{}.csv", timestamp))
+                }
+                Err(e) => {
+                    log::error!("Failed to create videos directory: {}", e);
+                    self.preset_status = format!("Metrics logging failed: {}", e);
+                    return;
+                }
+            }
+        } else {
+            PathBuf::from(chosen)
+        };
+
+        match MetricsLogger::create(&path, self.app.sim_config.num_types) {
+            Ok(logger) => {
+                log::info!("Started metrics logging: {}", path.display());
+                self.preset_status = format!("Logging metrics to {}", path.display());
+                self.metrics_logger = Some(logger);
+                self.metrics_step = 0;
+            }
+            Err(e) => {
+                log::error!("Failed to start metrics logging: {}", e);
+                self.preset_status = format!("Metrics logging failed: {}", e);
+            }
+        }
+    }
+
+    /// Stop metrics logging and flush the CSV file.
+    pub(crate) fn stop_metrics_logging(&mut self) {
+        let Some(logger) = self.metrics_logger.take() else {
+            return;
+        };
+        let rows = logger.rows_written();
+        match logger.finish() {
+            Ok(path) => {
+                let path = path.display().to_string();
+                log::info!("Saved {} metrics rows to {}", rows, path);
+                self.preset_status = format!("Saved {} metrics rows", rows);
+                self.last_capture_path = Some(path);
+            }
+            Err(e) => {
+                log::error!("Failed to finish metrics log: {}", e);
+                self.preset_status = format!("Metrics logging failed: {}", e);
+            }
+        }
+    }
+
+    /// Log a metrics row if this step is due.
+    ///
+    /// Called once per simulation step. Blocks on a full particle readback,
+    /// hence the interval.
+    pub(crate) fn log_metrics_frame(&mut self) {
+        let Some(logger) = self.metrics_logger.as_mut() else {
+            return;
+        };
+        let step = self.metrics_step;
+        self.metrics_step += 1;
+        if !step.is_multiple_of(u64::from(self.metrics_interval.max(1))) {
+            return;
+        }
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+
+        let particles = gpu
+            .buffers
+            .read_particles(&gpu.context.device, &gpu.context.queue);
+        if let Err(e) = logger.write_row(step, &particles) {
+            log::error!("Metrics logging stopped: {}", e);
+            self.preset_status = format!("Metrics logging stopped: {}", e);
+            self.stop_metrics_logging();
+        }
+    }
+}
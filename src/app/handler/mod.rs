@@ -3,22 +3,52 @@
 //! This module contains the `AppHandler` struct which manages the application
 //! lifecycle, including GPU initialization, event handling, rendering, and UI.
 
+mod autosave;
 mod brush;
 mod buffer_sync;
+mod cpu_compare;
+mod cycle;
+mod display;
 mod events;
+mod exit;
+mod gamepad;
 mod gpu_compute;
+mod image_seed;
 mod init;
+mod lineage;
+mod metrics;
+mod pick;
 mod presets_ops;
+mod quality;
 mod recording;
 mod render;
+mod reset;
+mod rules_import;
+mod shared_state;
+mod stagnation;
+mod state_capture;
+mod svg_export;
+mod thermostat;
+mod time_reversal;
+mod type_labels;
 mod ui;
+mod undo;
 mod update;
+mod view;
 
+use std::collections::HashMap;
 use std::time::Instant;
 
-use crate::app::gpu_state::GpuState;
-use crate::app::{App, BrushState, CameraState, Preset};
+use crate::app::gpu_state::{GpuState, PassToggles};
+use crate::app::{
+    AdaptiveQuality, App, AutoRecord, BrushState, CameraState, ExplorationState, Lineage, Preset,
+    QualitySettings, RecoveryState, StagnationWatchdog, UndoHistory, WindowOptions,
+};
+use crate::metrics_logger::{DEFAULT_METRICS_INTERVAL, MetricsLogger};
+use crate::simulation::{StepParity, TrajectoryDivergence};
+use crate::state_recorder::{StatePlayer, StateRecorder};
 use crate::video_recorder::{VideoFormat, VideoRecorder};
+use pick::InspectedParticle;
 
 /// Application handler for the winit event loop.
 pub(crate) struct AppHandler {
@@ -38,6 +68,11 @@ pub(crate) struct AppHandler {
     pub(crate) fps: f32,
     /// Smoothed FPS (EMA) to reduce jitter in the HUD.
     pub(crate) fps_ema: f32,
+    /// Run exactly one simulation step on the next update while paused.
+    pub(crate) step_requested: bool,
+    /// Leave the next frame time out of the FPS average (it spans a
+    /// single step, which isn't representative).
+    pub(crate) skip_fps_sample: bool,
     /// Show UI sidebar.
     pub(crate) show_ui: bool,
     /// UI: Is Simulation section open?
@@ -56,24 +91,49 @@ pub(crate) struct AppHandler {
     pub(crate) ui_presets_open: bool,
     /// UI: Is Keyboard Shortcuts section open?
     pub(crate) ui_keyboard_shortcuts_open: bool,
+    /// UI: Is Debug section open?
+    pub(crate) ui_debug_open: bool,
     /// Available presets list.
     pub(crate) preset_list: Vec<String>,
-    /// Currently selected preset name for loading.
+    /// Monitor and fullscreen selection.
+    pub(crate) window_options: WindowOptions,
+    /// Current palette hue rotation in degrees.
+    pub(crate) palette_hue_offset: f32,
+    /// Favorite presets shown in the hotbar.
+    pub(crate) favorite_presets: Vec<String>,
+    /// Preset thumbnails loaded as egui textures (`None` = no thumbnail).
+    pub(crate) preset_thumbnails: HashMap<String, Option<egui::TextureHandle>>,
+    /// Preset whose thumbnail is captured from the next rendered frame.
+    pub(crate) pending_preset_thumbnail: Option<String>,
+    /// Currently selected preset key for loading.
     pub(crate) selected_preset: String,
+    /// Category shown in the preset picker.
+    pub(crate) preset_category: String,
     /// Name for saving new preset.
     pub(crate) save_preset_name: String,
+    /// Category for saving new preset (empty = uncategorized).
+    pub(crate) save_preset_category: String,
     /// Status message for preset operations.
     pub(crate) preset_status: String,
     /// Last captured file path (screenshot or video) for "Open" button.
     pub(crate) last_capture_path: Option<String>,
     /// Screenshot requested flag.
     pub(crate) screenshot_requested: bool,
+    /// Copy-screenshot-to-clipboard requested flag.
+    pub(crate) clipboard_requested: bool,
     /// Screenshot counter for unique filenames.
     pub(crate) screenshot_counter: u32,
     /// Video recording active flag.
     pub(crate) is_recording: bool,
     /// Hide UI when capturing screenshots/recordings.
     pub(crate) capture_hide_ui: bool,
+    /// While recording, step the simulation by a fixed dt per update instead
+    /// of wall-clock time so every recorded frame covers the same sim time.
+    pub(crate) deterministic_recording: bool,
+    /// Cap SVG exports to `svg_max_particles` by subsampling.
+    pub(crate) svg_cap_particles: bool,
+    /// Maximum circles written when SVG capping is enabled.
+    pub(crate) svg_max_particles: usize,
     /// Recorded frames for native GIF export (fallback when ffmpeg unavailable).
     pub(crate) recorded_frames: Vec<image::RgbaImage>,
     /// Frame skip counter for recording (record every N frames).
@@ -90,18 +150,125 @@ pub(crate) struct AppHandler {
     pub(crate) use_ffmpeg: bool,
     /// Flag to stop recording after current frame (avoids borrow conflicts).
     pub(crate) pending_stop_recording: bool,
+    /// Disk-backed particle state recorder (active while capturing).
+    pub(crate) state_recorder: Option<StateRecorder>,
+    /// Record a state frame every N simulation steps.
+    pub(crate) state_record_interval: u32,
+    /// Simulation steps since the state capture started.
+    pub(crate) state_record_step: u64,
+    /// CSV metrics logger (active while logging).
+    pub(crate) metrics_logger: Option<MetricsLogger>,
+    /// Log a metrics row every N simulation steps.
+    pub(crate) metrics_interval: u32,
+    /// Simulation steps since metrics logging started.
+    pub(crate) metrics_step: u64,
+    /// CSV path for metrics logging (empty = timestamped file in videos dir).
+    pub(crate) metrics_log_path: String,
+    /// Loaded state recording for playback.
+    pub(crate) state_player: Option<StatePlayer>,
+    /// Current playback frame index.
+    pub(crate) state_player_frame: usize,
+    /// Advance playback one frame per update.
+    pub(crate) state_playing: bool,
+    /// Path of the state recording to load for playback.
+    pub(crate) state_player_path: String,
+    /// Path to an external attraction-matrix JSON to import.
+    pub(crate) rules_import_path: String,
+    /// Path of the state file to export to or import from.
+    pub(crate) state_file_path: String,
+    /// Path to an image to seed particle positions and types from.
+    pub(crate) image_seed_path: String,
     /// Camera state for pan/zoom.
     pub(crate) camera: CameraState,
+    /// Game controller input; `None` when no controller backend exists.
+    #[cfg(feature = "gamepad")]
+    pub(crate) gamepad: Option<crate::app::GamepadPoller>,
     /// Brush state for user interaction tools.
     pub(crate) brush: BrushState,
     /// Current mouse position in screen coordinates.
     pub(crate) mouse_screen_pos: glam::Vec2,
+    /// Current keyboard modifiers (Ctrl+click pins a brush point).
+    pub(crate) modifiers: winit::keyboard::ModifiersState,
     /// Flag indicating particles were modified and need GPU buffer sync.
     pub(crate) needs_sync: bool,
     /// Flag indicating spatial hash buffers need recreating (e.g., cell size changed).
     pub(crate) needs_sync_spatial_buffers: bool,
     /// Last time metrics were logged.
     pub(crate) last_log_time: Instant,
+    /// Lower bound for randomized self-interaction values.
+    pub(crate) self_interaction_min: f32,
+    /// Upper bound for randomized self-interaction values.
+    pub(crate) self_interaction_max: f32,
+    /// Value applied to all diagonal entries by the global slider.
+    pub(crate) global_self_interaction: f32,
+    /// Type shift used by the cyclic symmetry tool.
+    pub(crate) cyclic_period: usize,
+    /// Absolute sum per row/column targeted by the normalization tools.
+    pub(crate) matrix_normalize_target: f32,
+    /// Matrix cell being drag-edited and its unsnapped value.
+    pub(crate) matrix_drag: Option<(usize, usize, f32)>,
+    /// Show the force-vs-distance plot under the matrix editor.
+    pub(crate) show_force_curve: bool,
+    /// Type pair (from, to) plotted by the force curve.
+    pub(crate) force_curve_pair: (usize, usize),
+    /// Debug toggles for individual compute/render passes.
+    pub(crate) pass_toggles: PassToggles,
+    /// Debug readout: inspect the particle under (or nearest to) the cursor.
+    pub(crate) debug_pick: bool,
+    /// Particle shown by the inspector readout.
+    pub(crate) inspected_particle: Option<InspectedParticle>,
+    /// Time of the last inspector readback.
+    pub(crate) last_inspect: Instant,
+    /// Time of the last Fit view readback.
+    pub(crate) last_fit: Instant,
+    /// Label particles with their type index when zoomed in.
+    pub(crate) show_type_labels: bool,
+    /// Periodically compare the GPU step against a CPU reference step.
+    pub(crate) cpu_compare: bool,
+    /// Last GPU vs CPU divergence measurement.
+    pub(crate) cpu_compare_result: Option<TrajectoryDivergence>,
+    /// Last time a GPU vs CPU comparison ran.
+    pub(crate) last_cpu_compare: Instant,
+    /// Last one-step GPU/CPU parity check and its score (1.0 = at tolerance).
+    pub(crate) step_parity: Option<(StepParity, f32)>,
+    /// Recovery scene from an unclean exit, awaiting restore/discard.
+    pub(crate) pending_recovery: Option<RecoveryState>,
+    /// Preset given on the command line (name, preset), applied once the
+    /// GPU is ready.
+    pub(crate) startup_preset: Option<(String, Preset)>,
+    /// Auto-record request from the command line, started once the GPU is
+    /// ready.
+    pub(crate) auto_record: Option<AutoRecord>,
+    /// An auto-record session is running; the app quits when it stops.
+    pub(crate) auto_recording: bool,
+    /// Frames still to capture before the auto-record session stops.
+    pub(crate) auto_record_frames_left: u32,
+    /// Last time the scene was autosaved.
+    pub(crate) last_autosave: Instant,
+    /// Stagnation watchdog state.
+    pub(crate) stagnation: StagnationWatchdog,
+    /// Adaptive quality controller.
+    pub(crate) adaptive_quality: AdaptiveQuality,
+    /// User settings overridden by adaptive quality (None at full quality).
+    pub(crate) quality_base: Option<QualitySettings>,
+    /// Last time the mean speed was sampled for the watchdog.
+    pub(crate) last_stagnation_check: Instant,
+    /// Most recent mean particle speed sample.
+    pub(crate) mean_speed: f32,
+    /// Last time the kinetic temperature was sampled.
+    pub(crate) last_temperature_check: Instant,
+    /// Most recent kinetic temperature sample (mean `0.5 * |v|^2`).
+    pub(crate) temperature: f32,
+    /// Per-step velocity multiplier set by the thermostat (1.0 = off).
+    pub(crate) velocity_scale: f32,
+    /// Back/forward history of randomized explorations.
+    pub(crate) lineage: Lineage,
+    /// Undo/redo history of matrix and generator edits.
+    pub(crate) undo_history: UndoHistory,
+    /// Transient HUD message and when it was shown.
+    pub(crate) hud_message: Option<(String, Instant)>,
+    /// Force f32 storage for this session (config setting or `--no-f16`).
+    pub(crate) force_f32: bool,
 }
 
 impl AppHandler {
@@ -144,9 +311,21 @@ impl AppHandler {
         Ok(dir)
     }
 
-    pub(crate) fn new(reset_config: bool) -> Self {
+    pub(crate) fn new(
+        reset_config: bool,
+        no_f16: bool,
+        window: WindowOptions,
+        startup_preset: Option<(String, Preset)>,
+        auto_record: Option<AutoRecord>,
+    ) -> Self {
         let app = App::new(reset_config);
+        let window_options = WindowOptions {
+            monitor: window.monitor.or(app.config.window_monitor),
+            ..window
+        };
+        let force_f32 = no_f16 || app.config.force_f32;
         let preset_list = Preset::list_presets().unwrap_or_default();
+        let favorite_presets = Preset::list_favorites().unwrap_or_default();
 
         // Capture config values before moving 'app'
         let ui_simulation_open = app.config.ui_simulation_open;
@@ -157,6 +336,18 @@ impl AppHandler {
         let ui_rendering_open = app.config.ui_rendering_open;
         let ui_presets_open = app.config.ui_presets_open;
         let ui_keyboard_shortcuts_open = app.config.ui_keyboard_shortcuts_open;
+        let ui_debug_open = app.config.ui_debug_open;
+
+        let pending_recovery = match RecoveryState::load() {
+            Ok(state) => state,
+            Err(e) => {
+                log::warn!("Ignoring unreadable recovery file: {}", e);
+                None
+            }
+        };
+        if let Some(state) = &pending_recovery {
+            log::info!("Found recovery scene autosaved at {}", state.saved_at);
+        }
 
         let mouse_screen_pos = glam::Vec2::ZERO;
         let last_log_time = Instant::now();
@@ -173,7 +364,14 @@ impl AppHandler {
             "  Spatial Hash Cell Size: {}",
             app.sim_config.spatial_hash_cell_size
         );
-        log::info!("  F16 Mode: Enabled (if supported)");
+        if force_f32 {
+            log::info!("  F16 Mode: Disabled (forced f32)");
+        } else {
+            log::info!("  F16 Mode: Enabled (if supported)");
+        }
+
+        let mut lineage = Lineage::default();
+        lineage.record(ExplorationState::capture(&app));
 
         Self {
             app,
@@ -184,6 +382,8 @@ impl AppHandler {
             last_fps_time: Instant::now(),
             fps: 0.0,
             fps_ema: 0.0,
+            step_requested: false,
+            skip_fps_sample: false,
             show_ui: true,
             ui_simulation_open,
             ui_physics_open,
@@ -193,15 +393,27 @@ impl AppHandler {
             ui_rendering_open,
             ui_presets_open,
             ui_keyboard_shortcuts_open,
+            ui_debug_open,
             preset_list,
+            favorite_presets,
+            preset_thumbnails: HashMap::new(),
+            pending_preset_thumbnail: None,
+            palette_hue_offset: 0.0,
+            window_options,
             selected_preset: String::new(),
+            preset_category: String::new(),
             save_preset_name: String::from("my_preset"),
+            save_preset_category: String::new(),
             preset_status: String::new(),
             last_capture_path: None,
             screenshot_requested: false,
+            clipboard_requested: false,
             screenshot_counter: 0,
             is_recording: false,
             capture_hide_ui: true,
+            deterministic_recording: false,
+            svg_cap_particles: true,
+            svg_max_particles: crate::svg_export::SVG_PARTICLE_WARN_THRESHOLD,
             recorded_frames: Vec::new(),
             video_frame_skip: 2,
             video_frame_counter: 0,
@@ -210,12 +422,65 @@ impl AppHandler {
             video_format: VideoFormat::MP4,
             use_ffmpeg: true,
             pending_stop_recording: false,
+            state_recorder: None,
+            state_record_interval: 1,
+            state_record_step: 0,
+            metrics_logger: None,
+            metrics_interval: DEFAULT_METRICS_INTERVAL,
+            metrics_step: 0,
+            metrics_log_path: String::new(),
+            state_player: None,
+            state_player_frame: 0,
+            state_playing: false,
+            state_player_path: String::new(),
+            rules_import_path: String::new(),
+            state_file_path: String::new(),
+            image_seed_path: String::new(),
             camera: CameraState::default(),
+            #[cfg(feature = "gamepad")]
+            gamepad: crate::app::GamepadPoller::new(),
             brush: BrushState::default(),
             mouse_screen_pos,
+            modifiers: winit::keyboard::ModifiersState::empty(),
             needs_sync: false,
             needs_sync_spatial_buffers: false,
             last_log_time,
+            self_interaction_min: -0.3,
+            self_interaction_max: 0.6,
+            global_self_interaction: 0.0,
+            cyclic_period: 1,
+            matrix_normalize_target: 1.0,
+            matrix_drag: None,
+            show_force_curve: false,
+            force_curve_pair: (0, 0),
+            pass_toggles: PassToggles::default(),
+            debug_pick: false,
+            inspected_particle: None,
+            last_inspect: Instant::now(),
+            last_fit: Instant::now(),
+            show_type_labels: false,
+            cpu_compare: false,
+            cpu_compare_result: None,
+            last_cpu_compare: Instant::now(),
+            step_parity: None,
+            pending_recovery,
+            startup_preset,
+            auto_record,
+            auto_recording: false,
+            auto_record_frames_left: 0,
+            last_autosave: Instant::now(),
+            stagnation: StagnationWatchdog::default(),
+            adaptive_quality: AdaptiveQuality::default(),
+            quality_base: None,
+            last_stagnation_check: Instant::now(),
+            mean_speed: 0.0,
+            last_temperature_check: Instant::now(),
+            temperature: 0.0,
+            velocity_scale: 1.0,
+            lineage,
+            undo_history: UndoHistory::default(),
+            hud_message: None,
+            force_f32,
         }
     }
 }
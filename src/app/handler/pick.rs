@@ -0,0 +1,126 @@
+//! Picking the particle under the cursor.
+
+use std::time::Instant;
+
+use super::AppHandler;
+use crate::renderer::gpu::CameraUniform;
+use crate::simulation::{Particle, nearest_particle};
+
+/// Seconds between inspector refreshes; each one reads all particles back.
+const INSPECT_INTERVAL_SECS: f32 = 0.1;
+
+/// Particle shown by the inspector readout.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct InspectedParticle {
+    /// Index in the GPU buffer order at the time of the readback.
+    pub(crate) index: usize,
+    /// Particle state from the readback.
+    pub(crate) particle: Particle,
+    /// Whether the particle's sprite covers the cursor (otherwise it is just
+    /// the nearest one).
+    pub(crate) under_cursor: bool,
+    /// World distance from the cursor to the particle's nearest image.
+    pub(crate) distance: f32,
+}
+
+impl AppHandler {
+    /// Refresh the inspected particle from a throttled particle readback.
+    ///
+    /// The GPU pick decides when a sprite covers the cursor. Otherwise, and
+    /// for mirror/infinite-wrap copies the pick pass can't see, the particle
+    /// whose nearest image is closest to the cursor's world position is
+    /// shown instead.
+    pub(crate) fn update_inspected_particle(&mut self, now: Instant) {
+        if now.duration_since(self.last_inspect).as_secs_f32() < INSPECT_INTERVAL_SECS {
+            return;
+        }
+        self.last_inspect = now;
+
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+        let screen_size = glam::Vec2::new(
+            gpu.context.surface_config.width as f32,
+            gpu.context.surface_config.height as f32,
+        );
+        // Pick and read back together so the index matches the data
+        let picked = self.pick_particle(self.mouse_screen_pos);
+        let particles = gpu
+            .buffers
+            .read_particles(&gpu.context.device, &gpu.context.queue);
+
+        let world_pos = self.camera.screen_to_world(
+            self.mouse_screen_pos,
+            screen_size,
+            self.app.sim_config.world_size,
+        );
+        let hit = match picked {
+            Some(index) if (index as usize) < particles.len() => {
+                let index = index as usize;
+                let distance = (particles[index].position() - world_pos).length();
+                Some((index, distance, true))
+            }
+            _ => nearest_particle(&particles, world_pos, &self.app.sim_config)
+                .map(|(index, distance)| (index, distance, false)),
+        };
+        self.inspected_particle = hit.map(|(index, distance, under_cursor)| InspectedParticle {
+            index,
+            particle: particles[index],
+            under_cursor,
+            distance,
+        });
+    }
+
+    /// Return the index of the particle drawn under `screen_pos`, if any.
+    ///
+    /// Runs a dedicated 1×1 GPU pick pass through the same camera transform
+    /// as rendering, so zoom and pan are respected. When sprites overlap, the
+    /// particle whose center is nearest to the cursor wins. Returns `None`
+    /// when no particle disk covers the pixel (empty space, or the cursor is
+    /// outside the viewport).
+    ///
+    /// The index refers to the current GPU buffer order. The spatial hash
+    /// re-sorts particles every step, so indices are only valid until the
+    /// next simulation step. Mirror and infinite-wrap copies are not
+    /// pickable; only the primary instance of each particle is.
+    pub(crate) fn pick_particle(&self, screen_pos: glam::Vec2) -> Option<u32> {
+        let gpu = self.gpu.as_ref()?;
+        let screen_size = glam::Vec2::new(
+            gpu.context.surface_config.width as f32,
+            gpu.context.surface_config.height as f32,
+        );
+        if screen_pos.x < 0.0
+            || screen_pos.y < 0.0
+            || screen_pos.x >= screen_size.x
+            || screen_pos.y >= screen_size.y
+        {
+            return None;
+        }
+
+        let world_size = self.app.sim_config.world_size;
+        let world_pos = self
+            .camera
+            .screen_to_world(screen_pos, screen_size, world_size);
+        let camera = CameraUniform::with_zoom_and_offset(
+            world_size.x,
+            world_size.y,
+            self.camera.zoom,
+            self.camera.offset.x,
+            self.camera.offset.y,
+        )
+        .for_pixel(world_pos.x, world_pos.y, screen_size.x, screen_size.y);
+
+        let bind_group = gpu.pick.create_bind_group(
+            &gpu.context.device,
+            gpu.buffers.current_pos_type(),
+            &gpu.buffers,
+        );
+        gpu.pick.pick(
+            &gpu.context.device,
+            &gpu.context.queue,
+            &bind_group,
+            &camera,
+            gpu.buffers.num_particles,
+        )
+    }
+}
@@ -6,11 +6,84 @@ use crate::app::Preset;
 impl AppHandler {
     pub(crate) fn refresh_presets(&mut self) {
         self.preset_list = Preset::list_presets().unwrap_or_default();
+        self.favorite_presets = Preset::list_favorites().unwrap_or_default();
+        self.preset_thumbnails.clear();
     }
 
+    /// Thumbnail texture for a preset, loaded from disk on first use.
+    pub(crate) fn preset_thumbnail(
+        &mut self,
+        ctx: &egui::Context,
+        name: &str,
+    ) -> Option<egui::TextureHandle> {
+        self.preset_thumbnails
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                let image = Preset::load_thumbnail(name)?;
+                let size = [image.width() as usize, image.height() as usize];
+                let color = egui::ColorImage::from_rgba_unmultiplied(size, image.as_raw());
+                Some(ctx.load_texture(
+                    format!("preset_thumbnail_{}", name),
+                    color,
+                    egui::TextureOptions::LINEAR,
+                ))
+            })
+            .clone()
+    }
+
+    /// Save a captured frame (without UI) as the pending preset's thumbnail.
+    pub(crate) fn save_preset_thumbnail(&mut self, frame: Option<&image::RgbaImage>) {
+        let Some(name) = self.pending_preset_thumbnail.take() else {
+            return;
+        };
+        let Some(frame) = frame else {
+            log::warn!("Failed to capture thumbnail for preset {}", name);
+            return;
+        };
+        match Preset::save_thumbnail(&name, frame) {
+            Ok(()) => {
+                self.preset_thumbnails.remove(&name);
+                log::info!("Saved thumbnail for preset {}", name);
+            }
+            Err(e) => log::error!("Failed to save preset thumbnail: {}", e),
+        }
+    }
+
+    /// Toggle whether a saved preset appears in the favorites hotbar.
+    pub(crate) fn toggle_favorite(&mut self, name: &str) {
+        let favorite = !self.favorite_presets.iter().any(|f| f == name);
+        match Preset::set_favorite(name, favorite) {
+            Ok(()) => {
+                self.refresh_presets();
+                self.preset_status = if favorite {
+                    format!("Added to favorites: {}", name)
+                } else {
+                    format!("Removed from favorites: {}", name)
+                };
+            }
+            Err(e) => {
+                self.preset_status = format!("Error: {}", e);
+                log::error!("Failed to update favorite: {}", e);
+            }
+        }
+    }
+
+    /// Load the favorite in hotbar `slot` (0-based), if any.
+    ///
+    /// Switching to a preset rebuilds the GPU buffers, so expect a short
+    /// hitch, most noticeable when the particle count is large.
+    pub(crate) fn load_favorite(&mut self, slot: usize) {
+        if let Some(name) = self.favorite_presets.get(slot).cloned() {
+            self.selected_preset = name.clone();
+            self.load_preset(&name);
+        }
+    }
+
+    /// Save the current state as the preset with key `name`
+    /// (`category/name` for a categorized preset, see [`Preset::key_for`]).
     pub(crate) fn save_preset(&mut self, name: &str) {
-        let preset = Preset::new(
-            name,
+        let mut preset = Preset::new(
+            Preset::name_of(name),
             &self.app.sim_config,
             &self.app.interaction_matrix,
             &self.app.radius_matrix,
@@ -18,13 +91,17 @@ impl AppHandler {
             self.app.current_palette,
             self.app.current_pattern,
         );
+        // Overwriting a favorite keeps it in the hotbar
+        preset.favorite = self.favorite_presets.iter().any(|f| f == name);
+        preset.camera = Some(self.camera);
 
-        match Preset::ensure_presets_dir() {
-            Ok(dir) => {
-                let path = dir.join(format!("{}.json", name));
+        match Preset::ensure_path_for(name) {
+            Ok(path) => {
                 match preset.save_to_file(&path) {
                     Ok(()) => {
                         self.preset_status = format!("Saved: {}", name);
+                        // Captured from the next rendered frame
+                        self.pending_preset_thumbnail = Some(name.to_string());
                         self.refresh_presets();
                         log::info!("Saved preset to {}", path.display());
                     }
@@ -42,67 +119,11 @@ impl AppHandler {
     }
 
     pub(crate) fn load_preset(&mut self, name: &str) {
-        let dir = Preset::presets_dir();
-        let path = dir.join(format!("{}.json", name));
+        let path = Preset::path_for(name);
 
         match Preset::load_from_file(&path) {
             Ok(preset) => {
-                // Apply the preset
-                self.app.sim_config = preset.sim_config;
-                self.app.interaction_matrix = preset.interaction_matrix;
-                self.app.radius_matrix = preset.radius_matrix;
-                self.app.current_rule = preset.rule_type;
-                self.app.current_palette = preset.palette_type;
-                self.app.current_pattern = preset.position_pattern;
-
-                // Mirror into persisted config so settings survive restart
-                self.app.config.sim_num_particles = self.app.sim_config.num_particles;
-                self.app.config.sim_num_types = self.app.sim_config.num_types;
-                self.app.config.phys_force_factor = self.app.sim_config.force_factor;
-                self.app.config.phys_friction = self.app.sim_config.friction;
-                self.app.config.phys_repel_strength = self.app.sim_config.repel_strength;
-                self.app.config.phys_max_velocity = self.app.sim_config.max_velocity;
-                self.app.config.phys_boundary_mode = self.app.sim_config.boundary_mode;
-                self.app.config.phys_wall_repel_strength = self.app.sim_config.wall_repel_strength;
-                self.app.config.phys_mirror_wrap_count = self.app.sim_config.mirror_wrap_count;
-                self.app.config.gen_rule = self.app.current_rule;
-                self.app.config.gen_palette = self.app.current_palette;
-                self.app.config.gen_pattern = self.app.current_pattern;
-                self.app.config.render_particle_size = self.app.sim_config.particle_size;
-                self.app.config.render_background_color = self.app.sim_config.background_color;
-                self.app.config.render_glow_enabled = self.app.sim_config.enable_glow;
-                self.app.config.render_glow_intensity = self.app.sim_config.glow_intensity;
-                self.app.config.render_glow_size = self.app.sim_config.glow_size;
-                self.app.config.render_glow_steepness = self.app.sim_config.glow_steepness;
-                self.app.config.render_spatial_hash_cell_size =
-                    self.app.sim_config.spatial_hash_cell_size;
-
-                // Regenerate colors from palette
-                self.app.colors = crate::generators::colors::generate_colors(
-                    self.app.current_palette,
-                    self.app.sim_config.num_types as usize,
-                );
-
-                // Regenerate particles from pattern
-                let spawn_config = crate::generators::positions::SpawnConfig {
-                    num_particles: self.app.sim_config.num_particles as usize,
-                    num_types: self.app.sim_config.num_types as usize,
-                    width: self.app.sim_config.world_size.x,
-                    height: self.app.sim_config.world_size.y,
-                };
-                self.app.particles = crate::generators::positions::generate_positions(
-                    self.app.current_pattern,
-                    &spawn_config,
-                );
-
-                // Resize physics engine
-                self.app.physics.resize(self.app.particles.len());
-
-                // Sync GPU buffers
-                self.sync_buffers();
-                self.sync_interaction_matrix();
-                self.sync_colors();
-
+                self.apply_preset(preset);
                 self.preset_status = format!("Loaded: {}", name);
                 log::info!("Loaded preset: {}", name);
             }
@@ -112,4 +133,87 @@ impl AppHandler {
             }
         }
     }
+
+    /// Apply a preset to the running simulation, regenerating colors and
+    /// particles and syncing all GPU buffers.
+    pub(crate) fn apply_preset(&mut self, preset: Preset) {
+        self.restore_quality();
+        let saved_world_size = preset.sim_config.world_size;
+        self.app.sim_config = preset.sim_config;
+        self.app.interaction_matrix = preset.interaction_matrix;
+        self.app.radius_matrix = preset.radius_matrix;
+        self.app.current_rule = preset.rule_type;
+        self.app.current_palette = preset.palette_type;
+        self.app.current_pattern = preset.position_pattern;
+
+        // Mirror into persisted config so settings survive restart
+        self.app.config.sim_num_particles = self.app.sim_config.num_particles;
+        self.app.config.sim_num_types = self.app.sim_config.num_types;
+        self.app.config.sim_seed = self.app.sim_config.seed;
+        self.app.config.sim_type_names = self.app.sim_config.type_names.clone();
+        self.app.config.phys_force_factor = self.app.sim_config.force_factor;
+        self.app.config.phys_matrix_strength = self.app.sim_config.matrix_strength;
+        self.app.config.phys_spin_coupling = self.app.sim_config.spin_coupling;
+        self.app.config.phys_spin_friction = self.app.sim_config.spin_friction;
+        self.app.config.phys_target_temperature = self.app.sim_config.target_temperature;
+        self.app.config.phys_friction = self.app.sim_config.friction;
+        self.app.config.phys_repel_strength = self.app.sim_config.repel_strength;
+        self.app.config.phys_repel_radius = self.app.sim_config.repel_radius;
+        self.app.config.phys_max_velocity = self.app.sim_config.max_velocity;
+        self.app.config.phys_velocity_clamp = self.app.sim_config.velocity_clamp;
+        self.app.config.phys_fixed_dt = self.app.sim_config.fixed_dt;
+        self.app.config.phys_substeps = self.app.sim_config.substeps;
+        self.app.config.phys_boundary_mode = self.app.sim_config.boundary_mode;
+        self.app.config.phys_world_shape = self.app.sim_config.world_shape;
+        self.app.config.phys_wall_repel_strength = self.app.sim_config.wall_repel_strength;
+        self.app.config.phys_mirror_wrap_count = self.app.sim_config.mirror_wrap_count;
+        self.app.config.gen_rule = self.app.current_rule;
+        self.app.config.gen_palette = self.app.current_palette;
+        self.app.config.gen_palette_jitter = self.app.sim_config.palette_jitter;
+        self.app.config.gen_pattern = self.app.current_pattern;
+        self.app.config.render_enable_3d = self.app.sim_config.enable_3d;
+        self.app.config.render_depth_limit = self.app.sim_config.depth_limit;
+        self.app.config.render_particle_size = self.app.sim_config.particle_size;
+        self.app.config.render_background_color = self.app.sim_config.background_color;
+        self.app.config.render_background_gradient = self.app.sim_config.background_gradient;
+        self.app.config.render_glow_enabled = self.app.sim_config.enable_glow;
+        self.app.config.render_glow_intensity = self.app.sim_config.glow_intensity;
+        self.app.config.render_glow_size = self.app.sim_config.glow_size;
+        self.app.config.render_glow_steepness = self.app.sim_config.glow_steepness;
+        self.app.config.render_glow_zoom_compensate = self.app.sim_config.glow_zoom_compensate;
+        self.app.config.render_trails_enabled = self.app.sim_config.enable_trails;
+        self.app.config.render_trail_fade = self.app.sim_config.trail_fade;
+        self.app.config.render_vignette = self.app.sim_config.vignette;
+        self.app.config.render_color_mode = self.app.sim_config.color_mode;
+        self.app.config.render_particle_shape = self.app.sim_config.particle_shape;
+        self.app.config.render_blend_mode = self.app.sim_config.blend_mode;
+        self.app.config.render_colormap = self.app.sim_config.colormap;
+        self.app.config.render_color_speed_range = self.app.sim_config.color_speed_range;
+        self.app.config.render_animate_palette = self.app.sim_config.animate_palette;
+        self.app.config.render_palette_hue_speed = self.app.sim_config.palette_hue_speed;
+        self.app.config.render_seam_fade = self.app.sim_config.seam_fade;
+        self.app.config.render_velocity_streaks = self.app.sim_config.velocity_streaks;
+        self.app.config.render_streak_length = self.app.sim_config.streak_length;
+        self.app.config.render_spatial_hash_cell_size = self.app.sim_config.spatial_hash_cell_size;
+
+        // Regenerate colors and particles from the preset's seed (if any),
+        // keeping its saved matrix
+        self.app.regenerate_from_seed(true);
+
+        // Sync GPU buffers
+        self.sync_buffers();
+        self.sync_interaction_matrix();
+        self.sync_colors();
+        self.sync_colormap();
+        self.match_world_to_window();
+
+        // Saved framing is relative to the preset's world; rescale it onto
+        // the world now in effect so the view isn't lost
+        if self.app.config.restore_preset_camera
+            && let Some(camera) = preset.camera
+        {
+            self.camera = camera.rescaled(saved_world_size, self.app.sim_config.world_size);
+            self.update_camera();
+        }
+    }
 }
@@ -0,0 +1,46 @@
+//! Adaptive quality: throttle effects to hold the target frame rate.
+
+use std::time::Instant;
+
+use super::AppHandler;
+use crate::app::QualitySettings;
+
+impl AppHandler {
+    /// Step the adaptive quality level from the smoothed FPS and apply it.
+    pub(crate) fn update_adaptive_quality(&mut self, now: Instant) {
+        if !self.app.config.adaptive_quality {
+            self.restore_quality();
+            return;
+        }
+        let target = self.app.config.target_fps.max(1) as f32;
+        let Some(level) = self.adaptive_quality.observe(self.fps_ema, target, now) else {
+            return;
+        };
+
+        let base = *self.quality_base.get_or_insert(QualitySettings {
+            enable_glow: self.app.sim_config.enable_glow,
+            neighbor_budget: self.app.sim_config.neighbor_budget,
+        });
+        let settings = base.at_level(level);
+        self.app.sim_config.enable_glow = settings.enable_glow;
+        self.app.sim_config.neighbor_budget = settings.neighbor_budget;
+        if level == 0 {
+            self.quality_base = None;
+        }
+        log::info!(
+            "Adaptive quality level {} (FPS {:.1}, target {:.0})",
+            level,
+            self.fps_ema,
+            target
+        );
+    }
+
+    /// Put back the user's settings and return to full quality.
+    pub(crate) fn restore_quality(&mut self) {
+        self.adaptive_quality.reset();
+        if let Some(base) = self.quality_base.take() {
+            self.app.sim_config.enable_glow = base.enable_glow;
+            self.app.sim_config.neighbor_budget = base.neighbor_budget;
+        }
+    }
+}
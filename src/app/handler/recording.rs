@@ -3,7 +3,38 @@
 use super::AppHandler;
 use crate::video_recorder::VideoRecorder;
 
+/// Target framerate of recorded videos (the native GIF delay matches).
+const RECORDING_FPS: u32 = 30;
+
 impl AppHandler {
+    /// Simulation step for deterministic recording: one recorded frame spans
+    /// `video_frame_skip` updates and exactly `1 / RECORDING_FPS` of sim time.
+    pub(crate) fn recording_step_dt(&self) -> f32 {
+        1.0 / (RECORDING_FPS * self.video_frame_skip.max(1)) as f32
+    }
+
+    /// Start the command-line auto-record session, if one was requested.
+    ///
+    /// Recording uses the fixed timestep so the video spans exactly the
+    /// requested sim time; frames are then driven from `about_to_wait`
+    /// rather than redraw requests, which an unfocused or occluded window
+    /// may never receive.
+    pub(crate) fn start_auto_recording(&mut self) {
+        let Some(auto) = self.auto_record.take() else {
+            return;
+        };
+        log::info!(
+            "Auto-recording {}s of {}, then exiting",
+            auto.seconds,
+            auto.format.name()
+        );
+        self.video_format = auto.format;
+        self.deterministic_recording = true;
+        self.auto_record_frames_left = auto.frame_count(RECORDING_FPS);
+        self.auto_recording = true;
+        self.start_recording();
+    }
+
     /// Toggle video recording on/off.
     pub(crate) fn toggle_recording(&mut self) {
         if self.is_recording {
@@ -35,21 +66,22 @@ impl AppHandler {
         };
 
         let (width, height) = gpu.context.surface_size();
-        let fps = 30; // Target framerate for recording
+        let fps = RECORDING_FPS;
 
         let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
-        let filename = format!(
-            "recording_{}_{:03}.{}",
-            timestamp,
-            self.video_counter,
-            self.video_format.extension()
-        );
+        let stem = format!("recording_{}_{:03}", timestamp, self.video_counter);
+        // PNG sequences go into a folder of numbered frames
+        let filename = if self.video_format.is_sequence() {
+            stem
+        } else {
+            format!("{}.{}", stem, self.video_format.extension())
+        };
         self.video_counter += 1;
         let filepath = videos_dir.join(&filename);
         let filepath_str = filepath.display().to_string();
 
-        // Try ffmpeg-based recording first
-        if self.use_ffmpeg {
+        // Try ffmpeg-based recording first (PNG sequences don't need ffmpeg)
+        if self.use_ffmpeg || !self.video_format.uses_ffmpeg() {
             let mut recorder = VideoRecorder::new(width, height, fps, self.video_format);
             match recorder.start_recording(filepath_str.clone()) {
                 Ok(()) => {
@@ -61,6 +93,11 @@ impl AppHandler {
                     self.preset_status = format!("Recording {}... (F11 to stop)", format_name);
                     return;
                 }
+                Err(e) if self.video_format.is_sequence() => {
+                    log::error!("Failed to start PNG sequence: {}", e);
+                    self.preset_status = format!("Recording failed: {}", e);
+                    return;
+                }
                 Err(e) => {
                     log::warn!("ffmpeg not available: {}. Falling back to native GIF.", e);
                     // Fall through to native GIF recording
@@ -69,81 +69,172 @@ impl AppHandler {
             &screen_descriptor,
         );
 
-        // Clear background
+        let toggles = self.pass_toggles;
+
+        if gpu.msaa.requested() != self.app.config.msaa_samples {
+            gpu.set_msaa_samples(self.app.config.msaa_samples);
+        }
+
+        // With trails the scene accumulates in a persistent texture that is
+        // faded instead of cleared, then copied to the surface
+        let trails = self.app.sim_config.enable_trails && toggles.render;
+        let mut clear_scene = true;
+        if trails {
+            let (width, height) = gpu.context.surface_size();
+            clear_scene = gpu.trail.ensure_target(&gpu.context.device, width, height);
+            gpu.trail.update_fade(
+                &gpu.context.queue,
+                self.app.sim_config.background_color,
+                self.app.sim_config.trail_fade,
+            );
+        } else {
+            gpu.trail.release();
+        }
+        let scene_view = match gpu.trail.view() {
+            Some(trail_view) if trails => trail_view,
+            _ => &view,
+        };
+
+        // With MSAA the scene passes draw into the multisampled texture and
+        // the last one resolves it into the scene view. The texture keeps its
+        // contents between frames, so trails fade it like the trail texture.
+        let (width, height) = gpu.context.surface_size();
+        if gpu.msaa.ensure_target(&gpu.context.device, width, height) {
+            clear_scene = true;
+        }
+        let msaa_view = gpu.msaa.view();
+        let glow_pass = self.app.sim_config.enable_glow && toggles.render && toggles.glow;
+        let particle_pass = toggles.render && toggles.particles;
+        // In 3D mode the particle pass depth-tests against a depth buffer
+        // matching the scene target
+        let depth_test = self.app.sim_config.enable_3d && particle_pass;
+        if depth_test {
+            let sample_count = gpu.msaa.sample_count();
+            gpu.depth
+                .ensure_target(&gpu.context.device, width, height, sample_count);
+            gpu.render.ensure_depth_pipelines(&gpu.context.device);
+        } else {
+            gpu.depth.release();
+        }
+        let depth_view = gpu.depth.view();
+        let scene_attachment = |load, last_scene_pass: bool| wgpu::RenderPassColorAttachment {
+            view: msaa_view.unwrap_or(scene_view),
+            resolve_target: msaa_view.and(last_scene_pass.then_some(scene_view)),
+            ops: wgpu::Operations {
+                load,
+                store: wgpu::StoreOp::Store,
+            },
+            depth_slice: None,
+        };
+
+        // Clear background (or fade the previous frame's trails)
         {
+            let gradient = self.app.sim_config.background_gradient;
+            if let Some(gradient) = gradient {
+                let alpha = if clear_scene {
+                    1.0
+                } else {
+                    self.app.sim_config.trail_fade
+                };
+                gpu.background.update(&gpu.context.queue, gradient, alpha);
+            }
             let bg = self.app.sim_config.background_color;
-            let _render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            let load = if clear_scene {
+                wgpu::LoadOp::Clear(wgpu::Color {
+                    r: bg[0] as f64,
+                    g: bg[1] as f64,
+                    b: bg[2] as f64,
+                    a: 1.0,
+                })
+            } else {
+                wgpu::LoadOp::Load
+            };
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Clear Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Clear(wgpu::Color {
-                            r: bg[0] as f64,
-                            g: bg[1] as f64,
-                            b: bg[2] as f64,
-                            a: 1.0,
-                        }),
-                        store: wgpu::StoreOp::Store,
-                    },
-                    depth_slice: None,
-                })],
+                color_attachments: &[Some(scene_attachment(load, !glow_pass && !particle_pass))],
                 depth_stencil_attachment: None,
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
-            // Pass ends here, just clears the background
+            if gradient.is_some() {
+                // Gradient covers the clear, or fades trails toward itself
+                render_pass.set_pipeline(&gpu.background.pipeline);
+                render_pass.set_bind_group(0, &gpu.background.bind_group, &[]);
+                render_pass.set_vertex_buffer(0, gpu.render_buffers.fullscreen_quad.slice(..));
+                render_pass.draw(0..6, 0..1);
+            } else if !clear_scene {
+                render_pass.set_pipeline(&gpu.trail.fade_pipeline);
+                render_pass.set_bind_group(0, &gpu.trail.fade_bind_group, &[]);
+                render_pass.set_vertex_buffer(0, gpu.render_buffers.fullscreen_quad.slice(..));
+                render_pass.draw(0..6, 0..1);
+            }
         }
 
+        gpu.render.update_color_mode(
+            &gpu.context.queue,
+            &self.app.sim_config,
+            self.palette_hue_offset,
+        );
+
         // Render glow effect first (if enabled)
-        if self.app.sim_config.enable_glow {
+        if glow_pass {
             // Update glow params
             gpu.render
-                .update_glow(&gpu.context.queue, &self.app.sim_config);
+                .update_glow(&gpu.context.queue, &self.app.sim_config, self.camera.zoom);
+
+            // Pipeline and bind group are built lazily the first frame glow is on
+            gpu.render.ensure_glow_pipeline(&gpu.context.device);
+            let glow_bind_group = gpu.glow_bind_group.get_or_insert_with(|| {
+                gpu.render.create_glow_bind_group(
+                    &gpu.context.device,
+                    gpu.buffers.current_pos_type(),
+                    gpu.buffers.current_velocities(),
+                    gpu.buffers.current_depths(),
+                    &gpu.buffers,
+                )
+            });
 
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Glow Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Load, // Don't clear, load existing content
-                        store: wgpu::StoreOp::Store,
-                    },
-                    depth_slice: None,
-                })],
+                // Don't clear, load existing content
+                color_attachments: &[Some(scene_attachment(wgpu::LoadOp::Load, !particle_pass))],
                 depth_stencil_attachment: None,
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
 
-            render_pass.set_pipeline(&gpu.render.glow_pipeline);
-            render_pass.set_bind_group(0, &gpu.glow_bind_group, &[]);
+            let glow_pipeline = gpu.render.glow_pipeline.as_ref();
+            render_pass.set_pipeline(glow_pipeline.expect("glow pipeline built above"));
+            render_pass.set_bind_group(0, &*glow_bind_group, &[]);
             render_pass.draw(0..4, 0..gpu.buffers.num_particles);
         }
 
         // Render solid particles on top
-        {
+        if particle_pass {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Particle Render Pass"),
-                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
-                    ops: wgpu::Operations {
-                        load: wgpu::LoadOp::Load, // Don't clear, load existing content (glow)
-                        store: wgpu::StoreOp::Store,
-                    },
-                    depth_slice: None,
-                })],
-                depth_stencil_attachment: None,
+                // Don't clear, load existing content (glow)
+                color_attachments: &[Some(scene_attachment(wgpu::LoadOp::Load, true))],
+                depth_stencil_attachment: depth_view.map(|view| {
+                    wgpu::RenderPassDepthStencilAttachment {
+                        view,
+                        depth_ops: Some(wgpu::Operations {
+                            load: wgpu::LoadOp::Clear(1.0),
+                            store: wgpu::StoreOp::Discard,
+                        }),
+                        stencil_ops: None,
+                    }
+                }),
                 timestamp_writes: None,
                 occlusion_query_set: None,
             });
 
+            let blend_mode = self.app.sim_config.blend_mode;
             match self.app.sim_config.boundary_mode {
-                BoundaryMode::Repel | BoundaryMode::Wrap => {
+                BoundaryMode::Repel | BoundaryMode::Wrap | BoundaryMode::Sticky => {
                     // Standard rendering - one instance per particle
-                    render_pass.set_pipeline(&gpu.render.particle_pipeline);
+                    render_pass
+                        .set_pipeline(gpu.render.particle_pipeline_for(blend_mode, depth_test));
                     render_pass.set_bind_group(0, &gpu.render_bind_group, &[]);
                     render_pass.draw(0..4, 0..gpu.buffers.num_particles);
                 }
@@ -152,7 +243,8 @@ impl AppHandler {
                     // Update mirror params
                     gpu.render
                         .update_mirror(&gpu.context.queue, &self.app.sim_config);
-                    render_pass.set_pipeline(&gpu.render.mirror_pipeline);
+                    render_pass
+                        .set_pipeline(gpu.render.mirror_pipeline_for(blend_mode, depth_test));
                     render_pass.set_bind_group(0, &gpu.mirror_bind_group, &[]);
                     // Draw 4 vertices per particle copy, num_particles * mirror_copies instances
                     let num_copies = self.app.sim_config.mirror_wrap_count;
@@ -183,7 +275,8 @@ impl AppHandler {
                         camera_center_y,
                         self.camera.zoom,
                     );
-                    render_pass.set_pipeline(&gpu.render.infinite_pipeline);
+                    render_pass
+                        .set_pipeline(gpu.render.infinite_pipeline_for(blend_mode, depth_test));
                     render_pass.set_bind_group(0, &gpu.infinite_bind_group, &[]);
                     // Draw 4 vertices per particle copy, num_particles * total_copies instances
                     let total_copies = infinite_params.total_copies();
@@ -192,8 +285,57 @@ impl AppHandler {
             }
         }
 
+        // Copy the trail texture to the surface
+        if trails && let Some(blit_bind_group) = gpu.trail.blit_bind_group() {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Trail Blit Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&gpu.trail.blit_pipeline);
+            render_pass.set_bind_group(0, blit_bind_group, &[]);
+            render_pass.set_vertex_buffer(0, gpu.render_buffers.fullscreen_quad.slice(..));
+            render_pass.draw(0..6, 0..1);
+        }
+
+        // Vignette over the finished scene, on the surface so trails never
+        // accumulate it
+        let vignette = self.app.sim_config.vignette;
+        if vignette > 0.0 && toggles.render {
+            gpu.vignette.update(&gpu.context.queue, vignette);
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Vignette Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(&gpu.vignette.pipeline);
+            render_pass.set_bind_group(0, &gpu.vignette.bind_group, &[]);
+            render_pass.set_vertex_buffer(0, gpu.render_buffers.fullscreen_quad.slice(..));
+            render_pass.draw(0..6, 0..1);
+        }
+
         // Render brush circle indicator (if visible)
-        {
+        if toggles.render && toggles.brush_circle {
             // Update brush render params
             gpu.brush_pipelines.update_render(
                 &gpu.context.queue,
@@ -227,8 +369,9 @@ impl AppHandler {
         }
 
         // Capture frame without UI if needed (before egui render)
-        let need_capture_without_ui =
-            self.capture_hide_ui && (self.screenshot_requested || self.is_recording);
+        let need_capture_without_ui = (self.capture_hide_ui
+            && (self.screenshot_requested || self.clipboard_requested || self.is_recording))
+            || self.pending_preset_thumbnail.is_some();
 
         let frame_without_ui = if need_capture_without_ui {
             // Submit current encoder to get the frame without UI
@@ -272,6 +415,13 @@ impl AppHandler {
 
         gpu.context.submit(encoder.finish());
 
+        // Preset thumbnails never include the UI
+        let thumbnail_frame = if self.pending_preset_thumbnail.is_some() {
+            Some(frame_without_ui.clone())
+        } else {
+            None
+        };
+
         // Capture screenshot if requested
         if self.screenshot_requested {
             self.screenshot_requested = false;
@@ -319,6 +469,35 @@ impl AppHandler {
             }
         }
 
+        // Copy screenshot to clipboard if requested
+        if self.clipboard_requested {
+            self.clipboard_requested = false;
+            let image = if self.capture_hide_ui {
+                frame_without_ui.clone()
+            } else {
+                gpu.context.capture_frame(&frame.texture)
+            };
+            if let Some(image) = image {
+                match Self::copy_image_to_clipboard(&image) {
+                    Ok(()) => {
+                        log::info!(
+                            "Screenshot copied to clipboard ({}x{})",
+                            image.width(),
+                            image.height()
+                        );
+                        self.preset_status = "Screenshot copied to clipboard".to_string();
+                    }
+                    Err(e) => {
+                        log::error!("Failed to copy screenshot to clipboard: {}", e);
+                        self.preset_status = format!("Clipboard copy failed: {}", e);
+                    }
+                }
+            } else {
+                log::error!("Failed to capture screenshot for clipboard");
+                self.preset_status = "Screenshot capture failed".to_string();
+            }
+        }
+
         // Capture frame for video/GIF recording
         if self.is_recording {
             self.video_frame_counter += 1;
@@ -361,6 +540,14 @@ impl AppHandler {
                             self.pending_stop_recording = true;
                         }
                     }
+
+                    if self.auto_recording {
+                        self.auto_record_frames_left =
+                            self.auto_record_frames_left.saturating_sub(1);
+                        if self.auto_record_frames_left == 0 {
+                            self.pending_stop_recording = true;
+                        }
+                    }
                 }
             }
         }
@@ -377,5 +564,25 @@ impl AppHandler {
             self.pending_stop_recording = false;
             self.stop_recording();
         }
+
+        // Save the preset thumbnail (after gpu borrow is released)
+        if let Some(thumbnail_frame) = thumbnail_frame {
+            self.save_preset_thumbnail(thumbnail_frame.as_ref());
+        }
+    }
+
+    /// Place a captured frame on the system clipboard as raw RGBA pixels.
+    ///
+    /// Clipboard image support varies by platform (e.g. headless or Wayland
+    /// sessions without a clipboard manager), so failures are returned for
+    /// the caller to report rather than treated as fatal.
+    fn copy_image_to_clipboard(image: &image::RgbaImage) -> anyhow::Result<()> {
+        let mut clipboard = arboard::Clipboard::new()?;
+        clipboard.set_image(arboard::ImageData {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            bytes: std::borrow::Cow::Borrowed(image.as_raw()),
+        })?;
+        Ok(())
     }
 }
@@ -0,0 +1,97 @@
+//! Scoped resets that touch a single subsystem.
+
+use super::AppHandler;
+
+/// What a reset action restores.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ResetScope {
+    /// Respawn particles with the current pattern.
+    Particles,
+    /// Restore default physics parameters.
+    Physics,
+    /// Clear the interaction matrix and restore default radii.
+    Matrix,
+    /// Restore the default palette.
+    Colors,
+    /// Reset camera zoom and pan.
+    Camera,
+    /// Reset every setting to its default.
+    All,
+}
+
+impl ResetScope {
+    /// All scopes in menu order.
+    pub(crate) fn all() -> &'static [ResetScope] {
+        &[
+            ResetScope::Particles,
+            ResetScope::Physics,
+            ResetScope::Matrix,
+            ResetScope::Colors,
+            ResetScope::Camera,
+            ResetScope::All,
+        ]
+    }
+
+    /// Menu label.
+    pub(crate) fn display_name(&self) -> &'static str {
+        match self {
+            ResetScope::Particles => "Reset Particles",
+            ResetScope::Physics => "Reset Physics",
+            ResetScope::Matrix => "Reset Matrix",
+            ResetScope::Colors => "Reset Colors",
+            ResetScope::Camera => "Reset Camera",
+            ResetScope::All => "Reset All",
+        }
+    }
+}
+
+impl AppHandler {
+    /// Reset one subsystem and sync only the GPU state it affects.
+    pub(crate) fn reset(&mut self, scope: ResetScope) {
+        match scope {
+            ResetScope::Particles => {
+                self.app.regenerate_particles();
+                self.sync_buffers();
+            }
+            ResetScope::Physics => {
+                let old_boundary = (
+                    self.app.sim_config.boundary_mode,
+                    self.app.sim_config.world_shape,
+                );
+                self.app.reset_physics();
+                let new_boundary = (
+                    self.app.sim_config.boundary_mode,
+                    self.app.sim_config.world_shape,
+                );
+                if new_boundary != old_boundary {
+                    self.sync_particles_from_gpu();
+                    self.normalize_particle_positions();
+                    self.sync_buffers();
+                }
+            }
+            ResetScope::Matrix => {
+                self.push_undo();
+                self.app.reset_matrix();
+                self.sync_interaction_matrix();
+                self.sync_radius_matrix();
+                self.sync_spatial_buffers();
+                self.record_exploration();
+            }
+            ResetScope::Colors => {
+                self.push_undo();
+                self.app.reset_colors();
+                self.sync_colors();
+                self.record_exploration();
+            }
+            ResetScope::Camera => {
+                self.camera.reset();
+                self.update_camera();
+            }
+            ResetScope::All => {
+                self.reset_to_defaults();
+                return;
+            }
+        }
+        self.preset_status = format!("{} done.", scope.display_name());
+    }
+}
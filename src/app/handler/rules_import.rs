@@ -0,0 +1,101 @@
+//! Import of interaction matrices from other particle-life tools, and
+//! matrix copy/paste through the clipboard.
+
+use super::AppHandler;
+use crate::generators::rules::import::parse_attraction_json;
+use crate::simulation::InteractionMatrix;
+
+impl AppHandler {
+    /// Import an external attraction-matrix JSON from `rules_import_path`.
+    ///
+    /// A matrix with a different number of types also resizes the radius
+    /// matrix and palette and respawns the particles.
+    pub(crate) fn import_external_rules(&mut self) {
+        let path = self.rules_import_path.trim().to_string();
+        let imported = std::fs::read_to_string(&path)
+            .map_err(anyhow::Error::from)
+            .and_then(|json| parse_attraction_json(&json));
+        let imported = match imported {
+            Ok(imported) => imported,
+            Err(e) => {
+                log::error!("Failed to import rules from {}: {:#}", path, e);
+                self.preset_status = format!("Import failed: {:#}", e);
+                return;
+            }
+        };
+
+        self.push_undo();
+        let num_types = imported.matrix.size;
+        if num_types as u32 != self.app.sim_config.num_types {
+            self.app.sim_config.num_types = num_types as u32;
+            self.app.config.sim_num_types = num_types as u32;
+            self.app.radius_matrix = self.app.default_radius_matrix(num_types);
+            self.app.rebalance_radii_for_density();
+            self.app.regenerate_colors();
+            self.app.interaction_matrix = imported.matrix;
+            self.app.regenerate_particles();
+            self.sync_buffers();
+        } else {
+            self.app.interaction_matrix = imported.matrix;
+            self.sync_interaction_matrix();
+        }
+        self.record_exploration();
+
+        log::info!("Imported {}-type rules from {}", num_types, path);
+        self.preset_status = match imported.rescaled_by {
+            Some(scale) => {
+                format!("Imported {num_types} types (values rescaled by 1/{scale:.2} to fit -1..1)")
+            }
+            None => format!("Imported {num_types} types"),
+        };
+    }
+
+    /// Copy the interaction matrix to the clipboard as JSON.
+    pub(crate) fn copy_matrix_to_clipboard(&mut self) {
+        let json = self.app.interaction_matrix.to_json();
+        let result = arboard::Clipboard::new().and_then(|mut c| c.set_text(json));
+        self.preset_status = match result {
+            Ok(()) => format!(
+                "Copied {}-type matrix to clipboard",
+                self.app.interaction_matrix.size
+            ),
+            Err(e) => {
+                log::error!("Failed to copy matrix to clipboard: {}", e);
+                format!("Clipboard copy failed: {}", e)
+            }
+        };
+    }
+
+    /// Replace the interaction matrix with JSON from the clipboard.
+    ///
+    /// A matrix for a different number of types is truncated or zero-filled
+    /// to the current type count rather than rejected.
+    pub(crate) fn paste_matrix_from_clipboard(&mut self) {
+        let pasted = arboard::Clipboard::new()
+            .and_then(|mut c| c.get_text())
+            .map_err(|e| e.to_string())
+            .and_then(|json| InteractionMatrix::from_json(&json));
+        let matrix = match pasted {
+            Ok(matrix) => matrix,
+            Err(e) => {
+                log::error!("Failed to paste matrix: {}", e);
+                self.preset_status = format!("Paste failed: {}", e);
+                return;
+            }
+        };
+
+        self.push_undo();
+        let num_types = self.app.sim_config.num_types as usize;
+        self.preset_status = if matrix.size == num_types {
+            format!("Pasted {num_types}-type matrix")
+        } else {
+            format!(
+                "Warning: pasted {}-type matrix resized to {num_types} types",
+                matrix.size
+            )
+        };
+        self.app.interaction_matrix = matrix.resized(num_types);
+        self.sync_interaction_matrix();
+        self.record_exploration();
+    }
+}
@@ -0,0 +1,136 @@
+//! Export and import of the full app state as a single shareable file.
+
+use super::AppHandler;
+use crate::app::{ImportedState, STATE_FILE_VERSION, SharedState};
+
+impl AppHandler {
+    /// Write the full app state to `state_file_path`, or to a timestamped
+    /// file in the screenshots directory when no path is set.
+    pub(crate) fn export_state(&mut self) {
+        let chosen = self.state_file_path.trim();
+        let path = if chosen.is_empty() {
+            match Self::ensure_screenshots_dir() {
+                Ok(dir) => {
+                    let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+                    dir.join(format!("state_{}.json", timestamp))
+                }
+                Err(e) => {
+                    log::error!("Failed to create screenshots directory: {}", e);
+                    self.preset_status = format!("State export failed: {}", e);
+                    return;
+                }
+            }
+        } else {
+            std::path::PathBuf::from(chosen)
+        };
+
+        match SharedState::capture(&self.app, self.camera).save_to_file(&path) {
+            Ok(()) => {
+                let path_str = path.display().to_string();
+                log::info!("Exported state to {}", path_str);
+                self.preset_status = format!("State exported: {}", path_str);
+                self.last_capture_path = Some(path_str);
+            }
+            Err(e) => {
+                log::error!("Failed to export state: {:#}", e);
+                self.preset_status = format!("State export failed: {:#}", e);
+            }
+        }
+    }
+
+    /// Load a state file from `state_file_path` and apply it.
+    ///
+    /// A file from a newer version is still applied: sections this build
+    /// can't read keep their current values and the status line says so.
+    pub(crate) fn import_state(&mut self) {
+        let path = self.state_file_path.trim().to_string();
+        if path.is_empty() {
+            self.preset_status = "State import failed: enter the file path first".to_string();
+            return;
+        }
+        let fallback = SharedState::capture(&self.app, self.camera);
+        let imported = match SharedState::load_from_file(&path, &fallback) {
+            Ok(imported) => imported,
+            Err(e) => {
+                log::error!("Failed to import state from {}: {:#}", path, e);
+                self.preset_status = format!("State import failed: {:#}", e);
+                return;
+            }
+        };
+        if imported.is_newer() {
+            log::warn!(
+                "State file {} is version {}, newer than the supported version {}",
+                path,
+                imported.version,
+                STATE_FILE_VERSION
+            );
+        }
+        self.preset_status = import_status(&imported);
+        log::info!("Imported state from {}", path);
+        self.apply_shared_state(imported.state);
+    }
+
+    /// Replace the running state with `state`, regenerating particles and
+    /// syncing all GPU buffers.
+    fn apply_shared_state(&mut self, state: SharedState) {
+        self.restore_quality();
+
+        // Window placement belongs to this machine, not the shared scene
+        let mut config = state.config;
+        config.window_width = self.app.config.window_width;
+        config.window_height = self.app.config.window_height;
+        config.window_monitor = self.app.config.window_monitor;
+        if config.vsync != self.app.config.vsync {
+            self.pending_vsync = Some(config.vsync);
+        }
+        self.app.auto_scale_radii = config.auto_scale_radii;
+        self.app.radii_relative_to_world = config.radii_relative_to_world;
+        self.app.config = config;
+
+        let saved_world_size = state.sim_config.world_size;
+        self.app.sim_config = state.sim_config;
+        self.app.interaction_matrix = state.interaction_matrix;
+        self.app.radius_matrix = state.radius_matrix;
+        let generators = state.generators;
+        self.app.current_rule = generators.rule;
+        self.app.current_palette = generators.palette;
+        self.app.current_pattern = generators.pattern;
+        self.app.current_spawn_velocity = generators.spawn_velocity;
+        self.app.current_spawn_sampling = generators.spawn_sampling;
+        self.app.current_type_weights = generators.type_weights;
+
+        // Particles come from the seed; the saved colors win over the
+        // regenerated palette when they match the type count
+        self.app.regenerate_from_seed(true);
+        if state.colors.len() == self.app.sim_config.num_types as usize {
+            self.app.colors = state.colors;
+        }
+
+        self.sync_buffers();
+        self.sync_interaction_matrix();
+        self.sync_colors();
+        self.sync_colormap();
+        self.match_world_to_window();
+
+        self.camera = state
+            .camera
+            .rescaled(saved_world_size, self.app.sim_config.world_size);
+        self.update_camera();
+    }
+}
+
+/// Status line for an import, noting a newer file version and any sections
+/// that kept their current values.
+fn import_status(imported: &ImportedState) -> String {
+    let mut status = "State imported".to_string();
+    if imported.is_newer() {
+        status += &format!(
+            " (file version {} is newer than {}; loaded what this version understands)",
+            imported.version, STATE_FILE_VERSION
+        );
+    }
+    if !imported.skipped.is_empty() {
+        status += &format!("; kept current: {}", imported.skipped.join(", "));
+    }
+    status
+}
@@ -0,0 +1,88 @@
+//! Stagnation watchdog: pause or stir scenes that have frozen.
+
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use super::AppHandler;
+use crate::app::StagnationAction;
+
+/// Seconds between mean speed samples (each is a blocking velocity readback).
+const STAGNATION_SAMPLE_SECS: f32 = 1.0;
+
+/// Stir impulse as a fraction of `max_velocity`.
+const STIR_STRENGTH: f32 = 0.5;
+
+impl AppHandler {
+    /// Sample the mean speed periodically and apply the stagnation action once
+    /// the scene has stayed below the threshold long enough.
+    pub(crate) fn check_stagnation(&mut self, now: Instant) {
+        let action = self.app.config.stagnation_action;
+        if action == StagnationAction::None || !self.app.running {
+            self.stagnation.reset();
+            return;
+        }
+        if now.duration_since(self.last_stagnation_check).as_secs_f32() < STAGNATION_SAMPLE_SECS {
+            return;
+        }
+        self.last_stagnation_check = now;
+
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+        self.mean_speed = gpu
+            .buffers
+            .read_velocity_stats(&gpu.context.device, &gpu.context.queue)
+            .mean_speed;
+
+        let hold = Duration::from_secs_f32(self.app.config.stagnation_seconds.max(0.0));
+        if !self.stagnation.observe(
+            self.mean_speed,
+            self.app.config.stagnation_speed_threshold,
+            hold,
+            now,
+        ) {
+            return;
+        }
+
+        match action {
+            StagnationAction::None => {}
+            StagnationAction::Pause => {
+                self.app.running = false;
+                log::info!(
+                    "Scene stagnant (mean speed {:.3}); pausing",
+                    self.mean_speed
+                );
+                self.preset_status = "Paused: scene stopped moving".to_string();
+            }
+            StagnationAction::Stir => {
+                self.stir_particles();
+                log::info!(
+                    "Scene stagnant (mean speed {:.3}); stirring",
+                    self.mean_speed
+                );
+                self.preset_status = "Stirred: scene stopped moving".to_string();
+            }
+        }
+    }
+
+    /// Give every particle a random velocity kick, drawn from the app's
+    /// seeded generator so seeded runs stir the same way.
+    pub(crate) fn stir_particles(&mut self) {
+        self.sync_particles_from_gpu();
+
+        let strength = self.app.sim_config.max_velocity * STIR_STRENGTH;
+        let rng = &mut self.app.rng;
+        for p in &mut self.app.particles {
+            let angle = rng.random_range(0.0..std::f32::consts::TAU);
+            let speed = rng.random_range(0.5..=1.0) * strength;
+            p.vx += angle.cos() * speed;
+            p.vy += angle.sin() * speed;
+        }
+
+        if let Some(gpu) = &self.gpu {
+            gpu.buffers
+                .update_particles(&gpu.context.queue, &self.app.particles);
+        }
+    }
+}
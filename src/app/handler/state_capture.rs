@@ -0,0 +1,171 @@
+//! Raw particle state recording and playback.
+
+use super::AppHandler;
+use crate::state_recorder::{STATE_EXTENSION, StateHeader, StatePlayer, StateRecorder};
+
+impl AppHandler {
+    /// Toggle disk-backed particle state recording.
+    pub(crate) fn toggle_state_recording(&mut self) {
+        if self.state_recorder.is_some() {
+            self.stop_state_recording();
+        } else {
+            self.start_state_recording();
+        }
+    }
+
+    /// Start streaming particle states to a new file in the videos directory.
+    pub(crate) fn start_state_recording(&mut self) {
+        let dir = match Self::ensure_videos_dir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                log::error!("Failed to create videos directory: {}", e);
+                self.preset_status = format!("State recording failed: {}", e);
+                return;
+            }
+        };
+
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let path = dir.join(format!("states_{}.{}", timestamp, STATE_EXTENSION));
+        let header = StateHeader {
+            num_types: self.app.sim_config.num_types,
+            world_width: self.app.sim_config.world_size.x,
+            world_height: self.app.sim_config.world_size.y,
+        };
+
+        match StateRecorder::create(&path, header) {
+            Ok(recorder) => {
+                log::info!("Started state recording: {}", path.display());
+                self.preset_status = format!("Recording states to {}", path.display());
+                self.state_recorder = Some(recorder);
+                self.state_record_step = 0;
+            }
+            Err(e) => {
+                log::error!("Failed to start state recording: {}", e);
+                self.preset_status = format!("State recording failed: {}", e);
+            }
+        }
+    }
+
+    /// Stop state recording and flush the file.
+    pub(crate) fn stop_state_recording(&mut self) {
+        let Some(recorder) = self.state_recorder.take() else {
+            return;
+        };
+        let frames = recorder.frames_written();
+        match recorder.finish() {
+            Ok(path) => {
+                let path = path.display().to_string();
+                log::info!("Saved {} state frames to {}", frames, path);
+                self.preset_status = format!("Saved {} state frames", frames);
+                self.state_player_path = path.clone();
+                self.last_capture_path = Some(path);
+            }
+            Err(e) => {
+                log::error!("Failed to finish state recording: {}", e);
+                self.preset_status = format!("State recording failed: {}", e);
+            }
+        }
+    }
+
+    /// Capture the current GPU particle state if this step is due.
+    ///
+    /// Called once per simulation step. Blocks on a positions-only readback.
+    pub(crate) fn record_state_frame(&mut self) {
+        let Some(recorder) = self.state_recorder.as_mut() else {
+            return;
+        };
+        let step = self.state_record_step;
+        self.state_record_step += 1;
+        if !step.is_multiple_of(u64::from(self.state_record_interval.max(1))) {
+            return;
+        }
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+
+        let pos_types = gpu
+            .buffers
+            .read_pos_types(&gpu.context.device, &gpu.context.queue);
+        if let Err(e) = recorder.write_frame(step, &pos_types) {
+            log::error!("State recording stopped: {}", e);
+            self.preset_status = format!("State recording stopped: {}", e);
+            self.stop_state_recording();
+        }
+    }
+
+    /// Load a state recording for playback and pause the simulation.
+    pub(crate) fn load_state_playback(&mut self) {
+        let path = self.state_player_path.trim().to_string();
+        match StatePlayer::open(&path) {
+            Ok(player) if player.frame_count() > 0 => {
+                log::info!(
+                    "Loaded state recording {} ({} frames)",
+                    path,
+                    player.frame_count()
+                );
+                self.preset_status = format!("Loaded {} state frames", player.frame_count());
+                self.state_player = Some(player);
+                self.state_playing = false;
+                self.app.running = false;
+                self.show_state_frame(0);
+            }
+            Ok(_) => self.preset_status = "State recording has no frames".to_string(),
+            Err(e) => {
+                log::error!("Failed to load state recording: {}", e);
+                self.preset_status = format!("Load failed: {}", e);
+            }
+        }
+    }
+
+    /// Close the loaded state recording.
+    pub(crate) fn close_state_playback(&mut self) {
+        self.state_player = None;
+        self.state_playing = false;
+    }
+
+    /// Upload a recorded frame to the GPU.
+    pub(crate) fn show_state_frame(&mut self, index: usize) {
+        let Some(player) = self.state_player.as_mut() else {
+            return;
+        };
+        let particles = match player.read_frame(index) {
+            Ok(particles) => particles,
+            Err(e) => {
+                log::error!("Failed to read state frame: {}", e);
+                self.preset_status = format!("Playback failed: {}", e);
+                self.close_state_playback();
+                return;
+            }
+        };
+        self.state_player_frame = index;
+
+        let same_count = particles.len() == self.app.particles.len();
+        self.app.particles = particles;
+        if same_count {
+            if let Some(gpu) = &self.gpu {
+                gpu.buffers
+                    .update_particles(&gpu.context.queue, &self.app.particles);
+            }
+        } else {
+            self.app.sim_config.num_particles = self.app.particles.len() as u32;
+            self.app.physics.resize(self.app.particles.len());
+            self.sync_buffers();
+        }
+    }
+
+    /// Advance playback by one frame, stopping at the end.
+    pub(crate) fn advance_state_playback(&mut self) {
+        let Some(player) = &self.state_player else {
+            return;
+        };
+        if !self.state_playing {
+            return;
+        }
+        let next = self.state_player_frame + 1;
+        if next >= player.frame_count() {
+            self.state_playing = false;
+            return;
+        }
+        self.show_state_frame(next);
+    }
+}
@@ -0,0 +1,39 @@
+//! SVG export of the current particle positions.
+
+use super::AppHandler;
+
+impl AppHandler {
+    /// Read particles back from the GPU and export them as an SVG into the
+    /// screenshots directory.
+    pub(crate) fn export_svg(&mut self) {
+        let dir = match Self::ensure_screenshots_dir() {
+            Ok(dir) => dir,
+            Err(e) => {
+                log::error!("Failed to create screenshots directory: {}", e);
+                self.preset_status = format!("SVG export failed: {}", e);
+                return;
+            }
+        };
+
+        self.sync_particles_from_gpu();
+
+        let timestamp = chrono::Local::now().format("%Y%m%d_%H%M%S");
+        let filename = format!("particles_{}_{:03}.svg", timestamp, self.screenshot_counter);
+        self.screenshot_counter += 1;
+        let path = dir.join(&filename);
+
+        let max_particles = self.svg_cap_particles.then_some(self.svg_max_particles);
+        match self.app.export_svg(&path, max_particles) {
+            Ok(written) => {
+                let path_str = path.display().to_string();
+                log::info!("Exported {} particles to {}", written, path_str);
+                self.preset_status = format!("SVG saved: {} ({} particles)", filename, written);
+                self.last_capture_path = Some(path_str);
+            }
+            Err(e) => {
+                log::error!("Failed to export SVG: {}", e);
+                self.preset_status = format!("SVG export failed: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Kinetic temperature sampling and the velocity-rescaling thermostat.
+
+use std::time::Instant;
+
+use super::AppHandler;
+use crate::app::{THERMOSTAT_SAMPLE_SECS, thermostat_scale};
+
+impl AppHandler {
+    /// Sample the kinetic temperature periodically and update the velocity
+    /// multiplier the advance shader applies every step.
+    ///
+    /// Samples only while the temperature is visible or the thermostat is
+    /// on, since each sample is a blocking velocity readback.
+    pub(crate) fn update_thermostat(&mut self, now: Instant) {
+        let target = self.app.sim_config.target_temperature;
+        if !self.app.running {
+            self.velocity_scale = 1.0;
+            return;
+        }
+        let stats_visible = self.show_ui || self.app.config.show_stats_overlay;
+        if target.is_none() {
+            self.velocity_scale = 1.0;
+            if !stats_visible {
+                return;
+            }
+        }
+        if now
+            .duration_since(self.last_temperature_check)
+            .as_secs_f32()
+            < THERMOSTAT_SAMPLE_SECS
+        {
+            return;
+        }
+        self.last_temperature_check = now;
+
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+        self.temperature = gpu
+            .buffers
+            .read_velocity_stats(&gpu.context.device, &gpu.context.queue)
+            .temperature;
+
+        // Spread the correction over the steps expected before the next sample
+        let steps = self.fps_ema.max(1.0)
+            * self.app.sim_config.substeps_per_frame() as f32
+            * THERMOSTAT_SAMPLE_SECS;
+        self.velocity_scale = target.map_or(1.0, |t| thermostat_scale(self.temperature, t, steps));
+    }
+}
@@ -0,0 +1,20 @@
+//! Time reversal: flipping the sign of every particle's velocity.
+
+use super::AppHandler;
+
+impl AppHandler {
+    /// Negate every particle's velocity (T).
+    ///
+    /// Only the velocity buffers are rewritten, so spin survives. With f16
+    /// velocity storage negation just flips the sign bit, so the round trip
+    /// through the CPU adds no rounding error.
+    pub(crate) fn reverse_velocities(&mut self) {
+        self.sync_particles_from_gpu();
+        self.app.reverse_velocities();
+        if let Some(gpu) = &self.gpu {
+            gpu.buffers
+                .update_velocities(&gpu.context.queue, &self.app.particles);
+        }
+        self.show_hud_message("Velocities reversed".to_string());
+    }
+}
@@ -0,0 +1,59 @@
+//! Debug overlay labelling each particle with its type index.
+
+use super::AppHandler;
+
+/// Labels only appear at or above this camera zoom.
+pub(crate) const TYPE_LABEL_MIN_ZOOM: f32 = 4.0;
+/// Most labels drawn per frame, to keep the overlay cheap.
+pub(crate) const MAX_TYPE_LABELS: usize = 500;
+
+impl AppHandler {
+    /// Draw the type number next to each visible particle while zoomed in.
+    ///
+    /// Reads particles back from the GPU every frame (a blocking copy), so
+    /// it is meant for small-N experiments. Labels sit behind the UI panels
+    /// and stop after [`MAX_TYPE_LABELS`].
+    pub(crate) fn draw_type_labels(&self, ctx: &egui::Context) {
+        if !self.show_type_labels || self.camera.zoom < TYPE_LABEL_MIN_ZOOM {
+            return;
+        }
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+
+        let screen_size = glam::Vec2::new(
+            gpu.context.surface_config.width as f32,
+            gpu.context.surface_config.height as f32,
+        );
+        let world_size = self.app.sim_config.world_size;
+        let pixels_per_point = ctx.pixels_per_point();
+        let painter = ctx.layer_painter(egui::LayerId::new(
+            egui::Order::Background,
+            egui::Id::new("type_labels"),
+        ));
+        let font = egui::FontId::monospace(10.0);
+
+        let particles = gpu
+            .buffers
+            .read_particles(&gpu.context.device, &gpu.context.queue);
+        let visible = particles.iter().filter_map(|p| {
+            let screen =
+                self.camera
+                    .world_to_screen(glam::Vec2::new(p.x, p.y), screen_size, world_size);
+            let on_screen = screen.x >= 0.0
+                && screen.y >= 0.0
+                && screen.x < screen_size.x
+                && screen.y < screen_size.y;
+            on_screen.then_some((screen / pixels_per_point, p.particle_type))
+        });
+        for (pos, particle_type) in visible.take(MAX_TYPE_LABELS) {
+            painter.text(
+                egui::pos2(pos.x + 3.0, pos.y - 3.0),
+                egui::Align2::LEFT_BOTTOM,
+                particle_type.to_string(),
+                font.clone(),
+                egui::Color32::WHITE,
+            );
+        }
+    }
+}
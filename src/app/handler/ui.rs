@@ -1,17 +1,35 @@
 //! UI rendering using egui.
 
 use super::AppHandler;
-use crate::app::{BrushTool, Preset};
+use super::cpu_compare::PARITY_MAX_PARTICLES;
+use super::reset::ResetScope;
+use super::type_labels::{MAX_TYPE_LABELS, TYPE_LABEL_MIN_ZOOM};
+use crate::app::gpu_state::PassToggles;
+use crate::app::{
+    BrushTool, MAX_QUALITY_LEVEL, MIN_FPS_CAP, Preset, StagnationAction, UNCATEGORIZED,
+};
 use crate::generators::{
-    colors::{PaletteType, generate_colors},
-    positions::PositionPattern,
-    rules::{RuleType, generate_rules},
+    colors::PaletteType,
+    positions::{PositionPattern, SpawnConfig, SpawnSampling, SpawnVelocity, type_counts},
+    rules::RuleType,
+};
+use crate::metrics_logger::DEFAULT_METRICS_INTERVAL;
+use crate::renderer::gpu::{MAX_EXTRA_BRUSH_POINTS, MSAA_SAMPLE_COUNTS};
+use crate::simulation::{
+    BlendMode, BoundaryMode, ColorMode, MAX_NUM_TYPES, MIN_REPEL_RADIUS, ParticleShape,
+    VelocityClampMode, WorldShape, pair_force,
 };
-use crate::simulation::{BoundaryMode, RadiusMatrix};
+use crate::state_recorder::StateRecorder;
+use crate::svg_export::SVG_PARTICLE_WARN_THRESHOLD;
 use crate::video_recorder::VideoFormat;
 
 impl AppHandler {
     pub(crate) fn draw_ui(&mut self, ctx: &egui::Context) {
+        self.draw_recovery_prompt(ctx);
+        self.draw_stats_overlay(ctx);
+        self.draw_hud_message(ctx);
+        self.draw_type_labels(ctx);
+
         if !self.show_ui {
             return;
         }
@@ -22,43 +40,15 @@ impl AppHandler {
             .show(ctx, |ui| {
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     ui.heading("Par Particle Life");
+                    self.draw_favorites_hotbar(ui);
                     ui.separator();
 
-                    // Stats
-                    ui.horizontal(|ui| {
-                        ui.label(format!("FPS: {:.1}", self.fps));
-                        ui.separator();
-                        ui.label(format!("EMA: {:.1}", self.fps_ema));
-                        ui.separator();
-                        ui.label(format!("Particles: {}", self.app.particles.len()));
-                    });
-
-                    if let Some(gpu) = &self.gpu
-                        && gpu.gpu_total_ms > 0.0
-                    {
-                        ui.label(format!("GPU (spatial): {:.2} ms", gpu.gpu_total_ms));
-                        ui.collapsing("GPU pass timings", |ui| {
-                            for (label, ms) in &gpu.gpu_pass_ms {
-                                ui.label(format!("{:<12} {:>6.3} ms", label, ms));
-                            }
-                        });
-                    }
-                    // Window and simulation dimensions
-                    let (win_w, win_h) = self
-                        .gpu
-                        .as_ref()
-                        .map(|g| g.context.surface_size())
-                        .unwrap_or((
-                            self.app.sim_config.world_size.x as u32,
-                            self.app.sim_config.world_size.y as u32,
-                        ));
-                    ui.label(format!(
-                        "Window: {}x{} | World: {:.0}x{:.0}",
-                        win_w,
-                        win_h,
-                        self.app.sim_config.world_size.x,
-                        self.app.sim_config.world_size.y
-                    ));
+                    self.draw_stats(ui);
+                    ui.checkbox(
+                        &mut self.app.config.show_stats_overlay,
+                        "Stats overlay (F3)",
+                    )
+                    .on_hover_text("Floating stats window, visible even with the UI hidden");
                     ui.separator();
 
                     // Playback controls
@@ -73,9 +63,61 @@ impl AppHandler {
                         {
                             self.app.toggle_running();
                         }
-                        if ui.button("🔄 Reset").clicked() {
-                            self.app.regenerate_particles();
-                            self.sync_buffers();
+                        if ui
+                            .add_enabled(!self.app.running, egui::Button::new("⏭ Step"))
+                            .on_hover_text("Advance one physics step while paused (.)")
+                            .clicked()
+                        {
+                            self.request_single_step();
+                        }
+                        if ui
+                            .button("⏪ Reverse")
+                            .on_hover_text(
+                                "Negate every particle's velocity (T). Friction and clamping \
+                                 lose energy, so the scene only roughly rewinds",
+                            )
+                            .clicked()
+                        {
+                            self.reverse_velocities();
+                        }
+                        if ui
+                            .button("🧹 Clear Particles")
+                            .on_hover_text(
+                                "Remove every particle to start from an empty canvas, \
+                                 then build with the Draw brush",
+                            )
+                            .clicked()
+                        {
+                            self.clear_particles();
+                        }
+                        let mut reset_scope = None;
+                        ui.menu_button("🔄 Reset ▾", |ui| {
+                            for &scope in ResetScope::all() {
+                                if ui.button(scope.display_name()).clicked() {
+                                    reset_scope = Some(scope);
+                                    ui.close();
+                                }
+                            }
+                        });
+                        if let Some(scope) = reset_scope {
+                            self.reset(scope);
+                        }
+                        if ui
+                            .button("🏠 Reset View")
+                            .on_hover_text("Zoom 1x centered on the world (0)")
+                            .clicked()
+                        {
+                            self.reset_view();
+                        }
+                        if ui
+                            .button("🔍 Fit")
+                            .on_hover_text(
+                                "Zoom and pan to show every particle (F). With mirror or \
+                                 infinite wrap this frames one world tile",
+                            )
+                            .clicked()
+                        {
+                            self.fit_view();
                         }
                         if ui.button("🎛 Toggle Controls (H)").clicked() {
                             self.show_ui = !self.show_ui;
@@ -86,6 +128,10 @@ impl AppHandler {
                             self.screenshot_requested = true;
                             log::info!("Screenshot requested via button");
                         }
+                        if ui.button("📋 Copy (F10)").clicked() {
+                            self.clipboard_requested = true;
+                            log::info!("Clipboard screenshot requested via button");
+                        }
                         let record_label = if self.is_recording {
                             "⏹ Stop Recording (F11)".to_string()
                         } else {
@@ -96,9 +142,50 @@ impl AppHandler {
                         }
                     });
                     ui.checkbox(&mut self.capture_hide_ui, "Hide UI for capture");
+                    ui.checkbox(&mut self.deterministic_recording, "Fixed timestep while recording")
+                        .on_hover_text(
+                            "Advance the simulation by a fixed step per frame while recording, \
+                             so the video plays at a constant speed regardless of real FPS. \
+                             Overrides the Physics fixed timestep while recording",
+                        );
 
-                    // Video format selection (only when not recording)
+                    // Vector export of particle positions
                     ui.horizontal(|ui| {
+                        if ui
+                            .button("🖋 Export SVG")
+                            .on_hover_text("Save particle positions as scalable vector circles")
+                            .clicked()
+                        {
+                            self.export_svg();
+                        }
+                        ui.checkbox(&mut self.svg_cap_particles, "Cap at");
+                        ui.add_enabled(
+                            self.svg_cap_particles,
+                            egui::DragValue::new(&mut self.svg_max_particles)
+                                .range(1000..=1_048_576)
+                                .speed(1000),
+                        );
+                    });
+                    let num_particles = self.app.sim_config.num_particles as usize;
+                    if num_particles > SVG_PARTICLE_WARN_THRESHOLD
+                        && (!self.svg_cap_particles
+                            || self.svg_max_particles > SVG_PARTICLE_WARN_THRESHOLD)
+                    {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!(
+                                "⚠ {} circles will make a very large SVG; consider capping",
+                                if self.svg_cap_particles {
+                                    self.svg_max_particles.min(num_particles)
+                                } else {
+                                    num_particles
+                                }
+                            ),
+                        );
+                    }
+
+                    // Video format selection (only when not recording)
+                    ui.horizontal_wrapped(|ui| {
                         ui.label("Format:");
                         let enabled = !self.is_recording;
                         ui.add_enabled_ui(enabled, |ui| {
@@ -125,6 +212,8 @@ impl AppHandler {
                             }
                         });
                     }
+                    self.draw_state_capture_ui(ui);
+                    self.draw_metrics_logging_ui(ui);
                     ui.separator();
 
                     // Simulation settings
@@ -150,22 +239,23 @@ impl AppHandler {
                                 self.app.sim_config.num_particles = num_particles;
                                 self.app.config.sim_num_particles = num_particles;
                                 self.app.rebalance_radii_for_density();
-                                self.app.regenerate_particles();
-                                self.sync_buffers();
+                                self.resize_particle_count(num_particles);
                             }
 
                             let mut num_types = self.app.sim_config.num_types;
-                            ui.add(egui::Slider::new(&mut num_types, 2..=16).text("Types"));
+                            ui.add(egui::Slider::new(&mut num_types, 2..=MAX_NUM_TYPES).text("Types"));
                             if num_types != self.app.sim_config.num_types {
+                                self.push_undo();
                                 self.app.sim_config.num_types = num_types;
                                 self.app.config.sim_num_types = num_types;
                                 self.app.radius_matrix =
-                                    RadiusMatrix::default_for_size(num_types as usize);
+                                    self.app.default_radius_matrix(num_types as usize);
                                 self.app.rebalance_radii_for_density();
                                 self.app.regenerate_rules();
                                 self.app.regenerate_colors();
                                 self.app.regenerate_particles();
                                 self.sync_buffers();
+                                self.record_exploration();
                             }
 
                             let mut auto_scale = self.app.auto_scale_radii;
@@ -176,6 +266,7 @@ impl AppHandler {
                             )
                             .changed();
                             if auto_changed {
+                                self.push_undo();
                                 self.app.auto_scale_radii = auto_scale;
                                 self.app.config.auto_scale_radii = auto_scale;
 
@@ -183,7 +274,7 @@ impl AppHandler {
                                     self.app.rebalance_radii_for_density();
                                 } else {
                                     // Reset to defaults when disabling auto-scaling
-                                    self.app.radius_matrix = RadiusMatrix::default_for_size(
+                                    self.app.radius_matrix = self.app.default_radius_matrix(
                                         self.app.sim_config.num_types as usize,
                                     );
                                     let max_r = self.app.radius_matrix.max_interaction_radius();
@@ -193,6 +284,106 @@ impl AppHandler {
 
                                 self.sync_buffers();
                             }
+
+                            let mut relative = self.app.radii_relative_to_world;
+                            if ui
+                                .checkbox(&mut relative, "Radii relative to world")
+                                .on_hover_text(
+                                    "Default radii are fractions of the shorter world side, and \
+                                     radii and cell size rescale with the world",
+                                )
+                                .changed()
+                            {
+                                self.push_undo();
+                                self.app.radii_relative_to_world = relative;
+                                self.app.config.radii_relative_to_world = relative;
+                                self.app.radius_matrix = self.app.default_radius_matrix(
+                                    self.app.sim_config.num_types as usize,
+                                );
+                                self.app.rebalance_radii_for_density();
+                                let max_r = self.app.radius_matrix.max_interaction_radius();
+                                self.app.sim_config.spatial_hash_cell_size =
+                                    self.app.sim_config.spatial_hash_cell_size.max(max_r);
+                                self.app.config.render_spatial_hash_cell_size =
+                                    self.app.sim_config.spatial_hash_cell_size;
+                                self.sync_buffers();
+                            }
+
+                            // Generation seed: locking replays rules, colors and
+                            // particles from the same stream
+                            let mut seed = self.app.sim_config.seed;
+                            ui.horizontal(|ui| {
+                                let mut locked = seed.is_some();
+                                if ui
+                                    .checkbox(&mut locked, "Lock seed")
+                                    .on_hover_text(
+                                        "Generate rules, colors and particles from a fixed seed",
+                                    )
+                                    .changed()
+                                {
+                                    seed = locked.then(rand::random::<u64>);
+                                }
+                                if let Some(value) = &mut seed {
+                                    ui.add(egui::DragValue::new(value).speed(1.0));
+                                }
+                                if ui.button("Randomize Seed").clicked() {
+                                    seed = Some(rand::random::<u64>());
+                                }
+                            });
+                            if seed != self.app.sim_config.seed {
+                                self.app.sim_config.seed = seed;
+                                self.app.config.sim_seed = seed;
+                                if seed.is_some() {
+                                    self.push_undo();
+                                    self.app.regenerate_from_seed(false);
+                                    self.sync_buffers();
+                                    self.record_exploration();
+                                } else {
+                                    self.app.reset_rng();
+                                }
+                            }
+
+                            ui.separator();
+                            egui::ComboBox::from_label("Stagnation action")
+                                .selected_text(self.app.config.stagnation_action.display_name())
+                                .show_ui(ui, |ui| {
+                                    for &action in StagnationAction::all() {
+                                        ui.selectable_value(
+                                            &mut self.app.config.stagnation_action,
+                                            action,
+                                            action.display_name(),
+                                        );
+                                    }
+                                })
+                                .response
+                                .on_hover_text(
+                                    "Pause or stir the simulation when particles stop moving",
+                                );
+                            if self.app.config.stagnation_action != StagnationAction::None {
+                                ui.add(
+                                    egui::Slider::new(
+                                        &mut self.app.config.stagnation_speed_threshold,
+                                        0.0..=20.0,
+                                    )
+                                    .text("Still below speed"),
+                                );
+                                ui.add(
+                                    egui::Slider::new(
+                                        &mut self.app.config.stagnation_seconds,
+                                        1.0..=120.0,
+                                    )
+                                    .suffix(" s")
+                                    .text("For"),
+                                );
+                                let stagnant = self
+                                    .stagnation
+                                    .stagnant_for(std::time::Instant::now())
+                                    .as_secs_f32();
+                                ui.label(format!(
+                                    "Mean speed: {:.2} (still for {:.0}s)",
+                                    self.mean_speed, stagnant
+                                ));
+                            }
                         });
                     self.ui_simulation_open = response.openness > 0.5;
 
@@ -212,6 +403,7 @@ impl AppHandler {
                                     .text("Friction"),
                             );
                             self.app.config.phys_friction = self.app.sim_config.friction;
+                            self.draw_type_physics_editor(ui);
                             ui.add(
                                 egui::Slider::new(
                                     &mut self.app.sim_config.repel_strength,
@@ -221,6 +413,18 @@ impl AppHandler {
                             );
                             self.app.config.phys_repel_strength =
                                 self.app.sim_config.repel_strength;
+                            ui.add(
+                                egui::Slider::new(
+                                    &mut self.app.sim_config.repel_radius,
+                                    MIN_REPEL_RADIUS..=1.0,
+                                )
+                                .text("Repel Radius"),
+                            )
+                            .on_hover_text(
+                                "Where attraction flips to repulsion, as a fraction of each \
+                                 pair's min radius. Lower values let clusters pack tighter.",
+                            );
+                            self.app.config.phys_repel_radius = self.app.sim_config.repel_radius;
                             ui.add(
                                 egui::Slider::new(
                                     &mut self.app.sim_config.max_velocity,
@@ -229,6 +433,125 @@ impl AppHandler {
                                 .text("Max Velocity"),
                             );
                             self.app.config.phys_max_velocity = self.app.sim_config.max_velocity;
+                            egui::ComboBox::from_label("Velocity clamp")
+                                .selected_text(self.app.sim_config.velocity_clamp.display_name())
+                                .show_ui(ui, |ui| {
+                                    for &mode in VelocityClampMode::all() {
+                                        ui.selectable_value(
+                                            &mut self.app.sim_config.velocity_clamp,
+                                            mode,
+                                            mode.display_name(),
+                                        );
+                                    }
+                                })
+                                .response
+                                .on_hover_text(
+                                    "Soft approaches Max Velocity smoothly instead of snapping \
+                                     fast particles to it",
+                                );
+                            self.app.config.phys_velocity_clamp =
+                                self.app.sim_config.velocity_clamp;
+
+                            // Fixed timestep decouples simulation speed from the display rate
+                            let mut fixed_step = self.app.sim_config.fixed_dt.is_some();
+                            if ui
+                                .checkbox(&mut fixed_step, "Fixed timestep")
+                                .on_hover_text(
+                                    "Run a fixed number of equal physics steps per frame, so \
+                                     stability doesn't depend on FPS",
+                                )
+                                .changed()
+                            {
+                                self.app.sim_config.fixed_dt = fixed_step.then_some(1.0 / 60.0);
+                            }
+                            if let Some(fixed_dt) = &mut self.app.sim_config.fixed_dt {
+                                ui.add(
+                                    egui::Slider::new(fixed_dt, 0.001..=0.05)
+                                        .suffix(" s")
+                                        .text("Step dt"),
+                                );
+                                ui.add(
+                                    egui::Slider::new(&mut self.app.sim_config.substeps, 1..=16)
+                                        .text("Substeps per frame"),
+                                );
+                            }
+                            self.app.config.phys_fixed_dt = self.app.sim_config.fixed_dt;
+                            self.app.config.phys_substeps = self.app.sim_config.substeps;
+
+                            // Thermostat rescales velocities toward a target temperature
+                            let mut thermostat = self.app.sim_config.target_temperature.is_some();
+                            if ui
+                                .checkbox(&mut thermostat, "Thermostat")
+                                .on_hover_text(
+                                    "Scale all velocities each step to hold the mean kinetic \
+                                     energy near a target temperature",
+                                )
+                                .changed()
+                            {
+                                let start = if self.temperature > 0.0 {
+                                    self.temperature
+                                } else {
+                                    100.0
+                                };
+                                self.app.sim_config.target_temperature =
+                                    thermostat.then_some(start.clamp(1.0, 20000.0));
+                            }
+                            if let Some(target) = &mut self.app.sim_config.target_temperature {
+                                ui.add(
+                                    egui::Slider::new(target, 1.0..=20000.0)
+                                        .logarithmic(true)
+                                        .text("Target Temperature"),
+                                );
+                            }
+                            self.app.config.phys_target_temperature =
+                                self.app.sim_config.target_temperature;
+
+                            ui.add(
+                                egui::Slider::new(
+                                    &mut self.app.sim_config.neighbor_budget,
+                                    0..=2048,
+                                )
+                                .text("Neighbor Budget"),
+                            )
+                            .on_hover_text(
+                                "Max neighbors checked per particle (0 = unlimited). \
+                                 Caps the cost of dense clusters.",
+                            );
+                            if self.app.sim_config.neighbor_budget > 0 {
+                                ui.checkbox(
+                                    &mut self.app.sim_config.fair_neighbor_budget,
+                                    "Fair budget sampling",
+                                )
+                                .on_hover_text(
+                                    "Spread the budgeted neighbors across each bin from a \
+                                     per-frame hashed offset instead of taking the first ones",
+                                );
+                            }
+
+                            // Spin (visual orientation driven by tangential velocity)
+                            ui.add(
+                                egui::Slider::new(
+                                    &mut self.app.sim_config.spin_coupling,
+                                    0.0..=1.0,
+                                )
+                                .text("Spin Coupling"),
+                            )
+                            .on_hover_text(
+                                "Torque on each particle's orientation from its tangential velocity \
+                                 (0 = off). Shows an orientation notch on particles.",
+                            );
+                            self.app.config.phys_spin_coupling = self.app.sim_config.spin_coupling;
+                            if self.app.sim_config.spin_coupling > 0.0 {
+                                ui.add(
+                                    egui::Slider::new(
+                                        &mut self.app.sim_config.spin_friction,
+                                        0.0..=1.0,
+                                    )
+                                    .text("Spin Friction"),
+                                );
+                                self.app.config.phys_spin_friction =
+                                    self.app.sim_config.spin_friction;
+                            }
 
                             // Boundary mode
                             let boundary_modes = [
@@ -236,6 +559,7 @@ impl AppHandler {
                                 (BoundaryMode::Wrap, "Wrap"),
                                 (BoundaryMode::MirrorWrap, "Mirror"),
                                 (BoundaryMode::InfiniteWrap, "Infinite"),
+                                (BoundaryMode::Sticky, "Sticky"),
                             ];
                             let old_boundary_mode = self.app.sim_config.boundary_mode;
                             egui::ComboBox::from_label("Boundary")
@@ -244,6 +568,7 @@ impl AppHandler {
                                     BoundaryMode::Wrap => "Wrap",
                                     BoundaryMode::MirrorWrap => "Mirror",
                                     BoundaryMode::InfiniteWrap => "Infinite",
+                                    BoundaryMode::Sticky => "Sticky",
                                 })
                                 .show_ui(ui, |ui| {
                                     for (mode, name) in boundary_modes {
@@ -263,6 +588,66 @@ impl AppHandler {
                             }
                             self.app.config.phys_boundary_mode = self.app.sim_config.boundary_mode;
 
+                            // World shape
+                            let old_world_shape = self.app.sim_config.world_shape;
+                            egui::ComboBox::from_label("World Shape")
+                                .selected_text(self.app.sim_config.world_shape.display_name())
+                                .show_ui(ui, |ui| {
+                                    for &shape in WorldShape::all() {
+                                        ui.selectable_value(
+                                            &mut self.app.sim_config.world_shape,
+                                            shape,
+                                            shape.display_name(),
+                                        );
+                                    }
+                                })
+                                .response
+                                .on_hover_text(
+                                    "Circle confines particles to the circle inscribed in the world",
+                                );
+                            if self.app.sim_config.world_shape != old_world_shape {
+                                self.sync_particles_from_gpu();
+                                self.normalize_particle_positions();
+                                self.sync_buffers();
+                            }
+                            self.app.config.phys_world_shape = self.app.sim_config.world_shape;
+
+                            if ui
+                                .checkbox(
+                                    &mut self.app.config.match_world_to_window,
+                                    "Match world to window",
+                                )
+                                .on_hover_text(
+                                    "Resize the world to the window, rescaling particles to fit",
+                                )
+                                .changed()
+                            {
+                                self.match_world_to_window();
+                            }
+
+                            // Explicit world size; disabled while it follows the window
+                            let mut world_size = self.app.sim_config.world_size;
+                            ui.add_enabled_ui(!self.app.config.match_world_to_window, |ui| {
+                                ui.add(
+                                    egui::Slider::new(&mut world_size.x, 100.0..=10000.0)
+                                        .text("World Width")
+                                        .logarithmic(true)
+                                        .integer(),
+                                );
+                                ui.add(
+                                    egui::Slider::new(&mut world_size.y, 100.0..=10000.0)
+                                        .text("World Height")
+                                        .logarithmic(true)
+                                        .integer(),
+                                )
+                                .on_hover_text("Particles are rescaled to keep the pattern");
+                            });
+                            if world_size != self.app.sim_config.world_size
+                                && let Err(e) = self.resize_world(world_size)
+                            {
+                                self.preset_status = format!("Invalid world size: {}", e);
+                            }
+
                             // Wall repel strength (only visible in Repel mode)
                             if self.app.sim_config.boundary_mode == BoundaryMode::Repel {
                                 ui.add(
@@ -316,17 +701,61 @@ impl AppHandler {
                                     }
                                 });
                             if new_rule != self.app.current_rule {
+                                self.push_undo();
                                 self.app.current_rule = new_rule;
                                 self.app.config.gen_rule = new_rule;
                                 self.app.regenerate_rules();
                                 self.sync_interaction_matrix();
+                                self.record_exploration();
                             }
 
                             if ui.button("🎲 Randomize Rules").clicked() {
+                                self.push_undo();
                                 self.app.regenerate_rules();
                                 self.sync_interaction_matrix();
+                                self.record_exploration();
                             }
 
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .button("🧬 Mutate Matrix")
+                                    .on_hover_text(
+                                        "Add Gaussian noise to every matrix entry (N)",
+                                    )
+                                    .clicked()
+                                {
+                                    self.push_undo();
+                                    self.app.mutate_rules();
+                                    self.sync_interaction_matrix();
+                                    self.record_exploration();
+                                }
+                                ui.add(
+                                    egui::Slider::new(
+                                        &mut self.app.config.gen_mutation_sigma,
+                                        0.01..=0.5,
+                                    )
+                                    .text("Sigma"),
+                                );
+                            });
+
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut self.rules_import_path)
+                                        .hint_text("path to matrix .json")
+                                        .desired_width(140.0),
+                                );
+                                if ui
+                                    .button("Import external rules…")
+                                    .on_hover_text(
+                                        "Load an attraction matrix from another particle-life \
+                                         tool (JSON array of rows or flat row-major list)",
+                                    )
+                                    .clicked()
+                                {
+                                    self.import_external_rules();
+                                }
+                            });
+
                             ui.separator();
 
                             // Palette type
@@ -341,10 +770,32 @@ impl AppHandler {
                                     }
                                 });
                             if new_palette != self.app.current_palette {
+                                self.push_undo();
                                 self.app.current_palette = new_palette;
                                 self.app.config.gen_palette = new_palette;
                                 self.app.regenerate_colors();
                                 self.sync_colors();
+                                self.record_exploration();
+                            }
+
+                            if ui
+                                .add(
+                                    egui::Slider::new(
+                                        &mut self.app.sim_config.palette_jitter,
+                                        0.0..=1.0,
+                                    )
+                                    .text("Color jitter"),
+                                )
+                                .on_hover_text(
+                                    "Shift each type's hue and brightness by a seeded random \
+                                     amount to tell similar colors apart",
+                                )
+                                .changed()
+                            {
+                                self.app.config.gen_palette_jitter =
+                                    self.app.sim_config.palette_jitter;
+                                self.app.apply_palette_jitter();
+                                self.sync_colors();
                             }
 
                             ui.separator();
@@ -361,6 +812,7 @@ impl AppHandler {
                                     }
                                 });
                             if new_pattern != self.app.current_pattern {
+                                self.push_undo();
                                 self.app.current_pattern = new_pattern;
                                 self.app.config.gen_pattern = new_pattern;
 
@@ -371,21 +823,120 @@ impl AppHandler {
                                         self.app.sim_config.num_types = required;
                                         self.app.config.sim_num_types = required;
                                         self.app.radius_matrix =
-                                            RadiusMatrix::default_for_size(required as usize);
-                                        self.app.interaction_matrix = generate_rules(
-                                            self.app.current_rule,
-                                            required as usize,
-                                        );
-                                        self.app.colors = generate_colors(
-                                            self.app.current_palette,
-                                            required as usize,
-                                        );
+                                            self.app.default_radius_matrix(required as usize);
+                                        self.app.regenerate_rules();
+                                        self.app.regenerate_colors();
                                     }
                                 }
 
                                 self.app.regenerate_particles();
                                 self.sync_buffers();
+                                self.record_exploration();
+                            }
+
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut self.image_seed_path)
+                                        .hint_text("path to image")
+                                        .desired_width(140.0),
+                                );
+                                if ui
+                                    .button("Spawn from image…")
+                                    .on_hover_text(
+                                        "Place particles by image brightness, typed by the \
+                                         nearest palette color",
+                                    )
+                                    .clicked()
+                                {
+                                    self.spawn_from_image();
+                                }
+                            });
+
+                            // Initial velocity field
+                            let mut spawn_velocity = self.app.current_spawn_velocity;
+                            egui::ComboBox::from_label("Spawn Velocity")
+                                .selected_text(spawn_velocity.display_name())
+                                .show_ui(ui, |ui| {
+                                    for &kind in SpawnVelocity::all() {
+                                        if ui
+                                            .selectable_label(
+                                                spawn_velocity.same_kind(&kind),
+                                                kind.display_name(),
+                                            )
+                                            .clicked()
+                                            && !spawn_velocity.same_kind(&kind)
+                                        {
+                                            spawn_velocity = kind;
+                                        }
+                                    }
+                                });
+                            let mut respawn = spawn_velocity != self.app.current_spawn_velocity;
+                            let slider = match &mut spawn_velocity {
+                                SpawnVelocity::Zero => None,
+                                SpawnVelocity::Rotational(omega) => Some(
+                                    ui.add(egui::Slider::new(omega, -2.0..=2.0).text("Spin (rad/s)")),
+                                ),
+                                SpawnVelocity::Radial(speed) => Some(
+                                    ui.add(egui::Slider::new(speed, -300.0..=300.0).text("Speed")),
+                                ),
+                                SpawnVelocity::Random(max) => Some(
+                                    ui.add(egui::Slider::new(max, 0.0..=300.0).text("Max Speed")),
+                                ),
+                            };
+                            // Respawn once the slider is released rather than every drag frame
+                            if let Some(slider) = slider {
+                                respawn = (respawn && !slider.dragged()) || slider.drag_stopped();
+                            }
+                            self.app.current_spawn_velocity = spawn_velocity;
+                            self.app.config.gen_spawn_velocity = spawn_velocity;
+                            if respawn {
+                                self.app.regenerate_particles();
+                                self.sync_buffers();
+                            }
+
+                            // Coordinate sampling (Random, Disk and Stripes patterns)
+                            let old_sampling = self.app.current_spawn_sampling;
+                            egui::ComboBox::from_label("Sampling")
+                                .selected_text(self.app.current_spawn_sampling.display_name())
+                                .show_ui(ui, |ui| {
+                                    for &sampling in SpawnSampling::all() {
+                                        ui.selectable_value(
+                                            &mut self.app.current_spawn_sampling,
+                                            sampling,
+                                            sampling.display_name(),
+                                        );
+                                    }
+                                })
+                                .response
+                                .on_hover_text(
+                                    "Low discrepancy spreads Random, Disk and Stripes spawns evenly",
+                                );
+                            if self.app.current_spawn_sampling != old_sampling {
+                                self.app.config.gen_spawn_sampling =
+                                    self.app.current_spawn_sampling;
+                                self.app.regenerate_particles();
+                                self.sync_buffers();
+                            }
+
+                            if ui
+                                .checkbox(
+                                    &mut self.app.config.gen_shuffle_particles,
+                                    "Shuffle draw order",
+                                )
+                                .on_hover_text(
+                                    "Shuffle spawned particles so no type always draws on top; \
+                                     the order is fixed by the seed",
+                                )
+                                .changed()
+                            {
+                                self.app.regenerate_particles();
+                                self.sync_buffers();
                             }
+
+                            self.draw_type_weights(ui);
+
+                            ui.separator();
+                            self.draw_lineage(ui);
                         });
                     self.ui_generators_open = response.openness > 0.5;
 
@@ -394,6 +945,7 @@ impl AppHandler {
                         .id_salt("interaction_matrix_header")
                         .default_open(self.ui_interaction_matrix_open)
                         .show(ui, |ui| {
+                            self.draw_type_names_editor(ui);
                             self.draw_matrix_editor(ui);
                         });
                     self.ui_interaction_matrix_open = response.openness > 0.5;
@@ -425,6 +977,15 @@ impl AppHandler {
                         });
                     self.ui_presets_open = response.openness > 0.5;
 
+                    // Debug pass toggles
+                    let response = egui::CollapsingHeader::new("Debug")
+                        .id_salt("debug_header")
+                        .default_open(self.ui_debug_open)
+                        .show(ui, |ui| {
+                            self.draw_debug_ui(ui);
+                        });
+                    self.ui_debug_open = response.openness > 0.5;
+
                     ui.separator();
 
                     // Keyboard shortcuts help
@@ -433,9 +994,20 @@ impl AppHandler {
                         .default_open(self.ui_keyboard_shortcuts_open)
                         .show(ui, |ui| {
                             ui.label("Space - Pause/Resume");
+                            ui.label(". - Single Step (paused)");
                             ui.label("R - Regenerate Particles");
                             ui.label("M - New Interaction Matrix");
+                            ui.label("N - Mutate Interaction Matrix");
+                            ui.label("Ctrl+Z / Ctrl+Shift+Z - Undo/Redo Matrix Edit");
+                            ui.label("[ / ] - Previous/Next Palette");
+                            ui.label("{ / } - Previous/Next Rule Type");
                             ui.label("H - Toggle UI");
+                            ui.label("T - Reverse Velocities");
+                            ui.label("G - Toggle Gravity Well");
+                            ui.label("0 / C - Reset View");
+                            ui.label("F - Fit View to Particles");
+                            ui.label("F3 - Toggle Stats Overlay");
+                            ui.label("Alt+1..9 - Load Favorite Preset");
                             ui.label("Escape - Quit");
                         });
                     self.ui_keyboard_shortcuts_open = response.openness > 0.5;
@@ -443,20 +1015,145 @@ impl AppHandler {
             });
     }
 
-    fn draw_brush_tools(&mut self, ui: &mut egui::Ui) {
-        // Tool selection
+    /// FPS, GPU timings, VRAM and size readouts, shared by the sidebar and
+    /// the floating stats overlay.
+    fn draw_stats(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
-            for &tool in BrushTool::all() {
-                let selected = self.brush.tool == tool;
-                let text = if tool == BrushTool::Erase {
-                    format!("{} {}", tool.icon(), tool.name())
-                } else {
-                    tool.name().to_string()
-                };
-                if ui.selectable_label(selected, text).clicked() {
-                    self.brush.tool = tool;
-                }
-            }
+            ui.label(format!("FPS: {:.1}", self.fps));
+            ui.separator();
+            ui.label(format!("EMA: {:.1}", self.fps_ema));
+            ui.separator();
+            ui.label(format!("Particles: {}", self.app.particles.len()));
+        });
+        ui.label(format!("Temperature: {:.1}", self.temperature))
+            .on_hover_text("Mean kinetic energy per particle (0.5 * |v|^2)");
+
+        if let Some(gpu) = &self.gpu {
+            const MB: f64 = 1024.0 * 1024.0;
+            let vram = gpu.vram_usage();
+            ui.collapsing(format!("VRAM: ~{:.1} MB", vram.total() as f64 / MB), |ui| {
+                ui.label(format!(
+                    "Particles:    {:>8.2} MB",
+                    vram.particles as f64 / MB
+                ));
+                ui.label(format!(
+                    "Spatial bins: {:>8.2} MB",
+                    vram.spatial_bins as f64 / MB
+                ));
+                ui.label(format!(
+                    "Matrices:     {:>8.2} MB",
+                    vram.matrices as f64 / MB
+                ));
+                ui.label(format!("Other:        {:>8.2} MB", vram.other as f64 / MB));
+            });
+            ui.label(if gpu.buffers.use_f16 {
+                "Precision: f16 velocities"
+            } else if self.force_f32 {
+                "Precision: f32 (forced)"
+            } else {
+                "Precision: f32"
+            });
+            ui.label(format!(
+                "Spatial workgroup: {}",
+                gpu.spatial_pipelines.workgroup_size
+            ));
+        }
+
+        if let Some(gpu) = &self.gpu
+            && gpu.gpu_total_ms > 0.0
+        {
+            ui.label(format!("GPU (spatial): {:.2} ms", gpu.gpu_total_ms));
+            ui.collapsing("GPU pass timings", |ui| {
+                for (label, ms) in &gpu.gpu_pass_ms {
+                    ui.label(format!("{:<12} {:>6.3} ms", label, ms));
+                }
+            });
+        }
+        if self.cpu_compare {
+            ui.label(match &self.cpu_compare_result {
+                Some(div) => format!(
+                    "GPU vs CPU: max {:.3}, mean {:.4}{}",
+                    div.max,
+                    div.mean,
+                    if div.unmatched > 0 {
+                        format!(" ({} unmatched)", div.unmatched)
+                    } else {
+                        String::new()
+                    }
+                ),
+                None => "GPU vs CPU: waiting for sample".to_string(),
+            });
+        }
+        // Window and simulation dimensions
+        let (win_w, win_h) = self
+            .gpu
+            .as_ref()
+            .map(|g| g.context.surface_size())
+            .unwrap_or((
+                self.app.sim_config.world_size.x as u32,
+                self.app.sim_config.world_size.y as u32,
+            ));
+        ui.label(format!(
+            "Window: {}x{} | World: {:.0}x{:.0}",
+            win_w, win_h, self.app.sim_config.world_size.x, self.app.sim_config.world_size.y
+        ));
+    }
+
+    /// Floating, movable stats window that stays visible when the
+    /// sidebar is hidden.
+    fn draw_stats_overlay(&mut self, ctx: &egui::Context) {
+        if !self.app.config.show_stats_overlay {
+            return;
+        }
+        let mut open = true;
+        egui::Window::new("Stats")
+            .id(egui::Id::new("stats_overlay"))
+            .open(&mut open)
+            .resizable(false)
+            .default_pos([ctx.content_rect().right() - 320.0, 10.0])
+            .show(ctx, |ui| {
+                self.draw_stats(ui);
+            });
+        if !open {
+            self.app.config.show_stats_overlay = false;
+        }
+    }
+
+    /// Briefly show the latest HUD message (e.g. palette or rule changes
+    /// from the keyboard), centered near the top of the window.
+    fn draw_hud_message(&mut self, ctx: &egui::Context) {
+        const HUD_MESSAGE_SECS: f32 = 1.5;
+        let Some((message, shown_at)) = &self.hud_message else {
+            return;
+        };
+        if shown_at.elapsed().as_secs_f32() > HUD_MESSAGE_SECS {
+            self.hud_message = None;
+            return;
+        }
+        egui::Area::new(egui::Id::new("hud_message"))
+            .anchor(egui::Align2::CENTER_TOP, [0.0, 20.0])
+            .interactable(false)
+            .show(ctx, |ui| {
+                egui::Frame::popup(ui.style()).show(ui, |ui| {
+                    ui.heading(message.as_str());
+                });
+            });
+    }
+
+    fn draw_brush_tools(&mut self, ui: &mut egui::Ui) {
+        // Tool selection
+        ui.horizontal(|ui| {
+            for &tool in BrushTool::all() {
+                let selected = self.brush.tool == tool;
+                let text = if tool == BrushTool::Erase {
+                    format!("{} {}", tool.icon(), tool.name())
+                } else {
+                    tool.name().to_string()
+                };
+                if ui.selectable_label(selected, text).clicked() {
+                    self.brush.tool = tool;
+                }
+            }
         });
 
         if self.brush.tool != BrushTool::None {
@@ -479,26 +1176,47 @@ impl AppHandler {
                 ui.add(
                     egui::Slider::new(&mut self.brush.repel_force, 1.0..=100.0).text("Repel Force"),
                 );
-            } else if self.brush.tool == BrushTool::Draw {
-                ui.add(
-                    egui::Slider::new(&mut self.brush.draw_intensity, 1..=200).text("Intensity"),
-                );
+            } else if matches!(self.brush.tool, BrushTool::Draw | BrushTool::Emitter) {
+                if self.brush.tool == BrushTool::Draw {
+                    ui.add(
+                        egui::Slider::new(&mut self.brush.draw_intensity, 1..=200)
+                            .text("Intensity"),
+                    );
+                } else {
+                    ui.add(
+                        egui::Slider::new(&mut self.brush.emit_rate, 10.0..=5000.0)
+                            .text("Rate (particles/s)")
+                            .logarithmic(true),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut self.brush.emit_cap, 1000..=262_144)
+                            .text("Max Particles")
+                            .logarithmic(true),
+                    )
+                    .on_hover_text("The emitter stops once the simulation holds this many");
+                    if self.app.particles.len() >= self.brush.emit_cap as usize {
+                        ui.colored_label(
+                            egui::Color32::from_rgb(230, 180, 80),
+                            "Particle cap reached: emitter paused",
+                        );
+                    }
+                }
 
                 // Type selector for Draw tool
                 let num_types = self.app.sim_config.num_types as i32;
                 let type_label = if self.brush.draw_type < 0 {
                     "Random".to_string()
                 } else {
-                    format!("Type {}", self.brush.draw_type)
+                    self.app.sim_config.type_name(self.brush.draw_type as usize)
                 };
                 egui::ComboBox::from_label("Draw Type")
                     .selected_text(type_label)
                     .show_ui(ui, |ui| {
                         ui.selectable_value(&mut self.brush.draw_type, -1, "Random");
                         for i in 0..num_types {
-                            // Show color swatch with type number
+                            // Show color swatch with type name
                             let color = self.app.colors[i as usize];
-                            let label = format!("Type {}", i);
+                            let label = self.app.sim_config.type_name(i as usize);
                             ui.horizontal(|ui| {
                                 let size = egui::vec2(12.0, 12.0);
                                 let (response, painter) =
@@ -521,22 +1239,34 @@ impl AppHandler {
                             });
                         }
                     });
+
+                ui.horizontal(|ui| {
+                    ui.checkbox(&mut self.brush.draw_static, "Static")
+                        .on_hover_text(
+                            "Spawn pinned particles that never move but still exert forces",
+                        );
+                    if ui.button("Release Static").clicked() {
+                        self.release_static_particles();
+                    }
+                });
             } else if self.brush.tool == BrushTool::Erase {
                 // Type selector for Erase tool
                 let num_types = self.app.sim_config.num_types as i32;
                 let type_label = if self.brush.target_type < 0 {
                     "All".to_string()
                 } else {
-                    format!("Type {}", self.brush.target_type)
+                    self.app
+                        .sim_config
+                        .type_name(self.brush.target_type as usize)
                 };
                 egui::ComboBox::from_label("Target Type")
                     .selected_text(type_label)
                     .show_ui(ui, |ui| {
                         ui.selectable_value(&mut self.brush.target_type, -1, "All");
                         for i in 0..num_types {
-                            // Show color swatch with type number
+                            // Show color swatch with type name
                             let color = self.app.colors[i as usize];
-                            let label = format!("Type {}", i);
+                            let label = self.app.sim_config.type_name(i as usize);
                             ui.horizontal(|ui| {
                                 let size = egui::vec2(12.0, 12.0);
                                 let (response, painter) =
@@ -561,6 +1291,34 @@ impl AppHandler {
                     });
             }
 
+            // Per-type locks shield species from every brush tool
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Locked:")
+                    .on_hover_text("Locked types are never drawn, erased or pushed by the brush");
+                for t in 0..self.app.sim_config.num_types {
+                    let color = self.app.colors[t as usize];
+                    let (response, painter) =
+                        ui.allocate_painter(egui::vec2(12.0, 12.0), egui::Sense::hover());
+                    painter.rect_filled(
+                        response.rect,
+                        2.0,
+                        egui::Color32::from_rgb(
+                            (color[0] * 255.0) as u8,
+                            (color[1] * 255.0) as u8,
+                            (color[2] * 255.0) as u8,
+                        ),
+                    );
+                    let mut locked = self.brush.is_locked(t);
+                    if ui
+                        .checkbox(&mut locked, "")
+                        .on_hover_text(format!("Lock type {}", t))
+                        .changed()
+                    {
+                        self.brush.set_locked(t, locked);
+                    }
+                }
+            });
+
             // Directional force (for attract/repel)
             if matches!(self.brush.tool, BrushTool::Attract | BrushTool::Repel) {
                 ui.add(
@@ -569,8 +1327,75 @@ impl AppHandler {
                 );
             }
 
-            // Show circle toggle
+            // Extra brush points (Ctrl+click pins, multi-touch)
+            if matches!(self.brush.tool, BrushTool::Attract | BrushTool::Repel)
+                || !self.brush.pinned_points.is_empty()
+            {
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "Pinned points: {}/{}",
+                        self.brush.pinned_points.len(),
+                        MAX_EXTRA_BRUSH_POINTS
+                    ))
+                    .on_hover_text(
+                        "Ctrl+click pins a persistent point with the current tool's force. \
+                         Touch points also act as brushes.",
+                    );
+                    if ui
+                        .add_enabled(
+                            !self.brush.pinned_points.is_empty(),
+                            egui::Button::new("Clear"),
+                        )
+                        .clicked()
+                    {
+                        self.brush.pinned_points.clear();
+                    }
+                });
+            }
+
+            // Gravity well pulls toward the cursor regardless of tool and radius
+            ui.checkbox(&mut self.brush.gravity_well, "Gravity Well (G)")
+                .on_hover_text(
+                    "Pull every particle toward the cursor, wherever it is. \
+                     Constant inside the brush radius, fading with distance beyond it.",
+                );
+            if self.brush.gravity_well {
+                ui.add(
+                    egui::Slider::new(&mut self.brush.well_strength, 0.0..=100.0)
+                        .text("Well Strength"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.brush.well_falloff, 0.0..=3.0).text("Well Falloff"),
+                )
+                .on_hover_text("Distance exponent: 0 = constant pull, 1 = inverse distance");
+            }
+
+            // Show circle toggle and appearance
             ui.checkbox(&mut self.brush.show_circle, "Show Circle");
+            if self.brush.show_circle {
+                ui.add(
+                    egui::Slider::new(&mut self.brush.circle_line_width, 0.5..=10.0)
+                        .text("Line Width"),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.brush.circle_fill_alpha, 0.0..=0.5)
+                        .text("Fill Opacity"),
+                );
+                ui.checkbox(&mut self.brush.circle_contrast, "High Contrast")
+                    .on_hover_text(
+                        "Dark halo with a brightened outline, visible on any background",
+                    );
+                ui.horizontal(|ui| {
+                    let mut override_color = self.brush.circle_color.is_some();
+                    ui.checkbox(&mut override_color, "Custom Color");
+                    if override_color {
+                        let color = self.brush.circle_color.get_or_insert([1.0, 1.0, 1.0]);
+                        ui.color_edit_button_rgb(color);
+                    } else {
+                        self.brush.circle_color = None;
+                    }
+                });
+            }
 
             ui.separator();
             ui.label("Left-click to use brush");
@@ -586,9 +1411,45 @@ impl AppHandler {
 
         ui.horizontal(|ui| {
             ui.label("Background");
-            ui.color_edit_button_rgb(&mut self.app.sim_config.background_color);
+            let gradient = &mut self.app.sim_config.background_gradient;
+            match gradient {
+                Some([top, bottom]) => {
+                    ui.color_edit_button_rgb(top).on_hover_text("Top color");
+                    ui.color_edit_button_rgb(bottom)
+                        .on_hover_text("Bottom color");
+                }
+                None => {
+                    ui.color_edit_button_rgb(&mut self.app.sim_config.background_color);
+                }
+            }
+            let mut use_gradient = gradient.is_some();
+            if ui
+                .checkbox(&mut use_gradient, "Gradient")
+                .on_hover_text("Vertical two-color gradient instead of a solid color")
+                .changed()
+            {
+                let bg = self.app.sim_config.background_color;
+                self.app.sim_config.background_gradient = use_gradient.then_some([bg, bg]);
+            }
         });
         self.app.config.render_background_color = self.app.sim_config.background_color;
+        self.app.config.render_background_gradient = self.app.sim_config.background_gradient;
+
+        ui.separator();
+
+        ui.checkbox(&mut self.app.config.adaptive_quality, "Adaptive quality")
+            .on_hover_text(
+                "Drop glow, then cap the neighbor budget, while the FPS stays below the \
+                 target; restore them once there is headroom",
+            );
+        if self.app.config.adaptive_quality {
+            ui.add(egui::Slider::new(&mut self.app.config.target_fps, 15..=240).text("Target FPS"));
+            ui.label(format!(
+                "Quality level: {}/{}",
+                self.adaptive_quality.level(),
+                MAX_QUALITY_LEVEL
+            ));
+        }
 
         let vsync_changed = ui
             .checkbox(&mut self.app.config.vsync, "VSync (present)")
@@ -597,6 +1458,77 @@ impl AppHandler {
             self.pending_vsync = Some(self.app.config.vsync);
         }
 
+        let msaa_label = |n: u32| {
+            if n <= 1 {
+                "Off".to_string()
+            } else {
+                format!("{}x", n)
+            }
+        };
+        egui::ComboBox::from_label("Anti-aliasing (MSAA)")
+            .selected_text(msaa_label(self.app.config.msaa_samples))
+            .show_ui(ui, |ui| {
+                for n in MSAA_SAMPLE_COUNTS {
+                    ui.selectable_value(&mut self.app.config.msaa_samples, n, msaa_label(n));
+                }
+            })
+            .response
+            .on_hover_text("Multisample particle edges so small dots do not shimmer");
+        if let Some(gpu) = &self.gpu
+            && gpu.msaa.requested() == self.app.config.msaa_samples
+            && gpu.msaa.sample_count() != self.app.config.msaa_samples.max(1)
+        {
+            ui.label(format!(
+                "{}x MSAA is not supported on this device; using {}x",
+                self.app.config.msaa_samples,
+                gpu.msaa.sample_count()
+            ));
+        }
+
+        ui.horizontal(|ui| {
+            let mut capped = self.app.config.fps_cap.is_some();
+            if ui
+                .checkbox(&mut capped, "FPS cap")
+                .on_hover_text("Limit the frame rate while VSync is off to save power")
+                .changed()
+            {
+                self.app.config.fps_cap = capped.then_some(60);
+            }
+            if let Some(cap) = &mut self.app.config.fps_cap {
+                ui.add_enabled(
+                    !self.app.config.vsync,
+                    egui::Slider::new(cap, MIN_FPS_CAP..=240).suffix(" fps"),
+                )
+                .on_disabled_hover_text("VSync paces frames while it is on");
+            }
+        });
+
+        ui.checkbox(&mut self.app.config.force_f32, "Force f32 storage")
+            .on_hover_text("Disable f16 velocity storage (applies on restart)");
+
+        // Monitor and exclusive fullscreen
+        let labels = self.monitor_labels();
+        let old_options = self.window_options;
+        let selected = self
+            .window_options
+            .monitor
+            .and_then(|i| labels.get(i).cloned())
+            .unwrap_or_else(|| "Primary".to_string());
+        egui::ComboBox::from_label("Monitor")
+            .selected_text(selected)
+            .show_ui(ui, |ui| {
+                ui.selectable_value(&mut self.window_options.monitor, None, "Primary");
+                for (i, label) in labels.iter().enumerate() {
+                    ui.selectable_value(&mut self.window_options.monitor, Some(i), label);
+                }
+            });
+        ui.checkbox(&mut self.window_options.fullscreen, "Exclusive fullscreen")
+            .on_hover_text("Fullscreen at the monitor's native video mode");
+        if self.window_options != old_options {
+            self.app.config.window_monitor = self.window_options.monitor;
+            self.apply_window_options();
+        }
+
         ui.separator();
 
         // Spatial hashing is mandatory
@@ -619,6 +1551,141 @@ impl AppHandler {
 
         ui.separator();
 
+        // Color mode
+        egui::ComboBox::from_label("Color Mode")
+            .selected_text(self.app.sim_config.color_mode.display_name())
+            .show_ui(ui, |ui| {
+                for &mode in ColorMode::all() {
+                    ui.selectable_value(
+                        &mut self.app.sim_config.color_mode,
+                        mode,
+                        mode.display_name(),
+                    );
+                }
+            });
+        self.app.config.render_color_mode = self.app.sim_config.color_mode;
+
+        egui::ComboBox::from_label("Particle Shape")
+            .selected_text(self.app.sim_config.particle_shape.display_name())
+            .show_ui(ui, |ui| {
+                for &shape in ParticleShape::all() {
+                    ui.selectable_value(
+                        &mut self.app.sim_config.particle_shape,
+                        shape,
+                        shape.display_name(),
+                    );
+                }
+            });
+        self.app.config.render_particle_shape = self.app.sim_config.particle_shape;
+
+        egui::ComboBox::from_label("Blend Mode")
+            .selected_text(self.app.sim_config.blend_mode.display_name())
+            .show_ui(ui, |ui| {
+                for &mode in BlendMode::all() {
+                    ui.selectable_value(
+                        &mut self.app.sim_config.blend_mode,
+                        mode,
+                        mode.display_name(),
+                    );
+                }
+            })
+            .response
+            .on_hover_text("Additive makes dense clusters glow brighter instead of overlapping");
+        self.app.config.render_blend_mode = self.app.sim_config.blend_mode;
+
+        if self.app.sim_config.color_mode.uses_colormap() {
+            let old_colormap = self.app.sim_config.colormap;
+            egui::ComboBox::from_label("Colormap")
+                .selected_text(self.app.sim_config.colormap.display_name())
+                .show_ui(ui, |ui| {
+                    for &palette in PaletteType::all() {
+                        ui.selectable_value(
+                            &mut self.app.sim_config.colormap,
+                            palette,
+                            palette.display_name(),
+                        );
+                    }
+                });
+            if self.app.sim_config.colormap != old_colormap {
+                self.app.config.render_colormap = self.app.sim_config.colormap;
+                self.sync_colormap();
+            }
+            ui.add(
+                egui::Slider::new(&mut self.app.sim_config.color_speed_range, 1.0..=1000.0)
+                    .logarithmic(true)
+                    .text("Speed Range"),
+            )
+            .on_hover_text("Speed mapped to the top of the colormap");
+            self.app.config.render_color_speed_range = self.app.sim_config.color_speed_range;
+        }
+
+        ui.separator();
+
+        ui.checkbox(&mut self.app.sim_config.animate_palette, "Animate palette")
+            .on_hover_text("Rotate type colors around the color wheel over time");
+        self.app.config.render_animate_palette = self.app.sim_config.animate_palette;
+        if self.app.sim_config.animate_palette {
+            ui.add(
+                egui::Slider::new(&mut self.app.sim_config.palette_hue_speed, -180.0..=180.0)
+                    .text("Hue Speed (°/s)"),
+            );
+            self.app.config.render_palette_hue_speed = self.app.sim_config.palette_hue_speed;
+        }
+
+        ui.separator();
+
+        if self.app.sim_config.boundary_mode == BoundaryMode::Wrap
+            && self.app.sim_config.world_shape == WorldShape::Rectangle
+        {
+            ui.add(
+                egui::Slider::new(&mut self.app.sim_config.seam_fade, 0.0..=0.2).text("Seam Fade"),
+            )
+            .on_hover_text("Fade particles near the world edges so wrapping looks seamless");
+            self.app.config.render_seam_fade = self.app.sim_config.seam_fade;
+            ui.separator();
+        }
+
+        ui.checkbox(
+            &mut self.app.sim_config.velocity_streaks,
+            "Velocity streaks",
+        )
+        .on_hover_text("Stretch each particle along its velocity");
+        self.app.config.render_velocity_streaks = self.app.sim_config.velocity_streaks;
+        if self.app.sim_config.velocity_streaks {
+            ui.add(
+                egui::Slider::new(&mut self.app.sim_config.streak_length, 0.005..=0.5)
+                    .logarithmic(true)
+                    .text("Streak Length"),
+            )
+            .on_hover_text("Streak length per unit of speed (seconds of travel)");
+            self.app.config.render_streak_length = self.app.sim_config.streak_length;
+        }
+
+        ui.separator();
+
+        let was_3d = self.app.sim_config.enable_3d;
+        ui.checkbox(&mut self.app.sim_config.enable_3d, "3D depth")
+            .on_hover_text("Nearer particles draw bigger and brighter and cover farther ones");
+        self.app.config.render_enable_3d = self.app.sim_config.enable_3d;
+        let mut redepth = self.app.sim_config.enable_3d && !was_3d;
+        if self.app.sim_config.enable_3d {
+            let response = ui
+                .add(
+                    egui::Slider::new(&mut self.app.sim_config.depth_limit, 10.0..=2000.0)
+                        .logarithmic(true)
+                        .text("Depth"),
+                )
+                .on_hover_text("Depth range; the farthest particles draw at half size");
+            self.app.config.render_depth_limit = self.app.sim_config.depth_limit;
+            // Re-spread once the slider is released rather than every drag frame
+            redepth |= response.drag_stopped() || (response.changed() && !response.dragged());
+        }
+        if redepth {
+            self.assign_depths();
+        }
+
+        ui.separator();
+
         // Glow effect toggle
         ui.checkbox(&mut self.app.sim_config.enable_glow, "Glow Effect");
         self.app.config.render_glow_enabled = self.app.sim_config.enable_glow;
@@ -638,6 +1705,314 @@ impl AppHandler {
                     .text("Steepness"),
             );
             self.app.config.render_glow_steepness = self.app.sim_config.glow_steepness;
+            ui.checkbox(
+                &mut self.app.sim_config.glow_zoom_compensate,
+                "Compensate for zoom",
+            )
+            .on_hover_text("Keep the glow the same size on screen when zooming");
+            self.app.config.render_glow_zoom_compensate = self.app.sim_config.glow_zoom_compensate;
+        }
+
+        ui.separator();
+
+        // Motion trails
+        ui.checkbox(&mut self.app.sim_config.enable_trails, "Trails")
+            .on_hover_text("Fade the previous frame instead of clearing it");
+        self.app.config.render_trails_enabled = self.app.sim_config.enable_trails;
+
+        if self.app.sim_config.enable_trails {
+            ui.add(
+                egui::Slider::new(&mut self.app.sim_config.trail_fade, 0.01..=1.0)
+                    .logarithmic(true)
+                    .text("Trail Fade"),
+            )
+            .on_hover_text("Background opacity drawn each frame; lower = longer trails");
+            self.app.config.render_trail_fade = self.app.sim_config.trail_fade;
+        }
+
+        ui.separator();
+
+        ui.add(egui::Slider::new(&mut self.app.sim_config.vignette, 0.0..=1.0).text("Vignette"))
+            .on_hover_text("Darken toward the screen corners; 0 = off");
+        self.app.config.render_vignette = self.app.sim_config.vignette;
+    }
+
+    fn draw_debug_ui(&mut self, ui: &mut egui::Ui) {
+        ui.label("Disable passes to see where GPU time goes:");
+        let toggles = &mut self.pass_toggles;
+
+        ui.label("Compute");
+        ui.checkbox(&mut toggles.binning, "Binning (clear/count/prefix)");
+        ui.checkbox(&mut toggles.sort, "Sort")
+            .on_hover_text("Disabling breaks neighbor lookups (expected)");
+        ui.checkbox(&mut toggles.forces, "Forces");
+        ui.checkbox(&mut toggles.advance, "Advance");
+
+        ui.label("Render");
+        ui.checkbox(&mut toggles.render, "Render particles (off = compute only)");
+        ui.add_enabled_ui(toggles.render, |ui| {
+            ui.checkbox(&mut toggles.glow, "Glow");
+            ui.checkbox(&mut toggles.particles, "Particles");
+            ui.checkbox(&mut toggles.brush_circle, "Brush circle");
+        });
+
+        if ui.button("Enable All").clicked() {
+            *toggles = PassToggles::default();
+        }
+
+        ui.separator();
+        ui.checkbox(&mut self.debug_pick, "Inspect particle under cursor")
+            .on_hover_text(
+                "Show the particle under the cursor, or the nearest one. Runs a GPU pick \
+                 pass and a full particle readback 10 times a second while enabled",
+            );
+        if self.debug_pick {
+            self.update_inspected_particle(std::time::Instant::now());
+            match self.inspected_particle {
+                Some(inspected) => {
+                    let p = inspected.particle;
+                    let type_index = p.particle_type as usize;
+                    egui::Grid::new("inspected_particle_grid")
+                        .num_columns(2)
+                        .show(ui, |ui| {
+                            ui.label("Particle");
+                            ui.label(if inspected.under_cursor {
+                                format!("#{} (under cursor)", inspected.index)
+                            } else {
+                                format!(
+                                    "#{} (nearest, {:.1} away)",
+                                    inspected.index, inspected.distance
+                                )
+                            });
+                            ui.end_row();
+                            ui.label("Type");
+                            ui.label(format!(
+                                "{} ({}){}",
+                                self.app.sim_config.type_name(type_index),
+                                type_index,
+                                if p.is_static() { ", static" } else { "" }
+                            ));
+                            ui.end_row();
+                            ui.label("Position");
+                            ui.label(format!("({:.1}, {:.1})", p.x, p.y));
+                            ui.end_row();
+                            ui.label("Velocity");
+                            ui.label(format!(
+                                "({:.1}, {:.1}), speed {:.1}",
+                                p.vx,
+                                p.vy,
+                                p.speed()
+                            ));
+                            ui.end_row();
+                        });
+                }
+                None => {
+                    ui.label("Inspected particle: none");
+                }
+            }
+        } else {
+            self.inspected_particle = None;
+        }
+
+        ui.checkbox(
+            &mut self.show_type_labels,
+            "Label particle types when zoomed in",
+        )
+        .on_hover_text(format!(
+            "Draw each particle's type index at zoom {}x and above (up to {} labels). \
+                 Reads particles back from the GPU every frame; for small setups.",
+            TYPE_LABEL_MIN_ZOOM, MAX_TYPE_LABELS
+        ));
+
+        ui.checkbox(&mut self.cpu_compare, "Compare against CPU")
+            .on_hover_text(
+                "Once a second, step the pre-step GPU state on the CPU and report how far \
+                 the GPU result diverges (two blocking readbacks; expensive). \
+                 Brush input also shows up as divergence.",
+            );
+        if !self.cpu_compare {
+            self.cpu_compare_result = None;
+        }
+
+        let parity_allowed = self.app.sim_config.num_particles <= PARITY_MAX_PARTICLES;
+        if ui
+            .add_enabled(parity_allowed, egui::Button::new("Verify GPU/CPU step"))
+            .on_hover_text(
+                "Run one GPU step and one CPU step from the same state and log the largest \
+                 position/velocity discrepancy (advances the simulation one step)",
+            )
+            .on_disabled_hover_text(format!(
+                "Only available up to {} particles",
+                PARITY_MAX_PARTICLES
+            ))
+            .clicked()
+        {
+            self.verify_step();
+        }
+        if let Some((parity, score)) = &self.step_parity {
+            ui.label(format!(
+                "Parity: pos {:.5}, vel {:.5} ({})",
+                parity.max_position,
+                parity.max_velocity,
+                if *score <= 1.0 { "ok" } else { "FAIL" }
+            ));
+        }
+    }
+
+    /// Draw raw particle state recording and playback controls.
+    fn draw_state_capture_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Particle State Recording")
+            .id_salt("state_capture_header")
+            .default_open(false)
+            .show(ui, |ui| {
+                let recording = self.state_recorder.is_some();
+                ui.horizontal(|ui| {
+                    let label = if recording {
+                        "⏹ Stop State Capture"
+                    } else {
+                        "🔴 Capture States"
+                    };
+                    if ui.button(label).clicked() {
+                        self.toggle_state_recording();
+                    }
+                    ui.add_enabled(
+                        !recording,
+                        egui::DragValue::new(&mut self.state_record_interval)
+                            .range(1..=600)
+                            .prefix("every ")
+                            .suffix(" steps"),
+                    );
+                });
+
+                // Disk usage: unbounded, so make the cost visible up front
+                let per_frame =
+                    StateRecorder::estimate_bytes_per_frame(self.app.sim_config.num_particles);
+                let frames_per_min =
+                    self.fps_ema.max(1.0) * 60.0 / self.state_record_interval.max(1) as f32;
+                let mb_per_min = per_frame as f32 * frames_per_min / (1024.0 * 1024.0);
+                let estimate = format!("≈ {:.0} MB/min at current FPS", mb_per_min);
+                if mb_per_min > 1024.0 {
+                    ui.colored_label(egui::Color32::YELLOW, format!("⚠ {}", estimate));
+                } else {
+                    ui.label(estimate);
+                }
+                if let Some(recorder) = &self.state_recorder {
+                    ui.label(format!(
+                        "{} frames, {:.1} MB",
+                        recorder.frames_written(),
+                        recorder.bytes_written() as f32 / (1024.0 * 1024.0)
+                    ));
+                }
+
+                ui.separator();
+                ui.horizontal(|ui| {
+                    ui.add(
+                        egui::TextEdit::singleline(&mut self.state_player_path)
+                            .hint_text("path to .plstate")
+                            .desired_width(160.0),
+                    );
+                    if ui.button("Load").clicked() {
+                        self.load_state_playback();
+                    }
+                });
+
+                let Some(frame_count) = self.state_player.as_ref().map(|p| p.frame_count()) else {
+                    return;
+                };
+                let mut frame = self.state_player_frame;
+                ui.horizontal(|ui| {
+                    let play_label = if self.state_playing { "⏸" } else { "▶" };
+                    if ui.button(play_label).clicked() {
+                        self.state_playing = !self.state_playing;
+                        self.app.running = false;
+                        if self.state_playing && frame + 1 >= frame_count {
+                            frame = 0;
+                        }
+                    }
+                    ui.add(egui::Slider::new(&mut frame, 0..=frame_count - 1).text("Frame"));
+                    if ui.button("Close").clicked() {
+                        self.close_state_playback();
+                    }
+                });
+                if frame != self.state_player_frame {
+                    self.show_state_frame(frame);
+                }
+            });
+    }
+
+    /// Draw CSV metrics logging controls.
+    fn draw_metrics_logging_ui(&mut self, ui: &mut egui::Ui) {
+        egui::CollapsingHeader::new("Metrics Logging")
+            .id_salt("metrics_logging_header")
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut logging = self.metrics_logger.is_some();
+                if ui
+                    .checkbox(&mut logging, "Log population & energy to CSV")
+                    .on_hover_text(
+                        "Every N steps, write frame, per-type counts, total kinetic energy \
+                         and mean speed as a CSV row",
+                    )
+                    .changed()
+                {
+                    self.set_metrics_logging(logging);
+                }
+                ui.add_enabled(
+                    !logging,
+                    egui::TextEdit::singleline(&mut self.metrics_log_path)
+                        .hint_text("CSV path (blank = videos folder)")
+                        .desired_width(200.0),
+                );
+                ui.add(
+                    egui::Slider::new(&mut self.metrics_interval, 1..=600)
+                        .text("Interval (steps)")
+                        .logarithmic(true),
+                );
+                // Each row reads every particle back from the GPU
+                let warning = "⚠ Each row stalls the GPU for a full particle readback";
+                if self.metrics_interval < DEFAULT_METRICS_INTERVAL {
+                    ui.colored_label(egui::Color32::YELLOW, warning);
+                } else {
+                    ui.label(warning);
+                }
+                if let Some(logger) = &self.metrics_logger {
+                    ui.label(format!(
+                        "{} rows → {}",
+                        logger.rows_written(),
+                        logger.path().display()
+                    ));
+                }
+            });
+    }
+
+    /// Offer to restore the scene autosaved before an unclean exit.
+    fn draw_recovery_prompt(&mut self, ctx: &egui::Context) {
+        let Some(state) = &self.pending_recovery else {
+            return;
+        };
+
+        let mut restore = false;
+        let mut discard = false;
+        egui::Window::new("Recover Session")
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+            .show(ctx, |ui| {
+                ui.label("The previous session did not exit cleanly.");
+                ui.label(format!("Autosaved scene from {}", state.saved_at));
+                if state.particles.is_some() {
+                    ui.label("Includes particle positions.");
+                }
+                ui.horizontal(|ui| {
+                    restore = ui.button("Restore").clicked();
+                    discard = ui.button("Discard").clicked();
+                });
+            });
+
+        if restore {
+            self.restore_recovery();
+        } else if discard {
+            self.discard_recovery();
         }
     }
 
@@ -649,23 +2024,66 @@ impl AppHandler {
         }
 
         // Load section
+        let ctx = ui.ctx().clone();
+        let presets: Vec<(String, Option<egui::TextureHandle>)> = self
+            .preset_list
+            .clone()
+            .into_iter()
+            .map(|name| {
+                let thumbnail = self.preset_thumbnail(&ctx, &name);
+                (name, thumbnail)
+            })
+            .collect();
+        // Two-level picker: the category follows the selected preset, and
+        // picking another category clears a selection outside it
+        let categories = Preset::categories(&self.preset_list);
+        if !self.selected_preset.is_empty() {
+            self.preset_category = Preset::category_of(&self.selected_preset).to_string();
+        } else if !categories.contains(&self.preset_category)
+            && let Some(first) = categories.first()
+        {
+            self.preset_category = first.clone();
+        }
         ui.label("Load preset:");
+        let old_category = self.preset_category.clone();
+        egui::ComboBox::from_label("Category")
+            .selected_text(&self.preset_category)
+            .show_ui(ui, |ui| {
+                for category in &categories {
+                    ui.selectable_value(&mut self.preset_category, category.clone(), category);
+                }
+            });
+        if self.preset_category != old_category {
+            self.selected_preset.clear();
+        }
         ui.horizontal(|ui| {
             let selected = if self.selected_preset.is_empty() {
                 "Select..."
             } else {
-                &self.selected_preset
+                Preset::name_of(&self.selected_preset)
             };
 
             egui::ComboBox::from_id_salt("preset_select")
                 .selected_text(selected)
                 .show_ui(ui, |ui| {
-                    for preset_name in &self.preset_list.clone() {
-                        ui.selectable_value(
-                            &mut self.selected_preset,
-                            preset_name.clone(),
-                            preset_name,
-                        );
+                    let in_category = presets
+                        .iter()
+                        .filter(|(key, _)| Preset::category_of(key) == self.preset_category);
+                    for (preset_name, thumbnail) in in_category {
+                        ui.horizontal(|ui| {
+                            if let Some(texture) = thumbnail {
+                                ui.add(
+                                    egui::Image::new(texture)
+                                        .max_size(egui::vec2(48.0, 48.0))
+                                        .maintain_aspect_ratio(true),
+                                );
+                            }
+                            ui.selectable_value(
+                                &mut self.selected_preset,
+                                preset_name.clone(),
+                                Preset::name_of(preset_name),
+                            );
+                        });
                     }
                 });
 
@@ -673,8 +2091,31 @@ impl AppHandler {
                 let name = self.selected_preset.clone();
                 self.load_preset(&name);
             }
+
+            if !self.selected_preset.is_empty() {
+                let is_favorite = self.favorite_presets.contains(&self.selected_preset);
+                if ui
+                    .selectable_label(is_favorite, "⭐")
+                    .on_hover_text("Show in the favorites hotbar (Alt+1..9)")
+                    .clicked()
+                {
+                    let name = self.selected_preset.clone();
+                    self.toggle_favorite(&name);
+                }
+            }
         });
 
+        if let Some((_, Some(texture))) = presets
+            .iter()
+            .find(|(name, _)| *name == self.selected_preset)
+        {
+            ui.add(
+                egui::Image::new(texture)
+                    .max_size(egui::vec2(256.0, 160.0))
+                    .maintain_aspect_ratio(true),
+            );
+        }
+
         if ui.button("🔄 Refresh List").clicked() {
             self.refresh_presets();
         }
@@ -684,10 +2125,54 @@ impl AppHandler {
         // Save section
         ui.label("Save preset:");
         ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.save_preset_category)
+                    .hint_text(UNCATEGORIZED)
+                    .desired_width(100.0),
+            )
+            .on_hover_text("Category subfolder; leave empty to save uncategorized");
+            ui.label("/");
             ui.text_edit_singleline(&mut self.save_preset_name);
             if ui.button("Save").clicked() && !self.save_preset_name.is_empty() {
-                let name = self.save_preset_name.clone();
-                self.save_preset(&name);
+                match Preset::key_for(&self.save_preset_category, &self.save_preset_name) {
+                    Ok(key) => self.save_preset(&key),
+                    Err(e) => self.preset_status = format!("Error: {}", e),
+                }
+            }
+        });
+
+        ui.checkbox(
+            &mut self.app.config.restore_preset_camera,
+            "Restore camera with preset",
+        )
+        .on_hover_text("Apply the zoom/pan saved with a preset when loading it");
+
+        ui.separator();
+
+        // Whole-app state as one shareable file
+        ui.label("Share full state:");
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::TextEdit::singleline(&mut self.state_file_path)
+                    .hint_text("path to state .json")
+                    .desired_width(140.0),
+            );
+            if ui
+                .button("Export State")
+                .on_hover_text(
+                    "Write config, matrices, colors, generators and camera to one file \
+                     (a timestamped file in the screenshots folder when the path is empty)",
+                )
+                .clicked()
+            {
+                self.export_state();
+            }
+            if ui
+                .button("Import State")
+                .on_hover_text("Load a state file exported by this or another version")
+                .clicked()
+            {
+                self.import_state();
             }
         });
 
@@ -710,28 +2195,217 @@ impl AppHandler {
 
         ui.separator();
 
+        // Crash recovery autosave
+        ui.add(
+            egui::Slider::new(&mut self.app.config.autosave_interval_minutes, 0..=60)
+                .text("Autosave (min)"),
+        )
+        .on_hover_text("Minutes between recovery autosaves (0 = disabled)");
+        ui.checkbox(
+            &mut self.app.config.autosave_particles,
+            "Autosave particle positions",
+        )
+        .on_hover_text("Include positions in autosaves (requires a GPU readback)");
+
+        ui.separator();
+
         if ui.button("Reset All Settings to Defaults").clicked() {
             self.reset_to_defaults();
         }
     }
 
+    /// Clickable chips for favorite presets, bound to Alt+1..9.
+    fn draw_favorites_hotbar(&mut self, ui: &mut egui::Ui) {
+        if self.favorite_presets.is_empty() {
+            return;
+        }
+        let mut clicked = None;
+        ui.horizontal_wrapped(|ui| {
+            for (slot, name) in self.favorite_presets.iter().enumerate() {
+                let selected = *name == self.selected_preset;
+                if ui
+                    .selectable_label(selected, format!("{} {}", slot + 1, name))
+                    .on_hover_text(format!("Load {} (Alt+{})", name, slot + 1))
+                    .clicked()
+                {
+                    clicked = Some(slot);
+                }
+            }
+        });
+        if let Some(slot) = clicked {
+            self.load_favorite(slot);
+        }
+    }
+
+    /// Back/Forward navigation and breadcrumbs for the exploration lineage.
+    fn draw_lineage(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .add_enabled(self.lineage.can_go_back(), egui::Button::new("◀ Back"))
+                .clicked()
+            {
+                self.exploration_back();
+            }
+            if ui
+                .add_enabled(
+                    self.lineage.can_go_forward(),
+                    egui::Button::new("Forward ▶"),
+                )
+                .clicked()
+            {
+                self.exploration_forward();
+            }
+            ui.label(format!(
+                "{}/{}",
+                self.lineage.cursor() + 1,
+                self.lineage.entries().len()
+            ));
+        });
+
+        let mut jump_to = None;
+        egui::CollapsingHeader::new("History")
+            .id_salt("lineage_history")
+            .default_open(false)
+            .show(ui, |ui| {
+                egui::ScrollArea::vertical()
+                    .max_height(120.0)
+                    .show(ui, |ui| {
+                        let cursor = self.lineage.cursor();
+                        for (i, state) in self.lineage.entries().iter().enumerate().rev() {
+                            if ui.selectable_label(i == cursor, state.label()).clicked() {
+                                jump_to = Some(i);
+                            }
+                        }
+                    });
+            });
+        if let Some(index) = jump_to {
+            self.exploration_go_to(index);
+        }
+    }
+
     pub(crate) fn draw_matrix_editor(&mut self, ui: &mut egui::Ui) {
         let num_types = self.app.sim_config.num_types as usize;
-        let cell_size = 18.0;
         let spacing = 2.0;
+        // Shrink cells so large type counts still fit the panel
+        let cell_size =
+            ((ui.available_width() - 20.0) / num_types.max(1) as f32 - spacing).clamp(8.0, 18.0);
+        let swatch_radius = (cell_size * 0.3).min(5.0);
 
-        ui.label("Scroll over cells to edit attraction/repulsion:");
+        // Non-destructive strength multiplier for the whole matrix
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::Slider::new(&mut self.app.sim_config.matrix_strength, 0.0..=2.0)
+                    .text("Matrix strength"),
+            )
+            .on_hover_text(
+                "Scales only the attraction/repulsion matrix values, keeping the \
+                 rule's structure. Force Factor instead scales the whole force \
+                 calculation, including close-range repulsion.",
+            );
+            self.app.config.phys_matrix_strength = self.app.sim_config.matrix_strength;
+            if ui
+                .add_enabled(
+                    self.app.sim_config.matrix_strength != 1.0,
+                    egui::Button::new("Bake"),
+                )
+                .on_hover_text("Multiply the stored matrix by the strength and reset it to 1.0")
+                .clicked()
+            {
+                self.app
+                    .interaction_matrix
+                    .scale(self.app.sim_config.matrix_strength);
+                self.app.interaction_matrix.clamp(-1.0, 1.0);
+                self.app.sim_config.matrix_strength = 1.0;
+                self.app.config.phys_matrix_strength = 1.0;
+                self.sync_interaction_matrix();
+            }
+        });
+        ui.horizontal(|ui| {
+            if ui
+                .button("📋 Copy Matrix")
+                .on_hover_text("Copy the matrix to the clipboard as JSON")
+                .clicked()
+            {
+                self.copy_matrix_to_clipboard();
+            }
+            if ui
+                .button("📥 Paste Matrix")
+                .on_hover_text(
+                    "Replace the matrix with JSON from the clipboard, resizing it to \
+                     the current number of types",
+                )
+                .clicked()
+            {
+                self.paste_matrix_from_clipboard();
+            }
+            if ui
+                .add_enabled(self.undo_history.can_undo(), egui::Button::new("↶ Undo"))
+                .on_hover_text("Revert the last matrix or generator change (Ctrl+Z)")
+                .clicked()
+            {
+                self.undo();
+            }
+            if ui
+                .add_enabled(self.undo_history.can_redo(), egui::Button::new("↷ Redo"))
+                .on_hover_text("Reapply the last undone change (Ctrl+Shift+Z)")
+                .clicked()
+            {
+                self.redo();
+            }
+        });
+        ui.add_space(4.0);
+
+        // Name legend for the swatches along the grid edges
+        if self
+            .app
+            .sim_config
+            .type_names
+            .iter()
+            .any(|n| !n.trim().is_empty())
+        {
+            ui.horizontal_wrapped(|ui| {
+                for t in 0..num_types {
+                    let color = self.app.colors[t];
+                    let (rect, _) =
+                        ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+                    ui.painter().circle_filled(
+                        rect.center(),
+                        5.0,
+                        egui::Color32::from_rgb(
+                            (color[0] * 255.0) as u8,
+                            (color[1] * 255.0) as u8,
+                            (color[2] * 255.0) as u8,
+                        ),
+                    );
+                    ui.label(self.app.sim_config.type_name(t));
+                }
+            });
+            ui.add_space(4.0);
+        }
+
+        ui.label("Drag cells up/down to set values (Shift: fine), scroll to step:");
         ui.add_space(4.0);
 
         // Calculate total size
         let total_size = (cell_size + spacing) * num_types as f32 + 20.0; // +20 for labels
 
         // Matrix grid
-        let (response, painter) =
-            ui.allocate_painter(egui::vec2(total_size, total_size), egui::Sense::click());
+        let (response, painter) = ui.allocate_painter(
+            egui::vec2(total_size, total_size),
+            egui::Sense::click_and_drag(),
+        );
 
         let rect = response.rect;
         let origin = rect.min + egui::vec2(20.0, 20.0); // Offset for labels
+        let pitch = cell_size + spacing;
+        let cell_at = |pos: egui::Pos2| {
+            let rel = pos - origin;
+            if rel.x < 0.0 || rel.y < 0.0 {
+                return None;
+            }
+            let (i, j) = ((rel.y / pitch) as usize, (rel.x / pitch) as usize);
+            (i < num_types && j < num_types).then_some((i, j))
+        };
 
         // Draw column labels (colors)
         for j in 0..num_types {
@@ -744,7 +2418,7 @@ impl AppHandler {
                 (color[2] * 255.0) as u8,
                 255,
             );
-            painter.circle_filled(egui::pos2(x, y), 5.0, egui_color);
+            painter.circle_filled(egui::pos2(x, y), swatch_radius, egui_color);
         }
 
         // Draw row labels (colors)
@@ -758,17 +2432,45 @@ impl AppHandler {
                 (color[2] * 255.0) as u8,
                 255,
             );
-            painter.circle_filled(egui::pos2(x, y), 5.0, egui_color);
+            painter.circle_filled(egui::pos2(x, y), swatch_radius, egui_color);
         }
 
-        // Track if we need to update the matrix
+        // Track if we need to update the matrix; edits are undone as a whole
+        let matrix_before = self.app.interaction_matrix.clone();
         let mut matrix_changed = false;
 
-        // Draw cells and handle clicks
+        // Vertical drag sets a continuous value on the cell the press started on
+        if response.drag_started() {
+            self.matrix_drag = ui
+                .input(|input| input.pointer.press_origin())
+                .and_then(cell_at)
+                .map(|(i, j)| (i, j, self.app.interaction_matrix.get(i, j)));
+        }
+        if let Some((i, j, raw)) = &mut self.matrix_drag {
+            if response.dragged() {
+                // Shift trades speed for 0.01 steps
+                let fine = ui.input(|input| input.modifiers.shift);
+                let (per_pixel, step) = if fine { (0.002, 0.01) } else { (0.01, 0.05) };
+                *raw = (*raw - response.drag_delta().y * per_pixel).clamp(-1.0, 1.0);
+                // Snap, then round to 2 decimals like the generators
+                let value = ((*raw / step).round() * step * 100.0).round() / 100.0;
+                if value != self.app.interaction_matrix.get(*i, *j) {
+                    self.app.interaction_matrix.set(*i, *j, value);
+                    matrix_changed = true;
+                }
+            }
+            if !response.dragged() || response.drag_stopped() {
+                self.matrix_drag = None;
+            }
+        }
+        let dragged_cell = self.matrix_drag.map(|(i, j, _)| (i, j));
+        let hovered_cell = response.hover_pos().and_then(cell_at);
+
+        // Draw cells
         for i in 0..num_types {
             for j in 0..num_types {
-                let x = origin.x + (j as f32) * (cell_size + spacing);
-                let y = origin.y + (i as f32) * (cell_size + spacing);
+                let x = origin.x + (j as f32) * pitch;
+                let y = origin.y + (i as f32) * pitch;
                 let cell_rect =
                     egui::Rect::from_min_size(egui::pos2(x, y), egui::vec2(cell_size, cell_size));
 
@@ -791,17 +2493,19 @@ impl AppHandler {
 
                 painter.rect_filled(cell_rect, 2.0, cell_color);
 
-                // Highlight on hover
-                if cell_rect.contains(response.hover_pos().unwrap_or(egui::pos2(-100.0, -100.0))) {
+                let active = dragged_cell.or(hovered_cell) == Some((i, j));
+                if active {
                     painter.rect_stroke(
                         cell_rect,
                         2.0,
                         egui::Stroke::new(2.0, egui::Color32::WHITE),
                         egui::StrokeKind::Outside,
                     );
+                }
 
-                    // Handle scroll wheel to change value
-                    // Cycles through -1 -> 0 -> 1 so neutral (0) is between attract and repel
+                // Scroll wheel steps coarsely: -1 -> 0 -> 1 so neutral (0)
+                // is between attract and repel
+                if active && dragged_cell.is_none() {
                     let scroll_delta = ui.input(|i| i.raw_scroll_delta.y);
                     if scroll_delta != 0.0 {
                         let new_value = if scroll_delta > 0.0 {
@@ -822,22 +2526,177 @@ impl AppHandler {
                         self.app.interaction_matrix.set(i, j, new_value);
                         matrix_changed = true;
                     }
-
-                    // Show tooltip using on_hover_ui
-                    response.clone().on_hover_ui_at_pointer(|ui| {
-                        ui.label(format!("Type {} -> Type {}", i, j));
-                        ui.label(format!("Value: {:.2}", value));
-                        ui.label("Scroll to change value");
-                    });
                 }
             }
         }
 
+        // Live readout for the dragged or hovered cell
+        match dragged_cell.or(hovered_cell) {
+            Some((i, j)) => ui.label(format!(
+                "{} -> {}: {:+.2}",
+                self.app.sim_config.type_name(i),
+                self.app.sim_config.type_name(j),
+                self.app.interaction_matrix.get(i, j)
+            )),
+            None => ui.weak("Hover a cell to see its value"),
+        };
+
+        // Force curve follows the dragged or hovered cell
+        if let Some(cell) = dragged_cell.or(hovered_cell) {
+            self.force_curve_pair = cell;
+        }
+        ui.checkbox(&mut self.show_force_curve, "Show force curve")
+            .on_hover_text("Plot force against distance for the hovered type pair");
+        if self.show_force_curve {
+            self.draw_force_curve(ui);
+        }
+
+        ui.add_space(4.0);
+
+        // Self-interaction (diagonal) tools
+        ui.label("Self-interaction:");
+        ui.horizontal(|ui| {
+            ui.add(
+                egui::DragValue::new(&mut self.self_interaction_min)
+                    .range(-1.0..=1.0)
+                    .speed(0.01)
+                    .prefix("min "),
+            );
+            ui.add(
+                egui::DragValue::new(&mut self.self_interaction_max)
+                    .range(-1.0..=1.0)
+                    .speed(0.01)
+                    .prefix("max "),
+            );
+            if ui
+                .button("🎲 Randomize")
+                .on_hover_text("Re-roll diagonal values, keeping cross-type values")
+                .clicked()
+            {
+                self.app.interaction_matrix.randomize_diagonal(
+                    &mut rand::rng(),
+                    self.self_interaction_min,
+                    self.self_interaction_max,
+                );
+                matrix_changed = true;
+            }
+        });
+        if ui
+            .add(
+                egui::Slider::new(&mut self.global_self_interaction, -1.0..=1.0)
+                    .text("Global self-interaction"),
+            )
+            .changed()
+        {
+            self.app
+                .interaction_matrix
+                .set_diagonal(self.global_self_interaction);
+            matrix_changed = true;
+        }
+
+        // Rotational symmetry across types
+        ui.horizontal(|ui| {
+            let max_period = self.app.interaction_matrix.size.saturating_sub(1).max(1);
+            self.cyclic_period = self.cyclic_period.clamp(1, max_period);
+            ui.label("Cyclic symmetry:");
+            ui.add(
+                egui::DragValue::new(&mut self.cyclic_period)
+                    .range(1..=max_period)
+                    .prefix("shift "),
+            );
+            if ui
+                .button("Apply")
+                .on_hover_text(
+                    "Average entries with their type-shifted counterparts so rotating \
+                     all types by the shift leaves the matrix unchanged",
+                )
+                .clicked()
+            {
+                self.app
+                    .interaction_matrix
+                    .cyclic_symmetrize(self.cyclic_period);
+                matrix_changed = true;
+            }
+        });
+
+        // Balance each type's net reaction (rows) or influence (columns)
+        ui.horizontal(|ui| {
+            ui.label("Normalize to:");
+            ui.add(
+                egui::DragValue::new(&mut self.matrix_normalize_target)
+                    .range(0.1..=num_types as f32)
+                    .speed(0.01),
+            )
+            .on_hover_text("Absolute values of each row or column sum to this");
+            let target = self.matrix_normalize_target;
+            let matrix = &mut self.app.interaction_matrix;
+            if ui
+                .button("Rows")
+                .on_hover_text("Scale how strongly each type reacts to all others, keeping signs")
+                .clicked()
+            {
+                matrix.normalize_rows(target);
+                matrix.clamp(-1.0, 1.0);
+                matrix_changed = true;
+            }
+            if ui
+                .button("Columns")
+                .on_hover_text("Scale how strongly each type affects all others, keeping signs")
+                .clicked()
+            {
+                matrix.normalize_cols(target);
+                matrix.clamp(-1.0, 1.0);
+                matrix_changed = true;
+            }
+        });
+
+        // One-click whole-matrix transforms
+        ui.horizontal_wrapped(|ui| {
+            let matrix = &mut self.app.interaction_matrix;
+            if ui
+                .button("Symmetrize")
+                .on_hover_text("Average each pair so both types treat each other alike")
+                .clicked()
+            {
+                matrix.symmetrize();
+                matrix_changed = true;
+            }
+            if ui
+                .button("Transpose")
+                .on_hover_text("Swap rows and columns")
+                .clicked()
+            {
+                matrix.transpose();
+                matrix_changed = true;
+            }
+            if ui
+                .button("Invert")
+                .on_hover_text("Negate every value (attraction <-> repulsion)")
+                .clicked()
+            {
+                matrix.invert();
+                matrix_changed = true;
+            }
+            if ui
+                .button("Zero Diagonal")
+                .on_hover_text("Remove all self-interaction")
+                .clicked()
+            {
+                matrix.set_diagonal(0.0);
+                matrix_changed = true;
+            }
+        });
+
         // Update GPU buffers if matrix changed
         if matrix_changed {
+            self.push_matrix_undo(matrix_before);
             self.sync_interaction_matrix();
         }
 
+        ui.add_space(4.0);
+        self.draw_radius_editor(ui);
+        self.draw_anisotropy_editor(ui);
+
         ui.add_space(4.0);
 
         // Legend
@@ -856,4 +2715,440 @@ impl AppHandler {
             ui.label("Repel");
         });
     }
+
+    /// Draw per-type spawn weight sliders; particles respawn when a slider
+    /// is released.
+    fn draw_type_weights(&mut self, ui: &mut egui::Ui) {
+        let num_types = self.app.sim_config.num_types as usize;
+        egui::CollapsingHeader::new("Type Weights")
+            .id_salt("type_weights_header")
+            .show(ui, |ui| {
+                let weights = &mut self.app.current_type_weights;
+                weights.resize(num_types, 1.0);
+                let counts = type_counts(&SpawnConfig {
+                    num_particles: self.app.sim_config.num_particles as usize,
+                    num_types,
+                    width: 0.0,
+                    height: 0.0,
+                    velocity: SpawnVelocity::Zero,
+                    sampling: SpawnSampling::Uniform,
+                    type_weights: weights.clone(),
+                });
+
+                let mut respawn = false;
+                for (t, weight) in weights.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        let color = self.app.colors[t];
+                        let (rect, _) =
+                            ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+                        ui.painter().circle_filled(
+                            rect.center(),
+                            5.0,
+                            egui::Color32::from_rgb(
+                                (color[0] * 255.0) as u8,
+                                (color[1] * 255.0) as u8,
+                                (color[2] * 255.0) as u8,
+                            ),
+                        );
+                        let response = ui.add(
+                            egui::Slider::new(weight, 0.0..=4.0).text(format!("{}", counts[t])),
+                        );
+                        respawn |=
+                            response.drag_stopped() || (response.changed() && !response.dragged());
+                    });
+                }
+
+                if ui
+                    .button("Even")
+                    .on_hover_text("Give every type the same share")
+                    .clicked()
+                {
+                    weights.iter_mut().for_each(|w| *w = 1.0);
+                    respawn = true;
+                }
+
+                if respawn {
+                    self.app.config.gen_type_weights = self.app.current_type_weights.clone();
+                    self.app.regenerate_particles();
+                    self.sync_buffers();
+                }
+            });
+    }
+
+    /// Draw text fields naming each particle type. Names are display-only
+    /// and saved with the config and presets.
+    fn draw_type_names_editor(&mut self, ui: &mut egui::Ui) {
+        let num_types = self.app.sim_config.num_types as usize;
+
+        egui::CollapsingHeader::new("Types")
+            .id_salt("type_names_header")
+            .default_open(false)
+            .show(ui, |ui| {
+                let mut names = self.app.sim_config.type_names.clone();
+                names.resize(num_types, String::new());
+                let mut changed = false;
+                for (t, name) in names.iter_mut().enumerate() {
+                    ui.horizontal(|ui| {
+                        let color = self.app.colors[t];
+                        let (rect, _) =
+                            ui.allocate_exact_size(egui::vec2(10.0, 10.0), egui::Sense::hover());
+                        ui.painter().circle_filled(
+                            rect.center(),
+                            5.0,
+                            egui::Color32::from_rgb(
+                                (color[0] * 255.0) as u8,
+                                (color[1] * 255.0) as u8,
+                                (color[2] * 255.0) as u8,
+                            ),
+                        );
+                        changed |= ui
+                            .add(
+                                egui::TextEdit::singleline(name)
+                                    .hint_text(format!("Type {}", t))
+                                    .desired_width(140.0),
+                            )
+                            .changed();
+                    });
+                }
+
+                let any_named = names.iter().any(|n| !n.trim().is_empty());
+                if ui
+                    .add_enabled(any_named, egui::Button::new("Clear names"))
+                    .clicked()
+                {
+                    names.clear();
+                    changed = true;
+                }
+
+                if changed {
+                    // All-blank names collapse back to "unnamed"
+                    if !names.iter().any(|n| !n.trim().is_empty()) {
+                        names.clear();
+                    }
+                    self.app.sim_config.type_names = names.clone();
+                    self.app.config.sim_type_names = names;
+                }
+            });
+    }
+
+    /// Draw per-type friction and force factor overrides.
+    fn draw_type_physics_editor(&mut self, ui: &mut egui::Ui) {
+        let num_types = self.app.sim_config.num_types as usize;
+
+        egui::CollapsingHeader::new("Per-Type Physics")
+            .id_salt("type_physics_header")
+            .default_open(false)
+            .show(ui, |ui| {
+                let cfg = &mut self.app.sim_config;
+                if cfg.type_friction.is_empty() && cfg.type_force_factor.is_empty() {
+                    ui.label("All types use the global Friction and Force Factor");
+                    if ui
+                        .button("Customize per type")
+                        .on_hover_text("Give each type its own friction and force factor")
+                        .clicked()
+                    {
+                        cfg.type_friction = vec![cfg.friction; num_types];
+                        cfg.type_force_factor = vec![cfg.force_factor; num_types];
+                    }
+                    return;
+                }
+                cfg.type_friction.resize(num_types, cfg.friction);
+                cfg.type_force_factor.resize(num_types, cfg.force_factor);
+
+                for t in 0..num_types {
+                    ui.horizontal(|ui| {
+                        let color = self
+                            .app
+                            .colors
+                            .get(t)
+                            .map(|c| {
+                                egui::Color32::from_rgb(
+                                    (c[0] * 255.0) as u8,
+                                    (c[1] * 255.0) as u8,
+                                    (c[2] * 255.0) as u8,
+                                )
+                            })
+                            .unwrap_or(egui::Color32::GRAY);
+                        ui.colored_label(color, self.app.sim_config.type_name(t));
+                        ui.add(
+                            egui::DragValue::new(&mut self.app.sim_config.type_friction[t])
+                                .range(0.0..=1.0)
+                                .speed(0.005)
+                                .prefix("friction "),
+                        );
+                        ui.add(
+                            egui::DragValue::new(&mut self.app.sim_config.type_force_factor[t])
+                                .range(0.1..=5.0)
+                                .speed(0.01)
+                                .prefix("force "),
+                        );
+                    });
+                }
+                if ui.button("Use global values").clicked() {
+                    self.app.sim_config.type_friction.clear();
+                    self.app.sim_config.type_force_factor.clear();
+                }
+            });
+    }
+
+    /// Draw per-type anisotropic (elliptical) interaction radius controls.
+    fn draw_anisotropy_editor(&mut self, ui: &mut egui::Ui) {
+        let num_types = self.app.radius_matrix.size;
+        let mut changed = false;
+
+        egui::CollapsingHeader::new("Anisotropic Radii")
+            .id_salt("anisotropy_header")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.label("Per-type ellipse: aspect 1.0 is circular")
+                    .on_hover_text(
+                        "Squashes a type's interaction range across its axis. \
+                         Adds a small per-neighbor cost when any aspect > 1.",
+                    );
+                for t in 0..num_types {
+                    let (orientation, mut aspect) = self.app.radius_matrix.get_anisotropy(t);
+                    let mut degrees = orientation.to_degrees();
+                    ui.horizontal(|ui| {
+                        let color = self
+                            .app
+                            .colors
+                            .get(t)
+                            .map(|c| {
+                                egui::Color32::from_rgb(
+                                    (c[0] * 255.0) as u8,
+                                    (c[1] * 255.0) as u8,
+                                    (c[2] * 255.0) as u8,
+                                )
+                            })
+                            .unwrap_or(egui::Color32::GRAY);
+                        ui.colored_label(color, self.app.sim_config.type_name(t));
+                        let aspect_changed = ui
+                            .add(
+                                egui::DragValue::new(&mut aspect)
+                                    .range(1.0..=8.0)
+                                    .speed(0.02)
+                                    .prefix("aspect "),
+                            )
+                            .changed();
+                        let angle_changed = ui
+                            .add(
+                                egui::DragValue::new(&mut degrees)
+                                    .range(-180.0..=180.0)
+                                    .speed(1.0)
+                                    .suffix("°"),
+                            )
+                            .changed();
+                        if aspect_changed || angle_changed {
+                            self.push_undo_edit("anisotropy");
+                            self.app
+                                .radius_matrix
+                                .set_anisotropy(t, degrees.to_radians(), aspect);
+                            changed = true;
+                        }
+                    });
+                }
+                if ui.button("Reset to circular").clicked() {
+                    self.push_undo();
+                    self.app.radius_matrix.clear_anisotropy();
+                    changed = true;
+                }
+            });
+
+        if changed {
+            self.sync_radius_matrix();
+        }
+    }
+
+    /// Draw the per-pair min/max interaction radius grids.
+    /// Plot the force one type feels toward another against distance,
+    /// computed from the live matrix and radius values so edits show up
+    /// immediately. Green is attraction, red repulsion; the vertical lines
+    /// mark the min and max radius.
+    fn draw_force_curve(&self, ui: &mut egui::Ui) {
+        const SAMPLES: usize = 128;
+        let (from, to) = self.force_curve_pair;
+        let num_types = self
+            .app
+            .interaction_matrix
+            .size
+            .min(self.app.radius_matrix.size);
+        if from >= num_types || to >= num_types {
+            ui.weak("Hover a matrix cell to pick a type pair");
+            return;
+        }
+
+        let config = &self.app.sim_config;
+        let min_r = self.app.radius_matrix.get_min(from, to);
+        let max_r = self.app.radius_matrix.get_max(from, to);
+        let attraction = self.app.interaction_matrix.get(from, to);
+        let range = (max_r * 1.1).max(1.0);
+        let samples: Vec<(f32, f32)> = (0..=SAMPLES)
+            .map(|k| {
+                let dist = range * k as f32 / SAMPLES as f32;
+                let force = pair_force(dist, min_r, max_r, attraction, config);
+                (dist, force / config.force_factor)
+            })
+            .collect();
+        let peak = samples
+            .iter()
+            .map(|&(_, force)| force.abs())
+            .fold(0.0, f32::max)
+            .max(1e-6);
+
+        let (response, painter) = ui.allocate_painter(
+            egui::vec2(ui.available_width().min(260.0), 100.0),
+            egui::Sense::hover(),
+        );
+        let rect = response.rect;
+        let to_screen = |dist: f32, force: f32| {
+            egui::pos2(
+                rect.left() + dist / range * rect.width(),
+                rect.center().y - force / peak * rect.height() * 0.45,
+            )
+        };
+        let axis = egui::Stroke::new(1.0, egui::Color32::from_gray(80));
+
+        painter.rect_filled(rect, 2.0, egui::Color32::from_gray(25));
+        painter.hline(rect.x_range(), rect.center().y, axis);
+        for radius in [min_r, max_r] {
+            painter.vline(to_screen(radius, 0.0).x, rect.y_range(), axis);
+        }
+        for pair in samples.windows(2) {
+            let (d0, f0) = pair[0];
+            let (d1, f1) = pair[1];
+            let color = if f0 + f1 >= 0.0 {
+                egui::Color32::from_rgb(80, 220, 80)
+            } else {
+                egui::Color32::from_rgb(220, 80, 80)
+            };
+            painter.line_segment(
+                [to_screen(d0, f0), to_screen(d1, f1)],
+                egui::Stroke::new(2.0, color),
+            );
+        }
+
+        // Readout at the cursor
+        if let Some(pos) = response.hover_pos() {
+            let dist = (pos.x - rect.left()) / rect.width() * range;
+            let force = pair_force(dist, min_r, max_r, attraction, config) / config.force_factor;
+            painter.vline(
+                pos.x,
+                rect.y_range(),
+                egui::Stroke::new(1.0, egui::Color32::WHITE),
+            );
+            painter.text(
+                rect.left_top() + egui::vec2(4.0, 2.0),
+                egui::Align2::LEFT_TOP,
+                format!("d {:.0}: {:+.3}", dist, force),
+                egui::FontId::monospace(11.0),
+                egui::Color32::WHITE,
+            );
+        }
+
+        ui.label(format!(
+            "{} -> {}: min {:.0}, max {:.0}, peak {:.3}",
+            self.app.sim_config.type_name(from),
+            self.app.sim_config.type_name(to),
+            min_r,
+            max_r,
+            peak
+        ));
+    }
+
+    fn draw_radius_editor(&mut self, ui: &mut egui::Ui) {
+        let num_types = self.app.radius_matrix.size;
+        let old_max_radius = self.app.radius_matrix.max_interaction_radius();
+        let mut changed = false;
+
+        egui::CollapsingHeader::new("Radius Matrix")
+            .id_salt("radius_matrix_header")
+            .default_open(false)
+            .show(ui, |ui| {
+                ui.label("Rows act on columns; drag a cell to edit")
+                    .on_hover_text(
+                        "Min is the close-range repulsion distance, Max the interaction \
+                     range. Auto-scaled radii are recomputed when the particle \
+                     count changes.",
+                    );
+                let type_color = |t: usize| {
+                    self.app
+                        .colors
+                        .get(t)
+                        .map(|c| {
+                            egui::Color32::from_rgb(
+                                (c[0] * 255.0) as u8,
+                                (c[1] * 255.0) as u8,
+                                (c[2] * 255.0) as u8,
+                            )
+                        })
+                        .unwrap_or(egui::Color32::GRAY)
+                };
+                let colors: Vec<_> = (0..num_types).map(type_color).collect();
+
+                for (title, is_min) in [("Min radius", true), ("Max radius", false)] {
+                    ui.label(title);
+                    // Many types overflow the panel width; scroll instead
+                    egui::ScrollArea::horizontal()
+                        .id_salt(("radius_scroll", is_min))
+                        .show(ui, |ui| {
+                            egui::Grid::new(("radius_grid", is_min))
+                                .spacing([2.0, 2.0])
+                                .show(ui, |ui| {
+                                    ui.label("");
+                                    for color in &colors {
+                                        ui.colored_label(*color, "■");
+                                    }
+                                    ui.end_row();
+
+                                    for (from, color) in colors.iter().enumerate() {
+                                        ui.colored_label(*color, "■");
+                                        for to in 0..num_types {
+                                            let radius = &self.app.radius_matrix;
+                                            let mut value = if is_min {
+                                                radius.get_min(from, to)
+                                            } else {
+                                                radius.get_max(from, to)
+                                            };
+                                            let response = ui
+                                                .add(
+                                                    egui::DragValue::new(&mut value)
+                                                        .range(0.0..=500.0)
+                                                        .speed(0.5)
+                                                        .max_decimals(0),
+                                                )
+                                                .on_hover_text(format!(
+                                                    "{} → {}",
+                                                    self.app.sim_config.type_name(from),
+                                                    self.app.sim_config.type_name(to)
+                                                ));
+                                            if response.changed() {
+                                                self.push_undo_edit("radius");
+                                                let radius = &mut self.app.radius_matrix;
+                                                if is_min {
+                                                    radius.set_min(from, to, value);
+                                                } else {
+                                                    radius.set_max(from, to, value);
+                                                }
+                                                changed = true;
+                                            }
+                                        }
+                                        ui.end_row();
+                                    }
+                                });
+                        });
+                }
+            });
+
+        if changed {
+            self.sync_radius_matrix();
+
+            // The spatial hash cell must cover the largest interaction range
+            let max_r = self.app.radius_matrix.max_interaction_radius();
+            if max_r != old_max_radius {
+                self.app.sim_config.spatial_hash_cell_size =
+                    self.app.config.render_spatial_hash_cell_size.max(max_r);
+                self.sync_spatial_buffers();
+            }
+        }
+    }
 }
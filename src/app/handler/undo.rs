@@ -0,0 +1,56 @@
+//! Undo/redo of matrix and generator edits.
+
+use std::time::Instant;
+
+use super::AppHandler;
+use crate::app::ExplorationState;
+use crate::app::lineage::matrix_hash;
+use crate::simulation::InteractionMatrix;
+
+impl AppHandler {
+    /// Snapshot the current generator state before a discrete change
+    /// (randomize, mutate, rule/palette/pattern switch, import).
+    pub(crate) fn push_undo(&mut self) {
+        self.undo_history.push(ExplorationState::capture(&self.app));
+    }
+
+    /// Snapshot the current state before an edit to `target`; rapid edits
+    /// to the same target share one undo step.
+    pub(crate) fn push_undo_edit(&mut self, target: &'static str) {
+        self.undo_history
+            .push_edit(target, Instant::now(), ExplorationState::capture(&self.app));
+    }
+
+    /// Record an interaction matrix edit that has already been applied,
+    /// given the matrix from `before` it.
+    pub(crate) fn push_matrix_undo(&mut self, before: InteractionMatrix) {
+        let mut state = ExplorationState::capture(&self.app);
+        state.matrix_hash = matrix_hash(&before);
+        state.interaction_matrix = before;
+        self.undo_history.push_edit("matrix", Instant::now(), state);
+    }
+
+    /// Revert the last matrix or generator change (Ctrl+Z).
+    pub(crate) fn undo(&mut self) {
+        let current = ExplorationState::capture(&self.app);
+        match self.undo_history.undo(current) {
+            Some(state) => {
+                self.restore_exploration(state);
+                self.show_hud_message("Undo".to_string());
+            }
+            None => self.show_hud_message("Nothing to undo".to_string()),
+        }
+    }
+
+    /// Reapply the last undone change (Ctrl+Shift+Z).
+    pub(crate) fn redo(&mut self) {
+        let current = ExplorationState::capture(&self.app);
+        match self.undo_history.redo(current) {
+            Some(state) => {
+                self.restore_exploration(state);
+                self.show_hud_message("Redo".to_string());
+            }
+            None => self.show_hud_message("Nothing to redo".to_string()),
+        }
+    }
+}
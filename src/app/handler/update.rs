@@ -3,8 +3,57 @@
 use std::time::Instant;
 
 use super::AppHandler;
+use crate::app::frame_cap_wait;
+use crate::renderer::gpu::fair_sample_seed;
+
+/// Timestep used for a single step while paused.
+const SINGLE_STEP_DT: f32 = 1.0 / 60.0;
+
+/// Physics timestep and number of steps for one frame.
+///
+/// A single step while paused runs once at the fixed timestep (or
+/// [`SINGLE_STEP_DT`]). Deterministic recording (`recording_dt`) takes
+/// precedence over a fixed timestep, so each recorded frame covers exactly
+/// its share of sim time; the frame is still split into `substeps` steps for
+/// stability. Otherwise a fixed timestep decouples simulation speed from the
+/// display rate, and without one the capped frame time is used.
+fn frame_timestep(
+    frame_dt: f32,
+    single_step: bool,
+    recording_dt: Option<f32>,
+    fixed_dt: Option<f32>,
+    substeps: u32,
+) -> (f32, u32) {
+    if single_step {
+        (fixed_dt.unwrap_or(SINGLE_STEP_DT), 1)
+    } else if let Some(recording_dt) = recording_dt {
+        let substeps = substeps.max(1);
+        (recording_dt / substeps as f32, substeps)
+    } else if let Some(fixed_dt) = fixed_dt {
+        (fixed_dt, substeps)
+    } else {
+        (frame_dt, 1)
+    }
+}
 
 impl AppHandler {
+    /// Queue one physics step for the next update. Ignored while running.
+    pub(crate) fn request_single_step(&mut self) {
+        if !self.app.running {
+            self.step_requested = true;
+        }
+    }
+
+    /// Sleep until the frame cap allows the next frame. Call before
+    /// [`Self::update`], which restarts the frame timer.
+    pub(crate) fn wait_for_frame_cap(&self) {
+        let elapsed = self.last_frame.elapsed();
+        if let Some(wait) = frame_cap_wait(self.app.config.fps_cap, self.app.config.vsync, elapsed)
+        {
+            std::thread::sleep(wait);
+        }
+    }
+
     pub(crate) fn update(&mut self) {
         let now = Instant::now();
         let dt = now.duration_since(self.last_frame).as_secs_f32();
@@ -16,7 +65,9 @@ impl AppHandler {
         } else {
             self.fps_ema.max(self.fps)
         };
-        if self.fps_ema == 0.0 {
+        if std::mem::take(&mut self.skip_fps_sample) {
+            // The previous frame ran a single step; don't let it skew the average
+        } else if self.fps_ema == 0.0 {
             self.fps_ema = inst_fps;
         } else {
             // Heavier smoothing to dampen spikes.
@@ -32,13 +83,33 @@ impl AppHandler {
             self.last_fps_time = now;
         }
 
-        let dt_capped = dt.min(1.0 / 30.0); // Cap dt to avoid instability
+        // A single step only applies while paused
+        let single_step = std::mem::take(&mut self.step_requested) && !self.app.running;
+        let advance = self.app.running || single_step;
+
+        // Advance sim time by a fixed step so recorded frames stay evenly
+        // spaced in sim time even when encoding stalls the render loop
+        let recording_dt =
+            (self.is_recording && self.deterministic_recording).then(|| self.recording_step_dt());
+        let dt_capped = recording_dt.unwrap_or(dt.min(1.0 / 30.0)); // Cap dt to avoid instability
+
+        // Physics timestep and steps this frame
+        let (sim_dt, substeps) = frame_timestep(
+            dt_capped,
+            single_step,
+            recording_dt,
+            self.app.sim_config.fixed_dt,
+            self.app.sim_config.substeps_per_frame(),
+        );
 
         // Spatial hash is always enabled; enforce even if a preset/file had it off
         self.app.sim_config.use_spatial_hash = true;
 
-        // Process brush tools (Draw/Erase modify particles)
-        self.process_brush_tools();
+        // Controller pan/zoom, parameter nudges and button actions
+        self.poll_gamepad(dt_capped);
+
+        // Process brush tools (Draw/Erase/Emitter modify particles)
+        self.process_brush_tools(dt_capped);
 
         // Sync GPU buffers if particles were modified
         if self.needs_sync {
@@ -53,19 +124,58 @@ impl AppHandler {
         }
 
         // Update params for UI changes (only once per frame)
+        if advance {
+            self.app.sim_frame = self.app.sim_frame.wrapping_add(1);
+        }
         if let Some(gpu_state_ref) = self.gpu.as_ref() {
             // Immutable borrow for update_params
             gpu_state_ref.buffers.update_params(
                 &gpu_state_ref.context.queue,
                 &self.app.sim_config,
-                dt_capped,
+                sim_dt,
+                fair_sample_seed(self.app.sim_config.seed, self.app.sim_frame, 0),
+                self.velocity_scale,
             );
         }
 
-        if self.app.running {
-            // GPU compute physics
-            self.run_gpu_compute(dt_capped);
+        // Palette hue animation runs even while paused (render-only)
+        if self.app.sim_config.animate_palette {
+            self.palette_hue_offset = (self.palette_hue_offset
+                + self.app.sim_config.palette_hue_speed * dt_capped)
+                .rem_euclid(360.0);
+        }
+
+        if advance {
+            // GPU compute physics (optionally checked against a CPU step)
+            let compare_from = self.begin_cpu_compare(now);
+            self.run_gpu_compute(substeps);
+            self.finish_cpu_compare(compare_from, sim_dt, substeps);
+            // Disk-backed particle state capture
+            self.record_state_frame();
+            // CSV population/energy metrics
+            self.log_metrics_frame();
+        } else {
+            // Attract/Repel still shape the scene while paused
+            self.run_paused_brush(dt_capped);
         }
+        if single_step {
+            self.skip_fps_sample = true;
+        }
+
+        // Detect frozen scenes and pause or stir them
+        self.check_stagnation(now);
+
+        // Sample temperature and steer it toward the thermostat target
+        self.update_thermostat(now);
+
+        // Drop or restore effects to hold the target FPS
+        self.update_adaptive_quality(now);
+
+        // Recorded state playback
+        self.advance_state_playback();
+
+        // Periodic crash-recovery autosave
+        self.maybe_autosave(now);
 
         // --- Start of Logging and Dynamic Adjustment Block (Moved to End) ---
         // Periodic metrics logging (every 10 seconds)
@@ -86,12 +196,11 @@ impl AppHandler {
                     timings_info = format!("Timings: [{}]", timings.join(", "));
                 }
 
-                // Read bin counts (blocking!)
-                let use_a = gpu_state.spatial_buffers.current_offset_buffer == 0;
+                // Read bin offsets (blocking!)
                 let offsets = gpu_state.spatial_buffers.read_bin_counts(
                     &gpu_state.context.device,
                     &gpu_state.context.queue,
-                    use_a,
+                    true,
                 );
 
                 if offsets.len() >= 2 {
@@ -171,3 +280,40 @@ impl AppHandler {
         // --- End of Logging and Dynamic Adjustment Block ---
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recording_step_takes_precedence_over_fixed_dt() {
+        let fixed = Some(1.0 / 120.0);
+        let recording = Some(1.0 / 60.0);
+
+        // Recording: the frame spans the recording step, split into substeps
+        let (dt, steps) = frame_timestep(0.02, false, recording, fixed, 2);
+        assert_eq!(steps, 2);
+        assert!((dt * steps as f32 - 1.0 / 60.0).abs() < 1e-7);
+        assert_eq!(
+            frame_timestep(0.02, false, recording, None, 1),
+            (1.0 / 60.0, 1)
+        );
+
+        // Not recording: the fixed timestep, then the frame time
+        assert_eq!(
+            frame_timestep(0.02, false, None, fixed, 3),
+            (1.0 / 120.0, 3)
+        );
+        assert_eq!(frame_timestep(0.02, false, None, None, 1), (0.02, 1));
+
+        // A paused single step is always one step
+        assert_eq!(
+            frame_timestep(0.02, true, recording, fixed, 3),
+            (1.0 / 120.0, 1)
+        );
+        assert_eq!(
+            frame_timestep(0.02, true, None, None, 1),
+            (SINGLE_STEP_DT, 1)
+        );
+    }
+}
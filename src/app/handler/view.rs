@@ -0,0 +1,56 @@
+//! Reset View and Fit: camera framing shortcuts.
+
+use std::time::Instant;
+
+use glam::Vec2;
+
+use super::AppHandler;
+use crate::app::CameraState;
+use crate::simulation::BoundaryMode;
+
+/// Minimum seconds between Fit readbacks, so a held key doesn't block
+/// every frame on a full particle readback.
+const FIT_INTERVAL_SECS: f32 = 0.25;
+
+impl AppHandler {
+    /// Return to the default view: zoom 1 centered on the world (0).
+    pub(crate) fn reset_view(&mut self) {
+        self.camera.reset();
+        self.update_camera();
+    }
+
+    /// Zoom and pan so every particle is visible (F).
+    ///
+    /// Mirror and infinite wrap draw the world as repeating tiles with no
+    /// edge to fit against, so those frame exactly one world tile instead.
+    pub(crate) fn fit_view(&mut self) {
+        let now = Instant::now();
+        if now.duration_since(self.last_fit).as_secs_f32() < FIT_INTERVAL_SECS {
+            return;
+        }
+        self.last_fit = now;
+
+        if matches!(
+            self.app.sim_config.boundary_mode,
+            BoundaryMode::MirrorWrap | BoundaryMode::InfiniteWrap
+        ) {
+            self.reset_view();
+            return;
+        }
+        let Some(gpu) = &self.gpu else {
+            return;
+        };
+        let particles = gpu
+            .buffers
+            .read_particles(&gpu.context.device, &gpu.context.queue);
+        let (min, max) = particles.iter().fold(
+            (Vec2::splat(f32::MAX), Vec2::splat(f32::MIN)),
+            |(min, max), p| (min.min(p.position()), max.max(p.position())),
+        );
+        let world_size = self.app.sim_config.world_size;
+        let CameraState { offset, zoom, .. } = CameraState::fitting(min, max, world_size);
+        self.camera.offset = offset;
+        self.camera.zoom = zoom;
+        self.update_camera();
+    }
+}
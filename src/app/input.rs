@@ -6,6 +6,9 @@
 use glam::Vec2;
 use serde::{Deserialize, Serialize};
 
+/// Extra room around the particle bounds when fitting the view.
+const FIT_MARGIN: f32 = 1.05;
+
 /// Brush tool types for user interaction.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub enum BrushTool {
@@ -20,6 +23,8 @@ pub enum BrushTool {
     Attract,
     /// Repel particles away from brush position.
     Repel,
+    /// Continuously emit particles at the cursor while held.
+    Emitter,
 }
 
 impl BrushTool {
@@ -31,6 +36,7 @@ impl BrushTool {
             BrushTool::Erase,
             BrushTool::Attract,
             BrushTool::Repel,
+            BrushTool::Emitter,
         ]
     }
 
@@ -42,6 +48,7 @@ impl BrushTool {
             BrushTool::Erase => "Erase",
             BrushTool::Attract => "Attract",
             BrushTool::Repel => "Repel",
+            BrushTool::Emitter => "Emitter",
         }
     }
 
@@ -53,12 +60,13 @@ impl BrushTool {
             BrushTool::Erase => "🧹",
             BrushTool::Attract => "[>]",
             BrushTool::Repel => "[<]",
+            BrushTool::Emitter => "[*]",
         }
     }
 }
 
 /// Brush state for user interaction tools.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub struct BrushState {
     /// Current brush tool.
     pub tool: BrushTool,
@@ -76,14 +84,51 @@ pub struct BrushState {
     pub directional_force: f32,
     /// Number of particles to spawn per frame in Draw mode.
     pub draw_intensity: u32,
-    /// Particle type to draw (-1 for random).
+    /// Particle type to draw (-1 for random). Also used by the Emitter.
     pub draw_type: i32,
+    /// Spawn pinned (static) particles with Draw and Emitter.
+    pub draw_static: bool,
+    /// Particles emitted per second by the Emitter tool.
+    pub emit_rate: f32,
+    /// Total particle count at which the Emitter stops.
+    pub emit_cap: u32,
+    /// Fractional particles carried over between frames by the Emitter.
+    pub emit_carry: f32,
     /// Show brush circle indicator.
     pub show_circle: bool,
+    /// Brush circle outline width in world units.
+    pub circle_line_width: f32,
+    /// Opacity of the filled disk inside the circle (0 = outline only).
+    pub circle_fill_alpha: f32,
+    /// Draw a dark halo and brighten the outline so it reads on any backdrop.
+    pub circle_contrast: bool,
+    /// Circle color override (None = color by tool).
+    pub circle_color: Option<[f32; 3]>,
     /// Is brush currently active (mouse pressed)?
     pub is_active: bool,
     /// Target particle type for attract/repel/erase (-1 for all).
     pub target_type: i32,
+    /// Per-type lock flags; locked types are never drawn, erased or pushed.
+    pub locked_types: Vec<bool>,
+    /// Active touch points as (touch id, world position).
+    pub touch_points: Vec<(u64, Vec2)>,
+    /// Persistent attract/repel points added with Ctrl+click.
+    pub pinned_points: Vec<BrushPoint>,
+    /// Gravity well: pull toward the cursor across the whole world.
+    pub gravity_well: bool,
+    /// Gravity well strength (0.0 - 100.0).
+    pub well_strength: f32,
+    /// Gravity well falloff exponent (0 = constant, 1 = inverse distance).
+    pub well_falloff: f32,
+}
+
+/// A persistent brush point that keeps applying force without the mouse.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BrushPoint {
+    /// Position in world coordinates.
+    pub position: Vec2,
+    /// Signed force (positive for attract, negative for repel).
+    pub force: f32,
 }
 
 impl Default for BrushState {
@@ -98,9 +143,23 @@ impl Default for BrushState {
             directional_force: 40.0,
             draw_intensity: 50,
             draw_type: -1, // Random type
+            draw_static: false,
+            emit_rate: 300.0,
+            emit_cap: 128_000,
+            emit_carry: 0.0,
             show_circle: true,
+            circle_line_width: 2.0,
+            circle_fill_alpha: 0.0,
+            circle_contrast: true,
+            circle_color: None,
             is_active: false,
             target_type: -1, // All types
+            locked_types: Vec::new(),
+            touch_points: Vec::new(),
+            pinned_points: Vec::new(),
+            gravity_well: false,
+            well_strength: 10.0,
+            well_falloff: 1.0,
         }
     }
 }
@@ -114,6 +173,78 @@ impl BrushState {
         self.position = new_pos;
     }
 
+    /// Number of particles the Emitter should spawn this frame.
+    ///
+    /// Accumulates `emit_rate * dt`, keeps the fractional remainder for the
+    /// next frame and never exceeds the room left below `emit_cap`.
+    pub fn take_emit_count(&mut self, dt: f32, current: usize) -> usize {
+        let room = (self.emit_cap as usize).saturating_sub(current);
+        if room == 0 {
+            self.emit_carry = 0.0;
+            return 0;
+        }
+        self.emit_carry += self.emit_rate.max(0.0) * dt.max(0.0);
+        let whole = self.emit_carry.floor();
+        self.emit_carry -= whole;
+        (whole as usize).min(room)
+    }
+
+    /// Pin a persistent point at the current position with the current
+    /// tool's force. The oldest pin is dropped once all extra slots are used.
+    pub fn pin_point(&mut self, max_points: usize) {
+        let force = self.get_force();
+        if force == 0.0 || max_points == 0 {
+            return;
+        }
+        if self.pinned_points.len() >= max_points {
+            self.pinned_points.remove(0);
+        }
+        self.pinned_points.push(BrushPoint {
+            position: self.position,
+            force,
+        });
+    }
+
+    /// Track a touch point, adding it if new.
+    pub fn set_touch_point(&mut self, id: u64, position: Vec2) {
+        match self.touch_points.iter_mut().find(|(tid, _)| *tid == id) {
+            Some(point) => point.1 = position,
+            None => self.touch_points.push((id, position)),
+        }
+    }
+
+    /// Stop tracking a touch point.
+    pub fn remove_touch_point(&mut self, id: u64) {
+        self.touch_points.retain(|(tid, _)| *tid != id);
+    }
+
+    /// Whether the brush must leave particles of this type alone.
+    pub fn is_locked(&self, particle_type: u32) -> bool {
+        self.locked_types
+            .get(particle_type as usize)
+            .copied()
+            .unwrap_or(false)
+    }
+
+    /// Lock or unlock a particle type, growing the flag list as needed.
+    pub fn set_locked(&mut self, particle_type: u32, locked: bool) {
+        let index = particle_type as usize;
+        if index >= self.locked_types.len() {
+            self.locked_types.resize(index + 1, false);
+        }
+        self.locked_types[index] = locked;
+    }
+
+    /// Locked types as a bitmask for the brush shader (bit `t` = type `t`).
+    pub fn locked_mask(&self) -> u32 {
+        self.locked_types
+            .iter()
+            .take(32)
+            .enumerate()
+            .filter(|&(_, &locked)| locked)
+            .fold(0, |mask, (t, _)| mask | (1 << t))
+    }
+
     /// Get the signed force value (positive for attract, negative for repel).
     pub fn get_force(&self) -> f32 {
         match self.tool {
@@ -152,15 +283,19 @@ pub struct InputState {
 }
 
 /// Camera state for pan and zoom.
-#[derive(Debug, Clone, Copy)]
+///
+/// Only the view (offset and zoom) is serialized; interaction state is not.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct CameraState {
     /// Camera offset in world coordinates (pan).
     pub offset: Vec2,
     /// Zoom level (1.0 = default, >1 = zoomed in, <1 = zoomed out).
     pub zoom: f32,
     /// Is the user currently panning?
+    #[serde(skip)]
     pub is_panning: bool,
     /// Last mouse position for pan delta calculation.
+    #[serde(skip)]
     pub last_mouse_pos: Vec2,
 }
 
@@ -196,6 +331,41 @@ impl CameraState {
         self.offset = world_pos - (world_pos - self.offset) * zoom_ratio;
     }
 
+    /// Map a view saved for a world of size `from` onto a world of size `to`,
+    /// keeping the same relative framing.
+    ///
+    /// The offset is clamped so the view stays over the world, and an
+    /// unusable `from` size falls back to the default view.
+    pub fn rescaled(self, from: Vec2, to: Vec2) -> Self {
+        if from.x <= 0.0 || from.y <= 0.0 || !self.zoom.is_finite() {
+            return Self::default();
+        }
+        let half = to * 0.5;
+        Self {
+            offset: (self.offset * (to / from)).clamp(-half, half),
+            zoom: self.zoom.clamp(0.1, 10.0),
+            ..Self::default()
+        }
+    }
+
+    /// View that frames the world-space box `min..max` with a small margin.
+    ///
+    /// Zoom is the largest that keeps the whole box visible (clamped to the
+    /// usual zoom range). An inverted or non-finite box (no particles)
+    /// falls back to the default view.
+    pub fn fitting(min: Vec2, max: Vec2, world_size: Vec2) -> Self {
+        let extent = (max - min) * FIT_MARGIN;
+        if !extent.is_finite() || extent.x < 0.0 || extent.y < 0.0 {
+            return Self::default();
+        }
+        let zoom = (world_size / extent.max(Vec2::splat(f32::EPSILON))).min_element();
+        Self {
+            offset: (min + max) * 0.5 - world_size * 0.5,
+            zoom: zoom.clamp(0.1, 10.0),
+            ..Self::default()
+        }
+    }
+
     /// Simple zoom that keeps center fixed.
     pub fn zoom_center(&mut self, factor: f32) {
         self.zoom = (self.zoom * factor).clamp(0.1, 10.0);
@@ -221,12 +391,77 @@ impl CameraState {
 
         Vec2::new(world_x, world_y)
     }
+
+    /// Convert world coordinates to screen coordinates (0,0 at top-left).
+    ///
+    /// Inverse of [`Self::screen_to_world`]; matches the render shaders'
+    /// `CameraUniform::with_zoom_and_offset` transform.
+    pub fn world_to_screen(&self, world_pos: Vec2, screen_size: Vec2, world_size: Vec2) -> Vec2 {
+        let normalized = ((world_pos - self.offset) / (0.5 * world_size) - 1.0) * self.zoom;
+        (normalized + 1.0) * 0.5 * screen_size
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_camera_fitting_frames_box() {
+        let world = Vec2::new(1000.0, 500.0);
+        // Box in the right half: zoom limited by its height
+        let camera = CameraState::fitting(Vec2::new(600.0, 100.0), Vec2::new(800.0, 300.0), world);
+        assert!((camera.zoom - 500.0 / (200.0 * FIT_MARGIN)).abs() < 1e-4);
+        assert_eq!(camera.offset, Vec2::new(200.0, -50.0));
+        for corner in [Vec2::new(600.0, 100.0), Vec2::new(800.0, 300.0)] {
+            let screen = camera.world_to_screen(corner, Vec2::new(800.0, 600.0), world);
+            assert!((0.0..=800.0).contains(&screen.x) && (0.0..=600.0).contains(&screen.y));
+        }
+
+        // A box covering the world frames it like the default view
+        let whole = CameraState::fitting(Vec2::ZERO, world, world);
+        assert_eq!(whole.offset, Vec2::ZERO);
+        assert!(whole.zoom < 1.0);
+
+        // A single point zooms in as far as allowed; nothing falls back
+        assert_eq!(CameraState::fitting(Vec2::ONE, Vec2::ONE, world).zoom, 10.0);
+        let none = CameraState::fitting(Vec2::splat(f32::MAX), Vec2::splat(f32::MIN), world);
+        assert_eq!((none.offset, none.zoom), (Vec2::ZERO, 1.0));
+    }
+
+    #[test]
+    fn test_brush_locked_types() {
+        let mut brush = BrushState::default();
+        assert!(!brush.is_locked(3));
+        assert_eq!(brush.locked_mask(), 0);
+
+        brush.set_locked(3, true);
+        brush.set_locked(0, true);
+        assert!(brush.is_locked(3));
+        assert!(!brush.is_locked(1));
+        assert_eq!(brush.locked_mask(), 0b1001);
+
+        brush.set_locked(3, false);
+        assert_eq!(brush.locked_mask(), 0b1);
+    }
+
+    #[test]
+    fn test_emit_count_rate_and_cap() {
+        let mut brush = BrushState {
+            emit_rate: 100.0,
+            emit_cap: 1000,
+            ..Default::default()
+        };
+        // 100/s at 60 fps: fractions carry over so a second yields ~100
+        let total: usize = (0..60).map(|_| brush.take_emit_count(1.0 / 60.0, 0)).sum();
+        assert!((99..=100).contains(&total), "emitted {total}");
+
+        // Never overshoot the cap, and stop entirely once full
+        assert_eq!(brush.take_emit_count(1.0, 995), 5);
+        assert_eq!(brush.take_emit_count(1.0, 1000), 0);
+        assert_eq!(brush.emit_carry, 0.0);
+    }
+
     #[test]
     fn test_screen_to_world_mapping() {
         let camera = CameraState::default();
@@ -250,10 +485,44 @@ mod tests {
         assert_eq!(world_center, Vec2::new(800.0, 600.0));
     }
 
+    #[test]
+    fn test_world_to_screen_matches_camera_uniform() {
+        let camera = CameraState {
+            zoom: 3.0,
+            offset: Vec2::new(120.0, -40.0),
+            ..Default::default()
+        };
+        let screen_size = Vec2::new(800.0, 600.0);
+        let world_size = Vec2::new(1600.0, 1200.0);
+        let uniform = crate::renderer::gpu::CameraUniform::with_zoom_and_offset(
+            world_size.x,
+            world_size.y,
+            camera.zoom,
+            camera.offset.x,
+            camera.offset.y,
+        );
+
+        let world = Vec2::new(900.0, 500.0);
+        let screen = camera.world_to_screen(world, screen_size, world_size);
+        // Shader: clip = (world - center) * scale, with y flipped to screen
+        let clip_x = (world.x - uniform.center_x) * uniform.scale_x;
+        let clip_y = -(world.y - uniform.center_y) * uniform.scale_y;
+        let expected = Vec2::new(
+            (clip_x + 1.0) * 0.5 * screen_size.x,
+            (1.0 - clip_y) * 0.5 * screen_size.y,
+        );
+        assert!((screen - expected).length() < 1e-3);
+
+        let round_trip = camera.screen_to_world(screen, screen_size, world_size);
+        assert!((round_trip - world).length() < 1e-3);
+    }
+
     #[test]
     fn test_screen_to_world_with_zoom() {
-        let mut camera = CameraState::default();
-        camera.zoom = 2.0;
+        let camera = CameraState {
+            zoom: 2.0,
+            ..Default::default()
+        };
         let screen_size = Vec2::new(800.0, 600.0);
         let world_size = Vec2::new(1600.0, 1200.0);
 
@@ -262,4 +531,139 @@ mod tests {
         let world_center = camera.screen_to_world(screen_center, screen_size, world_size);
         assert_eq!(world_center, Vec2::new(800.0, 600.0));
     }
+
+    #[test]
+    fn test_camera_rescaled_to_new_world() {
+        let camera = CameraState {
+            offset: Vec2::new(100.0, -50.0),
+            zoom: 3.0,
+            ..Default::default()
+        };
+        let saved = Vec2::new(1000.0, 1000.0);
+
+        let same = camera.rescaled(saved, saved);
+        assert_eq!(same.offset, camera.offset);
+        assert_eq!(same.zoom, 3.0);
+
+        // Offset follows the world so the same region stays framed
+        let doubled = camera.rescaled(saved, Vec2::new(2000.0, 500.0));
+        assert_eq!(doubled.offset, Vec2::new(200.0, -25.0));
+
+        // Far-off pans are pulled back over the world
+        let lost = CameraState {
+            offset: Vec2::new(5000.0, 0.0),
+            ..camera
+        };
+        assert_eq!(lost.rescaled(saved, saved).offset.x, 500.0);
+    }
+
+    #[test]
+    fn test_brush_pin_and_touch_points() {
+        let mut brush = BrushState {
+            tool: BrushTool::Attract,
+            ..Default::default()
+        };
+        for i in 0..4 {
+            brush.position = Vec2::new(i as f32, 0.0);
+            brush.pin_point(3);
+        }
+        // Oldest pin is dropped once the extra slots are full
+        assert_eq!(brush.pinned_points.len(), 3);
+        assert_eq!(brush.pinned_points[0].position, Vec2::new(1.0, 0.0));
+        assert!(brush.pinned_points[0].force > 0.0);
+
+        brush.set_touch_point(7, Vec2::new(5.0, 5.0));
+        brush.set_touch_point(7, Vec2::new(6.0, 5.0));
+        assert_eq!(brush.touch_points, vec![(7, Vec2::new(6.0, 5.0))]);
+        brush.remove_touch_point(7);
+        assert!(brush.touch_points.is_empty());
+
+        // Tools without a force don't pin
+        brush.tool = BrushTool::Draw;
+        brush.pin_point(3);
+        assert_eq!(brush.pinned_points.len(), 3);
+    }
+
+    #[test]
+    fn test_gravity_well_uses_reserved_slot() {
+        use crate::renderer::gpu::{BrushParamsUniform, GRAVITY_WELL_SLOT};
+
+        let mut brush = BrushState {
+            tool: BrushTool::Attract,
+            gravity_well: true,
+            ..Default::default()
+        };
+        for i in 0..4 {
+            brush.position = Vec2::new(i as f32, 0.0);
+            brush.pin_point(3);
+        }
+        brush.position = Vec2::new(10.0, 20.0);
+
+        let points = BrushParamsUniform::points_from_brush_state(&brush, 100);
+        // Pins fill the slots before the well without displacing it
+        assert!(
+            points[1..GRAVITY_WELL_SLOT]
+                .iter()
+                .all(|p| p.is_active == 1)
+        );
+        assert!(
+            points[..GRAVITY_WELL_SLOT]
+                .iter()
+                .all(|p| p.infinite_range == 0)
+        );
+
+        let well = points[GRAVITY_WELL_SLOT];
+        assert_eq!((well.is_active, well.infinite_range), (1, 1));
+        assert_eq!((well.pos_x, well.pos_y), (10.0, 20.0));
+        assert_eq!(well.falloff_exponent, brush.well_falloff);
+
+        brush.gravity_well = false;
+        let points = BrushParamsUniform::points_from_brush_state(&brush, 100);
+        assert_eq!(points[GRAVITY_WELL_SLOT].is_active, 0);
+    }
+
+    #[test]
+    fn test_sculpt_points_use_mouse_only() {
+        use crate::renderer::gpu::BrushParamsUniform;
+        use crate::simulation::SimulationConfig;
+
+        let mut brush = BrushState {
+            tool: BrushTool::Repel,
+            gravity_well: true,
+            is_active: true,
+            ..Default::default()
+        };
+        brush.pin_point(3);
+        let config = SimulationConfig::default();
+
+        let points = BrushParamsUniform::sculpt_points(&brush, 100, 0.02, &config);
+        assert_eq!(points[0].is_active, 1);
+        assert_eq!(points[0].sculpt_dt, 0.02);
+        assert_eq!(points[0].world_width, config.world_size.x);
+        // Pins and the gravity well don't sculpt
+        assert!(points[1..].iter().all(|p| p.is_active == 0));
+    }
+
+    #[test]
+    fn test_inactive_points_disable_every_slot() {
+        use crate::renderer::gpu::BrushParamsUniform;
+
+        let mut brush = BrushState {
+            tool: BrushTool::Attract,
+            gravity_well: true,
+            is_active: true,
+            ..Default::default()
+        };
+        brush.touch_points.push((1, Vec2::new(5.0, 5.0)));
+        for i in 0..2 {
+            brush.position = Vec2::new(i as f32, 0.0);
+            brush.pin_point(3);
+        }
+
+        let points = BrushParamsUniform::points_from_brush_state(&brush, 100);
+        assert!(points.iter().all(|p| p.is_active == 1));
+        // Later substeps get no mouse, touch, pinned or gravity well force
+        let points = BrushParamsUniform::inactive_points(&brush, 100);
+        assert!(points.iter().all(|p| p.is_active == 0));
+    }
 }
@@ -0,0 +1,207 @@
+//! Exploration lineage: a bounded back/forward history of generator states.
+//!
+//! Every randomize pushes a snapshot of the rule, palette, pattern and the
+//! exact matrices and colors it produced, so an earlier exploration can be
+//! restored even though the generators are random.
+
+use crate::generators::{
+    colors::{Color, PaletteType},
+    positions::PositionPattern,
+    rules::RuleType,
+};
+use crate::simulation::{InteractionMatrix, RadiusMatrix};
+
+use super::App;
+
+/// Default number of explorations kept.
+pub const DEFAULT_LINEAGE_CAPACITY: usize = 32;
+
+/// Snapshot of the generator state after a randomize.
+#[derive(Debug, Clone)]
+pub struct ExplorationState {
+    /// Rule generator.
+    pub rule: RuleType,
+    /// Color palette.
+    pub palette: PaletteType,
+    /// Spawn pattern.
+    pub pattern: PositionPattern,
+    /// Number of particle types.
+    pub num_types: u32,
+    /// Interaction matrix produced by the rule generator.
+    pub interaction_matrix: InteractionMatrix,
+    /// Radius matrices.
+    pub radius_matrix: RadiusMatrix,
+    /// Colors produced by the palette generator, before jitter.
+    pub colors: Vec<Color>,
+    /// Hash of the interaction matrix, for telling explorations apart.
+    pub matrix_hash: u32,
+}
+
+impl ExplorationState {
+    /// Capture the current generator state of `app`.
+    pub fn capture(app: &App) -> Self {
+        Self {
+            rule: app.current_rule,
+            palette: app.current_palette,
+            pattern: app.current_pattern,
+            num_types: app.sim_config.num_types,
+            interaction_matrix: app.interaction_matrix.clone(),
+            radius_matrix: app.radius_matrix.clone(),
+            colors: app.palette_colors.clone(),
+            matrix_hash: matrix_hash(&app.interaction_matrix),
+        }
+    }
+
+    /// Short label for breadcrumb display.
+    pub fn label(&self) -> String {
+        format!(
+            "{} · {} · {} · #{:04x}",
+            self.rule.display_name(),
+            self.palette.display_name(),
+            self.pattern.display_name(),
+            self.matrix_hash & 0xffff
+        )
+    }
+}
+
+/// FNV-1a hash of the matrix values.
+pub fn matrix_hash(matrix: &InteractionMatrix) -> u32 {
+    let mut hash: u32 = 0x811c_9dc5;
+    for value in &matrix.data {
+        for byte in value.to_bits().to_le_bytes() {
+            hash ^= u32::from(byte);
+            hash = hash.wrapping_mul(0x0100_0193);
+        }
+    }
+    hash
+}
+
+/// Bounded back/forward history of explorations.
+#[derive(Debug, Clone)]
+pub struct Lineage {
+    entries: Vec<ExplorationState>,
+    cursor: usize,
+    capacity: usize,
+}
+
+impl Lineage {
+    /// Create an empty lineage holding at most `capacity` explorations.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            cursor: 0,
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Record a new exploration after the current one, discarding any
+    /// forward history. The oldest entry is dropped when full.
+    pub fn record(&mut self, state: ExplorationState) {
+        if !self.entries.is_empty() {
+            self.entries.truncate(self.cursor + 1);
+        }
+        self.entries.push(state);
+        if self.entries.len() > self.capacity {
+            self.entries.remove(0);
+        }
+        self.cursor = self.entries.len() - 1;
+    }
+
+    /// Step back to the previous exploration.
+    pub fn back(&mut self) -> Option<&ExplorationState> {
+        if !self.can_go_back() {
+            return None;
+        }
+        self.cursor -= 1;
+        self.entries.get(self.cursor)
+    }
+
+    /// Step forward to the next exploration.
+    pub fn forward(&mut self) -> Option<&ExplorationState> {
+        if !self.can_go_forward() {
+            return None;
+        }
+        self.cursor += 1;
+        self.entries.get(self.cursor)
+    }
+
+    /// Jump to the exploration at `index`.
+    pub fn go_to(&mut self, index: usize) -> Option<&ExplorationState> {
+        if index >= self.entries.len() {
+            return None;
+        }
+        self.cursor = index;
+        self.entries.get(index)
+    }
+
+    /// Whether there is an earlier exploration.
+    pub fn can_go_back(&self) -> bool {
+        self.cursor > 0
+    }
+
+    /// Whether there is a later exploration.
+    pub fn can_go_forward(&self) -> bool {
+        self.cursor + 1 < self.entries.len()
+    }
+
+    /// All recorded explorations, oldest first.
+    pub fn entries(&self) -> &[ExplorationState] {
+        &self.entries
+    }
+
+    /// Index of the current exploration.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+}
+
+impl Default for Lineage {
+    fn default() -> Self {
+        Self::new(DEFAULT_LINEAGE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(value: f32) -> ExplorationState {
+        let matrix = InteractionMatrix::filled(2, value);
+        ExplorationState {
+            rule: RuleType::Random,
+            palette: PaletteType::Rainbow,
+            pattern: PositionPattern::Disk,
+            num_types: 2,
+            matrix_hash: matrix_hash(&matrix),
+            interaction_matrix: matrix,
+            radius_matrix: RadiusMatrix::default_for_size(2),
+            colors: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_lineage_navigation() {
+        let mut lineage = Lineage::new(3);
+        for v in [0.1, 0.2, 0.3, 0.4] {
+            lineage.record(state(v));
+        }
+        // Oldest dropped at capacity
+        assert_eq!(lineage.entries().len(), 3);
+        assert!(!lineage.can_go_forward());
+
+        let back = lineage.back().unwrap();
+        assert_eq!(back.interaction_matrix.get(0, 0), 0.3);
+        assert_eq!(lineage.back().unwrap().interaction_matrix.get(0, 0), 0.2);
+        assert!(lineage.back().is_none());
+        assert_eq!(lineage.forward().unwrap().interaction_matrix.get(0, 0), 0.3);
+
+        // Recording after going back discards forward history
+        lineage.record(state(0.9));
+        assert_eq!(lineage.entries().len(), 3);
+        assert!(!lineage.can_go_forward());
+        assert_ne!(
+            lineage.entries()[2].matrix_hash,
+            lineage.entries()[1].matrix_hash
+        );
+    }
+}
@@ -1,13 +1,38 @@
 //! Application module containing the main app state and entry point.
 
+mod auto_record;
+pub mod bench;
 mod config;
+pub mod display;
+mod frame_cap;
+mod gamepad;
 mod gpu_state;
 pub(crate) mod handler;
 mod input;
+mod lineage;
 mod preset;
+mod quality;
+mod recovery;
+mod shared_state;
+mod stagnation;
 mod state;
+mod thermostat;
+mod undo;
 
+pub use auto_record::AutoRecord;
 pub use config::AppConfig;
-pub use input::{BrushState, BrushTool, CameraState};
-pub use preset::Preset;
+pub use display::WindowOptions;
+pub use frame_cap::{MIN_FPS_CAP, frame_cap_wait};
+pub use gamepad::{GAMEPAD_DEADZONE, GamepadAction, GamepadAxes, GamepadPoller, apply_deadzone};
+pub use input::{BrushPoint, BrushState, BrushTool, CameraState};
+pub use lineage::{ExplorationState, Lineage};
+pub use preset::{Preset, UNCATEGORIZED};
+pub use quality::{AdaptiveQuality, MAX_QUALITY_LEVEL, QualitySettings};
+pub use recovery::RecoveryState;
+pub use shared_state::{
+    GeneratorSelection, ImportedState, STATE_FILE_FORMAT, STATE_FILE_VERSION, SharedState,
+};
+pub use stagnation::{StagnationAction, StagnationWatchdog};
 pub use state::App;
+pub use thermostat::{THERMOSTAT_SAMPLE_SECS, thermostat_scale};
+pub use undo::UndoHistory;
@@ -1,13 +1,32 @@
 //! Preset save/load functionality for simulation states.
+//!
+//! Presets are JSON files in [`Preset::presets_dir`], either directly or in
+//! one level of category subfolders. A preset is identified by its key: the
+//! file stem, prefixed with `category/` when it sits in a subfolder.
 
 use std::path::Path;
 
 use anyhow::{Context, Result};
+use image::RgbaImage;
 use serde::{Deserialize, Serialize};
 
+use super::CameraState;
 use crate::generators::{colors::PaletteType, positions::PositionPattern, rules::RuleType};
 use crate::simulation::{InteractionMatrix, RadiusMatrix, SimulationConfig};
 
+/// Maximum number of favorites shown in the hotbar (bound to Alt+1..9).
+pub const MAX_FAVORITES: usize = 9;
+
+/// Longest side in pixels of a preset thumbnail.
+pub const PRESET_THUMBNAIL_SIZE: u32 = 256;
+
+/// Category shown for presets saved directly in the presets directory.
+pub const UNCATEGORIZED: &str = "Uncategorized";
+
+/// Subfolder of the presets directory holding the crash-recovery autosave.
+/// It is not a category, so it is skipped when listing presets.
+pub(crate) const RECOVERY_DIR: &str = "recovery";
+
 /// A saved simulation preset containing all configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Preset {
@@ -25,6 +44,12 @@ pub struct Preset {
     pub palette_type: PaletteType,
     /// Position pattern.
     pub position_pattern: PositionPattern,
+    /// Shown in the favorites hotbar.
+    #[serde(default)]
+    pub favorite: bool,
+    /// Camera framing when the preset was saved.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera: Option<CameraState>,
 }
 
 impl Preset {
@@ -46,6 +71,8 @@ impl Preset {
             rule_type,
             palette_type,
             position_pattern,
+            favorite: false,
+            camera: None,
         }
     }
 
@@ -88,7 +115,8 @@ impl Preset {
         Ok(dir)
     }
 
-    /// List all presets in the presets directory.
+    /// List the keys of all presets in the presets directory and its
+    /// category subfolders (one level deep).
     pub fn list_presets() -> Result<Vec<String>> {
         let dir = Self::presets_dir();
         if !dir.exists() {
@@ -99,16 +127,246 @@ impl Preset {
         for entry in std::fs::read_dir(&dir)
             .with_context(|| format!("Failed to read presets directory: {}", dir.display()))?
         {
-            let entry = entry?;
-            let path = entry.path();
-            if path.extension().map(|e| e == "json").unwrap_or(false)
-                && let Some(name) = path.file_stem()
-            {
-                presets.push(name.to_string_lossy().into_owned());
+            let path = entry?.path();
+            if path.is_dir() {
+                let category = path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                if category == RECOVERY_DIR {
+                    continue;
+                }
+                let sub_entries = std::fs::read_dir(&path).with_context(|| {
+                    format!("Failed to read preset category: {}", path.display())
+                })?;
+                for sub_entry in sub_entries {
+                    if let Some(name) = preset_stem(&sub_entry?.path()) {
+                        presets.push(format!("{}/{}", category, name));
+                    }
+                }
+            } else if let Some(name) = preset_stem(&path) {
+                presets.push(name);
             }
         }
 
         presets.sort();
         Ok(presets)
     }
+
+    /// Key of the preset `name` in `category`. An empty category (or
+    /// [`UNCATEGORIZED`]) saves directly in the presets directory.
+    ///
+    /// Fails when either part would escape its folder.
+    pub fn key_for(category: &str, name: &str) -> Result<String> {
+        let category = category.trim();
+        let name = name.trim();
+        for part in [category, name] {
+            if part.contains(['/', '\\']) || part == "." || part == ".." {
+                anyhow::bail!("Invalid preset name or category: '{}'", part);
+            }
+        }
+        if category == RECOVERY_DIR {
+            anyhow::bail!("'{}' is reserved for crash recovery", RECOVERY_DIR);
+        }
+        if name.is_empty() {
+            anyhow::bail!("Preset name is empty");
+        }
+        Ok(if category.is_empty() || category == UNCATEGORIZED {
+            name.to_string()
+        } else {
+            format!("{}/{}", category, name)
+        })
+    }
+
+    /// Category of the preset `key`, [`UNCATEGORIZED`] for top-level presets.
+    pub fn category_of(key: &str) -> &str {
+        key.split_once('/')
+            .map_or(UNCATEGORIZED, |(category, _)| category)
+    }
+
+    /// Name of the preset `key` without its category.
+    pub fn name_of(key: &str) -> &str {
+        key.split_once('/').map_or(key, |(_, name)| name)
+    }
+
+    /// Distinct categories of `keys` in name order, with [`UNCATEGORIZED`]
+    /// first when any top-level presets exist.
+    pub fn categories(keys: &[String]) -> Vec<String> {
+        let mut categories: Vec<String> = keys
+            .iter()
+            .map(|key| Self::category_of(key))
+            .filter(|&category| category != UNCATEGORIZED)
+            .map(str::to_string)
+            .collect();
+        categories.sort();
+        categories.dedup();
+        if keys
+            .iter()
+            .any(|key| Self::category_of(key) == UNCATEGORIZED)
+        {
+            categories.insert(0, UNCATEGORIZED.to_string());
+        }
+        categories
+    }
+
+    /// Create the presets directory, and the category subfolder of `key` if
+    /// it has one, returning the path of the preset file.
+    pub fn ensure_path_for(key: &str) -> Result<std::path::PathBuf> {
+        let path = Self::path_for(key);
+        if let Some(dir) = path.parent()
+            && !dir.exists()
+        {
+            std::fs::create_dir_all(dir).with_context(|| {
+                format!("Failed to create presets directory: {}", dir.display())
+            })?;
+        }
+        Ok(path)
+    }
+
+    /// Path of the preset file with the given key.
+    pub fn path_for(name: &str) -> std::path::PathBuf {
+        Self::presets_dir().join(format!("{}.json", name))
+    }
+
+    /// Path of the thumbnail image saved next to the preset with the given key.
+    pub fn thumbnail_path_for(name: &str) -> std::path::PathBuf {
+        Self::presets_dir().join(format!("{}.png", name))
+    }
+
+    /// Downscale a captured frame and save it as the thumbnail of preset `name`.
+    pub fn save_thumbnail(name: &str, frame: &RgbaImage) -> Result<()> {
+        let path = Self::thumbnail_path_for(name);
+        thumbnail_image(frame)
+            .save(&path)
+            .with_context(|| format!("Failed to write thumbnail to {}", path.display()))
+    }
+
+    /// Load the thumbnail of preset `name`. Presets saved without one (for
+    /// example by older versions) give `None`.
+    pub fn load_thumbnail(name: &str) -> Option<RgbaImage> {
+        let path = Self::thumbnail_path_for(name);
+        if !path.exists() {
+            return None;
+        }
+        match image::open(&path) {
+            Ok(image) => Some(image.to_rgba8()),
+            Err(e) => {
+                log::warn!("Failed to load thumbnail {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    /// Load a saved preset by name, failing with the available names when
+    /// it doesn't exist.
+    pub fn load_named(name: &str) -> Result<Self> {
+        let path = Self::path_for(name);
+        if !path.exists() {
+            let available = Self::list_presets().unwrap_or_default();
+            let hint = if available.is_empty() {
+                "no presets are saved".to_string()
+            } else {
+                format!("available: {}", available.join(", "))
+            };
+            anyhow::bail!(
+                "Preset '{}' not found in {} ({})",
+                name,
+                Self::presets_dir().display(),
+                hint
+            );
+        }
+        Self::load_from_file(&path)
+    }
+
+    /// List favorite presets in name order, at most [`MAX_FAVORITES`].
+    ///
+    /// Presets that fail to load are skipped.
+    pub fn list_favorites() -> Result<Vec<String>> {
+        Ok(Self::list_presets()?
+            .into_iter()
+            .filter(|name| {
+                Self::load_from_file(Self::path_for(name))
+                    .map(|preset| preset.favorite)
+                    .unwrap_or(false)
+            })
+            .take(MAX_FAVORITES)
+            .collect())
+    }
+
+    /// Set the favorite flag of a saved preset, rewriting its file.
+    pub fn set_favorite(name: &str, favorite: bool) -> Result<()> {
+        let path = Self::path_for(name);
+        let mut preset = Self::load_from_file(&path)?;
+        preset.favorite = favorite;
+        preset.save_to_file(&path)
+    }
+}
+
+/// File stem of `path` if it is a preset (`.json`) file.
+fn preset_stem(path: &Path) -> Option<String> {
+    if path.extension().is_some_and(|e| e == "json") {
+        path.file_stem()
+            .map(|name| name.to_string_lossy().into_owned())
+    } else {
+        None
+    }
+}
+
+/// Shrink `frame` so its longer side is at most [`PRESET_THUMBNAIL_SIZE`],
+/// keeping the aspect ratio.
+pub fn thumbnail_image(frame: &RgbaImage) -> RgbaImage {
+    let (width, height) = frame.dimensions();
+    let longest = width.max(height);
+    if longest <= PRESET_THUMBNAIL_SIZE {
+        return frame.clone();
+    }
+    let scale = PRESET_THUMBNAIL_SIZE as f32 / longest as f32;
+    let thumb_w = ((width as f32 * scale).round() as u32).max(1);
+    let thumb_h = ((height as f32 * scale).round() as u32).max(1);
+    image::imageops::thumbnail(frame, thumb_w, thumb_h)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thumbnail_fits_and_keeps_aspect() {
+        let frame = RgbaImage::new(1920, 1080);
+        let thumb = thumbnail_image(&frame);
+        assert_eq!(thumb.dimensions(), (256, 144));
+
+        let small = RgbaImage::new(100, 50);
+        assert_eq!(thumbnail_image(&small).dimensions(), (100, 50));
+    }
+
+    #[test]
+    fn test_preset_keys_and_categories() {
+        assert_eq!(Preset::key_for("", "flat").unwrap(), "flat");
+        assert_eq!(Preset::key_for(UNCATEGORIZED, "flat").unwrap(), "flat");
+        assert_eq!(Preset::key_for(" Worms ", "a").unwrap(), "Worms/a");
+        assert!(Preset::key_for("a/b", "c").is_err());
+        assert!(Preset::key_for("..", "c").is_err());
+        assert!(Preset::key_for(RECOVERY_DIR, "c").is_err());
+        assert!(Preset::key_for("Worms", "").is_err());
+
+        assert_eq!(Preset::category_of("Worms/a"), "Worms");
+        assert_eq!(Preset::name_of("Worms/a"), "a");
+        assert_eq!(Preset::category_of("flat"), UNCATEGORIZED);
+        assert_eq!(Preset::name_of("flat"), "flat");
+
+        let keys: Vec<String> = ["Worms/b", "flat", "Cells/a", "Worms/a"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            Preset::categories(&keys),
+            vec![UNCATEGORIZED.to_string(), "Cells".into(), "Worms".into()]
+        );
+        assert_eq!(Preset::categories(&keys[..1]), vec!["Worms".to_string()]);
+    }
+
+    #[test]
+    fn test_missing_thumbnail_is_none() {
+        assert!(Preset::load_thumbnail("__no_such_preset_thumbnail__").is_none());
+    }
 }
@@ -0,0 +1,139 @@
+//! Adaptive quality: drop expensive effects to hold a target frame rate.
+//!
+//! Effects are dropped one level at a time while the frame rate stays below
+//! the target and restored in reverse order once there is headroom again:
+//!
+//! 1. Glow disabled
+//! 2. Neighbor budget capped at 256
+//! 3. Neighbor budget capped at 128
+//! 4. Neighbor budget capped at 64
+
+use std::time::{Duration, Instant};
+
+/// Neighbor budget cap for each level past glow (level 2 onwards).
+const NEIGHBOR_BUDGET_STEPS: [u32; 3] = [256, 128, 64];
+
+/// Highest quality level (most effects dropped).
+pub const MAX_QUALITY_LEVEL: usize = 1 + NEIGHBOR_BUDGET_STEPS.len();
+
+/// Degrade once the FPS stays below this fraction of the target.
+const DEGRADE_RATIO: f32 = 0.9;
+
+/// Restore once the FPS stays above this fraction of the target.
+const RESTORE_RATIO: f32 = 1.2;
+
+/// How long the FPS must stay low before degrading.
+const DEGRADE_HOLD: Duration = Duration::from_secs(2);
+
+/// How long the FPS must stay high before restoring (longer, to avoid flapping).
+const RESTORE_HOLD: Duration = Duration::from_secs(5);
+
+/// User settings the controller overrides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QualitySettings {
+    /// Glow pass enabled.
+    pub enable_glow: bool,
+    /// Neighbor budget (0 = unlimited).
+    pub neighbor_budget: u32,
+}
+
+impl QualitySettings {
+    /// Settings in effect at `level`, starting from the user's `self`.
+    pub fn at_level(self, level: usize) -> Self {
+        if level == 0 {
+            return self;
+        }
+        let neighbor_budget = match level.checked_sub(2) {
+            Some(step) => {
+                let cap = NEIGHBOR_BUDGET_STEPS[step.min(NEIGHBOR_BUDGET_STEPS.len() - 1)];
+                if self.neighbor_budget == 0 {
+                    cap
+                } else {
+                    self.neighbor_budget.min(cap)
+                }
+            }
+            None => self.neighbor_budget,
+        };
+        Self {
+            enable_glow: false,
+            neighbor_budget,
+        }
+    }
+}
+
+/// Feedback controller stepping the quality level with hysteresis.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AdaptiveQuality {
+    level: usize,
+    low_since: Option<Instant>,
+    high_since: Option<Instant>,
+}
+
+impl AdaptiveQuality {
+    /// Feed an FPS sample taken at `now`.
+    ///
+    /// Returns the new level when it changes.
+    pub fn observe(&mut self, fps: f32, target_fps: f32, now: Instant) -> Option<usize> {
+        if fps < target_fps * DEGRADE_RATIO && self.level < MAX_QUALITY_LEVEL {
+            self.high_since = None;
+            let since = *self.low_since.get_or_insert(now);
+            if now.duration_since(since) >= DEGRADE_HOLD {
+                self.low_since = None;
+                self.level += 1;
+                return Some(self.level);
+            }
+        } else if fps > target_fps * RESTORE_RATIO && self.level > 0 {
+            self.low_since = None;
+            let since = *self.high_since.get_or_insert(now);
+            if now.duration_since(since) >= RESTORE_HOLD {
+                self.high_since = None;
+                self.level -= 1;
+                return Some(self.level);
+            }
+        } else {
+            self.low_since = None;
+            self.high_since = None;
+        }
+        None
+    }
+
+    /// Current level (0 = full quality).
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Return to full quality and forget pending timers.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_adaptive_quality_hysteresis() {
+        let mut quality = AdaptiveQuality::default();
+        let t0 = Instant::now();
+        let secs = |s: u64| t0 + Duration::from_secs(s);
+
+        assert_eq!(quality.observe(30.0, 60.0, t0), None);
+        assert_eq!(quality.observe(30.0, 60.0, secs(2)), Some(1));
+        // Just above target is inside the dead band: no change either way
+        assert_eq!(quality.observe(62.0, 60.0, secs(3)), None);
+        assert_eq!(quality.observe(62.0, 60.0, secs(20)), None);
+        // Sustained headroom restores
+        assert_eq!(quality.observe(80.0, 60.0, secs(21)), None);
+        assert_eq!(quality.observe(80.0, 60.0, secs(26)), Some(0));
+
+        let user = QualitySettings {
+            enable_glow: true,
+            neighbor_budget: 0,
+        };
+        assert_eq!(user.at_level(0), user);
+        assert!(!user.at_level(1).enable_glow);
+        assert_eq!(user.at_level(1).neighbor_budget, 0);
+        assert_eq!(user.at_level(MAX_QUALITY_LEVEL).neighbor_budget, 64);
+    }
+}
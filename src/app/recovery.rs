@@ -0,0 +1,176 @@
+//! Crash recovery autosave for the current scene.
+//!
+//! The scene is periodically written to a `recovery` slot inside the presets
+//! directory. A clean exit removes the file, so finding one at startup means
+//! the previous session ended unexpectedly (e.g. GPU device loss).
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::Preset;
+use super::preset::RECOVERY_DIR;
+use crate::simulation::Particle;
+
+/// Compact particle record: `(x, y, vx, vy, type)`.
+pub type ParticleRecord = (f32, f32, f32, f32, u32);
+
+/// Autosaved scene used to recover from crashes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryState {
+    /// Local time the scene was saved (RFC 3339).
+    pub saved_at: String,
+    /// Configuration, matrices and generator selections.
+    pub preset: Preset,
+    /// Optional particle snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub particles: Option<Vec<ParticleRecord>>,
+}
+
+impl RecoveryState {
+    /// Create a recovery state, optionally including particle positions.
+    pub fn new(preset: Preset, particles: Option<&[Particle]>) -> Self {
+        Self {
+            saved_at: chrono::Local::now().to_rfc3339(),
+            preset,
+            particles: particles.map(|ps| {
+                ps.iter()
+                    .map(|p| (p.x, p.y, p.vx, p.vy, p.particle_type))
+                    .collect()
+            }),
+        }
+    }
+
+    /// Rebuild the particle list from the snapshot, if one was saved.
+    pub fn to_particles(&self) -> Option<Vec<Particle>> {
+        self.particles.as_ref().map(|ps| {
+            ps.iter()
+                .map(|&(x, y, vx, vy, t)| Particle::with_velocity(x, y, vx, vy, t))
+                .collect()
+        })
+    }
+
+    /// Path of the recovery file inside the presets directory.
+    pub fn recovery_path() -> std::path::PathBuf {
+        Preset::presets_dir().join(RECOVERY_DIR).join("scene.json")
+    }
+
+    /// Write the recovery file, creating the recovery directory if needed.
+    pub fn save(&self) -> Result<()> {
+        self.save_to(&Self::recovery_path())
+    }
+
+    /// Write the recovery file to `path`.
+    fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).with_context(|| {
+                format!("Failed to create recovery directory: {}", dir.display())
+            })?;
+        }
+        let json = serde_json::to_string(self).context("Failed to serialize recovery state")?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write recovery file {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Load the recovery file left behind by an unclean exit, if any.
+    pub fn load() -> Result<Option<Self>> {
+        let path = Self::recovery_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read recovery file {}", path.display()))?;
+        let state = serde_json::from_str(&json).context("Failed to deserialize recovery state")?;
+        Ok(Some(state))
+    }
+
+    /// Remove the recovery file (called on clean exit or when discarded).
+    pub fn clear() -> Result<()> {
+        Self::clear_at(&Self::recovery_path())
+    }
+
+    /// Remove the recovery file at the end of a clean exit, unless the
+    /// previous session's file is `pending` a restore/discard decision.
+    pub fn clear_on_exit(pending: bool) -> Result<()> {
+        Self::clear_on_exit_at(&Self::recovery_path(), pending)
+    }
+
+    fn clear_on_exit_at(path: &Path, pending: bool) -> Result<()> {
+        if pending {
+            return Ok(());
+        }
+        Self::clear_at(path)
+    }
+
+    fn clear_at(path: &Path) -> Result<()> {
+        if path.exists() {
+            std::fs::remove_file(path)
+                .with_context(|| format!("Failed to remove recovery file {}", path.display()))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::{colors::PaletteType, positions::PositionPattern, rules::RuleType};
+    use crate::simulation::{InteractionMatrix, RadiusMatrix, SimulationConfig};
+
+    #[test]
+    fn test_particle_snapshot_roundtrip() {
+        let preset = Preset::new(
+            "recovery",
+            &SimulationConfig::default(),
+            &InteractionMatrix::new(2),
+            &RadiusMatrix::default_for_size(2),
+            RuleType::Random,
+            PaletteType::Rainbow,
+            PositionPattern::Disk,
+        );
+        let particles = vec![
+            Particle::with_velocity(1.0, 2.0, 3.0, 4.0, 1),
+            Particle::new(5.0, 6.0, 0),
+        ];
+        let state = RecoveryState::new(preset, Some(&particles));
+
+        let json = serde_json::to_string(&state).unwrap();
+        let loaded: RecoveryState = serde_json::from_str(&json).unwrap();
+        let restored = loaded.to_particles().unwrap();
+
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored[0].x, 1.0);
+        assert_eq!(restored[0].vy, 4.0);
+        assert_eq!(restored[0].particle_type, 1);
+        assert_eq!(restored[1].y, 6.0);
+    }
+
+    #[test]
+    fn test_clean_exit_removes_recovery_file() {
+        let dir =
+            std::env::temp_dir().join(format!("par_particle_life_recovery_{}", std::process::id()));
+        let path = dir.join("scene.json");
+        let preset = Preset::new(
+            "recovery",
+            &SimulationConfig::default(),
+            &InteractionMatrix::new(2),
+            &RadiusMatrix::default_for_size(2),
+            RuleType::Random,
+            PaletteType::Rainbow,
+            PositionPattern::Disk,
+        );
+        RecoveryState::new(preset, None).save_to(&path).unwrap();
+
+        // A file from the previous session still awaiting a decision stays
+        RecoveryState::clear_on_exit_at(&path, true).unwrap();
+        assert!(path.exists());
+
+        RecoveryState::clear_on_exit_at(&path, false).unwrap();
+        assert!(!path.exists());
+        // Exiting again with no file is fine
+        RecoveryState::clear_on_exit_at(&path, false).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
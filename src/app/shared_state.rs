@@ -0,0 +1,355 @@
+//! Export and import of the full app state as one shareable file.
+//!
+//! Unlike presets, which live in the presets directory and only carry the
+//! simulation setup, a state file is a single portable JSON document with
+//! the whole [`AppConfig`], both matrices, the colors and the generator
+//! selections. It is tagged with [`STATE_FILE_FORMAT`] and a version so
+//! files from other app versions can be recognized.
+//!
+//! Import is lenient: each section is read on its own, and one that is
+//! missing or can't be parsed (for example because a newer version changed
+//! it) keeps its current value and is reported instead of failing the
+//! whole import. The simulation config and both matrices share the type
+//! count, so they are only taken from the file together, once they validate
+//! and agree on it.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use super::{App, AppConfig, CameraState};
+use crate::generators::{
+    colors::{Color, PaletteType},
+    positions::{PositionPattern, SpawnSampling, SpawnVelocity},
+    rules::RuleType,
+};
+use crate::simulation::{InteractionMatrix, RadiusMatrix, SimulationConfig};
+
+/// Value of the `format` tag identifying a state file.
+pub const STATE_FILE_FORMAT: &str = "par-particle-life-state";
+
+/// Newest state file version this build reads and the one it writes.
+pub const STATE_FILE_VERSION: u32 = 1;
+
+/// Generator selections used to (re)generate rules, colors and particles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GeneratorSelection {
+    /// Rule generator.
+    pub rule: RuleType,
+    /// Color palette generator.
+    pub palette: PaletteType,
+    /// Spawn position pattern.
+    pub pattern: PositionPattern,
+    /// Initial velocity field for spawned particles.
+    pub spawn_velocity: SpawnVelocity,
+    /// Coordinate sampling for spawn patterns.
+    pub spawn_sampling: SpawnSampling,
+    /// Relative particle share per type (empty = uniform).
+    pub type_weights: Vec<f32>,
+}
+
+/// Full app state as written to a state file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedState {
+    /// Always [`STATE_FILE_FORMAT`].
+    pub format: String,
+    /// File version, [`STATE_FILE_VERSION`] when written by this build.
+    pub version: u32,
+    /// Version of the app that wrote the file, for reference.
+    pub app_version: String,
+    /// Application configuration.
+    pub config: AppConfig,
+    /// Simulation configuration.
+    pub sim_config: SimulationConfig,
+    /// Interaction matrix.
+    pub interaction_matrix: InteractionMatrix,
+    /// Radius matrices.
+    pub radius_matrix: RadiusMatrix,
+    /// Type colors, with palette jitter applied.
+    pub colors: Vec<Color>,
+    /// Generator selections.
+    pub generators: GeneratorSelection,
+    /// Camera framing.
+    pub camera: CameraState,
+}
+
+/// Result of reading a state file.
+#[derive(Debug)]
+pub struct ImportedState {
+    /// The state, with skipped sections taken from the fallback.
+    pub state: SharedState,
+    /// Version the file was written with.
+    pub version: u32,
+    /// Sections that were missing or unreadable and kept their current value.
+    pub skipped: Vec<&'static str>,
+}
+
+impl ImportedState {
+    /// Whether the file is newer than this build supports.
+    pub fn is_newer(&self) -> bool {
+        self.version > STATE_FILE_VERSION
+    }
+}
+
+impl SharedState {
+    /// Capture the current state of `app` and the camera.
+    pub fn capture(app: &App, camera: CameraState) -> Self {
+        Self {
+            format: STATE_FILE_FORMAT.to_string(),
+            version: STATE_FILE_VERSION,
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            config: app.config.clone(),
+            sim_config: app.sim_config.clone(),
+            interaction_matrix: app.interaction_matrix.clone(),
+            radius_matrix: app.radius_matrix.clone(),
+            colors: app.colors.clone(),
+            generators: GeneratorSelection {
+                rule: app.current_rule,
+                palette: app.current_palette,
+                pattern: app.current_pattern,
+                spawn_velocity: app.current_spawn_velocity,
+                spawn_sampling: app.current_spawn_sampling,
+                type_weights: app.current_type_weights.clone(),
+            },
+            camera,
+        }
+    }
+
+    /// Write the state as pretty-printed JSON.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let json = serde_json::to_string_pretty(self).context("Failed to serialize state")?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write state to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Read a state file, taking any section it lacks or that fails to parse
+    /// from `fallback`.
+    pub fn load_from_file(path: impl AsRef<Path>, fallback: &Self) -> Result<ImportedState> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state from {}", path.display()))?;
+        Self::from_json(&json, fallback)
+    }
+
+    /// Parse a state file; see [`Self::load_from_file`].
+    ///
+    /// Fails only when the document is not a state file at all.
+    pub fn from_json(json: &str, fallback: &Self) -> Result<ImportedState> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).context("State file is not valid JSON")?;
+        let serde_json::Value::Object(sections) = value else {
+            anyhow::bail!("State file is not a JSON object");
+        };
+        if sections.get("format").and_then(|f| f.as_str()) != Some(STATE_FILE_FORMAT) {
+            anyhow::bail!("Not a {} file", STATE_FILE_FORMAT);
+        }
+        let version = sections
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .and_then(|v| u32::try_from(v).ok())
+            .context("State file has no version")?;
+
+        let app_version = sections
+            .get("app_version")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default()
+            .to_string();
+
+        let mut s = Sections {
+            sections,
+            skipped: Vec::new(),
+        };
+        let mut state = Self {
+            format: STATE_FILE_FORMAT.to_string(),
+            version,
+            app_version,
+            config: s.take("config", &fallback.config),
+            sim_config: s.take("sim_config", &fallback.sim_config),
+            interaction_matrix: s.take("interaction_matrix", &fallback.interaction_matrix),
+            radius_matrix: s.take("radius_matrix", &fallback.radius_matrix),
+            colors: s.take("colors", &fallback.colors),
+            generators: s.take("generators", &fallback.generators),
+            camera: s.take("camera", &fallback.camera),
+        };
+        state.reconcile_types(fallback, &mut s.skipped);
+        Ok(ImportedState {
+            state,
+            version,
+            skipped: s.skipped,
+        })
+    }
+
+    /// Fall back on the whole type-dependent group (sim config and both
+    /// matrices) unless it validates and agrees on `num_types`, and fall
+    /// back on colors that don't match the resulting type count.
+    fn reconcile_types(&mut self, fallback: &Self, skipped: &mut Vec<&'static str>) {
+        let num_types = self.sim_config.num_types as usize;
+        let consistent = self
+            .sim_config
+            .validate()
+            .and_then(|()| self.interaction_matrix.validate())
+            .and_then(|()| self.radius_matrix.validate())
+            .and_then(|()| {
+                let interaction = &self.interaction_matrix;
+                if interaction.size == num_types
+                    && interaction.data.len() == num_types * num_types
+                    && self.radius_matrix.size == num_types
+                {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "matrices of size {} and {} don't match {} types",
+                        interaction.size, self.radius_matrix.size, num_types
+                    ))
+                }
+            });
+        if let Err(e) = consistent {
+            log::warn!("Keeping current types, config and matrices: {}", e);
+            self.sim_config = fallback.sim_config.clone();
+            self.interaction_matrix = fallback.interaction_matrix.clone();
+            self.radius_matrix = fallback.radius_matrix.clone();
+            for key in ["sim_config", "interaction_matrix", "radius_matrix"] {
+                if !skipped.contains(&key) {
+                    skipped.push(key);
+                }
+            }
+        }
+        self.sim_config.resize_type_overrides();
+
+        if self.colors.len() != self.sim_config.num_types as usize && !skipped.contains(&"colors") {
+            self.colors = fallback.colors.clone();
+            skipped.push("colors");
+        }
+    }
+}
+
+/// Sections of a state file still to be read.
+struct Sections {
+    sections: serde_json::Map<String, serde_json::Value>,
+    skipped: Vec<&'static str>,
+}
+
+impl Sections {
+    /// Parse section `key`, or record it as skipped and copy `fallback`.
+    fn take<T: DeserializeOwned + Clone>(&mut self, key: &'static str, fallback: &T) -> T {
+        let parsed = self.sections.remove(key).and_then(|value| {
+            serde_json::from_value(value)
+                .inspect_err(|e| log::warn!("Skipping state section '{}': {}", key, e))
+                .ok()
+        });
+        parsed.unwrap_or_else(|| {
+            self.skipped.push(key);
+            fallback.clone()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_state() -> SharedState {
+        SharedState {
+            format: STATE_FILE_FORMAT.to_string(),
+            version: STATE_FILE_VERSION,
+            app_version: "0.0.0".to_string(),
+            config: AppConfig::default(),
+            sim_config: SimulationConfig {
+                num_types: 2,
+                ..Default::default()
+            },
+            interaction_matrix: InteractionMatrix::new(2),
+            radius_matrix: RadiusMatrix::default_for_size(2),
+            colors: vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]],
+            generators: GeneratorSelection {
+                rule: RuleType::Random,
+                palette: PaletteType::Rainbow,
+                pattern: PositionPattern::Disk,
+                spawn_velocity: SpawnVelocity::Zero,
+                spawn_sampling: SpawnSampling::Uniform,
+                type_weights: Vec::new(),
+            },
+            camera: CameraState::default(),
+        }
+    }
+
+    #[test]
+    fn test_state_import_keeps_unreadable_sections() {
+        let mut exported = sample_state();
+        exported.interaction_matrix.set(0, 1, 0.5);
+        exported.colors[0] = [0.0, 1.0, 0.0, 1.0];
+        let json = serde_json::to_string(&exported).unwrap();
+
+        // Same version: everything comes from the file
+        let fallback = sample_state();
+        let imported = SharedState::from_json(&json, &fallback).unwrap();
+        assert!(imported.skipped.is_empty() && !imported.is_newer());
+        assert_eq!(imported.state.interaction_matrix.get(0, 1), 0.5);
+        assert_eq!(imported.state.colors[0], [0.0, 1.0, 0.0, 1.0]);
+
+        // A newer file with a reshaped section and an unknown one: the rest
+        // still loads and the bad section keeps its current value
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["version"] = (STATE_FILE_VERSION + 1).into();
+        value["colors"] = serde_json::json!({ "space": "oklch", "values": [] });
+        value["future_section"] = serde_json::json!(42);
+        let imported = SharedState::from_json(&value.to_string(), &fallback).unwrap();
+        assert!(imported.is_newer());
+        assert_eq!(imported.skipped, vec!["colors"]);
+        assert_eq!(imported.state.colors, fallback.colors);
+        assert_eq!(imported.state.interaction_matrix.get(0, 1), 0.5);
+
+        // Other JSON is rejected outright
+        assert!(SharedState::from_json("{\"name\": \"preset\"}", &fallback).is_err());
+    }
+
+    #[test]
+    fn test_state_import_keeps_type_sections_together() {
+        let fallback = sample_state();
+        let mut exported = sample_state();
+        exported.sim_config.num_types = 3;
+        exported.interaction_matrix = InteractionMatrix::new(3);
+        exported.radius_matrix = RadiusMatrix::default_for_size(3);
+        exported.colors = vec![[1.0; 4]; 3];
+        let json = serde_json::to_string(&exported).unwrap();
+
+        // A consistent file is taken as a whole
+        let imported = SharedState::from_json(&json, &fallback).unwrap();
+        assert!(imported.skipped.is_empty());
+        assert_eq!(imported.state.sim_config.num_types, 3);
+
+        // sim_config parses but the matrix doesn't: the 3-type config must
+        // not be paired with the current 2x2 matrices
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["interaction_matrix"] = serde_json::json!("reshaped");
+        let imported = SharedState::from_json(&value.to_string(), &fallback).unwrap();
+        let state = &imported.state;
+        assert_eq!(state.sim_config.num_types, 2);
+        assert_eq!(state.interaction_matrix.size, 2);
+        assert_eq!(state.radius_matrix.size, 2);
+        assert_eq!(state.colors, fallback.colors);
+        for key in [
+            "sim_config",
+            "interaction_matrix",
+            "radius_matrix",
+            "colors",
+        ] {
+            assert!(imported.skipped.contains(&key), "{key} not reported");
+        }
+
+        // An invalid config falls back along with its matrices
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        value["sim_config"]["friction"] = 5.0.into();
+        let imported = SharedState::from_json(&value.to_string(), &fallback).unwrap();
+        assert_eq!(
+            imported.state.sim_config.friction,
+            fallback.sim_config.friction
+        );
+        assert_eq!(imported.state.interaction_matrix.size, 2);
+        assert!(imported.skipped.contains(&"sim_config"));
+    }
+}
@@ -0,0 +1,107 @@
+//! Detection of frozen (stagnant) scenes.
+
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// What to do when the simulation has been stagnant for too long.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum StagnationAction {
+    /// Do nothing.
+    #[default]
+    None,
+
+    /// Pause the simulation to save power.
+    Pause,
+
+    /// Inject a random velocity impulse to reheat the scene.
+    Stir,
+}
+
+impl StagnationAction {
+    /// Get all available stagnation actions.
+    pub fn all() -> &'static [StagnationAction] {
+        &[
+            StagnationAction::None,
+            StagnationAction::Pause,
+            StagnationAction::Stir,
+        ]
+    }
+
+    /// Get the display name for this action.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            StagnationAction::None => "None",
+            StagnationAction::Pause => "Pause",
+            StagnationAction::Stir => "Stir",
+        }
+    }
+}
+
+/// Tracks how long the mean particle speed has stayed below a threshold.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StagnationWatchdog {
+    still_since: Option<Instant>,
+}
+
+impl StagnationWatchdog {
+    /// Feed a mean speed sample taken at `now`.
+    ///
+    /// Returns `true` once every sample for at least `hold` has been at or
+    /// below `threshold`; the timer then restarts.
+    pub fn observe(
+        &mut self,
+        mean_speed: f32,
+        threshold: f32,
+        hold: Duration,
+        now: Instant,
+    ) -> bool {
+        if mean_speed > threshold {
+            self.still_since = None;
+            return false;
+        }
+        let since = *self.still_since.get_or_insert(now);
+        if now.duration_since(since) >= hold {
+            self.still_since = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Forget any stagnant period in progress.
+    pub fn reset(&mut self) {
+        self.still_since = None;
+    }
+
+    /// How long the scene has been stagnant as of `now`.
+    pub fn stagnant_for(&self, now: Instant) -> Duration {
+        self.still_since
+            .map(|since| now.duration_since(since))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watchdog_triggers_after_hold() {
+        let mut watchdog = StagnationWatchdog::default();
+        let hold = Duration::from_secs(5);
+        let t0 = Instant::now();
+
+        assert!(!watchdog.observe(0.1, 1.0, hold, t0));
+        assert!(!watchdog.observe(0.1, 1.0, hold, t0 + Duration::from_secs(3)));
+        // A fast sample restarts the timer
+        assert!(!watchdog.observe(5.0, 1.0, hold, t0 + Duration::from_secs(4)));
+        assert!(!watchdog.observe(0.1, 1.0, hold, t0 + Duration::from_secs(6)));
+        assert!(watchdog.observe(0.1, 1.0, hold, t0 + Duration::from_secs(11)));
+        // Timer restarts after triggering
+        assert_eq!(
+            watchdog.stagnant_for(t0 + Duration::from_secs(12)),
+            Duration::ZERO
+        );
+    }
+}
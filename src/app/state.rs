@@ -1,13 +1,19 @@
 //! Main application state.
 
+use std::path::Path;
+
 use anyhow::Result;
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
 use winit::event_loop::{ControlFlow, EventLoop};
 
-use super::{AppConfig, handler::AppHandler};
+use super::{AppConfig, AutoRecord, Preset, WindowOptions, handler::AppHandler};
 use crate::generators::{
-    colors::{Color, PaletteType, generate_colors},
-    positions::{PositionPattern, SpawnConfig, generate_positions},
-    rules::{RuleType, generate_rules},
+    colors::{Color, PaletteType, generate_colors_with_rng, jitter_colors},
+    image_seed::image_positions,
+    positions::{
+        PositionPattern, SpawnConfig, SpawnSampling, SpawnVelocity, generate_positions_with_rng,
+    },
+    rules::{RuleType, generate_rules_with_rng},
 };
 use crate::simulation::{
     InteractionMatrix, Particle, PhysicsEngine, RadiusMatrix, SimulationConfig,
@@ -25,8 +31,10 @@ pub struct App {
     pub interaction_matrix: InteractionMatrix,
     /// Radius matrices.
     pub radius_matrix: RadiusMatrix,
-    /// Color palette for particle types.
+    /// Color palette for particle types, with `palette_jitter` applied.
     pub colors: Vec<Color>,
+    /// Colors as produced by the palette generator, before jitter.
+    pub palette_colors: Vec<Color>,
     /// Physics engine.
     pub physics: PhysicsEngine,
     /// Is simulation running?
@@ -37,8 +45,22 @@ pub struct App {
     pub current_palette: PaletteType,
     /// Current position pattern.
     pub current_pattern: PositionPattern,
+    /// Current initial velocity field for spawned particles.
+    pub current_spawn_velocity: SpawnVelocity,
+    /// Current coordinate sampling for spawn patterns.
+    pub current_spawn_sampling: SpawnSampling,
+    /// Current relative particle share per type (empty = uniform).
+    pub current_type_weights: Vec<f32>,
     /// Auto-scale radii with density (persisted setting).
     pub auto_scale_radii: bool,
+    /// Default radii are fractions of the world size (persisted setting).
+    pub radii_relative_to_world: bool,
+    /// Random stream shared by all generators, seeded from
+    /// `sim_config.seed` when one is set.
+    pub rng: StdRng,
+    /// Physics frames stepped since the particles were last spawned. Seeds
+    /// fair neighbor sampling, so a seeded run samples the same way each time.
+    pub sim_frame: u32,
 }
 
 impl App {
@@ -50,23 +72,53 @@ impl App {
             AppConfig::load()
         };
         let auto_scale_radii = config.auto_scale_radii;
+        let radii_relative_to_world = config.radii_relative_to_world;
 
         let mut sim_config = SimulationConfig {
             num_particles: config.sim_num_particles,
             num_types: config.sim_num_types,
+            seed: config.sim_seed,
+            type_names: config.sim_type_names.clone(),
             force_factor: config.phys_force_factor,
+            matrix_strength: config.phys_matrix_strength,
+            spin_coupling: config.phys_spin_coupling,
+            spin_friction: config.phys_spin_friction,
+            target_temperature: config.phys_target_temperature,
             friction: config.phys_friction,
             repel_strength: config.phys_repel_strength,
+            repel_radius: config.phys_repel_radius,
             max_velocity: config.phys_max_velocity,
+            velocity_clamp: config.phys_velocity_clamp,
+            fixed_dt: config.phys_fixed_dt,
+            substeps: config.phys_substeps,
             boundary_mode: config.phys_boundary_mode,
+            world_shape: config.phys_world_shape,
             wall_repel_strength: config.phys_wall_repel_strength,
             mirror_wrap_count: config.phys_mirror_wrap_count,
+            enable_3d: config.render_enable_3d,
+            depth_limit: config.render_depth_limit,
             particle_size: config.render_particle_size,
             background_color: config.render_background_color,
+            background_gradient: config.render_background_gradient,
             enable_glow: config.render_glow_enabled,
             glow_intensity: config.render_glow_intensity,
             glow_size: config.render_glow_size,
             glow_steepness: config.render_glow_steepness,
+            glow_zoom_compensate: config.render_glow_zoom_compensate,
+            enable_trails: config.render_trails_enabled,
+            trail_fade: config.render_trail_fade,
+            vignette: config.render_vignette,
+            color_mode: config.render_color_mode,
+            particle_shape: config.render_particle_shape,
+            blend_mode: config.render_blend_mode,
+            colormap: config.render_colormap,
+            color_speed_range: config.render_color_speed_range,
+            animate_palette: config.render_animate_palette,
+            palette_hue_speed: config.render_palette_hue_speed,
+            palette_jitter: config.gen_palette_jitter,
+            seam_fade: config.render_seam_fade,
+            velocity_streaks: config.render_velocity_streaks,
+            streak_length: config.render_streak_length,
             spatial_hash_cell_size: config.render_spatial_hash_cell_size,
             use_spatial_hash: true, // always on
             ..SimulationConfig::default()
@@ -79,16 +131,30 @@ impl App {
         let current_rule = config.gen_rule;
         let current_palette = config.gen_palette;
         let current_pattern = config.gen_pattern;
-
-        let interaction_matrix = generate_rules(current_rule, num_types);
-        let mut radius_matrix = RadiusMatrix::default_for_size(num_types);
-        let colors = generate_colors(current_palette, num_types);
+        let current_spawn_velocity = config.gen_spawn_velocity;
+        let current_spawn_sampling = config.gen_spawn_sampling;
+        let current_type_weights = config.gen_type_weights.clone();
+
+        // Same draw order as `regenerate_from_seed`: rules, colors, particles
+        let mut rng = Self::seeded_rng(sim_config.seed);
+        let interaction_matrix = generate_rules_with_rng(current_rule, num_types, &mut rng);
+        let mut radius_matrix =
+            Self::default_radius_matrix_for(radii_relative_to_world, num_types, &sim_config);
+        let palette_colors = generate_colors_with_rng(current_palette, num_types, &mut rng);
+        let colors = jitter_colors(
+            &palette_colors,
+            sim_config.palette_jitter,
+            sim_config.seed.unwrap_or_default(),
+        );
 
         let spawn_config = SpawnConfig {
             num_particles: sim_config.num_particles as usize,
             num_types,
             width: sim_config.world_size.x,
             height: sim_config.world_size.y,
+            velocity: current_spawn_velocity,
+            sampling: current_spawn_sampling,
+            type_weights: current_type_weights.clone(),
         };
         // Scale radii to keep neighbor counts reasonable as particle density changes.
         if auto_scale_radii {
@@ -101,7 +167,10 @@ impl App {
             sim_config.spatial_hash_cell_size = sim_config.spatial_hash_cell_size.max(max_r);
         }
 
-        let particles = generate_positions(current_pattern, &spawn_config);
+        let mut particles = generate_positions_with_rng(current_pattern, &spawn_config, &mut rng);
+        if config.gen_shuffle_particles {
+            particles.shuffle(&mut rng);
+        }
 
         let physics = PhysicsEngine::new(particles.len());
 
@@ -112,23 +181,79 @@ impl App {
             interaction_matrix,
             radius_matrix,
             colors,
+            palette_colors,
             physics,
             running: true,
             current_rule,
             current_palette,
             current_pattern,
+            current_spawn_velocity,
+            current_spawn_sampling,
+            current_type_weights,
             auto_scale_radii,
+            radii_relative_to_world,
+            rng,
+            sim_frame: 0,
+        }
+    }
+
+    /// Generator RNG for `seed`, or a randomly seeded one for `None`.
+    pub fn seeded_rng(seed: Option<u64>) -> StdRng {
+        match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_rng(&mut rand::rng()),
         }
     }
 
+    /// Restart the generator stream from `sim_config.seed`.
+    pub fn reset_rng(&mut self) {
+        self.rng = Self::seeded_rng(self.sim_config.seed);
+    }
+
+    /// Restart the generator stream and regenerate rules, colors and
+    /// particles in that fixed order, so a seed always reproduces the same
+    /// starting state. With `keep_rules` the matrix draw still happens (to
+    /// keep the stream aligned) but the current matrix is left in place.
+    pub fn regenerate_from_seed(&mut self, keep_rules: bool) {
+        self.reset_rng();
+        let matrix = generate_rules_with_rng(
+            self.current_rule,
+            self.sim_config.num_types as usize,
+            &mut self.rng,
+        );
+        if !keep_rules {
+            self.interaction_matrix = matrix;
+        }
+        self.regenerate_colors();
+        self.regenerate_particles();
+    }
+
     /// Run the main application loop.
-    pub fn run(reset_config: bool) -> Result<()> {
+    ///
+    /// `no_f16` forces the f32 storage path for this session without
+    /// changing the persisted `force_f32` setting. `window` picks the monitor
+    /// and fullscreen mode; a monitor given there overrides the saved one.
+    /// `preset` names a saved preset applied before the first frame; an
+    /// unknown name is an error before any window opens. `auto_record`
+    /// records from the first frame for a fixed span of sim time, then quits.
+    pub fn run(
+        reset_config: bool,
+        no_f16: bool,
+        window: WindowOptions,
+        preset: Option<&str>,
+        auto_record: Option<AutoRecord>,
+    ) -> Result<()> {
         log::info!("Par Particle Life starting...");
 
+        let startup_preset = preset
+            .map(|name| Preset::load_named(name).map(|p| (name.to_string(), p)))
+            .transpose()?;
+
         let event_loop = EventLoop::new()?;
         event_loop.set_control_flow(ControlFlow::Poll);
 
-        let mut app_handler = AppHandler::new(reset_config);
+        let mut app_handler =
+            AppHandler::new(reset_config, no_f16, window, startup_preset, auto_record);
         event_loop.run_app(&mut app_handler)?;
 
         Ok(())
@@ -151,25 +276,208 @@ impl App {
 
     /// Regenerate particles with the current pattern.
     pub fn regenerate_particles(&mut self) {
-        let spawn_config = SpawnConfig {
-            num_particles: self.sim_config.num_particles as usize,
+        let spawn_config = self.spawn_config(self.sim_config.num_particles as usize);
+        self.particles =
+            generate_positions_with_rng(self.current_pattern, &spawn_config, &mut self.rng);
+        if self.config.gen_shuffle_particles {
+            self.shuffle_particles();
+        }
+        self.assign_depths(0);
+        self.physics.resize(self.particles.len());
+        self.sim_frame = 0;
+    }
+
+    /// Give `particles[start..]` random depths in `[0, depth_limit)` and
+    /// order that range far to near, so the 3D pass draws back-to-front.
+    ///
+    /// Does nothing with 3D off, leaving the generator stream (and so every
+    /// seeded 2D scene) untouched.
+    pub fn assign_depths(&mut self, start: usize) {
+        if !self.sim_config.enable_3d {
+            return;
+        }
+        let limit = self.sim_config.depth_limit.max(1.0);
+        let tail = &mut self.particles[start..];
+        for p in tail.iter_mut() {
+            p.z = self.rng.random::<f32>() * limit;
+        }
+        tail.sort_by(|a, b| b.z.total_cmp(&a.z));
+    }
+
+    /// Shuffle the particle array with the generator RNG.
+    ///
+    /// Spawn patterns emit particles grouped or cycled by type, so later
+    /// types draw on top and sit together in memory. Shuffling decorrelates
+    /// both from type; with a locked seed the order is the same every run.
+    pub fn shuffle_particles(&mut self) {
+        self.particles.shuffle(&mut self.rng);
+    }
+
+    /// Negate every particle's velocity.
+    ///
+    /// Friction and velocity clamping lose energy, so the reversed run only
+    /// retraces the recent past approximately.
+    pub fn reverse_velocities(&mut self) {
+        for p in &mut self.particles {
+            p.vx = -p.vx;
+            p.vy = -p.vy;
+        }
+    }
+
+    /// Respawn every particle over `image`: brighter pixels get more
+    /// particles, and each takes the type nearest its pixel's color.
+    pub fn spawn_from_image(&mut self, image: &image::RgbImage) {
+        let spawn_config = self.spawn_config(self.sim_config.num_particles as usize);
+        self.particles = image_positions(image, &self.colors, &spawn_config, &mut self.rng);
+        self.assign_depths(0);
+        self.physics.resize(self.particles.len());
+        self.sim_frame = 0;
+    }
+
+    /// Remove every particle, leaving an empty canvas for the Draw brush.
+    ///
+    /// The configured particle count (`config.sim_num_particles`) is left
+    /// alone, so it survives a restart and picking a count in the UI spawns
+    /// a full scene again.
+    pub fn clear_particles(&mut self) {
+        self.particles.clear();
+        self.sim_config.num_particles = 0;
+        self.physics.resize(0);
+        self.sim_frame = 0;
+    }
+
+    /// Change the particle count while keeping the existing particles.
+    ///
+    /// Shrinking truncates; growing appends particles spawned with the
+    /// current pattern, type count and type weights, so their types follow
+    /// the same distribution as a full regeneration.
+    pub fn resize_particles(&mut self, count: usize) {
+        let current = self.particles.len();
+        if count <= current {
+            self.particles.truncate(count);
+        } else {
+            let spawn_config = self.spawn_config(count - current);
+            let mut extra =
+                generate_positions_with_rng(self.current_pattern, &spawn_config, &mut self.rng);
+            if self.config.gen_shuffle_particles {
+                extra.shuffle(&mut self.rng);
+            }
+            self.particles.extend(extra);
+            self.assign_depths(current);
+        }
+        self.sim_config.num_particles = count as u32;
+        self.physics.resize(self.particles.len());
+    }
+
+    /// Spawn settings for `num_particles` particles with the current
+    /// generators and world size.
+    fn spawn_config(&self, num_particles: usize) -> SpawnConfig {
+        SpawnConfig {
+            num_particles,
             num_types: self.sim_config.num_types as usize,
             width: self.sim_config.world_size.x,
             height: self.sim_config.world_size.y,
-        };
-        self.particles = generate_positions(self.current_pattern, &spawn_config);
-        self.physics.resize(self.particles.len());
+            velocity: self.current_spawn_velocity,
+            sampling: self.current_spawn_sampling,
+            type_weights: self.current_type_weights.clone(),
+        }
     }
 
     /// Regenerate the interaction matrix with the current rule type.
     pub fn regenerate_rules(&mut self) {
-        self.interaction_matrix =
-            generate_rules(self.current_rule, self.sim_config.num_types as usize);
+        self.interaction_matrix = generate_rules_with_rng(
+            self.current_rule,
+            self.sim_config.num_types as usize,
+            &mut self.rng,
+        );
+    }
+
+    /// Perturb the current interaction matrix with Gaussian noise instead
+    /// of regenerating it.
+    pub fn mutate_rules(&mut self) {
+        self.interaction_matrix
+            .mutate(self.config.gen_mutation_sigma, &mut self.rng);
     }
 
     /// Regenerate the color palette.
     pub fn regenerate_colors(&mut self) {
-        self.colors = generate_colors(self.current_palette, self.sim_config.num_types as usize);
+        self.palette_colors = generate_colors_with_rng(
+            self.current_palette,
+            self.sim_config.num_types as usize,
+            &mut self.rng,
+        );
+        self.apply_palette_jitter();
+    }
+
+    /// Recompute `colors` from the generated palette and the current
+    /// `palette_jitter`, drawing offsets from the generation seed.
+    pub fn apply_palette_jitter(&mut self) {
+        self.colors = jitter_colors(
+            &self.palette_colors,
+            self.sim_config.palette_jitter,
+            self.sim_config.seed.unwrap_or_default(),
+        );
+    }
+
+    /// Reset physics parameters to their defaults, keeping the matrix,
+    /// colors and particles.
+    pub fn reset_physics(&mut self) {
+        let defaults = SimulationConfig::default();
+        let cfg = &mut self.sim_config;
+        cfg.force_factor = defaults.force_factor;
+        cfg.matrix_strength = defaults.matrix_strength;
+        cfg.friction = defaults.friction;
+        cfg.type_friction = defaults.type_friction;
+        cfg.type_force_factor = defaults.type_force_factor;
+        cfg.repel_strength = defaults.repel_strength;
+        cfg.repel_radius = defaults.repel_radius;
+        cfg.max_velocity = defaults.max_velocity;
+        cfg.velocity_clamp = defaults.velocity_clamp;
+        cfg.fixed_dt = defaults.fixed_dt;
+        cfg.substeps = defaults.substeps;
+        cfg.spin_coupling = defaults.spin_coupling;
+        cfg.spin_friction = defaults.spin_friction;
+        cfg.target_temperature = defaults.target_temperature;
+        cfg.boundary_mode = defaults.boundary_mode;
+        cfg.world_shape = defaults.world_shape;
+        cfg.wall_repel_strength = defaults.wall_repel_strength;
+        cfg.mirror_wrap_count = defaults.mirror_wrap_count;
+        cfg.max_bin_density = defaults.max_bin_density;
+        cfg.neighbor_budget = defaults.neighbor_budget;
+
+        self.config.phys_force_factor = cfg.force_factor;
+        self.config.phys_matrix_strength = cfg.matrix_strength;
+        self.config.phys_friction = cfg.friction;
+        self.config.phys_repel_strength = cfg.repel_strength;
+        self.config.phys_repel_radius = cfg.repel_radius;
+        self.config.phys_max_velocity = cfg.max_velocity;
+        self.config.phys_velocity_clamp = cfg.velocity_clamp;
+        self.config.phys_fixed_dt = cfg.fixed_dt;
+        self.config.phys_substeps = cfg.substeps;
+        self.config.phys_spin_coupling = cfg.spin_coupling;
+        self.config.phys_spin_friction = cfg.spin_friction;
+        self.config.phys_target_temperature = cfg.target_temperature;
+        self.config.phys_boundary_mode = cfg.boundary_mode;
+        self.config.phys_world_shape = cfg.world_shape;
+        self.config.phys_wall_repel_strength = cfg.wall_repel_strength;
+        self.config.phys_mirror_wrap_count = cfg.mirror_wrap_count;
+    }
+
+    /// Clear the interaction matrix and restore default radii, keeping
+    /// physics settings, colors and particles.
+    pub fn reset_matrix(&mut self) {
+        let num_types = self.sim_config.num_types as usize;
+        self.interaction_matrix = InteractionMatrix::new(num_types);
+        self.radius_matrix = self.default_radius_matrix(num_types);
+    }
+
+    /// Restore the default palette and regenerate type colors.
+    pub fn reset_colors(&mut self) {
+        self.current_palette = PaletteType::Rainbow;
+        self.config.gen_palette = self.current_palette;
+        self.sim_config.palette_jitter = 0.0;
+        self.config.gen_palette_jitter = 0.0;
+        self.regenerate_colors();
     }
 
     /// Toggle simulation running state.
@@ -177,12 +485,65 @@ impl App {
         self.running = !self.running;
     }
 
+    /// Export the CPU particle copy as an SVG, optionally subsampled to
+    /// `max_particles`. Returns the number of circles written.
+    ///
+    /// Callers should read particles back from the GPU first.
+    pub fn export_svg(&self, path: &Path, max_particles: Option<usize>) -> Result<usize> {
+        crate::svg_export::export_svg(
+            path,
+            &self.particles,
+            &self.colors,
+            &self.sim_config,
+            max_particles,
+        )
+    }
+
     /// Get colors as RGBA f32 arrays for GPU.
     pub fn colors_as_rgba(&self) -> Vec<[f32; 4]> {
         // Color is already [f32; 4], just clone
         self.colors.clone()
     }
 
+    /// Default radius matrix for `num_types`: fixed pixel radii, or
+    /// fractions of the world size when radii are relative to the world.
+    pub fn default_radius_matrix(&self, num_types: usize) -> RadiusMatrix {
+        Self::default_radius_matrix_for(self.radii_relative_to_world, num_types, &self.sim_config)
+    }
+
+    fn default_radius_matrix_for(
+        relative: bool,
+        num_types: usize,
+        sim_config: &SimulationConfig,
+    ) -> RadiusMatrix {
+        if relative {
+            RadiusMatrix::default_for_world(num_types, sim_config.world_size)
+        } else {
+            RadiusMatrix::default_for_size(num_types)
+        }
+    }
+
+    /// Rescale radii and the spatial hash cell size with the shorter world
+    /// side after a resize from `old_size`, when radii are relative to the
+    /// world. The cell size never drops below the largest radius.
+    pub(crate) fn rescale_radii_for_world(&mut self, old_size: glam::Vec2) {
+        if !self.radii_relative_to_world {
+            return;
+        }
+        let old_side = old_size.min_element();
+        let new_side = self.sim_config.world_size.min_element();
+        if old_side <= 0.0 || new_side <= 0.0 {
+            return;
+        }
+        let factor = new_side / old_side;
+        self.radius_matrix.scale(factor);
+
+        let max_r = self.radius_matrix.max_interaction_radius();
+        self.sim_config.spatial_hash_cell_size =
+            (self.sim_config.spatial_hash_cell_size * factor).max(max_r);
+        self.config.render_spatial_hash_cell_size = self.sim_config.spatial_hash_cell_size;
+    }
+
     /// Scale min/max interaction radii so neighbor counts stay roughly constant.
     /// We target a fixed expected neighbor count per particle by adjusting radii
     /// based on density (density * pi * r^2).
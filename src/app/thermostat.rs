@@ -0,0 +1,51 @@
+//! Velocity-rescaling thermostat toward a target kinetic temperature.
+//!
+//! Temperature is the mean kinetic energy per particle (`0.5 * |v|^2`, unit
+//! mass). It is sampled from a blocking velocity readback every
+//! [`THERMOSTAT_SAMPLE_SECS`]; in between, every simulation step multiplies
+//! all velocities by the same factor, spread so that one sample interval
+//! closes [`THERMOSTAT_RATE`] of the gap to the target (Berendsen-style).
+//! Closing only part of the gap per sample keeps the lagging measurement
+//! from overshooting into oscillation.
+
+/// Seconds between temperature samples.
+pub const THERMOSTAT_SAMPLE_SECS: f32 = 0.5;
+
+/// Fraction of the temperature gap closed per sample interval.
+pub const THERMOSTAT_RATE: f32 = 0.5;
+
+/// Per-step velocity multiplier that moves `current` toward `target` over
+/// `steps` simulation steps.
+///
+/// Returns 1.0 for a scene at rest (there is nothing to scale) or a
+/// non-positive target.
+pub fn thermostat_scale(current: f32, target: f32, steps: f32) -> f32 {
+    if current <= f32::EPSILON || target <= 0.0 || !current.is_finite() {
+        return 1.0;
+    }
+    // Temperature ratio wanted after one sample interval
+    let ratio = 1.0 + THERMOSTAT_RATE * (target / current - 1.0);
+    // Velocities scale with the square root of temperature
+    ratio.sqrt().powf(1.0 / steps.max(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thermostat_converges_without_overshoot() {
+        for (start, target) in [(400.0f32, 100.0f32), (25.0, 100.0)] {
+            let mut temperature = start;
+            let steps = 30.0;
+            for _ in 0..20 {
+                let scale = thermostat_scale(temperature, target, steps);
+                temperature *= scale.powf(2.0 * steps);
+                // Always approaches from the starting side
+                assert!((temperature - target) * (start - target) >= -1e-3);
+            }
+            assert!((temperature - target).abs() < 0.01 * target);
+        }
+        assert_eq!(thermostat_scale(0.0, 100.0, 30.0), 1.0);
+    }
+}
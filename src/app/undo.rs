@@ -0,0 +1,164 @@
+//! Bounded undo/redo history for matrix and generator edits.
+//!
+//! Each entry is an [`ExplorationState`] taken just before a change, so
+//! undoing restores the matrices, colors and generator selections exactly.
+//! Rapid edits to the same target (a cell drag, a slider) within
+//! [`COALESCE_WINDOW`] of each other share one entry.
+
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use super::ExplorationState;
+
+/// Default number of undo steps kept.
+pub const DEFAULT_UNDO_CAPACITY: usize = 32;
+
+/// Edits to the same target closer together than this merge into one entry.
+pub const COALESCE_WINDOW: Duration = Duration::from_millis(750);
+
+/// Undo and redo stacks of generator state snapshots.
+#[derive(Debug, Clone)]
+pub struct UndoHistory {
+    undo: VecDeque<ExplorationState>,
+    redo: Vec<ExplorationState>,
+    capacity: usize,
+    /// Target and time of the last coalescable edit.
+    last_edit: Option<(&'static str, Instant)>,
+}
+
+impl UndoHistory {
+    /// Create an empty history holding at most `capacity` undo steps.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            capacity: capacity.max(1),
+            last_edit: None,
+        }
+    }
+
+    /// Record the state from before a discrete change, discarding the redo
+    /// stack. The oldest entry is dropped when full.
+    pub fn push(&mut self, before: ExplorationState) {
+        self.last_edit = None;
+        self.push_entry(before);
+    }
+
+    /// Record the state from before an edit to `target`, merging it with
+    /// the previous entry if that edited the same target less than
+    /// [`COALESCE_WINDOW`] ago.
+    pub fn push_edit(&mut self, target: &'static str, now: Instant, before: ExplorationState) {
+        let coalesce = matches!(
+            self.last_edit,
+            Some((last, at)) if last == target && now.duration_since(at) < COALESCE_WINDOW
+        );
+        if !coalesce {
+            self.push_entry(before);
+        }
+        self.last_edit = Some((target, now));
+    }
+
+    fn push_entry(&mut self, before: ExplorationState) {
+        self.redo.clear();
+        self.undo.push_back(before);
+        if self.undo.len() > self.capacity {
+            self.undo.pop_front();
+        }
+    }
+
+    /// Step back: returns the state to restore and keeps `current` for redo.
+    pub fn undo(&mut self, current: ExplorationState) -> Option<ExplorationState> {
+        let state = self.undo.pop_back()?;
+        self.redo.push(current);
+        self.last_edit = None;
+        Some(state)
+    }
+
+    /// Step forward again: returns the state to restore and keeps `current`
+    /// for undo.
+    pub fn redo(&mut self, current: ExplorationState) -> Option<ExplorationState> {
+        let state = self.redo.pop()?;
+        self.undo.push_back(current);
+        self.last_edit = None;
+        Some(state)
+    }
+
+    /// Whether there is a change to undo.
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    /// Whether there is an undone change to redo.
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+impl Default for UndoHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_UNDO_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::lineage::matrix_hash;
+    use crate::generators::{colors::PaletteType, positions::PositionPattern, rules::RuleType};
+    use crate::simulation::{InteractionMatrix, RadiusMatrix};
+
+    fn state(value: f32) -> ExplorationState {
+        let matrix = InteractionMatrix::filled(2, value);
+        ExplorationState {
+            rule: RuleType::Random,
+            palette: PaletteType::Rainbow,
+            pattern: PositionPattern::Disk,
+            num_types: 2,
+            matrix_hash: matrix_hash(&matrix),
+            interaction_matrix: matrix,
+            radius_matrix: RadiusMatrix::default_for_size(2),
+            colors: Vec::new(),
+        }
+    }
+
+    fn value(state: &ExplorationState) -> f32 {
+        state.interaction_matrix.get(0, 0)
+    }
+
+    #[test]
+    fn test_undo_redo_round_trip() {
+        let mut history = UndoHistory::new(2);
+        for v in [0.1, 0.2, 0.3] {
+            history.push(state(v));
+        }
+
+        // Oldest dropped at capacity; current state 0.4 goes to redo
+        assert_eq!(value(&history.undo(state(0.4)).unwrap()), 0.3);
+        assert_eq!(value(&history.undo(state(0.3)).unwrap()), 0.2);
+        assert!(history.undo(state(0.2)).is_none());
+        assert_eq!(value(&history.redo(state(0.2)).unwrap()), 0.3);
+
+        // A new change discards what is left to redo
+        history.push(state(0.3));
+        assert!(!history.can_redo());
+        assert!(history.can_undo());
+    }
+
+    #[test]
+    fn test_rapid_edits_coalesce() {
+        let mut history = UndoHistory::default();
+        let start = Instant::now();
+        history.push_edit("matrix", start, state(0.1));
+        history.push_edit("matrix", start + Duration::from_millis(500), state(0.2));
+        history.push_edit("matrix", start + Duration::from_millis(1000), state(0.3));
+        // A different target starts a new entry
+        history.push_edit("radius", start + Duration::from_millis(1100), state(0.4));
+        // So does a pause longer than the window
+        history.push_edit("radius", start + Duration::from_secs(3), state(0.5));
+
+        assert_eq!(value(&history.undo(state(0.6)).unwrap()), 0.5);
+        assert_eq!(value(&history.undo(state(0.5)).unwrap()), 0.4);
+        assert_eq!(value(&history.undo(state(0.4)).unwrap()), 0.1);
+        assert!(!history.can_undo());
+    }
+}
@@ -3,10 +3,12 @@
 //! This module provides 37 different color palette generators,
 //! from simple rainbow gradients to complex procedural palettes.
 
-use rand::Rng;
+use rand::{Rng, RngCore, SeedableRng, rngs::StdRng};
 use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 
+use crate::utils::color::rgb_to_hsv;
+
 /// A color in RGBA format with f32 components [0.0, 1.0].
 pub type Color = [f32; 4];
 
@@ -190,12 +192,22 @@ impl ColorPalette for PaletteType {
 
 /// Generate colors using the specified palette type.
 pub fn generate_colors(palette: PaletteType, num_types: usize) -> Vec<Color> {
+    generate_colors_with_rng(palette, num_types, &mut rand::rng())
+}
+
+/// Generate colors using the specified palette type, drawing any jitter
+/// from `rng`.
+pub fn generate_colors_with_rng(
+    palette: PaletteType,
+    num_types: usize,
+    rng: &mut dyn RngCore,
+) -> Vec<Color> {
     if num_types == 0 {
         return Vec::new();
     }
 
-    match palette {
-        PaletteType::Random => random_generator(num_types),
+    let mut colors = match palette {
+        PaletteType::Random => random_generator(num_types, rng),
         PaletteType::Rainbow => rainbow_generator(num_types),
         PaletteType::NeonWarm => neon_warm_generator(num_types),
         PaletteType::HeatmapClassic => gradient_palette(num_types, &HEATMAP_CLASSIC),
@@ -210,30 +222,108 @@ pub fn generate_colors(palette: PaletteType, num_types: usize) -> Vec<Color> {
         PaletteType::VioletFade => violet_fade_generator(num_types),
         PaletteType::Grayscale => gradient_palette(num_types, &GRAYSCALE),
         PaletteType::DesertWarm => gradient_palette(num_types, &DESERT_WARM),
-        PaletteType::DualGradient => dual_gradient_generator(num_types),
-        PaletteType::Candy => candy_generator(num_types),
-        PaletteType::OrganicFlow => organic_flow_generator(num_types),
-        PaletteType::EarthFlow => earth_flow_generator(num_types),
-        PaletteType::GameBoyDMG => gameboy_dmg_generator(num_types),
-        PaletteType::PaperAndInk => paper_ink_generator(num_types),
-        PaletteType::FluoroSport => fluoro_sport_generator(num_types),
-        PaletteType::MidnightCircuit => midnight_circuit_generator(num_types),
-        PaletteType::BioluminescentAbyss => biolum_abyss_generator(num_types),
-        PaletteType::Blueprint => blueprint_generator(num_types),
-        PaletteType::CyberDark => cyber_dark_generator(num_types),
+        PaletteType::DualGradient => dual_gradient_generator(num_types, rng),
+        PaletteType::Candy => candy_generator(num_types, rng),
+        PaletteType::OrganicFlow => organic_flow_generator(num_types, rng),
+        PaletteType::EarthFlow => earth_flow_generator(num_types, rng),
+        PaletteType::GameBoyDMG => gameboy_dmg_generator(num_types, rng),
+        PaletteType::PaperAndInk => paper_ink_generator(num_types, rng),
+        PaletteType::FluoroSport => fluoro_sport_generator(num_types, rng),
+        PaletteType::MidnightCircuit => midnight_circuit_generator(num_types, rng),
+        PaletteType::BioluminescentAbyss => biolum_abyss_generator(num_types, rng),
+        PaletteType::Blueprint => blueprint_generator(num_types, rng),
+        PaletteType::CyberDark => cyber_dark_generator(num_types, rng),
         PaletteType::HolographicFoil | PaletteType::HolographicFoil2 => {
-            holo_foil_generator(num_types)
+            holo_foil_generator(num_types, rng)
         }
-        PaletteType::MineralGemstones => gemstones_generator(num_types),
-        PaletteType::VaporwavePastel => vaporwave_pastel_generator(num_types),
-        PaletteType::SolarizedDrift => solarized_drift_generator(num_types),
-        PaletteType::Aurora => aurora_generator(num_types),
-        PaletteType::CyberNeon => cyber_neon_generator(num_types),
-        PaletteType::GoldenAngleJitter => golden_angle_jitter_generator(num_types),
-        PaletteType::CMYKMisregister => cmyk_misregister_generator(num_types),
-        PaletteType::AnodizedMetal => anodized_metal_generator(num_types),
-        PaletteType::InkBleedWatercolor => ink_bleed_watercolor_generator(num_types),
+        PaletteType::MineralGemstones => gemstones_generator(num_types, rng),
+        PaletteType::VaporwavePastel => vaporwave_pastel_generator(num_types, rng),
+        PaletteType::SolarizedDrift => solarized_drift_generator(num_types, rng),
+        PaletteType::Aurora => aurora_generator(num_types, rng),
+        PaletteType::CyberNeon => cyber_neon_generator(num_types, rng),
+        PaletteType::GoldenAngleJitter => golden_angle_jitter_generator(num_types, rng),
+        PaletteType::CMYKMisregister => cmyk_misregister_generator(num_types, rng),
+        PaletteType::AnodizedMetal => anodized_metal_generator(num_types, rng),
+        PaletteType::InkBleedWatercolor => ink_bleed_watercolor_generator(num_types, rng),
+    };
+    if num_types > SEPARATE_COLORS_ABOVE {
+        separate_similar_colors(&mut colors);
+    }
+    colors
+}
+
+/// Palettes are used exactly as designed up to this many types. Several
+/// cycle a handful of base colors, which repeat once there are more types.
+const SEPARATE_COLORS_ABOVE: usize = 16;
+/// Smallest RGB distance kept between colors when separating them.
+const MIN_COLOR_DISTANCE: f32 = 0.08;
+/// Candidate shifts tried per color when separating.
+const SEPARATE_ATTEMPTS: usize = 24;
+
+/// Move each color that nearly matches an earlier one to the nearby
+/// variant (hue stepped by the golden angle, value nudged) farthest from
+/// all earlier colors. Deterministic, so a seed still fixes the palette.
+fn separate_similar_colors(colors: &mut [Color]) {
+    let min_distance = |c: [f32; 3], others: &[Color]| {
+        others
+            .iter()
+            .map(|o| ((c[0] - o[0]).powi(2) + (c[1] - o[1]).powi(2) + (c[2] - o[2]).powi(2)).sqrt())
+            .fold(f32::MAX, f32::min)
+    };
+    for i in 1..colors.len() {
+        let (earlier, rest) = colors.split_at_mut(i);
+        let color = &mut rest[0];
+        let rgb = [color[0], color[1], color[2]];
+        if min_distance(rgb, earlier) >= MIN_COLOR_DISTANCE {
+            continue;
+        }
+        let [h, s, v] = rgb_to_hsv(rgb[0], rgb[1], rgb[2]);
+        let mut best = (min_distance(rgb, earlier), rgb);
+        for k in 1..=SEPARATE_ATTEMPTS {
+            let hue = (h + k as f32 * 137.508).rem_euclid(360.0);
+            // Alternate brighter/darker so gray palettes spread too
+            let step = 0.12 * k.div_ceil(2) as f32;
+            let value = if k % 2 == 1 { v + step } else { v - step };
+            let candidate = hsv_to_rgb(hue, s.max(0.35), clamp(value, 0.2, 1.0));
+            let distance = min_distance(candidate, earlier);
+            if distance > best.0 {
+                best = (distance, candidate);
+            }
+            if distance >= MIN_COLOR_DISTANCE {
+                break;
+            }
+        }
+        color[..3].copy_from_slice(&best.1);
+    }
+}
+
+/// Largest hue shift in degrees at full jitter.
+const MAX_JITTER_HUE: f32 = 30.0;
+/// Largest value (brightness) shift at full jitter.
+const MAX_JITTER_VALUE: f32 = 0.25;
+
+/// Perturb each color's hue and value by a bounded random amount to pull
+/// similar neighboring colors apart.
+///
+/// `amount` (0-1) scales the shift up to ±30° of hue and ±0.25 of value.
+/// Offsets are drawn from `seed`, so the same seed gives the same direction
+/// per type at every amount; an amount of 0 returns `colors` unchanged.
+pub fn jitter_colors(colors: &[Color], amount: f32, seed: u64) -> Vec<Color> {
+    if amount <= 0.0 {
+        return colors.to_vec();
     }
+    let amount = amount.min(1.0);
+    let mut rng = StdRng::seed_from_u64(seed);
+    colors
+        .iter()
+        .map(|c| {
+            let dh = rng.random_range(-1.0..=1.0) * amount * MAX_JITTER_HUE;
+            let dv = rng.random_range(-1.0..=1.0) * amount * MAX_JITTER_VALUE;
+            let [h, s, v] = rgb_to_hsv(c[0], c[1], c[2]);
+            let rgb = hsv_to_rgb((h + dh).rem_euclid(360.0), s, clamp(v + dv, 0.0, 1.0));
+            [rgb[0], rgb[1], rgb[2], c[3]]
+        })
+        .collect()
 }
 
 // === Helper Functions ===
@@ -572,8 +662,7 @@ const DESERT_WARM: [KeyColor; 5] = [
 
 // === Generator Implementations ===
 
-fn random_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn random_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     (0..n)
         .map(|_| [rng.random(), rng.random(), rng.random(), 1.0])
         .collect()
@@ -667,8 +756,7 @@ fn crimson_flame_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn dual_gradient_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn dual_gradient_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let start_h: f32 = rng.random::<f32>() * 360.0;
     let mut end_h: f32 = rng.random::<f32>() * 360.0;
 
@@ -702,8 +790,7 @@ fn dual_gradient_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn candy_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn candy_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let phi = 137.507_77_f32;
     let base_h: f32 = rng.random::<f32>() * 360.0;
 
@@ -719,8 +806,7 @@ fn candy_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn organic_flow_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn organic_flow_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let base_h: f32 = rng.random::<f32>() * 15.0;
 
     (0..n)
@@ -740,8 +826,7 @@ fn organic_flow_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn earth_flow_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn earth_flow_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let h_a: f32 = rng.random::<f32>() * 20.0 + 10.0;
     let h_b = (h_a + rng.random::<f32>() * 80.0 + 140.0) % 360.0;
     let phase = rng.random::<f32>() * PI;
@@ -769,8 +854,7 @@ fn earth_flow_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn gameboy_dmg_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn gameboy_dmg_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let steps = [0.2f32, 0.35, 0.55, 0.78];
     let hue: f32 = rng.random::<f32>() * 20.0 + 90.0;
 
@@ -784,8 +868,7 @@ fn gameboy_dmg_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn paper_ink_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn paper_ink_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let inks = [210.0f32, 30.0, 220.0];
 
     (0..n)
@@ -809,8 +892,7 @@ fn paper_ink_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn fluoro_sport_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn fluoro_sport_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let accents = [95.0f32, 175.0, 310.0];
 
     (0..n)
@@ -829,8 +911,7 @@ fn fluoro_sport_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn midnight_circuit_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn midnight_circuit_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let accent_h: f32 = rng.random::<f32>() * 340.0 + 10.0;
     let accent_period = (n / 3).max(3);
 
@@ -849,8 +930,7 @@ fn midnight_circuit_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn biolum_abyss_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn biolum_abyss_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let accent_count = (n / 4).clamp(1, 2);
 
     (0..n)
@@ -869,8 +949,7 @@ fn biolum_abyss_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn blueprint_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn blueprint_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let accent_count = (n / 5).clamp(1, 2);
 
     (0..n)
@@ -889,8 +968,7 @@ fn blueprint_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn cyber_dark_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn cyber_dark_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let accent_h: f32 = rng.random::<f32>() * 340.0 + 10.0;
     let accent_period = (n / 3).max(3);
 
@@ -908,8 +986,7 @@ fn cyber_dark_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn holo_foil_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn holo_foil_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let k1: f32 = rng.random::<f32>() * 0.6 + 0.8;
     let k2: f32 = rng.random::<f32>() * 1.4 + 2.2;
 
@@ -941,8 +1018,7 @@ fn holo_foil_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn gemstones_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn gemstones_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let hues = [140.0f32, 350.0, 220.0, 45.0, 200.0, 300.0];
 
     (0..n)
@@ -956,8 +1032,7 @@ fn gemstones_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn vaporwave_pastel_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn vaporwave_pastel_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let anchors = [320.0f32, 260.0, 170.0];
 
     (0..n)
@@ -972,8 +1047,7 @@ fn vaporwave_pastel_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn solarized_drift_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn solarized_drift_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let anchors = [
         (44.0f32, 0.55, 0.92),
         (44.0, 0.25, 0.60),
@@ -997,8 +1071,7 @@ fn solarized_drift_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn aurora_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn aurora_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let center: f32 = rng.random::<f32>() * 100.0 + 120.0;
 
     (0..n)
@@ -1014,8 +1087,7 @@ fn aurora_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn cyber_neon_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn cyber_neon_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let base_h: f32 = rng.random::<f32>() * 60.0 + 280.0;
 
     (0..n)
@@ -1031,8 +1103,7 @@ fn cyber_neon_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn golden_angle_jitter_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn golden_angle_jitter_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let phi = 137.507_77_f32;
     let base_h: f32 = rng.random::<f32>() * 360.0;
     let s_base: f32 = rng.random::<f32>() * 0.35 + 0.6;
@@ -1051,8 +1122,7 @@ fn golden_angle_jitter_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn cmyk_misregister_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn cmyk_misregister_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let inks = [200.0f32, 300.0, 55.0, 220.0];
 
     (0..n)
@@ -1067,8 +1137,7 @@ fn cmyk_misregister_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn anodized_metal_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn anodized_metal_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let hue0: f32 = rng.random::<f32>() * 140.0 + 180.0;
 
     (0..n)
@@ -1096,8 +1165,7 @@ fn anodized_metal_generator(n: usize) -> Vec<Color> {
         .collect()
 }
 
-fn ink_bleed_watercolor_generator(n: usize) -> Vec<Color> {
-    let mut rng = rand::rng();
+fn ink_bleed_watercolor_generator(n: usize, rng: &mut dyn RngCore) -> Vec<Color> {
     let center: f32 = rng.random::<f32>() * 70.0 + 190.0;
 
     (0..n)
@@ -1139,6 +1207,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_palettes_stay_distinct_at_max_types() {
+        let n = crate::simulation::MAX_NUM_TYPES as usize;
+        for palette in PaletteType::all() {
+            let mut rng = StdRng::seed_from_u64(1);
+            let colors = generate_colors_with_rng(*palette, n, &mut rng);
+            assert_eq!(colors.len(), n);
+            for i in 0..n {
+                for j in 0..i {
+                    let d = (0..3)
+                        .map(|k| (colors[i][k] - colors[j][k]).powi(2))
+                        .sum::<f32>()
+                        .sqrt();
+                    assert!(d > 0.05, "{:?}: types {j} and {i} look alike", palette);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_rainbow_hue_distribution() {
         let colors = rainbow_generator(6);
@@ -1164,4 +1251,18 @@ mod tests {
         assert!((green[1] - 1.0).abs() < 0.01);
         assert!(green[2] < 0.01);
     }
+
+    #[test]
+    fn test_jitter_colors() {
+        let base = generate_colors(PaletteType::Rainbow, 12);
+        assert_eq!(jitter_colors(&base, 0.0, 7), base);
+
+        let jittered = jitter_colors(&base, 0.5, 7);
+        assert_eq!(jittered, jitter_colors(&base, 0.5, 7));
+        assert_ne!(jittered, base);
+        for (j, b) in jittered.iter().zip(&base) {
+            assert!(j[..3].iter().all(|c| (0.0..=1.0).contains(c)));
+            assert_eq!(j[3], b[3]);
+        }
+    }
 }
@@ -0,0 +1,148 @@
+//! Seeding particles from an image.
+//!
+//! The image is fitted into the world with its aspect ratio kept (centered,
+//! letterboxed along the longer world axis). Each particle lands on a pixel
+//! picked with probability proportional to its luminance, so dark areas get
+//! few particles and black gets none, and takes the type whose palette color
+//! is nearest to that pixel's color.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use image::RgbImage;
+use image::imageops::FilterType;
+use rand::{Rng, RngCore};
+
+use super::colors::Color;
+use super::positions::SpawnConfig;
+use crate::simulation::Particle;
+
+/// Images are downscaled so their longer side is at most this many pixels
+/// before sampling; plenty for particle counts and keeps the weight table small.
+pub const MAX_IMAGE_SEED_SIDE: u32 = 512;
+
+/// Load the image at `path`, downscaled to at most [`MAX_IMAGE_SEED_SIDE`].
+pub fn load_seed_image(path: impl AsRef<Path>) -> Result<RgbImage> {
+    let path = path.as_ref();
+    let image =
+        image::open(path).with_context(|| format!("Failed to open image {}", path.display()))?;
+    let image = if image.width().max(image.height()) > MAX_IMAGE_SEED_SIDE {
+        image.resize(
+            MAX_IMAGE_SEED_SIDE,
+            MAX_IMAGE_SEED_SIDE,
+            FilterType::Triangle,
+        )
+    } else {
+        image
+    };
+    Ok(image.to_rgb8())
+}
+
+/// Index of the palette color nearest to `rgb` (components in 0..=1).
+pub fn nearest_palette_type(rgb: [f32; 3], palette: &[Color]) -> u32 {
+    palette
+        .iter()
+        .enumerate()
+        .map(|(i, c)| {
+            let d = (0..3).map(|k| (c[k] - rgb[k]).powi(2)).sum::<f32>();
+            (i, d)
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(i, _)| i as u32)
+}
+
+/// Spawn `config.num_particles` particles distributed over `image`.
+///
+/// Types are quantized to the first `config.num_types` entries of `palette`.
+/// An all-black image falls back to sampling every pixel evenly.
+pub fn image_positions(
+    image: &RgbImage,
+    palette: &[Color],
+    config: &SpawnConfig,
+    rng: &mut dyn RngCore,
+) -> Vec<Particle> {
+    let (img_w, img_h) = image.dimensions();
+    if img_w == 0 || img_h == 0 || config.num_particles == 0 || config.num_types == 0 {
+        return Vec::new();
+    }
+    let palette = &palette[..config.num_types.min(palette.len())];
+
+    // Cumulative luminance weights for picking pixels
+    let mut cumulative = Vec::with_capacity((img_w * img_h) as usize);
+    let mut total = 0.0f32;
+    for pixel in image.pixels() {
+        let [r, g, b] = pixel.0.map(|c| f32::from(c) / 255.0);
+        total += 0.2126 * r + 0.7152 * g + 0.0722 * b;
+        cumulative.push(total);
+    }
+    let uniform = total <= 0.0;
+
+    // Fit the image inside the world, centered, keeping its aspect
+    let scale = (config.width / img_w as f32).min(config.height / img_h as f32);
+    let origin_x = (config.width - img_w as f32 * scale) * 0.5;
+    let origin_y = (config.height - img_h as f32 * scale) * 0.5;
+
+    let mut particles = Vec::with_capacity(config.num_particles);
+    for _ in 0..config.num_particles {
+        let index = if uniform {
+            rng.random_range(0..cumulative.len())
+        } else {
+            let target = rng.random::<f32>() * total;
+            cumulative
+                .partition_point(|&c| c <= target)
+                .min(cumulative.len() - 1)
+        };
+        let px = index as u32 % img_w;
+        let py = index as u32 / img_w;
+
+        let rgb = image.get_pixel(px, py).0.map(|c| f32::from(c) / 255.0);
+        let particle_type = if palette.is_empty() {
+            0
+        } else {
+            nearest_palette_type(rgb, palette)
+        };
+
+        let x = origin_x + (px as f32 + rng.random::<f32>()) * scale;
+        let y = origin_y + (py as f32 + rng.random::<f32>()) * scale;
+        particles.push(Particle::new(x, y, particle_type));
+    }
+    config
+        .velocity
+        .apply(&mut particles, config.width, config.height, rng);
+    particles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generators::positions::{SpawnSampling, SpawnVelocity};
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_image_positions_follow_brightness_and_color() {
+        // 2x1 image: dark left pixel, bright red right pixel
+        let mut image = RgbImage::new(2, 1);
+        image.put_pixel(0, 0, image::Rgb([0, 0, 0]));
+        image.put_pixel(1, 0, image::Rgb([255, 0, 0]));
+        let palette: Vec<Color> = vec![[0.0, 0.0, 1.0, 1.0], [1.0, 0.1, 0.0, 1.0]];
+        let config = SpawnConfig {
+            num_particles: 200,
+            num_types: 2,
+            width: 400.0,
+            height: 400.0,
+            velocity: SpawnVelocity::default(),
+            sampling: SpawnSampling::default(),
+            type_weights: Vec::new(),
+        };
+        let mut rng = rand::rngs::StdRng::seed_from_u64(3);
+
+        let particles = image_positions(&image, &palette, &config, &mut rng);
+        assert_eq!(particles.len(), 200);
+        for p in &particles {
+            // Black gets nothing; the image is letterboxed into the middle band
+            assert!(p.x >= 200.0 && p.x <= 400.0, "x = {}", p.x);
+            assert!(p.y >= 100.0 && p.y <= 300.0, "y = {}", p.y);
+            assert_eq!(p.particle_type, 1);
+        }
+    }
+}
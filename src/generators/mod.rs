@@ -1,6 +1,7 @@
 //! Procedural generators for rules, colors, and positions.
 
 pub mod colors;
+pub mod image_seed;
 pub mod positions;
 pub mod rules;
 
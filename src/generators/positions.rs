@@ -3,7 +3,7 @@
 //! This module provides 27 different spawn patterns for particles,
 //! from simple random distributions to complex geometric arrangements.
 
-use rand::Rng;
+use rand::{Rng, RngCore};
 use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 
@@ -16,6 +16,164 @@ pub struct SpawnConfig {
     pub num_types: usize,
     pub width: f32,
     pub height: f32,
+    /// Initial velocity field applied after positions are generated.
+    pub velocity: SpawnVelocity,
+    /// How the Random, Disk and Stripes patterns draw coordinates.
+    pub sampling: SpawnSampling,
+    /// Relative share of particles per type (normalized internally). Empty
+    /// means uniform; missing entries count as 1.0.
+    pub type_weights: Vec<f32>,
+}
+
+/// Source of the coordinates drawn by sampling-based spawn patterns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum SpawnSampling {
+    /// Independent uniform random numbers (can look clumpy).
+    #[default]
+    Uniform,
+
+    /// Halton (2, 3) low-discrepancy sequence: even, blue-noise-like
+    /// coverage that is deterministic by construction.
+    LowDiscrepancy,
+}
+
+impl SpawnSampling {
+    /// Get all available sampling modes.
+    pub fn all() -> &'static [SpawnSampling] {
+        &[SpawnSampling::Uniform, SpawnSampling::LowDiscrepancy]
+    }
+
+    /// Get the display name for this sampling mode.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SpawnSampling::Uniform => "Uniform Random",
+            SpawnSampling::LowDiscrepancy => "Low Discrepancy (Halton)",
+        }
+    }
+}
+
+/// Radical inverse of `index` in `base`: the `index`-th element of the
+/// one-dimensional Halton sequence, in [0, 1).
+pub fn halton(mut index: u32, base: u32) -> f32 {
+    let mut result = 0.0f64;
+    let mut fraction = 1.0f64;
+    let inv_base = 1.0 / f64::from(base);
+    while index > 0 {
+        fraction *= inv_base;
+        result += fraction * f64::from(index % base);
+        index /= base;
+    }
+    result as f32
+}
+
+/// Draws 2D points in the unit square for a [`SpawnSampling`] mode.
+struct PointSampler<'a> {
+    rng: &'a mut dyn RngCore,
+    /// Next Halton index, or `None` for uniform sampling.
+    halton_index: Option<u32>,
+}
+
+impl<'a> PointSampler<'a> {
+    fn new(sampling: SpawnSampling, rng: &'a mut dyn RngCore) -> Self {
+        Self {
+            rng,
+            // Index 0 is the origin; start at 1
+            halton_index: (sampling == SpawnSampling::LowDiscrepancy).then_some(1),
+        }
+    }
+
+    /// Next point in [0, 1) x [0, 1).
+    fn next(&mut self) -> (f32, f32) {
+        match &mut self.halton_index {
+            Some(index) => {
+                let point = (halton(*index, 2), halton(*index, 3));
+                *index = index.wrapping_add(1);
+                point
+            }
+            None => (self.rng.random::<f32>(), self.rng.random::<f32>()),
+        }
+    }
+}
+
+/// Initial velocity field for freshly spawned particles.
+///
+/// Velocities are set from each particle's position relative to the world
+/// center, so the same pattern can start at rest, spinning, or exploding.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum SpawnVelocity {
+    /// Particles start at rest.
+    #[default]
+    Zero,
+    /// Rigid rotation around the world center with angular velocity `omega`
+    /// (radians per second, positive = counter-clockwise on screen).
+    Rotational(f32),
+    /// Radial speed away from the world center (negative = collapsing).
+    Radial(f32),
+    /// Random direction with speed up to the given maximum.
+    Random(f32),
+}
+
+impl SpawnVelocity {
+    /// One of each variant with a sensible default magnitude.
+    pub fn all() -> &'static [SpawnVelocity] {
+        &[
+            SpawnVelocity::Zero,
+            SpawnVelocity::Rotational(0.3),
+            SpawnVelocity::Radial(100.0),
+            SpawnVelocity::Random(100.0),
+        ]
+    }
+
+    /// Get the display name for this velocity field.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            SpawnVelocity::Zero => "Zero",
+            SpawnVelocity::Rotational(_) => "Rotational",
+            SpawnVelocity::Radial(_) => "Radial",
+            SpawnVelocity::Random(_) => "Random",
+        }
+    }
+
+    /// Whether `other` is the same variant, ignoring magnitude.
+    pub fn same_kind(&self, other: &SpawnVelocity) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// Set particle velocities for this field around the center of a
+    /// `width` x `height` world, drawing random directions from `rng`.
+    pub fn apply(
+        &self,
+        particles: &mut [Particle],
+        width: f32,
+        height: f32,
+        rng: &mut dyn RngCore,
+    ) {
+        let cx = width / 2.0;
+        let cy = height / 2.0;
+        for p in particles {
+            let dx = p.x - cx;
+            let dy = p.y - cy;
+            let (vx, vy) = match *self {
+                SpawnVelocity::Zero => (0.0, 0.0),
+                SpawnVelocity::Rotational(omega) => (-dy * omega, dx * omega),
+                SpawnVelocity::Radial(speed) => {
+                    let len = (dx * dx + dy * dy).sqrt();
+                    if len > 0.0 {
+                        (dx / len * speed, dy / len * speed)
+                    } else {
+                        (0.0, 0.0)
+                    }
+                }
+                SpawnVelocity::Random(max) => {
+                    let angle = rng.random::<f32>() * TAU;
+                    let speed = rng.random::<f32>() * max;
+                    (angle.cos() * speed, angle.sin() * speed)
+                }
+            };
+            p.vx = vx;
+            p.vy = vy;
+        }
+    }
 }
 
 /// Types of position patterns available.
@@ -169,40 +327,54 @@ impl PositionPattern {
 
 /// Generate particles using the specified pattern.
 pub fn generate_positions(pattern: PositionPattern, config: &SpawnConfig) -> Vec<Particle> {
+    generate_positions_with_rng(pattern, config, &mut rand::rng())
+}
+
+/// Generate particles using the specified pattern, drawing all randomness
+/// from `rng` so a seeded generator reproduces the same layout.
+pub fn generate_positions_with_rng(
+    pattern: PositionPattern,
+    config: &SpawnConfig,
+    rng: &mut dyn RngCore,
+) -> Vec<Particle> {
     if config.num_particles == 0 || config.num_types == 0 {
         return Vec::new();
     }
 
-    match pattern {
-        PositionPattern::Random => random_generator(config),
-        PositionPattern::Disk => disk_generator(config),
-        PositionPattern::Ring => ring_generator(config),
-        PositionPattern::Rings => rings_generator(config),
-        PositionPattern::Spiral => spiral_generator(config),
-        PositionPattern::Line => line_generator(config),
-        PositionPattern::RainbowDisk => rainbow_disk_generator(config),
-        PositionPattern::RainbowRing => rainbow_ring_generator(config),
-        PositionPattern::RainbowRings => rainbow_rings_generator(config),
-        PositionPattern::RainbowSpiral => rainbow_spiral_generator(config),
-        PositionPattern::RainbowLine => rainbow_line_generator(config),
-        PositionPattern::Stripes => stripes_generator(config),
+    let mut particles = match pattern {
+        PositionPattern::Random => random_generator(config, rng),
+        PositionPattern::Disk => disk_generator(config, rng),
+        PositionPattern::Ring => ring_generator(config, rng),
+        PositionPattern::Rings => rings_generator(config, rng),
+        PositionPattern::Spiral => spiral_generator(config, rng),
+        PositionPattern::Line => line_generator(config, rng),
+        PositionPattern::RainbowDisk => rainbow_disk_generator(config, rng),
+        PositionPattern::RainbowRing => rainbow_ring_generator(config, rng),
+        PositionPattern::RainbowRings => rainbow_rings_generator(config, rng),
+        PositionPattern::RainbowSpiral => rainbow_spiral_generator(config, rng),
+        PositionPattern::RainbowLine => rainbow_line_generator(config, rng),
+        PositionPattern::Stripes => stripes_generator(config, rng),
         PositionPattern::Border => border_generator(config),
         PositionPattern::Grid => grid_generator(config),
-        PositionPattern::WavyBands => wavy_bands_generator(config),
-        PositionPattern::SimpleFlower => simple_flower_generator(config),
-        PositionPattern::ChromaticFlower => chromatic_flower_generator(config),
-        PositionPattern::YinYang => yin_yang_generator(config),
-        PositionPattern::TwinCrescents => twin_crescents_generator(config),
-        PositionPattern::TwinSpirals => twin_spirals_generator(config),
-        PositionPattern::SpiralArms => spiral_arms_generator(config),
-        PositionPattern::PolarMaze => polar_maze_generator(config),
-        PositionPattern::ChaoticBands => chaotic_bands_generator(config),
-        PositionPattern::RadiantFans => radiant_fans_generator(config),
-        PositionPattern::SoftClusters => soft_clusters_generator(config),
-        PositionPattern::LinkedClusters => linked_clusters_generator(config),
-        PositionPattern::OrbitalBelts => orbital_belts_generator(config),
-        PositionPattern::BraidedBelts => braided_belts_generator(config),
-    }
+        PositionPattern::WavyBands => wavy_bands_generator(config, rng),
+        PositionPattern::SimpleFlower => simple_flower_generator(config, rng),
+        PositionPattern::ChromaticFlower => chromatic_flower_generator(config, rng),
+        PositionPattern::YinYang => yin_yang_generator(config, rng),
+        PositionPattern::TwinCrescents => twin_crescents_generator(config, rng),
+        PositionPattern::TwinSpirals => twin_spirals_generator(config, rng),
+        PositionPattern::SpiralArms => spiral_arms_generator(config, rng),
+        PositionPattern::PolarMaze => polar_maze_generator(config, rng),
+        PositionPattern::ChaoticBands => chaotic_bands_generator(config, rng),
+        PositionPattern::RadiantFans => radiant_fans_generator(config, rng),
+        PositionPattern::SoftClusters => soft_clusters_generator(config, rng),
+        PositionPattern::LinkedClusters => linked_clusters_generator(config, rng),
+        PositionPattern::OrbitalBelts => orbital_belts_generator(config, rng),
+        PositionPattern::BraidedBelts => braided_belts_generator(config, rng),
+    };
+    config
+        .velocity
+        .apply(&mut particles, config.width, config.height, rng);
+    particles
 }
 
 // === Helper Macros and Functions ===
@@ -213,45 +385,129 @@ fn create_particle(x: f32, y: f32, particle_type: u32) -> Particle {
     Particle::new(x, y, particle_type)
 }
 
+/// Fraction of particles for each type from `type_weights`, summing to 1.
+///
+/// Negative or non-finite weights count as zero; if nothing is left the
+/// split is uniform.
+pub fn type_shares(config: &SpawnConfig) -> Vec<f32> {
+    let n = config.num_types;
+    let weights: Vec<f32> = (0..n)
+        .map(|t| {
+            let w = config.type_weights.get(t).copied().unwrap_or(1.0);
+            if w.is_finite() { w.max(0.0) } else { 0.0 }
+        })
+        .collect();
+    let total: f32 = weights.iter().sum();
+    if total <= 0.0 {
+        return vec![1.0 / n.max(1) as f32; n];
+    }
+    weights.iter().map(|w| w / total).collect()
+}
+
+/// Number of particles of each type, apportioned from [`type_shares`] by
+/// largest remainder so the counts sum to `num_particles` exactly.
+pub fn type_counts(config: &SpawnConfig) -> Vec<usize> {
+    let exact: Vec<f64> = type_shares(config)
+        .iter()
+        .map(|&share| f64::from(share) * config.num_particles as f64)
+        .collect();
+    let mut counts: Vec<usize> = exact.iter().map(|e| e.floor() as usize).collect();
+    let missing = config
+        .num_particles
+        .saturating_sub(counts.iter().sum::<usize>());
+
+    // Stable sort: ties go to lower types, matching an even split
+    let mut order: Vec<usize> = (0..counts.len()).collect();
+    order.sort_by(|&a, &b| (exact[b] - exact[b].floor()).total_cmp(&(exact[a] - exact[a].floor())));
+    for &t in order.iter().take(missing) {
+        counts[t] += 1;
+    }
+    counts
+}
+
+/// Type whose cumulative share range contains `u` in [0, 1].
+fn type_at(shares: &[f32], u: f32) -> u32 {
+    let mut end = 0.0;
+    for (t, share) in shares.iter().enumerate() {
+        end += share;
+        if u < end {
+            return t as u32;
+        }
+    }
+    shares.len().saturating_sub(1) as u32
+}
+
+/// Interleaved type assignment for patterns that mix types: over
+/// `num_particles` draws each type appears exactly its [`type_counts`]
+/// times, spread evenly (smooth weighted round-robin). Uniform weights give
+/// plain round-robin.
+struct TypeCycle {
+    counts: Vec<i64>,
+    credit: Vec<i64>,
+    total: i64,
+}
+
+impl TypeCycle {
+    fn new(config: &SpawnConfig) -> Self {
+        let counts: Vec<i64> = type_counts(config).iter().map(|&c| c as i64).collect();
+        Self {
+            credit: vec![0; counts.len()],
+            total: counts.iter().sum(),
+            counts,
+        }
+    }
+
+    fn next_type(&mut self) -> u32 {
+        let mut best = 0;
+        for t in 0..self.counts.len() {
+            self.credit[t] += self.counts[t];
+            if self.credit[t] > self.credit[best] {
+                best = t;
+            }
+        }
+        self.credit[best] -= self.total;
+        best as u32
+    }
+}
+
 // === Generator Implementations ===
 
-fn random_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn random_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
+    let mut sampler = PointSampler::new(config.sampling, rng);
     let mut particles = Vec::with_capacity(config.num_particles);
-    let mut t = 0u32;
+    let mut types = TypeCycle::new(config);
 
     for _ in 0..config.num_particles {
-        let x = rng.random::<f32>() * config.width;
-        let y = rng.random::<f32>() * config.height;
-        particles.push(create_particle(x, y, t));
-        t = (t + 1) % config.num_types as u32;
+        let (u, v) = sampler.next();
+        let x = u * config.width;
+        let y = v * config.height;
+        particles.push(create_particle(x, y, types.next_type()));
     }
 
     particles
 }
 
-fn disk_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn disk_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
+    let mut sampler = PointSampler::new(config.sampling, rng);
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
     let r = 0.46 * config.width.min(config.height);
-    let mut t = 0u32;
+    let mut types = TypeCycle::new(config);
 
     for _ in 0..config.num_particles {
-        let th = rng.random::<f32>() * TAU;
-        let rr = r * rng.random::<f32>().sqrt();
+        let (u, v) = sampler.next();
+        let th = u * TAU;
+        let rr = r * v.sqrt();
         let x = cx + rr * th.cos();
         let y = cy + rr * th.sin();
-        particles.push(create_particle(x, y, t));
-        t = (t + 1) % config.num_types as u32;
+        particles.push(create_particle(x, y, types.next_type()));
     }
 
     particles
 }
 
-fn ring_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn ring_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
@@ -259,29 +515,27 @@ fn ring_generator(config: &SpawnConfig) -> Vec<Particle> {
     let thick = r * 0.2;
     let rot = rng.random::<f32>() * TAU;
     let dth = TAU / config.num_particles.max(1) as f32;
-    let mut t = 0u32;
+    let mut types = TypeCycle::new(config);
 
     for i in 0..config.num_particles {
         let th = rot + i as f32 * dth;
         let rr = r - rng.random::<f32>() * thick;
         let x = cx + rr * th.cos();
         let y = cy + rr * th.sin();
-        particles.push(create_particle(x, y, t));
-        t = (t + 1) % config.num_types as u32;
+        particles.push(create_particle(x, y, types.next_type()));
     }
 
     particles
 }
 
-fn rings_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn rings_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
     let max_r = 0.46 * config.width.min(config.height);
     let num_rings = rng.random_range(2..=8);
     let particles_per_ring = config.num_particles / num_rings;
-    let mut t = 0u32;
+    let mut types = TypeCycle::new(config);
 
     for ring in 0..num_rings {
         let f = if num_rings == 1 {
@@ -301,16 +555,14 @@ fn rings_generator(config: &SpawnConfig) -> Vec<Particle> {
             let rr = r + (rng.random::<f32>() - 0.5) * 0.04 * max_r;
             let x = cx + rr * th.cos();
             let y = cy + rr * th.sin();
-            particles.push(create_particle(x, y, t));
-            t = (t + 1) % config.num_types as u32;
+            particles.push(create_particle(x, y, types.next_type()));
         }
     }
 
     particles
 }
 
-fn spiral_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn spiral_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
@@ -319,7 +571,7 @@ fn spiral_generator(config: &SpawnConfig) -> Vec<Particle> {
     let turns = 1.2 + rng.random::<f32>() * 2.4;
     let rot = rng.random::<f32>() * TAU;
     let n1 = (config.num_particles - 1).max(1) as f32;
-    let mut t = 0u32;
+    let mut types = TypeCycle::new(config);
 
     for i in 0..config.num_particles {
         let u = i as f32 / n1;
@@ -327,15 +579,13 @@ fn spiral_generator(config: &SpawnConfig) -> Vec<Particle> {
         let rr = (u * r + (rng.random::<f32>() - 0.5) * 2.0 * thick).max(0.0);
         let x = cx + rr * th.cos();
         let y = cy + rr * th.sin();
-        particles.push(create_particle(x, y, t));
-        t = (t + 1) % config.num_types as u32;
+        particles.push(create_particle(x, y, types.next_type()));
     }
 
     particles
 }
 
-fn line_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn line_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let l = config.width * 0.92;
     let thick = config.height * 0.10;
@@ -347,38 +597,30 @@ fn line_generator(config: &SpawnConfig) -> Vec<Particle> {
     } else {
         0.0
     };
-    let mut t = 0u32;
+    let mut types = TypeCycle::new(config);
 
     for i in 0..config.num_particles {
         let x = x_start + step * i as f32;
         let y = cy + (rng.random::<f32>() - 0.5) * thick;
-        particles.push(create_particle(x, y, t));
-        t = (t + 1) % config.num_types as u32;
+        particles.push(create_particle(x, y, types.next_type()));
     }
 
     particles
 }
 
-fn rainbow_disk_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn rainbow_disk_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
     let r = 0.46 * config.width.min(config.height);
     let rot = rng.random::<f32>() * TAU;
-    let sector = TAU / config.num_types.max(1) as f32;
-    let per_type = config.num_particles / config.num_types;
-    let mut remainder = config.num_particles % config.num_types;
+    let shares = type_shares(config);
+    let counts = type_counts(config);
+    let mut th0 = rot;
 
     for t in 0..config.num_types {
-        let count = per_type
-            + if remainder > 0 {
-                remainder -= 1;
-                1
-            } else {
-                0
-            };
-        let th0 = rot + t as f32 * sector;
+        let count = counts[t];
+        let sector = TAU * shares[t];
 
         for j in 0..count {
             let th = th0 + sector * j as f32 / count as f32 + rng.random::<f32>() * 0.1;
@@ -387,32 +629,26 @@ fn rainbow_disk_generator(config: &SpawnConfig) -> Vec<Particle> {
             let y = cy + rr * th.sin();
             particles.push(create_particle(x, y, t as u32));
         }
+        th0 += sector;
     }
 
     particles
 }
 
-fn rainbow_ring_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn rainbow_ring_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
     let r = 0.46 * config.width.min(config.height);
     let thick = r * 0.2;
     let rot = rng.random::<f32>() * TAU;
-    let sector = TAU / config.num_types.max(1) as f32;
-    let per_type = config.num_particles / config.num_types;
-    let mut remainder = config.num_particles % config.num_types;
+    let shares = type_shares(config);
+    let counts = type_counts(config);
+    let mut th0 = rot;
 
     for t in 0..config.num_types {
-        let count = per_type
-            + if remainder > 0 {
-                remainder -= 1;
-                1
-            } else {
-                0
-            };
-        let th0 = rot + t as f32 * sector;
+        let count = counts[t];
+        let sector = TAU * shares[t];
 
         for j in 0..count {
             let th = th0 + sector * j as f32 / count as f32;
@@ -421,35 +657,27 @@ fn rainbow_ring_generator(config: &SpawnConfig) -> Vec<Particle> {
             let y = cy + rr * th.sin();
             particles.push(create_particle(x, y, t as u32));
         }
+        th0 += sector;
     }
 
     particles
 }
 
-fn rainbow_rings_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn rainbow_rings_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
     let max_r = 0.46 * config.width.min(config.height);
     let thick = 0.02 * max_r;
-    let per_ring = config.num_particles / config.num_types;
-    let mut remainder = config.num_particles % config.num_types;
+    let counts = type_counts(config);
 
-    for ring in 0..config.num_types {
+    for (ring, &count) in counts.iter().enumerate() {
         let f = if config.num_types == 1 {
             0.5
         } else {
             0.23 + 0.69 * ring as f32 / (config.num_types - 1) as f32
         };
         let r = f * max_r;
-        let count = per_ring
-            + if remainder > 0 {
-                remainder -= 1;
-                1
-            } else {
-                0
-            };
 
         for j in 0..count {
             let th = TAU * j as f32 / count as f32 + rng.random::<f32>() * 0.1;
@@ -463,8 +691,7 @@ fn rainbow_rings_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn rainbow_spiral_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn rainbow_spiral_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
@@ -473,6 +700,7 @@ fn rainbow_spiral_generator(config: &SpawnConfig) -> Vec<Particle> {
     let turns = 1.2 + rng.random::<f32>() * 2.4;
     let rot = rng.random::<f32>() * TAU;
     let n1 = (config.num_particles - 1).max(1) as f32;
+    let shares = type_shares(config);
 
     for i in 0..config.num_particles {
         let u = i as f32 / n1;
@@ -480,75 +708,60 @@ fn rainbow_spiral_generator(config: &SpawnConfig) -> Vec<Particle> {
         let rr = (u * r + (rng.random::<f32>() - 0.5) * 2.0 * thick).max(0.0);
         let x = cx + rr * th.cos();
         let y = cy + rr * th.sin();
-        let t = ((u * config.num_types as f32) as usize).min(config.num_types - 1) as u32;
-        particles.push(create_particle(x, y, t));
+        particles.push(create_particle(x, y, type_at(&shares, u)));
     }
 
     particles
 }
 
-fn rainbow_line_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn rainbow_line_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let l = config.width * 0.92;
     let thick = config.height * 0.10;
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
     let x_start = cx - l * 0.5;
-    let seg_w = l / config.num_types as f32;
-    let per_seg = config.num_particles / config.num_types;
-    let mut remainder = config.num_particles % config.num_types;
+    let shares = type_shares(config);
+    let counts = type_counts(config);
+    let mut x0 = x_start;
 
     for t in 0..config.num_types {
-        let count = per_seg
-            + if remainder > 0 {
-                remainder -= 1;
-                1
-            } else {
-                0
-            };
-        let x0 = x_start + t as f32 * seg_w;
+        let count = counts[t];
+        let seg_w = l * shares[t];
 
         for j in 0..count {
             let x = x0 + seg_w * (j as f32 + rng.random::<f32>()) / count as f32;
             let y = cy + (rng.random::<f32>() - 0.5) * thick;
             particles.push(create_particle(x, y, t as u32));
         }
+        x0 += seg_w;
     }
 
     particles
 }
 
-fn stripes_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn stripes_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let vertical = rng.random::<bool>();
-    let per_type = config.num_particles / config.num_types;
-    let mut remainder = config.num_particles % config.num_types;
+    let mut sampler = PointSampler::new(config.sampling, rng);
+    let shares = type_shares(config);
+    let counts = type_counts(config);
+    let mut start = 0.0;
 
     for t in 0..config.num_types {
-        let count = per_type
-            + if remainder > 0 {
-                remainder -= 1;
-                1
-            } else {
-                0
-            };
+        let count = counts[t];
+        let share = shares[t];
 
         for _ in 0..count {
+            let (u, v) = sampler.next();
             let (x, y) = if vertical {
-                let seg = config.width / config.num_types as f32;
-                let x = t as f32 * seg + rng.random::<f32>() * seg;
-                let y = rng.random::<f32>() * config.height;
-                (x, y)
+                ((start + u * share) * config.width, v * config.height)
             } else {
-                let seg = config.height / config.num_types as f32;
-                let x = rng.random::<f32>() * config.width;
-                let y = t as f32 * seg + rng.random::<f32>() * seg;
-                (x, y)
+                (u * config.width, (start + v * share) * config.height)
             };
             particles.push(create_particle(x, y, t as u32));
         }
+        start += share;
     }
 
     particles
@@ -566,7 +779,7 @@ fn border_generator(config: &SpawnConfig) -> Vec<Particle> {
 
     let step = p / config.num_particles as f32;
     let mut s = 0.0;
-    let mut t = 0u32;
+    let mut types = TypeCycle::new(config);
 
     for _ in 0..config.num_particles {
         let (x, y) = if s < w {
@@ -578,8 +791,7 @@ fn border_generator(config: &SpawnConfig) -> Vec<Particle> {
         } else {
             (inset, inset + (p - s))
         };
-        particles.push(create_particle(x, y, t));
-        t = (t + 1) % config.num_types as u32;
+        particles.push(create_particle(x, y, types.next_type()));
         s += step;
     }
 
@@ -592,7 +804,7 @@ fn grid_generator(config: &SpawnConfig) -> Vec<Particle> {
     let rows = config.num_particles.div_ceil(cols);
     let dx = config.width / cols as f32;
     let dy = config.height / rows as f32;
-    let mut t = 0u32;
+    let mut types = TypeCycle::new(config);
     let mut i = 0;
 
     'outer: for r in 0..rows {
@@ -602,8 +814,7 @@ fn grid_generator(config: &SpawnConfig) -> Vec<Particle> {
                 break 'outer;
             }
             let x = (c as f32 + 0.5) * dx;
-            particles.push(create_particle(x, y, t));
-            t = (t + 1) % config.num_types as u32;
+            particles.push(create_particle(x, y, types.next_type()));
             i += 1;
         }
     }
@@ -611,24 +822,15 @@ fn grid_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn wavy_bands_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn wavy_bands_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let seg_h = config.height / config.num_types as f32;
     let amp = 0.06 * config.height;
     let kx = (TAU / config.width) * (1.0 + (config.num_types % 3) as f32);
     let base_phase = rng.random::<f32>() * TAU;
-    let per_type = config.num_particles / config.num_types;
-    let mut remainder = config.num_particles % config.num_types;
+    let counts = type_counts(config);
 
-    for t in 0..config.num_types {
-        let count = per_type
-            + if remainder > 0 {
-                remainder -= 1;
-                1
-            } else {
-                0
-            };
+    for (t, &count) in counts.iter().enumerate() {
         let y0 = (t as f32 + 0.5) * seg_h;
         let phase = base_phase + t as f32 * 0.7;
 
@@ -645,8 +847,7 @@ fn wavy_bands_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn simple_flower_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn simple_flower_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let petals = rng.random_range(2..=8);
     let phase = rng.random::<f32>() * TAU;
@@ -654,7 +855,7 @@ fn simple_flower_generator(config: &SpawnConfig) -> Vec<Particle> {
     let cy = config.height * 0.5;
     let r = 0.46 * config.width.min(config.height);
     let jitter = 0.02 * r;
-    let mut t = 0u32;
+    let mut types = TypeCycle::new(config);
 
     for i in 0..config.num_particles {
         let u = (i as f32 + rng.random::<f32>()) / config.num_particles as f32;
@@ -663,16 +864,14 @@ fn simple_flower_generator(config: &SpawnConfig) -> Vec<Particle> {
         let rr = r_base + (rng.random::<f32>() - 0.5) * 2.0 * jitter;
         let x = cx + rr * th.cos();
         let y = cy + rr * th.sin();
-        particles.push(create_particle(x, y, t));
-        t = (t + 1) % config.num_types as u32;
+        particles.push(create_particle(x, y, types.next_type()));
     }
 
     particles
 }
 
-fn chromatic_flower_generator(config: &SpawnConfig) -> Vec<Particle> {
+fn chromatic_flower_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     // Simplified version - similar to simple_flower but with chromatic assignment
-    let mut rng = rand::rng();
     let mut particles = Vec::with_capacity(config.num_particles);
     let petals = rng.random_range(2..=7);
     let phase = rng.random::<f32>() * TAU;
@@ -680,17 +879,9 @@ fn chromatic_flower_generator(config: &SpawnConfig) -> Vec<Particle> {
     let cy = config.height * 0.5;
     let r = 0.46 * config.width.min(config.height);
     let jitter = 0.006 * config.width.min(config.height);
-    let per_type = config.num_particles / config.num_types;
-    let mut remainder = config.num_particles % config.num_types;
+    let counts = type_counts(config);
 
-    for t in 0..config.num_types {
-        let count = per_type
-            + if remainder > 0 {
-                remainder -= 1;
-                1
-            } else {
-                0
-            };
+    for (t, &count) in counts.iter().enumerate() {
         let scale = 0.62 + rng.random::<f32>() * 0.38;
         let r_scaled = r * scale;
         let type_phase = rng.random::<f32>() * TAU;
@@ -708,8 +899,7 @@ fn chromatic_flower_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn yin_yang_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn yin_yang_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
@@ -760,8 +950,7 @@ fn yin_yang_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn twin_crescents_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn twin_crescents_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let m = config.width.min(config.height);
     let cx = config.width * 0.5;
@@ -810,8 +999,7 @@ fn twin_crescents_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn twin_spirals_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn twin_spirals_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
@@ -845,25 +1033,16 @@ fn twin_spirals_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn spiral_arms_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn spiral_arms_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
     let max_r = 0.46 * config.width.min(config.height);
     let turns = 2.5;
     let thick = (0.07 / config.num_types as f32).min(0.02) * config.width.min(config.height);
-    let per_arm = config.num_particles / config.num_types;
-    let mut remainder = config.num_particles % config.num_types;
+    let counts = type_counts(config);
 
-    for arm in 0..config.num_types {
-        let count = per_arm
-            + if remainder > 0 {
-                remainder -= 1;
-                1
-            } else {
-                0
-            };
+    for (arm, &count) in counts.iter().enumerate() {
         let arm_rot = arm as f32 * TAU / config.num_types as f32;
         let n1 = (count - 1).max(1) as f32;
 
@@ -880,8 +1059,7 @@ fn spiral_arms_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn polar_maze_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn polar_maze_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
@@ -920,9 +1098,8 @@ fn polar_maze_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn chaotic_bands_generator(config: &SpawnConfig) -> Vec<Particle> {
+fn chaotic_bands_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     // Simplified version using random bands
-    let mut rng = rand::rng();
     let mut particles = Vec::with_capacity(config.num_particles);
     let lanes = rng.random_range(3..=10).min(config.num_types);
     let per_lane = config.num_particles / lanes;
@@ -957,8 +1134,7 @@ fn chaotic_bands_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn radiant_fans_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn radiant_fans_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let fans = config.num_types.clamp(3, 10);
     let cx = config.width * 0.5;
@@ -991,8 +1167,7 @@ fn radiant_fans_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn soft_clusters_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn soft_clusters_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let clusters = rng.random_range(2..=6).min(config.num_types).max(2);
     let m = config.width.min(config.height);
@@ -1028,13 +1203,12 @@ fn soft_clusters_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn linked_clusters_generator(config: &SpawnConfig) -> Vec<Particle> {
+fn linked_clusters_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     // Simplified - just clusters for now
-    soft_clusters_generator(config)
+    soft_clusters_generator(config, rng)
 }
 
-fn orbital_belts_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn orbital_belts_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
@@ -1076,8 +1250,7 @@ fn orbital_belts_generator(config: &SpawnConfig) -> Vec<Particle> {
     particles
 }
 
-fn braided_belts_generator(config: &SpawnConfig) -> Vec<Particle> {
-    let mut rng = rand::rng();
+fn braided_belts_generator(config: &SpawnConfig, rng: &mut dyn RngCore) -> Vec<Particle> {
     let mut particles = Vec::with_capacity(config.num_particles);
     let cx = config.width * 0.5;
     let cy = config.height * 0.5;
@@ -1124,6 +1297,7 @@ fn braided_belts_generator(config: &SpawnConfig) -> Vec<Particle> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::simulation::MAX_NUM_TYPES;
 
     fn test_config() -> SpawnConfig {
         SpawnConfig {
@@ -1131,20 +1305,35 @@ mod tests {
             num_types: 4,
             width: 800.0,
             height: 600.0,
+            velocity: SpawnVelocity::Zero,
+            sampling: SpawnSampling::Uniform,
+            type_weights: Vec::new(),
         }
     }
 
     #[test]
     fn test_all_patterns_produce_correct_count() {
-        let config = test_config();
-        for pattern in PositionPattern::all() {
-            let particles = generate_positions(*pattern, &config);
-            assert_eq!(
-                particles.len(),
-                config.num_particles,
-                "Pattern {:?} produced wrong count",
-                pattern
-            );
+        let many_types = SpawnConfig {
+            num_types: MAX_NUM_TYPES as usize,
+            ..test_config()
+        };
+        for config in [test_config(), many_types] {
+            for pattern in PositionPattern::all() {
+                let particles = generate_positions(*pattern, &config);
+                assert_eq!(
+                    particles.len(),
+                    config.num_particles,
+                    "Pattern {:?} produced wrong count",
+                    pattern
+                );
+                assert!(
+                    particles
+                        .iter()
+                        .all(|p| (p.particle_type as usize) < config.num_types),
+                    "Pattern {:?} produced an out-of-range type",
+                    pattern
+                );
+            }
         }
     }
 
@@ -1170,6 +1359,100 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_type_weights_set_exact_counts() {
+        let mut config = test_config();
+        assert_eq!(type_counts(&config), vec![25, 25, 25, 25]);
+        config.num_particles = 10;
+        assert_eq!(type_counts(&config), vec![3, 3, 2, 2]);
+
+        // Type 0 gets half, the rest split the remainder
+        config.num_particles = 100;
+        config.type_weights = vec![3.0, 1.0, 1.0, 1.0];
+        assert_eq!(type_counts(&config), vec![50, 17, 17, 16]);
+
+        for pattern in [
+            PositionPattern::Random,
+            PositionPattern::Disk,
+            PositionPattern::Grid,
+            PositionPattern::RainbowDisk,
+            PositionPattern::Stripes,
+        ] {
+            let mut per_type = [0usize; 4];
+            for p in generate_positions(pattern, &config) {
+                per_type[p.particle_type as usize] += 1;
+            }
+            assert_eq!(per_type, [50, 17, 17, 16], "pattern {:?}", pattern);
+        }
+
+        // Unusable weights fall back to uniform
+        config.type_weights = vec![0.0, -1.0, f32::NAN, 0.0];
+        assert_eq!(type_counts(&config), vec![25, 25, 25, 25]);
+    }
+
+    #[test]
+    fn test_seeded_generation_is_reproducible() {
+        use rand::{SeedableRng, rngs::StdRng};
+
+        let mut config = test_config();
+        config.velocity = SpawnVelocity::Random(50.0);
+        let snapshot = |seed: u64| -> Vec<[f32; 4]> {
+            let mut rng = StdRng::seed_from_u64(seed);
+            generate_positions_with_rng(PositionPattern::SoftClusters, &config, &mut rng)
+                .iter()
+                .map(|p| [p.x, p.y, p.vx, p.vy])
+                .collect()
+        };
+        assert_eq!(snapshot(42), snapshot(42));
+        assert_ne!(snapshot(42), snapshot(43));
+    }
+
+    #[test]
+    fn test_spawn_velocity_fields() {
+        let mut config = test_config();
+        config.velocity = SpawnVelocity::Rotational(0.5);
+        let particles = generate_positions(PositionPattern::Disk, &config);
+        for p in &particles {
+            // Rotation is tangential: velocity is perpendicular to the offset
+            let dx = p.x - config.width / 2.0;
+            let dy = p.y - config.height / 2.0;
+            assert!((p.vx * dx + p.vy * dy).abs() < 1e-2);
+        }
+
+        config.velocity = SpawnVelocity::Radial(10.0);
+        let particles = generate_positions(PositionPattern::Disk, &config);
+        for p in particles.iter().filter(|p| p.speed() > 0.0) {
+            assert!((p.speed() - 10.0).abs() < 1e-3);
+            let dx = p.x - config.width / 2.0;
+            let dy = p.y - config.height / 2.0;
+            assert!(p.vx * dx + p.vy * dy > 0.0);
+        }
+
+        config.velocity = SpawnVelocity::Zero;
+        let particles = generate_positions(PositionPattern::Disk, &config);
+        assert!(particles.iter().all(|p| p.vx == 0.0 && p.vy == 0.0));
+    }
+
+    #[test]
+    fn test_low_discrepancy_sampling() {
+        assert_eq!(halton(1, 2), 0.5);
+        assert_eq!(halton(3, 2), 0.75);
+        assert!((halton(1, 3) - 1.0 / 3.0).abs() < 1e-6);
+
+        // Every cell of a 4x4 grid gets close to its fair share
+        let mut config = test_config();
+        config.num_particles = 1600;
+        config.sampling = SpawnSampling::LowDiscrepancy;
+        let particles = generate_positions(PositionPattern::Random, &config);
+        let mut cells = [0usize; 16];
+        for p in &particles {
+            let cx = ((p.x / config.width * 4.0) as usize).min(3);
+            let cy = ((p.y / config.height * 4.0) as usize).min(3);
+            cells[cy * 4 + cx] += 1;
+        }
+        assert!(cells.iter().all(|&n| (95..=105).contains(&n)), "{cells:?}");
+    }
+
     #[test]
     fn test_empty_config() {
         let config = SpawnConfig {
@@ -1177,6 +1460,9 @@ mod tests {
             num_types: 4,
             width: 800.0,
             height: 600.0,
+            velocity: SpawnVelocity::Zero,
+            sampling: SpawnSampling::Uniform,
+            type_weights: Vec::new(),
         };
         let particles = generate_positions(PositionPattern::Random, &config);
         assert!(particles.is_empty());
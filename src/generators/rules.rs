@@ -1,10 +1,12 @@
 //! Rule generators for creating interaction matrices.
 //!
-//! This module contains 31 different algorithms for generating
+//! This module contains 32 different algorithms for generating
 //! particle interaction matrices, ranging from simple random
 //! patterns to complex mathematical constructs.
 
-use rand::Rng;
+pub mod import;
+
+use rand::{Rng, RngCore};
 use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 
@@ -46,6 +48,8 @@ pub enum RuleType {
     TriSpiral = 28,
     VortexAntivortex = 29,
     DriftedPatchwork = 30,
+    AntiSymmetric = 31,
+    Balanced = 32,
 }
 
 impl RuleType {
@@ -83,6 +87,8 @@ impl RuleType {
             RuleType::TriSpiral,
             RuleType::VortexAntivortex,
             RuleType::DriftedPatchwork,
+            RuleType::AntiSymmetric,
+            RuleType::Balanced,
         ]
     }
 
@@ -120,13 +126,15 @@ impl RuleType {
             RuleType::TriSpiral => "Tri-Spiral",
             RuleType::VortexAntivortex => "Vortex–Antivortex Lattice",
             RuleType::DriftedPatchwork => "Drifted Patchwork",
+            RuleType::AntiSymmetric => "Anti-symmetric",
+            RuleType::Balanced => "Balanced",
         }
     }
 
     /// Get the category for this rule type.
     pub fn category(&self) -> &'static str {
         match self {
-            RuleType::Random => "Default",
+            RuleType::Random | RuleType::Balanced => "Default",
             _ => "Experimental",
         }
     }
@@ -146,13 +154,23 @@ impl RuleGenerator for RuleType {
 
 /// Generate an interaction matrix using the specified rule type.
 pub fn generate_rules(rule_type: RuleType, num_types: usize) -> InteractionMatrix {
+    generate_rules_with_rng(rule_type, num_types, &mut rand::rng())
+}
+
+/// Generate an interaction matrix using the specified rule type, drawing
+/// random entries from `rng`.
+pub fn generate_rules_with_rng(
+    rule_type: RuleType,
+    num_types: usize,
+    rng: &mut dyn RngCore,
+) -> InteractionMatrix {
     if num_types == 0 {
         return InteractionMatrix::new(0);
     }
 
     let mut matrix = match rule_type {
-        RuleType::Random => random_generator(num_types),
-        RuleType::Symmetric => symmetric_generator(num_types),
+        RuleType::Random => random_generator(num_types, rng),
+        RuleType::Symmetric => symmetric_generator(num_types, rng),
         RuleType::Snake => snake_generator(num_types),
         RuleType::Chains1 => chains1_generator(num_types),
         RuleType::Chains2 => chains2_generator(num_types),
@@ -182,6 +200,8 @@ pub fn generate_rules(rule_type: RuleType, num_types: usize) -> InteractionMatri
         RuleType::TriSpiral => tri_spiral_generator(num_types),
         RuleType::VortexAntivortex => vortex_antivortex_generator(num_types),
         RuleType::DriftedPatchwork => drifted_patchwork_generator(num_types),
+        RuleType::AntiSymmetric => anti_symmetric_generator(num_types, rng),
+        RuleType::Balanced => balanced_generator(num_types, rng),
     };
 
     // Round all values to 2 decimal places for consistency
@@ -195,8 +215,7 @@ pub fn generate_rules(rule_type: RuleType, num_types: usize) -> InteractionMatri
 // === Generator Implementations ===
 
 /// Random matrix with values in [-1, 1).
-fn random_generator(n: usize) -> InteractionMatrix {
-    let mut rng = rand::rng();
+fn random_generator(n: usize, rng: &mut dyn RngCore) -> InteractionMatrix {
     let mut matrix = InteractionMatrix::new(n);
     for val in &mut matrix.data {
         *val = rng.random::<f32>() * 2.0 - 1.0;
@@ -205,12 +224,52 @@ fn random_generator(n: usize) -> InteractionMatrix {
 }
 
 /// Symmetric matrix (m[i][j] = m[j][i]).
-fn symmetric_generator(n: usize) -> InteractionMatrix {
-    let mut matrix = random_generator(n);
+fn symmetric_generator(n: usize, rng: &mut dyn RngCore) -> InteractionMatrix {
+    let mut matrix = random_generator(n, rng);
     matrix.symmetrize();
     matrix
 }
 
+/// Anti-symmetric matrix (m[i][j] = -m[j][i], zero diagonal): every pair
+/// chases or flees in equal measure, giving rotational dynamics.
+fn anti_symmetric_generator(n: usize, rng: &mut dyn RngCore) -> InteractionMatrix {
+    let mut matrix = random_generator(n, rng);
+    matrix.anti_symmetrize();
+    matrix
+}
+
+/// Random matrix whose rows each mix attraction and repulsion summing to
+/// zero, scaled so the strongest entry in a row is +/-1.
+///
+/// Zero row sums keep any type from pulling (or pushing) all others on
+/// balance, so the scene neither collapses nor scatters as often as with
+/// [`random_generator`]. Entries are rounded to two decimals with the
+/// rounding residue moved onto the row's weakest entry, so the sums stay
+/// zero after the final rounding. A single type has nothing to balance
+/// against and gets 0.
+fn balanced_generator(n: usize, rng: &mut dyn RngCore) -> InteractionMatrix {
+    let mut matrix = InteractionMatrix::new(n);
+    for i in 0..n {
+        let mut row: Vec<f32> = (0..n).map(|_| rng.random::<f32>() * 2.0 - 1.0).collect();
+        let mean = row.iter().sum::<f32>() / n as f32;
+        let max_abs = row.iter().map(|v| (v - mean).abs()).fold(0.0f32, f32::max);
+        if max_abs <= f32::EPSILON {
+            continue;
+        }
+        for v in &mut row {
+            *v = ((*v - mean) / max_abs * 100.0).round() / 100.0;
+        }
+        let residue = row.iter().sum::<f32>();
+        if let Some(weakest) = row.iter_mut().min_by(|a, b| a.abs().total_cmp(&b.abs())) {
+            *weakest = ((*weakest - residue) * 100.0).round() / 100.0;
+        }
+        for (j, v) in row.into_iter().enumerate() {
+            matrix.set(i, j, v);
+        }
+    }
+    matrix
+}
+
 /// Snake pattern: each type follows the next.
 fn snake_generator(n: usize) -> InteractionMatrix {
     let mut matrix = InteractionMatrix::new(n);
@@ -925,24 +984,27 @@ fn drifted_patchwork_generator(n: usize) -> InteractionMatrix {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::simulation::MAX_NUM_TYPES;
 
     #[test]
     fn test_all_generators_produce_valid_matrices() {
-        for rule_type in RuleType::all() {
-            let matrix = generate_rules(*rule_type, 8);
-            assert_eq!(matrix.size, 8);
-            assert_eq!(matrix.data.len(), 64);
-            assert!(
-                matrix.validate().is_ok(),
-                "Generator {:?} produced invalid matrix",
-                rule_type
-            );
+        for size in [8, MAX_NUM_TYPES as usize] {
+            for rule_type in RuleType::all() {
+                let matrix = generate_rules(*rule_type, size);
+                assert_eq!(matrix.size, size);
+                assert_eq!(matrix.data.len(), size * size);
+                assert!(
+                    matrix.validate().is_ok(),
+                    "Generator {:?} produced invalid {size}x{size} matrix",
+                    rule_type
+                );
+            }
         }
     }
 
     #[test]
     fn test_symmetric_generator() {
-        let matrix = symmetric_generator(4);
+        let matrix = symmetric_generator(4, &mut rand::rng());
         for i in 0..4 {
             for j in 0..4 {
                 assert!(
@@ -953,6 +1015,35 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_anti_symmetric_generator() {
+        let matrix = generate_rules(RuleType::AntiSymmetric, 5);
+        for i in 0..5 {
+            assert_eq!(matrix.get(i, i), 0.0);
+            for j in 0..5 {
+                assert_eq!(matrix.get(i, j), -matrix.get(j, i));
+            }
+        }
+    }
+
+    #[test]
+    fn test_balanced_generator_rows_sum_to_zero() {
+        for size in [2, 3, 7, MAX_NUM_TYPES as usize] {
+            let matrix = generate_rules(RuleType::Balanced, size);
+            for i in 0..size {
+                let row: Vec<f32> = (0..size).map(|j| matrix.get(i, j)).collect();
+                let sum: f32 = row.iter().sum();
+                assert!(sum.abs() < 1e-3, "row {i} of {size} sums to {sum}");
+                assert!(row.iter().any(|&v| v > 0.0), "row {i} has no attraction");
+                assert!(row.iter().any(|&v| v < 0.0), "row {i} has no repulsion");
+                assert!(row.iter().all(|v| v.abs() <= 1.0));
+            }
+        }
+
+        let single = generate_rules(RuleType::Balanced, 1);
+        assert_eq!(single.data, vec![0.0]);
+    }
+
     #[test]
     fn test_snake_generator() {
         let matrix = snake_generator(4);
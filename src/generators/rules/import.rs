@@ -0,0 +1,170 @@
+//! Import of interaction matrices exported by other particle-life tools.
+//!
+//! Supported format: a flat attraction-matrix JSON, as written by many web
+//! particle-life implementations. Any of these shapes is accepted:
+//!
+//! ```json
+//! [[0.5, -0.2], [0.1, 0.3]]
+//! {"matrix": [[0.5, -0.2], [0.1, 0.3]]}
+//! {"attraction": [0.5, -0.2, 0.1, 0.3]}
+//! ```
+//!
+//! The array is either a list of rows or a single row-major list whose length
+//! is a perfect square; the wrapping object may use the key `matrix`,
+//! `attraction`, or `rules` (other keys are ignored). Row `i`, column `j` is
+//! how strongly type `i` is attracted to type `j`, which matches
+//! [`InteractionMatrix::get`]. Values outside -1..1 are rescaled by the
+//! largest magnitude so the strongest rule maps to ±1.
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+use crate::simulation::{InteractionMatrix, MAX_NUM_TYPES};
+
+/// Largest number of types an imported matrix may have (the Types slider limit).
+pub const MAX_IMPORTED_TYPES: usize = MAX_NUM_TYPES as usize;
+
+/// Object keys that may hold the matrix.
+const MATRIX_KEYS: [&str; 3] = ["matrix", "attraction", "rules"];
+
+/// An interaction matrix parsed from an external format.
+#[derive(Debug, Clone)]
+pub struct ImportedRules {
+    /// The imported matrix; its size is the number of types.
+    pub matrix: InteractionMatrix,
+    /// Factor the values were divided by to fit -1..1, if they were rescaled.
+    pub rescaled_by: Option<f32>,
+}
+
+/// Parse a flat attraction-matrix JSON document.
+pub fn parse_attraction_json(json: &str) -> Result<ImportedRules> {
+    let value: Value = serde_json::from_str(json).context("Not valid JSON")?;
+    let array = match &value {
+        Value::Array(array) => array,
+        Value::Object(map) => {
+            let Some((_, found)) = MATRIX_KEYS
+                .iter()
+                .find_map(|key| map.get(*key).map(|v| (key, v)))
+            else {
+                bail!(
+                    "Expected a top-level array or an object with one of the keys {:?}",
+                    MATRIX_KEYS
+                );
+            };
+            let Value::Array(array) = found else {
+                bail!("The matrix entry must be an array");
+            };
+            array
+        }
+        _ => bail!("Expected a JSON array or object, found {}", kind(&value)),
+    };
+
+    let (size, values) = if array.iter().all(Value::is_array) {
+        flatten_rows(array)?
+    } else {
+        let values = numbers(array, "matrix")?;
+        let size = (values.len() as f64).sqrt().round() as usize;
+        if size * size != values.len() {
+            bail!(
+                "A flat matrix needs a square number of values, found {}",
+                values.len()
+            );
+        }
+        (size, values)
+    };
+
+    if !(2..=MAX_IMPORTED_TYPES).contains(&size) {
+        bail!(
+            "Matrix is {size}x{size}; supported sizes are 2x2 to {MAX_IMPORTED_TYPES}x{MAX_IMPORTED_TYPES}"
+        );
+    }
+
+    let max_abs = values.iter().fold(0.0f32, |m, v| m.max(v.abs()));
+    let rescaled_by = (max_abs > 1.0).then_some(max_abs);
+    let scale = rescaled_by.unwrap_or(1.0);
+    let mut matrix = InteractionMatrix::new(size);
+    for (i, v) in values.into_iter().enumerate() {
+        matrix.data[i] = v / scale;
+    }
+
+    Ok(ImportedRules {
+        matrix,
+        rescaled_by,
+    })
+}
+
+/// Flatten a list of rows, checking the matrix is square.
+fn flatten_rows(rows: &[Value]) -> Result<(usize, Vec<f32>)> {
+    let size = rows.len();
+    let mut values = Vec::with_capacity(size * size);
+    for (i, row) in rows.iter().enumerate() {
+        let row = numbers(row.as_array().map_or(&[][..], Vec::as_slice), "row")
+            .with_context(|| format!("In row {i}"))?;
+        if row.len() != size {
+            bail!(
+                "Matrix must be square: row {i} has {} values but there are {size} rows",
+                row.len()
+            );
+        }
+        values.extend(row);
+    }
+    Ok((size, values))
+}
+
+/// Convert an array of JSON numbers to finite f32 values.
+fn numbers(array: &[Value], what: &str) -> Result<Vec<f32>> {
+    array
+        .iter()
+        .enumerate()
+        .map(|(i, v)| match v.as_f64() {
+            Some(n) if n.is_finite() => Ok(n as f32),
+            _ => bail!("Entry {i} of the {what} is {}, expected a number", kind(v)),
+        })
+        .collect()
+}
+
+/// Human-readable JSON value kind for error messages.
+fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rows_and_flat_forms() {
+        let rows = parse_attraction_json("[[0.5, -0.2], [0.1, 0.3]]").unwrap();
+        assert_eq!(rows.matrix.size, 2);
+        assert_eq!(rows.matrix.get(0, 1), -0.2);
+        assert_eq!(rows.matrix.get(1, 0), 0.1);
+        assert!(rows.rescaled_by.is_none());
+
+        let flat =
+            parse_attraction_json(r#"{"attraction": [0.5, -0.2, 0.1, 0.3], "name": "x"}"#).unwrap();
+        assert_eq!(flat.matrix.data, rows.matrix.data);
+
+        let keyed = parse_attraction_json(r#"{"matrix": [[2, -4], [1, 0]]}"#).unwrap();
+        assert_eq!(keyed.rescaled_by, Some(4.0));
+        assert_eq!(keyed.matrix.get(0, 1), -1.0);
+        assert_eq!(keyed.matrix.get(0, 0), 0.5);
+    }
+
+    #[test]
+    fn test_parse_reports_mismatches() {
+        let err = |json: &str| format!("{:#}", parse_attraction_json(json).unwrap_err());
+        assert!(err("[[1, 0], [0]]").contains("square"));
+        assert!(err("[1, 0, 0]").contains("square number"));
+        assert!(err(r#"{"colors": []}"#).contains("keys"));
+        assert!(err(r#"[[1, "a"], [0, 1]]"#).contains("a string"));
+        assert!(err("[1]").contains("supported sizes"));
+        assert!(err("not json").contains("Not valid JSON"));
+    }
+}
@@ -7,24 +7,30 @@
 //! - **GPU Rendering**: Uses wgpu for high-performance particle simulation
 //! - **Interactive UI**: egui-based controls for real-time parameter adjustment
 //! - **Multiple Simulations**: Particle Life and Game of Life
+//! - **Headless Stepping**: [`Simulation`] runs on the CPU without a window
 //!
 //! ## Example
 //!
 //! ```no_run
-//! use par_particle_life::app::App;
+//! use par_particle_life::app::{App, WindowOptions};
 //!
 //! fn main() -> anyhow::Result<()> {
-//!     App::run(false)
+//!     App::run(false, false, WindowOptions::default(), None, None)
 //! }
 //! ```
 
 pub mod app;
 pub mod generators;
+pub mod metrics_logger;
 pub mod renderer;
 pub mod simulation;
+pub mod state_recorder;
+pub mod svg_export;
 pub mod ui;
 pub mod utils;
 pub mod video_recorder;
 
 pub use app::App;
-pub use simulation::{BoundaryMode, InteractionMatrix, Particle, RadiusMatrix, SimulationConfig};
+pub use simulation::{
+    BoundaryMode, InteractionMatrix, Particle, RadiusMatrix, Simulation, SimulationConfig,
+};
@@ -6,8 +6,16 @@
 //! - Multiple simulation modes (Particle Life, Game of Life)
 
 use anyhow::Result;
-use clap::Parser;
-use par_particle_life::App;
+use clap::{Parser, Subcommand};
+use par_particle_life::{
+    App,
+    app::{
+        AutoRecord, Preset, WindowOptions,
+        bench::{self, BenchOptions},
+    },
+    renderer::gpu::DEFAULT_SPATIAL_WORKGROUP_SIZE,
+    video_recorder::VideoFormat,
+};
 
 /// Par Particle Life - GPU-accelerated particle simulation in Rust.
 ///
@@ -21,6 +29,65 @@ struct Cli {
     /// Resets application configuration to defaults on startup.
     #[arg(long)]
     reset_config: bool,
+
+    /// Disables half-precision (f16) velocity storage, even on supporting GPUs.
+    #[arg(long)]
+    no_f16: bool,
+
+    /// Opens the window on this monitor (index in enumeration order).
+    #[arg(long, value_name = "INDEX")]
+    monitor: Option<usize>,
+
+    /// Starts in exclusive fullscreen at the monitor's native video mode.
+    #[arg(long)]
+    fullscreen: bool,
+
+    /// Loads the saved preset with this name before the first frame.
+    #[arg(long, value_name = "NAME")]
+    preset: Option<String>,
+
+    /// Prints the names of saved presets and exits.
+    #[arg(long, conflicts_with = "preset")]
+    list_presets: bool,
+
+    /// Records this many seconds of simulated time from startup, saves the
+    /// video and exits.
+    #[arg(long, value_name = "SECONDS")]
+    auto_record: Option<f32>,
+
+    /// Output format of --auto-record (mp4, webm or gif).
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "mp4",
+        requires = "auto_record"
+    )]
+    auto_record_format: VideoFormat,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Runs the compute passes headless and reports per-step timings.
+    Bench {
+        /// Number of particles to simulate.
+        #[arg(long, default_value_t = 64_000)]
+        particles: u32,
+
+        /// Number of particle types.
+        #[arg(long, default_value_t = 7)]
+        types: u32,
+
+        /// Number of timed steps.
+        #[arg(long, default_value_t = 1000)]
+        steps: u32,
+
+        /// Workgroup size of the spatial hash passes (power of two, 32-256).
+        #[arg(long, default_value_t = DEFAULT_SPATIAL_WORKGROUP_SIZE)]
+        workgroup_size: u32,
+    },
 }
 
 fn main() -> Result<()> {
@@ -29,6 +96,49 @@ fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
+    if let Some(Command::Bench {
+        particles,
+        types,
+        steps,
+        workgroup_size,
+    }) = cli.command
+    {
+        let report = bench::run(BenchOptions {
+            particles,
+            types,
+            steps,
+            allow_f16: !cli.no_f16,
+            workgroup_size,
+        })?;
+        print!("{}", report);
+        return Ok(());
+    }
+
+    if cli.list_presets {
+        let presets = Preset::list_presets()?;
+        if presets.is_empty() {
+            eprintln!("No presets saved in {}", Preset::presets_dir().display());
+        }
+        for name in presets {
+            println!("{}", name);
+        }
+        return Ok(());
+    }
+
     // Run the application
-    App::run(cli.reset_config)
+    let window = WindowOptions {
+        monitor: cli.monitor,
+        fullscreen: cli.fullscreen,
+    };
+    let auto_record = cli.auto_record.map(|seconds| AutoRecord {
+        seconds,
+        format: cli.auto_record_format,
+    });
+    App::run(
+        cli.reset_config,
+        cli.no_f16,
+        window,
+        cli.preset.as_deref(),
+        auto_record,
+    )
 }
@@ -7,8 +7,8 @@ use bytemuck::{Pod, Zeroable};
 use wgpu::{Buffer, BufferUsages, Device, Queue, util::DeviceExt};
 
 use crate::simulation::{
-    InteractionMatrix, Particle, ParticlePosType, ParticleVel, ParticleVelHalf, RadiusMatrix,
-    SimulationConfig,
+    BoundaryMode, InteractionMatrix, Particle, ParticlePosType, ParticleVel, ParticleVelHalf,
+    RadiusMatrix, SimulationConfig, WorldShape,
 };
 
 /// Parameters for spatial hashing uniform buffer.
@@ -59,10 +59,95 @@ pub struct GlowParamsUniform {
     pub glow_intensity: f32,
     /// Steepness of falloff (higher = sharper edge, 1.0-4.0).
     pub glow_steepness: f32,
+    /// Factor applied to `glow_size` (1/zoom with zoom compensation, else 1).
+    pub zoom_scale: f32,
+}
+
+/// Parameters for the trail fade pass.
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct TrailParamsUniform {
+    /// Background color (rgb) and per-frame fade alpha (a).
+    pub fade_color: [f32; 4],
+}
+
+/// Parameters for the background gradient pass.
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct BackgroundParamsUniform {
+    /// Top color (rgb) and pass alpha (a).
+    pub top: [f32; 4],
+    /// Bottom color (rgb); alpha unused.
+    pub bottom: [f32; 4],
+}
+
+/// Parameters for the vignette pass.
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct VignetteParamsUniform {
+    /// Darkening at the corners (0 = none, 1 = black).
+    pub intensity: f32,
+    /// Padding to 16 bytes.
+    pub _padding: [f32; 3],
+}
+
+/// Parameters for particle coloring.
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct ColorParamsUniform {
+    /// Color mode selector (see [`crate::simulation::ColorMode::shader_id`]).
+    pub mode: u32,
+    /// Number of entries in the colormap buffer.
+    pub colormap_size: u32,
+    /// Speed mapped to the last colormap entry.
+    pub speed_range: f32,
+    /// Streak length per unit of speed (0 = round dots).
+    pub streak_length: f32,
+    /// Hue rotation applied to type colors, in radians.
+    pub hue_offset: f32,
+    /// Fraction of each axis faded out near the world edges (Wrap mode only).
+    pub seam_fade: f32,
+    /// Depth range of 3D mode; sprites shrink and dim with depth (0 = 3D off).
+    pub depth_limit: f32,
     /// Padding for alignment.
     pub _padding: f32,
 }
 
+impl ColorParamsUniform {
+    /// Create color parameters from simulation config. `hue_offset` (degrees)
+    /// only applies while palette animation is enabled.
+    pub fn from_config(config: &SimulationConfig, colormap_size: u32, hue_offset: f32) -> Self {
+        Self {
+            mode: config.color_mode.shader_id(),
+            colormap_size,
+            speed_range: config.color_speed_range.max(f32::EPSILON),
+            streak_length: if config.velocity_streaks {
+                config.streak_length.max(0.0)
+            } else {
+                0.0
+            },
+            hue_offset: if config.animate_palette {
+                hue_offset.to_radians()
+            } else {
+                0.0
+            },
+            seam_fade: if config.boundary_mode == BoundaryMode::Wrap
+                && config.world_shape == WorldShape::Rectangle
+            {
+                config.seam_fade.clamp(0.0, 0.5)
+            } else {
+                0.0
+            },
+            depth_limit: if config.enable_3d {
+                config.depth_limit.max(1.0)
+            } else {
+                0.0
+            },
+            _padding: 0.0,
+        }
+    }
+}
+
 /// Parameters for mirror wrap rendering.
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 #[repr(C)]
@@ -140,18 +225,35 @@ impl InfiniteParamsUniform {
 }
 
 impl GlowParamsUniform {
-    /// Create glow parameters from simulation config.
-    pub fn from_config(config: &SimulationConfig) -> Self {
+    /// Create glow parameters from simulation config and camera zoom.
+    pub fn from_config(config: &SimulationConfig, zoom: f32) -> Self {
         Self {
             glow_size: config.glow_size,
             glow_intensity: config.glow_intensity,
             glow_steepness: config.glow_steepness,
-            _padding: 0.0,
+            zoom_scale: if config.glow_zoom_compensate {
+                1.0 / zoom.max(f32::EPSILON)
+            } else {
+                1.0
+            },
         }
     }
 }
 
+/// Number of simultaneous brush points (mouse, touches, pinned points and
+/// the gravity well).
+pub const MAX_BRUSH_POINTS: usize = 5;
+
+/// Brush slot reserved for the gravity well.
+pub const GRAVITY_WELL_SLOT: usize = MAX_BRUSH_POINTS - 1;
+
+/// Slots left for touch and pinned points after the mouse and gravity well.
+pub const MAX_EXTRA_BRUSH_POINTS: usize = MAX_BRUSH_POINTS - 2;
+
 /// Uniform buffer for brush interaction parameters.
+///
+/// The brush buffer holds [`MAX_BRUSH_POINTS`] of these; the advance shader
+/// sums the forces of every active point.
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 #[repr(C)]
 pub struct BrushParamsUniform {
@@ -175,11 +277,87 @@ pub struct BrushParamsUniform {
     pub num_particles: u32,
     /// Target particle type (-1 for all).
     pub target_type: i32,
+    /// Bitmask of locked particle types the brush must not affect.
+    pub locked_mask: u32,
+    /// Ignore `radius` and pull across the whole world (1 = gravity well).
+    pub infinite_range: u32,
+    /// Distance falloff exponent for infinite-range points (1 = inverse distance).
+    pub falloff_exponent: f32,
+    /// Paused sculpting: when positive, `brush_force.wgsl` moves particles by
+    /// the velocity change times this dt and leaves velocities alone.
+    pub sculpt_dt: f32,
+    /// World width, for keeping sculpted particles inside the world.
+    pub world_width: f32,
+    /// World height, for keeping sculpted particles inside the world.
+    pub world_height: f32,
+    /// Wrap sculpted particles around the world edges (1) instead of
+    /// clamping them (0).
+    pub sculpt_wrap: u32,
     /// Padding for 16-byte alignment.
-    pub _padding: [u32; 2],
+    pub _padding: [u32; 3],
 }
 
 impl BrushParamsUniform {
+    /// Build the full brush point array: the mouse brush first, then touch
+    /// points, then pinned points, with the gravity well in the last slot.
+    /// Unused slots are inactive.
+    pub fn points_from_brush_state(
+        brush: &crate::app::BrushState,
+        num_particles: u32,
+    ) -> [Self; MAX_BRUSH_POINTS] {
+        let mouse = Self::from_brush_state(brush, num_particles);
+        let mut points = [Self {
+            is_active: 0,
+            ..mouse
+        }; MAX_BRUSH_POINTS];
+        points[0] = mouse;
+
+        let force = brush.get_force();
+        let extra = brush
+            .touch_points
+            .iter()
+            .map(|&(_, position)| (position, force))
+            .chain(brush.pinned_points.iter().map(|p| (p.position, p.force)));
+        for (slot, (position, force)) in points[1..GRAVITY_WELL_SLOT].iter_mut().zip(extra) {
+            *slot = Self {
+                pos_x: position.x,
+                pos_y: position.y,
+                vel_x: 0.0,
+                vel_y: 0.0,
+                force,
+                is_active: u32::from(force != 0.0),
+                ..mouse
+            };
+        }
+
+        if brush.gravity_well {
+            let force = brush.well_strength * 10.0;
+            points[GRAVITY_WELL_SLOT] = Self {
+                vel_x: 0.0,
+                vel_y: 0.0,
+                force,
+                is_active: u32::from(force != 0.0),
+                target_type: -1,
+                infinite_range: 1,
+                falloff_exponent: brush.well_falloff,
+                ..mouse
+            };
+        }
+        points
+    }
+
+    /// Brush points with every slot inactive, so the advance shader applies
+    /// no brush force at all.
+    pub fn inactive_points(
+        brush: &crate::app::BrushState,
+        num_particles: u32,
+    ) -> [Self; MAX_BRUSH_POINTS] {
+        [Self {
+            is_active: 0,
+            ..Self::from_brush_state(brush, num_particles)
+        }; MAX_BRUSH_POINTS]
+    }
+
     /// Create brush parameters from brush state.
     pub fn from_brush_state(brush: &crate::app::BrushState, num_particles: u32) -> Self {
         Self {
@@ -193,15 +371,45 @@ impl BrushParamsUniform {
             is_active: if brush.is_active { 1 } else { 0 },
             num_particles,
             target_type: brush.target_type,
-            _padding: [0; 2],
+            locked_mask: brush.locked_mask(),
+            infinite_range: 0,
+            falloff_exponent: 0.0,
+            sculpt_dt: 0.0,
+            world_width: 0.0,
+            world_height: 0.0,
+            sculpt_wrap: 0,
+            _padding: [0; 3],
         }
     }
+
+    /// Brush points for sculpting while paused: only the mouse brush, moving
+    /// particles by `dt` worth of its velocity change.
+    pub fn sculpt_points(
+        brush: &crate::app::BrushState,
+        num_particles: u32,
+        dt: f32,
+        config: &crate::simulation::SimulationConfig,
+    ) -> [Self; MAX_BRUSH_POINTS] {
+        let mouse = Self {
+            sculpt_dt: dt,
+            world_width: config.world_size.x,
+            world_height: config.world_size.y,
+            sculpt_wrap: u32::from(crate::simulation::uses_wrapped_distance(config)),
+            ..Self::from_brush_state(brush, num_particles)
+        };
+        let mut points = [Self {
+            is_active: 0,
+            ..mouse
+        }; MAX_BRUSH_POINTS];
+        points[0] = mouse;
+        points
+    }
 }
 
 /// Uniform buffer for brush circle rendering parameters.
 ///
-/// WGSL memory layout: vec3<f32> has 16-byte alignment, so the struct
-/// needs explicit padding to match. Total size must be 80 bytes.
+/// WGSL memory layout: the trailing vec4<f32> padding is 16-byte aligned,
+/// so the struct needs explicit padding to match. Total size must be 80 bytes.
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 #[repr(C)]
 pub struct BrushRenderUniform {
@@ -231,10 +439,14 @@ pub struct BrushRenderUniform {
     pub camera_offset_x: f32,
     /// Camera offset Y.
     pub camera_offset_y: f32,
-    /// Padding to align vec3 to 16-byte boundary (52 bytes -> 64 bytes).
-    pub _padding1: [f32; 3],
-    /// Padding matching WGSL vec3<f32> (16-byte aligned, takes 16 bytes).
-    pub _padding2: [f32; 4],
+    /// Outline width in world units.
+    pub line_width: f32,
+    /// Opacity of the filled disk (0 = outline only).
+    pub fill_alpha: f32,
+    /// Draw contrast halo around the outline (0 = off, 1 = on).
+    pub contrast: u32,
+    /// Padding matching WGSL vec4<f32> (64 bytes -> 80 bytes).
+    pub _padding: [f32; 4],
 }
 
 impl BrushRenderUniform {
@@ -247,14 +459,15 @@ impl BrushRenderUniform {
         camera_offset_x: f32,
         camera_offset_y: f32,
     ) -> Self {
-        // Color based on tool type
-        let (r, g, b) = match brush.tool {
-            crate::app::BrushTool::None => (0.5, 0.5, 0.5),
-            crate::app::BrushTool::Draw => (0.2, 0.8, 0.2),
-            crate::app::BrushTool::Erase => (0.8, 0.2, 0.2),
-            crate::app::BrushTool::Attract => (0.2, 0.6, 0.9),
-            crate::app::BrushTool::Repel => (0.9, 0.6, 0.2),
-        };
+        // Color based on tool type unless overridden
+        let [r, g, b] = brush.circle_color.unwrap_or(match brush.tool {
+            crate::app::BrushTool::None => [0.5, 0.5, 0.5],
+            crate::app::BrushTool::Draw => [0.2, 0.8, 0.2],
+            crate::app::BrushTool::Erase => [0.8, 0.2, 0.2],
+            crate::app::BrushTool::Attract => [0.2, 0.6, 0.9],
+            crate::app::BrushTool::Repel => [0.9, 0.6, 0.2],
+            crate::app::BrushTool::Emitter => [0.8, 0.3, 0.9],
+        });
 
         Self {
             pos_x: brush.position.x,
@@ -274,8 +487,10 @@ impl BrushRenderUniform {
             camera_zoom,
             camera_offset_x,
             camera_offset_y,
-            _padding1: [0.0; 3],
-            _padding2: [0.0; 4],
+            line_width: brush.circle_line_width.max(0.1),
+            fill_alpha: brush.circle_fill_alpha.clamp(0.0, 1.0),
+            contrast: u32::from(brush.circle_contrast),
+            _padding: [0.0; 4],
         }
     }
 }
@@ -314,15 +529,52 @@ pub struct SimParamsUniform {
     pub max_bin_density: f32,
     /// Maximum neighbors to check per particle (0 = unlimited).
     pub neighbor_budget: u32,
-    /// Padding to match WGSL struct alignment (vec3<u32> requires 16-byte alignment + struct rounds to 16 bytes).
-    _padding: [u32; 6],
+    /// Multiplier applied to interaction matrix values only.
+    pub matrix_strength: f32,
+    /// World shape (0=Rectangle, 1=Circle).
+    pub world_shape: u32,
+    /// Spin torque from tangential velocity (0 = spin disabled).
+    pub spin_coupling: f32,
+    /// Rotational friction for spin.
+    pub spin_friction: f32,
+    /// Per-step seed for fair neighbor sampling (see [`fair_sample_seed`]).
+    pub sample_seed: u32,
+    /// Spread budgeted neighbor samples across each bin (1) instead of
+    /// taking the first ones (0).
+    pub fair_budget: u32,
+    /// Velocity clamp mode (0=Hard, 1=SoftTanh).
+    pub velocity_clamp_mode: u32,
+    /// Particle sprite shape (0=HardDisk, 1=SoftDisk, 2=Square, 3=Gaussian).
+    pub particle_shape: u32,
+    /// Thermostat multiplier on every velocity, applied before clamping
+    /// (1.0 = thermostat off).
+    pub velocity_scale: f32,
+    /// Repulsion core radius as a fraction of min radius (clamped).
+    pub repel_radius: f32,
+}
+
+/// Seed for fair neighbor sampling in physics step `substep` of frame
+/// `frame`, counted since the particles were spawned.
+///
+/// Mixing in the simulation seed makes different seeds sample differently.
+/// The GPU still isn't bit-reproducible, since the shader hashes sorted
+/// indices whose order within a bin depends on the bin sort's atomics.
+/// Unseeded runs (`None`) mix in 0.
+pub fn fair_sample_seed(seed: Option<u64>, frame: u32, substep: u32) -> u32 {
+    // SplitMix64 finalizer over the seed and step index
+    let step = (u64::from(frame) << 32) | u64::from(substep);
+    let mut x = seed
+        .unwrap_or(0)
+        .wrapping_add(step.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    (x ^ (x >> 32)) as u32
 }
 
 impl SimParamsUniform {
     /// Create uniform parameters from simulation config.
     pub fn from_config(config: &SimulationConfig, dt: f32) -> Self {
-        use crate::simulation::BoundaryMode;
-
         Self {
             num_particles: config.num_particles,
             num_types: config.num_types,
@@ -332,18 +584,22 @@ impl SimParamsUniform {
             max_velocity: config.max_velocity,
             world_width: config.world_size.x,
             world_height: config.world_size.y,
-            boundary_mode: match config.boundary_mode {
-                BoundaryMode::Repel => 0,
-                BoundaryMode::Wrap => 1,
-                BoundaryMode::MirrorWrap => 2,
-                BoundaryMode::InfiniteWrap => 3,
-            },
+            boundary_mode: config.boundary_mode.shader_id(),
             wall_repel_strength: config.wall_repel_strength,
             particle_size: config.particle_size,
             dt,
             max_bin_density: config.max_bin_density,
             neighbor_budget: config.neighbor_budget,
-            _padding: [0; 6],
+            matrix_strength: config.matrix_strength,
+            world_shape: config.world_shape.shader_id(),
+            spin_coupling: config.spin_coupling,
+            spin_friction: config.spin_friction,
+            sample_seed: 0,
+            fair_budget: u32::from(config.fair_neighbor_budget),
+            velocity_clamp_mode: config.velocity_clamp.shader_id(),
+            particle_shape: config.particle_shape.shader_id(),
+            velocity_scale: 1.0,
+            repel_radius: config.repel_core_fraction(),
         }
     }
 }
@@ -358,6 +614,9 @@ pub struct SimulationBuffers {
     pub pos_type: [Buffer; 2],
     /// Velocity buffers (double-buffered).
     pub velocities: [Buffer; 2],
+    /// Per-particle 3D depth (`f32`) buffers (double-buffered so the bin
+    /// sort can carry each depth along with its particle).
+    pub depths: [Buffer; 2],
     /// Current buffer index (0 or 1) - the "read" buffer for rendering.
     pub current_buffer: usize,
     /// Interaction matrix buffer.
@@ -366,12 +625,18 @@ pub struct SimulationBuffers {
     pub min_radius: Buffer,
     /// Maximum radius matrix buffer.
     pub max_radius: Buffer,
+    /// Per-type anisotropy buffer (`vec4<f32>(cos, sin, aspect, 0)`).
+    pub anisotropy: Buffer,
+    /// Per-type physics buffer (`vec2<f32>(friction, force_factor)`).
+    pub type_physics: Buffer,
     /// Simulation parameters uniform buffer.
     pub params: Buffer,
     /// Color palette buffer for particle types.
     pub colors: Buffer,
     /// Current number of particles.
     pub num_particles: u32,
+    /// Particle slots allocated in each particle buffer (>= `num_particles`).
+    pub capacity: u32,
     /// Current number of particle types.
     pub num_types: u32,
     /// Whether to use half-precision (f16) for particle storage.
@@ -399,6 +664,15 @@ impl SimulationBuffers {
         let num_particles = particles.len() as u32;
         let num_types = config.num_types;
 
+        // Storage bindings can't be empty: with no particles (a cleared
+        // canvas) keep room for one so the brush can refill in place
+        let placeholder = [Particle::new(0.0, 0.0, 0)];
+        let particles = if particles.is_empty() {
+            &placeholder[..]
+        } else {
+            particles
+        };
+
         // Check if we should use F16 (based on device features)
         // We can't access device features directly from here easily without passing them or checking device.
         // Assuming the caller will recreate buffers if they want to switch mode is safer, but here we check device.
@@ -452,6 +726,17 @@ impl SimulationBuffers {
             (v0, v1)
         };
 
+        // Depths are never simulated, but the bin sort reorders them with
+        // the particles, so they ping-pong like positions and velocities
+        let depth_data: Vec<f32> = particles.iter().map(|p| p.z).collect();
+        let depths = ["Particle Depth Buffer 0", "Particle Depth Buffer 1"].map(|label| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                contents: bytemuck::cast_slice(&depth_data),
+                usage: BufferUsages::STORAGE | BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
+            })
+        });
+
         // Create interaction matrix buffer
         let interaction_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Interaction Matrix Buffer"),
@@ -473,6 +758,20 @@ impl SimulationBuffers {
             usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
         });
 
+        // Create per-type anisotropy buffer
+        let anisotropy_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Anisotropy Buffer"),
+            contents: bytemuck::cast_slice(&radius_matrix.anisotropy_gpu_data()),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        });
+
+        // Create per-type friction/force factor buffer
+        let type_physics_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Type Physics Buffer"),
+            contents: bytemuck::cast_slice(&config.type_physics_gpu_data()),
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        });
+
         // Create simulation params uniform buffer
         let params = SimParamsUniform::from_config(config, 1.0 / 60.0);
         let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
@@ -491,13 +790,17 @@ impl SimulationBuffers {
         Self {
             pos_type: [pt0, pt1],
             velocities: [vel_buffer_0, vel_buffer_1],
+            depths,
             current_buffer: 0,
             interaction_matrix: interaction_buffer,
             min_radius: min_radius_buffer,
             max_radius: max_radius_buffer,
+            anisotropy: anisotropy_buffer,
+            type_physics: type_physics_buffer,
             params: params_buffer,
             colors: colors_buffer,
             num_particles,
+            capacity: particles.len() as u32,
             num_types,
             use_f16,
         }
@@ -523,32 +826,115 @@ impl SimulationBuffers {
         &self.velocities[1 - self.current_buffer]
     }
 
+    /// Get the current depth buffer.
+    pub fn current_depths(&self) -> &Buffer {
+        &self.depths[self.current_buffer]
+    }
+
+    /// Get the next depth buffer.
+    pub fn next_depths(&self) -> &Buffer {
+        &self.depths[1 - self.current_buffer]
+    }
+
     /// Swap the particle buffers after compute pass.
     pub fn swap_buffers(&mut self) {
         self.current_buffer = 1 - self.current_buffer;
     }
 
+    /// Bytes allocated for the particle position/velocity/depth storage.
+    pub fn particle_bytes(&self) -> u64 {
+        self.pos_type
+            .iter()
+            .chain(self.velocities.iter())
+            .chain(self.depths.iter())
+            .map(Buffer::size)
+            .sum::<u64>()
+    }
+
+    /// Bytes allocated for the interaction/radius matrices and per-type tables.
+    pub fn matrix_bytes(&self) -> u64 {
+        self.interaction_matrix.size()
+            + self.min_radius.size()
+            + self.max_radius.size()
+            + self.anisotropy.size()
+            + self.type_physics.size()
+    }
+
+    /// Estimated GPU memory used by all simulation buffers, in bytes.
+    pub fn estimated_bytes(&self) -> u64 {
+        self.particle_bytes() + self.matrix_bytes() + self.params.size() + self.colors.size()
+    }
+
     /// Update both particle buffers with new data.
     pub fn update_particles(&self, queue: &Queue, particles: &[Particle]) {
-        let pos_type_data: Vec<ParticlePosType> =
-            particles.iter().map(ParticlePosType::from).collect();
+        self.write_particles_from(queue, particles, 0);
+    }
+
+    /// Upload `particles[start..]` into both particle buffers at the same
+    /// offset, leaving the first `start` particles untouched.
+    pub fn write_particles_from(&self, queue: &Queue, particles: &[Particle], start: usize) {
+        let Some(tail) = particles.get(start..).filter(|tail| !tail.is_empty()) else {
+            return;
+        };
+
+        let pos_type_data: Vec<ParticlePosType> = tail.iter().map(ParticlePosType::from).collect();
         let pos_type_bytes = bytemuck::cast_slice(&pos_type_data);
+        let pos_offset = (start * std::mem::size_of::<ParticlePosType>()) as u64;
+        queue.write_buffer(&self.pos_type[0], pos_offset, pos_type_bytes);
+        queue.write_buffer(&self.pos_type[1], pos_offset, pos_type_bytes);
+        let depth_data: Vec<f32> = tail.iter().map(|p| p.z).collect();
+        let depth_offset = (start * std::mem::size_of::<f32>()) as u64;
+        queue.write_buffer(
+            &self.depths[0],
+            depth_offset,
+            bytemuck::cast_slice(&depth_data),
+        );
+        queue.write_buffer(
+            &self.depths[1],
+            depth_offset,
+            bytemuck::cast_slice(&depth_data),
+        );
+        self.write_velocities_from(queue, tail, start);
+    }
 
-        queue.write_buffer(&self.pos_type[0], 0, pos_type_bytes);
-        queue.write_buffer(&self.pos_type[1], 0, pos_type_bytes);
+    /// Upload only the velocities of `particles` into both velocity buffers,
+    /// leaving positions, types and spin untouched.
+    pub fn update_velocities(&self, queue: &Queue, particles: &[Particle]) {
+        if !particles.is_empty() {
+            self.write_velocities_from(queue, particles, 0);
+        }
+    }
 
+    /// Write the velocities of `tail` into both velocity buffers starting at
+    /// particle index `start`.
+    fn write_velocities_from(&self, queue: &Queue, tail: &[Particle], start: usize) {
         if self.use_f16 {
-            let vel_data: Vec<ParticleVelHalf> =
-                particles.iter().map(ParticleVelHalf::from).collect();
+            let vel_data: Vec<ParticleVelHalf> = tail.iter().map(ParticleVelHalf::from).collect();
             let vel_bytes = bytemuck::cast_slice(&vel_data);
-            queue.write_buffer(&self.velocities[0], 0, vel_bytes);
-            queue.write_buffer(&self.velocities[1], 0, vel_bytes);
+            let vel_offset = (start * std::mem::size_of::<ParticleVelHalf>()) as u64;
+            queue.write_buffer(&self.velocities[0], vel_offset, vel_bytes);
+            queue.write_buffer(&self.velocities[1], vel_offset, vel_bytes);
         } else {
-            let vel_data: Vec<ParticleVel> = particles.iter().map(ParticleVel::from).collect();
+            let vel_data: Vec<ParticleVel> = tail.iter().map(ParticleVel::from).collect();
             let vel_bytes = bytemuck::cast_slice(&vel_data);
-            queue.write_buffer(&self.velocities[0], 0, vel_bytes);
-            queue.write_buffer(&self.velocities[1], 0, vel_bytes);
+            let vel_offset = (start * std::mem::size_of::<ParticleVel>()) as u64;
+            queue.write_buffer(&self.velocities[0], vel_offset, vel_bytes);
+            queue.write_buffer(&self.velocities[1], vel_offset, vel_bytes);
+        }
+    }
+
+    /// Change the active particle count in place when it fits the
+    /// allocated buffers, uploading only the particles from `keep` on.
+    ///
+    /// Returns `false` (and changes nothing) when `particles` exceeds the
+    /// capacity, in which case the buffers must be recreated.
+    pub fn resize_particles(&mut self, queue: &Queue, particles: &[Particle], keep: usize) -> bool {
+        if particles.len() > self.capacity as usize {
+            return false;
         }
+        self.write_particles_from(queue, particles, keep);
+        self.num_particles = particles.len() as u32;
+        true
     }
 
     /// Update interaction matrix buffer.
@@ -572,12 +958,51 @@ impl SimulationBuffers {
             0,
             bytemuck::cast_slice(&matrix.max_radius),
         );
+        queue.write_buffer(
+            &self.anisotropy,
+            0,
+            bytemuck::cast_slice(&matrix.anisotropy_gpu_data()),
+        );
     }
 
-    /// Update simulation parameters uniform.
-    pub fn update_params(&self, queue: &Queue, config: &SimulationConfig, dt: f32) {
-        let params = SimParamsUniform::from_config(config, dt);
+    /// Update simulation parameters uniform. `sample_seed` seeds fair
+    /// neighbor sampling (see [`fair_sample_seed`]); `velocity_scale` is the
+    /// thermostat's per-step multiplier.
+    pub fn update_params(
+        &self,
+        queue: &Queue,
+        config: &SimulationConfig,
+        dt: f32,
+        sample_seed: u32,
+        velocity_scale: f32,
+    ) {
+        let params = SimParamsUniform {
+            sample_seed,
+            velocity_scale,
+            ..SimParamsUniform::from_config(config, dt)
+        };
         queue.write_buffer(&self.params, 0, bytemuck::bytes_of(&params));
+
+        // Overrides are edited live from the UI; the buffer is sized for the
+        // type count it was created with, so never write past it.
+        let type_physics = config.type_physics_gpu_data();
+        let capacity = self.type_physics.size() as usize / std::mem::size_of::<[f32; 2]>();
+        let len = type_physics.len().min(capacity);
+        queue.write_buffer(
+            &self.type_physics,
+            0,
+            bytemuck::cast_slice(&type_physics[..len]),
+        );
+    }
+
+    /// Replace only the fair sampling seed, e.g. between the substeps of a
+    /// frame, leaving the other parameters as uploaded.
+    pub fn update_sample_seed(&self, queue: &Queue, sample_seed: u32) {
+        queue.write_buffer(
+            &self.params,
+            std::mem::offset_of!(SimParamsUniform, sample_seed) as u64,
+            bytemuck::bytes_of(&sample_seed),
+        );
     }
 
     /// Update color palette buffer.
@@ -585,11 +1010,50 @@ impl SimulationBuffers {
         queue.write_buffer(&self.colors, 0, bytemuck::cast_slice(colors));
     }
 
+    /// Read only positions and types back from the GPU.
+    ///
+    /// Cheaper than [`Self::read_particles`] since velocities are skipped.
+    /// Note: This blocks until the GPU is done.
+    pub fn read_pos_types(&self, device: &Device, queue: &Queue) -> Vec<ParticlePosType> {
+        let size = u64::from(self.num_particles) * std::mem::size_of::<ParticlePosType>() as u64;
+        if size == 0 {
+            return Vec::new();
+        }
+
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle PosType Readback Buffer"),
+            size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("PosType Readback Encoder"),
+        });
+        encoder.copy_buffer_to_buffer(self.current_pos_type(), 0, &staging, 0, size);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+        rx.recv().unwrap().unwrap();
+
+        let pos_types = bytemuck::cast_slice(&slice.get_mapped_range()).to_vec();
+        staging.unmap();
+        pos_types
+    }
+
     /// Read particles back from GPU (for debugging or saving).
     ///
     /// Note: This blocks until the GPU is done.
     pub fn read_particles(&self, device: &Device, queue: &Queue) -> Vec<Particle> {
         let num = self.num_particles as usize;
+        if num == 0 {
+            return Vec::new();
+        }
 
         let pos_type_size = num * std::mem::size_of::<ParticlePosType>();
 
@@ -598,6 +1062,7 @@ impl SimulationBuffers {
         } else {
             num * std::mem::size_of::<ParticleVel>()
         };
+        let depth_size = num * std::mem::size_of::<f32>();
 
         // Create staging buffers for readback
         let staging_pos_type = device.create_buffer(&wgpu::BufferDescriptor {
@@ -614,6 +1079,13 @@ impl SimulationBuffers {
             mapped_at_creation: false,
         });
 
+        let staging_depth = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Particle Depth Staging Buffer"),
+            size: depth_size as u64,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
         // Copy from particle buffers to staging
         let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Particle Readback Encoder"),
@@ -632,14 +1104,23 @@ impl SimulationBuffers {
             0,
             vel_size as u64,
         );
+        encoder.copy_buffer_to_buffer(
+            self.current_depths(),
+            0,
+            &staging_depth,
+            0,
+            depth_size as u64,
+        );
         queue.submit(std::iter::once(encoder.finish()));
 
         // Map the staging buffers
         let slice_pos = staging_pos_type.slice(..);
         let slice_vel = staging_vel.slice(..);
+        let slice_depth = staging_depth.slice(..);
 
         let (tx, rx) = std::sync::mpsc::channel();
         let tx2 = tx.clone();
+        let tx3 = tx.clone();
 
         slice_pos.map_async(wgpu::MapMode::Read, move |result| {
             let _ = tx.send(result);
@@ -647,18 +1128,24 @@ impl SimulationBuffers {
         slice_vel.map_async(wgpu::MapMode::Read, move |result| {
             let _ = tx2.send(result);
         });
+        slice_depth.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx3.send(result);
+        });
 
         device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
 
-        // Wait for both mappings
-        rx.recv().unwrap().unwrap();
-        rx.recv().unwrap().unwrap();
+        // Wait for all mappings
+        for _ in 0..3 {
+            rx.recv().unwrap().unwrap();
+        }
 
         // Read the data
         let data_pos = slice_pos.get_mapped_range();
         let data_vel = slice_vel.get_mapped_range();
+        let data_depth = slice_depth.get_mapped_range();
 
         let pos_types: &[ParticlePosType] = bytemuck::cast_slice(&data_pos);
+        let depths: &[f32] = bytemuck::cast_slice(&data_depth);
         let mut particles = Vec::with_capacity(num);
 
         if self.use_f16 {
@@ -670,8 +1157,10 @@ impl SimulationBuffers {
                     y: pos_types[i].y,
                     vx: vels[i].vx.to_f32(),
                     vy: vels[i].vy.to_f32(),
-                    particle_type: pos_types[i].particle_type,
-                    _padding1: [0; 3],
+                    particle_type: pos_types[i].type_index(),
+                    is_static: u32::from(pos_types[i].is_static()),
+                    z: depths[i],
+                    _padding1: 0,
                     _padding2: [0; 4],
                 });
             }
@@ -684,8 +1173,10 @@ impl SimulationBuffers {
                     y: pos_types[i].y,
                     vx: vels[i].vx,
                     vy: vels[i].vy,
-                    particle_type: pos_types[i].particle_type,
-                    _padding1: [0; 3],
+                    particle_type: pos_types[i].type_index(),
+                    is_static: u32::from(pos_types[i].is_static()),
+                    z: depths[i],
+                    _padding1: 0,
                     _padding2: [0; 4],
                 });
             }
@@ -693,11 +1184,82 @@ impl SimulationBuffers {
 
         drop(data_pos);
         drop(data_vel);
+        drop(data_depth);
         staging_pos_type.unmap();
         staging_vel.unmap();
+        staging_depth.unmap();
 
         particles
     }
+
+    /// Read velocities back from the GPU and return the mean speed and
+    /// kinetic temperature.
+    ///
+    /// Note: This blocks until the GPU is done.
+    pub fn read_velocity_stats(&self, device: &Device, queue: &Queue) -> VelocityStats {
+        let num = self.num_particles as usize;
+        let vel_size = if self.use_f16 {
+            num * std::mem::size_of::<ParticleVelHalf>()
+        } else {
+            num * std::mem::size_of::<ParticleVel>()
+        } as u64;
+        if vel_size == 0 {
+            return VelocityStats::default();
+        }
+
+        let staging = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Velocity Readback Buffer"),
+            size: vel_size,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Velocity Readback Encoder"),
+        });
+        encoder.copy_buffer_to_buffer(self.current_velocities(), 0, &staging, 0, vel_size);
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = staging.slice(..);
+        let (tx, rx) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+        rx.recv().unwrap().unwrap();
+
+        let (speed_sum, speed_sq_sum) = {
+            let data = slice.get_mapped_range();
+            let sums = |(speed, speed_sq): (f64, f64), (vx, vy): (f32, f32)| {
+                let v = f64::from(vx.hypot(vy));
+                (speed + v, speed_sq + v * v)
+            };
+            if self.use_f16 {
+                let vels: &[ParticleVelHalf] = bytemuck::cast_slice(&data);
+                vels.iter()
+                    .map(|v| (v.vx.to_f32(), v.vy.to_f32()))
+                    .fold((0.0, 0.0), sums)
+            } else {
+                let vels: &[ParticleVel] = bytemuck::cast_slice(&data);
+                vels.iter().map(|v| (v.vx, v.vy)).fold((0.0, 0.0), sums)
+            }
+        };
+        staging.unmap();
+        VelocityStats {
+            mean_speed: (speed_sum / num as f64) as f32,
+            temperature: (0.5 * speed_sq_sum / num as f64) as f32,
+        }
+    }
+}
+
+/// Summary of particle velocities from [`SimulationBuffers::read_velocity_stats`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct VelocityStats {
+    /// Mean particle speed.
+    pub mean_speed: f32,
+    /// Kinetic temperature: mean kinetic energy per particle (`0.5 * |v|^2`,
+    /// unit mass).
+    pub temperature: f32,
 }
 
 /// Manages render-specific GPU buffers.
@@ -733,30 +1295,61 @@ impl RenderBuffers {
     }
 }
 
+/// Elements scanned by one workgroup of the tiled prefix sum.
+pub const PREFIX_SCAN_BLOCK_SIZE: u32 = 2048;
+
+/// Element count at each level of a tiled prefix sum over `len` values.
+///
+/// Level 0 is the data itself; each further level holds one block total per
+/// block of the level below, until a single block covers a level.
+pub fn prefix_scan_levels(len: u32) -> Vec<u32> {
+    let mut levels = vec![len.max(1)];
+    while let Some(&last) = levels.last()
+        && last > PREFIX_SCAN_BLOCK_SIZE
+    {
+        levels.push(last.div_ceil(PREFIX_SCAN_BLOCK_SIZE));
+    }
+    levels
+}
+
+/// Allocate the block-total buffers for a tiled prefix sum over `len` values,
+/// one per level (the last holds the grand total).
+pub fn create_prefix_scan_block_sums(device: &Device, len: u32) -> Vec<Buffer> {
+    prefix_scan_levels(len)
+        .iter()
+        .enumerate()
+        .map(|(level, &level_len)| {
+            let blocks = level_len.div_ceil(PREFIX_SCAN_BLOCK_SIZE);
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(&format!("Prefix Scan Block Sums Level {}", level)),
+                size: u64::from(blocks) * std::mem::size_of::<u32>() as u64,
+                usage: BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            })
+        })
+        .collect()
+}
+
 /// Manages GPU buffers for spatial hashing optimization.
 ///
 /// The spatial hash divides the world into a grid of cells (bins).
 /// Particles are counted and sorted by bin for efficient neighbor lookup.
 pub struct SpatialHashBuffers {
-    /// Count/offset buffer A for ping-pong prefix sum.
+    /// Bin counts, scanned in place into bin offsets.
     /// Size: total_bins + 1 (extra element for end offset).
     pub bin_counts_a: Buffer,
-    /// Count/offset buffer B for ping-pong prefix sum.
+    /// Per-bin atomic counters used while sorting.
     pub bin_counts_b: Buffer,
+    /// Block totals for each level of the prefix sum over `bin_counts_a`.
+    pub scan_block_sums: Vec<Buffer>,
     /// Spatial parameters uniform buffer.
     pub params: Buffer,
     /// Total bins uniform (for clear shader).
     pub total_bins_uniform: Buffer,
-    /// Prefix sum step size uniform (legacy, kept for compatibility).
-    pub step_size_uniform: Buffer,
-    /// Pre-allocated step size uniforms for each prefix sum pass.
-    /// This allows all passes to be added to a single encoder without
-    /// having to submit between passes for uniform updates.
-    pub step_size_uniforms: Vec<Buffer>,
     /// Current spatial parameters.
     pub spatial_params: SpatialParamsUniform,
-    /// Which buffer has the current prefix sum result (0 = A, 1 = B).
-    pub current_offset_buffer: usize,
+    /// Bins (plus end offset) the bin buffers were allocated for.
+    allocated_bins: u32,
 }
 
 impl SpatialHashBuffers {
@@ -768,7 +1361,7 @@ impl SpatialHashBuffers {
         // +1 for the extra end offset element
         let bin_buffer_size = ((total_bins + 1) as usize) * std::mem::size_of::<u32>();
 
-        // Create bin count/offset buffers for ping-pong
+        // Create bin count/offset and sort counter buffers
         let bin_counts_a = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("Bin Counts Buffer A"),
             size: bin_buffer_size as u64,
@@ -797,47 +1390,25 @@ impl SpatialHashBuffers {
             usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
         });
 
-        // Step size uniform for prefix sum (legacy)
-        let step_size_uniform = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Step Size Uniform"),
-            contents: bytemuck::bytes_of(&1u32),
-            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
-        });
-
-        // Pre-allocate step size uniforms for all possible prefix sum passes.
-        // We allocate for the maximum (32 passes) to handle any cell size changes
-        // without needing to reallocate buffers. Each buffer is just 4 bytes.
-        const MAX_PREFIX_PASSES: u32 = 32;
-        let step_size_uniforms: Vec<Buffer> = (0..MAX_PREFIX_PASSES)
-            .map(|pass_idx| {
-                let step_size = 1u32 << pass_idx;
-                device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-                    label: Some(&format!("Step Size Uniform Pass {}", pass_idx)),
-                    contents: bytemuck::bytes_of(&step_size),
-                    usage: BufferUsages::UNIFORM,
-                })
-            })
-            .collect();
+        // The scan covers the whole allocation so its bind groups and
+        // dispatch sizes stay fixed while the grid size changes.
+        let allocated_bins = total_bins + 1;
+        let scan_block_sums = create_prefix_scan_block_sums(device, allocated_bins);
 
         Self {
             bin_counts_a,
             bin_counts_b,
+            scan_block_sums,
             params,
             total_bins_uniform,
-            step_size_uniform,
-            step_size_uniforms,
             spatial_params,
-            current_offset_buffer: 0,
+            allocated_bins,
         }
     }
 
-    /// Get the current bin offset buffer (result of prefix sum).
+    /// Get the bin offset buffer (result of prefix sum).
     pub fn current_offsets(&self) -> &Buffer {
-        if self.current_offset_buffer == 0 {
-            &self.bin_counts_a
-        } else {
-            &self.bin_counts_b
-        }
+        &self.bin_counts_a
     }
 
     /// Update spatial parameters.
@@ -849,9 +1420,17 @@ impl SpatialHashBuffers {
         queue.write_buffer(&self.total_bins_uniform, 0, bytemuck::bytes_of(&total_bins));
     }
 
-    /// Update step size for prefix sum pass.
-    pub fn update_step_size(&self, queue: &Queue, step_size: u32) {
-        queue.write_buffer(&self.step_size_uniform, 0, bytemuck::bytes_of(&step_size));
+    /// Bytes allocated for the ping-pong bin count/offset buffers.
+    pub fn bin_bytes(&self) -> u64 {
+        self.bin_counts_a.size() + self.bin_counts_b.size()
+    }
+
+    /// Estimated GPU memory used by all spatial hash buffers, in bytes.
+    pub fn estimated_bytes(&self) -> u64 {
+        self.bin_bytes()
+            + self.params.size()
+            + self.total_bins_uniform.size()
+            + self.scan_block_sums.iter().map(Buffer::size).sum::<u64>()
     }
 
     /// Get total number of bins (including end offset element).
@@ -859,11 +1438,16 @@ impl SpatialHashBuffers {
         self.spatial_params.total_bins() + 1
     }
 
-    /// Calculate number of prefix sum passes needed.
+    /// Element count at each level of the prefix sum.
+    pub fn prefix_scan_levels(&self) -> Vec<u32> {
+        prefix_scan_levels(self.allocated_bins)
+    }
+
+    /// Number of prefix sum dispatches: a scan per level plus an offset
+    /// pass for every level below the top.
     pub fn prefix_sum_passes(&self) -> u32 {
-        let total = self.total_bins_with_end();
-        // ceil(log2(total))
-        32 - total.leading_zeros()
+        let levels = self.prefix_scan_levels().len() as u32;
+        2 * levels - 1
     }
 
     /// Read bin counts/offsets buffer back from GPU for debugging.
@@ -904,3 +1488,106 @@ impl SpatialHashBuffers {
         counts
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fair_sample_seed_depends_on_seed_frame_and_substep() {
+        let base = fair_sample_seed(Some(7), 3, 0);
+        // Reproducible for the same seed and step
+        assert_eq!(base, fair_sample_seed(Some(7), 3, 0));
+        // Every input changes it
+        assert_ne!(base, fair_sample_seed(Some(8), 3, 0));
+        assert_ne!(base, fair_sample_seed(Some(7), 4, 0));
+        assert_ne!(base, fair_sample_seed(Some(7), 3, 1));
+        assert_ne!(
+            fair_sample_seed(Some(7), 0, 1),
+            fair_sample_seed(Some(7), 1, 0)
+        );
+    }
+
+    #[test]
+    fn test_depth_limit_only_set_in_3d_mode() {
+        let mut config = SimulationConfig::default();
+        // 0 tells the shaders 3D is off, keeping 2D output unchanged
+        let params = ColorParamsUniform::from_config(&config, 64, 0.0);
+        assert_eq!(params.depth_limit, 0.0);
+
+        config.enable_3d = true;
+        config.depth_limit = 250.0;
+        let params = ColorParamsUniform::from_config(&config, 64, 0.0);
+        assert_eq!(params.depth_limit, 250.0);
+    }
+
+    /// Shrinks and regrows the particle count in place and reads it back;
+    /// skipped when no adapter exists.
+    #[test]
+    fn test_gpu_resize_particles_in_place() {
+        let instance = wgpu::Instance::default();
+        let Ok(adapter) =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        else {
+            eprintln!("No GPU adapter available; skipping particle resize test");
+            return;
+        };
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+                .expect("Failed to create device");
+
+        let config = SimulationConfig {
+            num_types: 2,
+            ..Default::default()
+        };
+        let particles: Vec<Particle> = (0..4)
+            .map(|i| Particle::new(i as f32 * 10.0, 5.0, i % 2))
+            .collect();
+        let mut buffers = SimulationBuffers::new(
+            &device,
+            &particles,
+            &InteractionMatrix::new(2),
+            &RadiusMatrix::default_for_size(2),
+            &[[1.0; 4]; 2],
+            &config,
+        );
+
+        // Shrinking keeps the allocation
+        assert!(buffers.resize_particles(&queue, &particles[..2], 2));
+        assert_eq!(buffers.num_particles, 2);
+        assert_eq!(buffers.capacity, 4);
+
+        // Regrowing within capacity uploads only the new tail
+        let mut grown = particles[..2].to_vec();
+        grown.push(Particle {
+            z: 7.0,
+            ..Particle::new(99.0, 1.0, 1)
+        });
+        assert!(buffers.resize_particles(&queue, &grown, 2));
+        let read = buffers.read_particles(&device, &queue);
+        assert_eq!(read.len(), 3);
+        assert_eq!(read[1].x, 10.0);
+        assert_eq!(read[2].x, 99.0);
+        assert_eq!(read[2].particle_type, 1);
+        assert_eq!(read[2].z, 7.0);
+
+        // Growing past the allocation needs new buffers
+        let too_many = vec![Particle::new(0.0, 0.0, 0); 5];
+        assert!(!buffers.resize_particles(&queue, &too_many, 3));
+        assert_eq!(buffers.num_particles, 3);
+
+        // A cleared canvas keeps a one-particle allocation and reads back empty
+        let mut empty = SimulationBuffers::new(
+            &device,
+            &[],
+            &InteractionMatrix::new(2),
+            &RadiusMatrix::default_for_size(2),
+            &[[1.0; 4]; 2],
+            &config,
+        );
+        assert_eq!((empty.num_particles, empty.capacity), (0, 1));
+        assert!(empty.read_particles(&device, &queue).is_empty());
+        assert!(empty.resize_particles(&queue, &particles[..1], 0));
+        assert_eq!(empty.read_particles(&device, &queue).len(), 1);
+    }
+}
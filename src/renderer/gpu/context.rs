@@ -46,7 +46,10 @@ impl GpuContext {
     /// 3. Request a high-performance adapter
     /// 4. Request a device with appropriate features and limits
     /// 5. Configure the surface for presentation
-    pub async fn new(window: Arc<Window>, vsync: bool) -> Result<Self> {
+    ///
+    /// When `allow_f16` is false, `SHADER_F16` is never requested so the
+    /// f32 storage path is used even on hardware that supports half precision.
+    pub async fn new(window: Arc<Window>, vsync: bool, allow_f16: bool) -> Result<Self> {
         // Create wgpu instance with all available backends
         let instance = Instance::new(&InstanceDescriptor {
             backends: wgpu::Backends::all(),
@@ -76,7 +79,7 @@ impl GpuContext {
         let (device, queue) = adapter
             .request_device(&wgpu::DeviceDescriptor {
                 label: Some("Device"),
-                required_features: Self::required_features(&adapter),
+                required_features: Self::required_features(&adapter, allow_f16),
                 required_limits: Self::required_limits(&adapter),
                 memory_hints: wgpu::MemoryHints::Performance,
                 ..Default::default()
@@ -123,6 +126,42 @@ impl GpuContext {
         })
     }
 
+    /// Request an adapter, device and queue without a window or surface.
+    ///
+    /// Uses the same features and limits as [`GpuContext::new`], so compute
+    /// pipelines behave identically. Intended for benchmarks and tests.
+    pub async fn new_headless(allow_f16: bool) -> Result<(Adapter, Device, Queue)> {
+        let instance = Instance::new(&InstanceDescriptor {
+            backends: wgpu::Backends::all(),
+            flags: wgpu::InstanceFlags::default(),
+            ..Default::default()
+        });
+
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await
+            .context("Failed to find a suitable GPU adapter")?;
+
+        log::info!("Using GPU: {:?}", adapter.get_info().name);
+        log::info!("Backend: {:?}", adapter.get_info().backend);
+
+        let (device, queue) = adapter
+            .request_device(&wgpu::DeviceDescriptor {
+                label: Some("Headless Device"),
+                required_features: Self::required_features(&adapter, allow_f16),
+                required_limits: Self::required_limits(&adapter),
+                memory_hints: wgpu::MemoryHints::Performance,
+                ..Default::default()
+            })
+            .await?;
+
+        Ok((adapter, device, queue))
+    }
+
     /// Select the best present mode for the vsync flag.
     fn select_present_mode(adapter: &Adapter, surface: &Surface, vsync: bool) -> PresentMode {
         let caps = surface.get_capabilities(adapter);
@@ -155,7 +194,7 @@ impl GpuContext {
     }
 
     /// Get required GPU features for particle simulation.
-    fn required_features(adapter: &Adapter) -> Features {
+    fn required_features(adapter: &Adapter, allow_f16: bool) -> Features {
         let available = adapter.features();
         let mut features = Features::empty();
 
@@ -167,7 +206,9 @@ impl GpuContext {
         }
 
         // Enable SHADER_F16 if available for bandwidth optimization.
-        if available.contains(Features::SHADER_F16) {
+        if !allow_f16 {
+            log::info!("SHADER_F16 disabled by configuration, using f32 storage");
+        } else if available.contains(Features::SHADER_F16) {
             log::info!("Enabling SHADER_F16 feature");
             features |= Features::SHADER_F16;
         }
@@ -194,6 +235,11 @@ impl GpuContext {
         }
     }
 
+    /// Whether the device was created with half-precision shader support.
+    pub fn shader_f16_enabled(&self) -> bool {
+        self.device.features().contains(Features::SHADER_F16)
+    }
+
     /// Resize the surface for a new window size.
     pub fn resize(&mut self, width: u32, height: u32) {
         if width > 0 && height > 0 {
@@ -14,10 +14,10 @@
 //! # Usage
 //!
 //! ```ignore
-//! let context = GpuContext::new(window, /*vsync=*/ true).await?;
+//! let context = GpuContext::new(window, /*vsync=*/ true, /*allow_f16=*/ true).await?;
 //! let buffers = SimulationBuffers::new(&context.device, ...);
 //! let pipelines = ComputePipelines::new(&context.device)?;
-//! let render = RenderPipelines::new(&context.device, surface_format)?;
+//! let render = RenderPipelines::new(&context.device, surface_format, 1)?;
 //!
 //! // Each frame:
 //! pipelines.compute_forces(&context, &buffers);
@@ -30,9 +30,17 @@ mod context;
 mod pipelines;
 
 pub use buffers::{
-    BrushParamsUniform, BrushRenderUniform, GlowParamsUniform, InfiniteParamsUniform,
-    MirrorParamsUniform, RenderBuffers, SimParamsUniform, SimulationBuffers, SpatialHashBuffers,
-    SpatialParamsUniform,
+    BackgroundParamsUniform, BrushParamsUniform, BrushRenderUniform, ColorParamsUniform,
+    GRAVITY_WELL_SLOT, GlowParamsUniform, InfiniteParamsUniform, MAX_BRUSH_POINTS,
+    MAX_EXTRA_BRUSH_POINTS, MirrorParamsUniform, PREFIX_SCAN_BLOCK_SIZE, RenderBuffers,
+    SimParamsUniform, SimulationBuffers, SpatialHashBuffers, SpatialParamsUniform,
+    TrailParamsUniform, VelocityStats, VignetteParamsUniform, create_prefix_scan_block_sums,
+    fair_sample_seed, prefix_scan_levels,
 };
 pub use context::GpuContext;
-pub use pipelines::{BrushPipelines, ComputePipelines, RenderPipelines, SpatialHashPipelines};
+pub use pipelines::{
+    BackgroundPipeline, BrushPipelines, COLORMAP_SIZE, CameraUniform, ComputePipelines,
+    DEFAULT_SPATIAL_WORKGROUP_SIZE, DepthTarget, MSAA_SAMPLE_COUNTS, MsaaTarget, PickPipeline,
+    PrefixScanPass, RenderPipelines, SpatialHashPipelines, TrailPipelines, VignettePipeline,
+    prefix_scan_passes, spatial_workgroup_size, supported_sample_count,
+};
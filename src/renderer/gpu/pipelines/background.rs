@@ -0,0 +1,184 @@
+//! Render pipeline for the gradient background.
+//!
+//! Draws a two-color vertical gradient with the fullscreen quad in place of
+//! the solid clear color. With trails on, the same pass is drawn with the
+//! trail fade alpha so old frames fade toward the gradient.
+
+use wgpu::util::DeviceExt;
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, BlendState, Buffer, BufferBindingType, BufferUsages,
+    ColorTargetState, ColorWrites, Device, FragmentState, FrontFace, MultisampleState,
+    PipelineCompilationOptions, PipelineLayoutDescriptor, PolygonMode, PrimitiveState,
+    PrimitiveTopology, Queue, RenderPipeline, RenderPipelineDescriptor, ShaderModuleDescriptor,
+    ShaderSource, ShaderStages, TextureFormat, VertexAttribute, VertexBufferLayout, VertexFormat,
+    VertexState, VertexStepMode,
+};
+
+use crate::renderer::gpu::BackgroundParamsUniform;
+
+/// Gradient background pipeline and its parameters.
+pub struct BackgroundPipeline {
+    /// Pipeline drawing the gradient quad, alpha blended.
+    pub pipeline: RenderPipeline,
+    /// Bind group for the gradient parameters.
+    pub bind_group: BindGroup,
+    /// Gradient parameters uniform buffer.
+    params_buffer: Buffer,
+    /// Bind group layout, kept to rebuild the pipeline.
+    bind_group_layout: BindGroupLayout,
+    /// Color target format.
+    format: TextureFormat,
+    /// MSAA sample count the pipeline is built with.
+    sample_count: u32,
+}
+
+impl BackgroundPipeline {
+    /// Create the gradient pipeline for the given surface format and MSAA
+    /// sample count.
+    pub fn new(device: &Device, surface_format: TextureFormat, sample_count: u32) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Background Bind Group Layout"),
+            entries: &[
+                // background params (uniform)
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline =
+            Self::create_pipeline(device, &bind_group_layout, surface_format, sample_count);
+
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Background Params Buffer"),
+            contents: bytemuck::bytes_of(&BackgroundParamsUniform {
+                top: [0.0, 0.0, 0.0, 1.0],
+                bottom: [0.0, 0.0, 0.0, 0.0],
+            }),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Background Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: params_buffer.as_entire_binding(),
+            }],
+        });
+
+        Self {
+            pipeline,
+            bind_group,
+            params_buffer,
+            bind_group_layout,
+            format: surface_format,
+            sample_count,
+        }
+    }
+
+    fn create_pipeline(
+        device: &Device,
+        bind_group_layout: &BindGroupLayout,
+        surface_format: TextureFormat,
+        sample_count: u32,
+    ) -> RenderPipeline {
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Background Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../../../shaders/background.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Background Pipeline Layout"),
+            bind_group_layouts: &[bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Background Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                // Fullscreen quad vertices from `RenderBuffers`: [x, y, u, v]
+                buffers: &[VertexBufferLayout {
+                    array_stride: 16,
+                    step_mode: VertexStepMode::Vertex,
+                    attributes: &[
+                        VertexAttribute {
+                            format: VertexFormat::Float32x2,
+                            offset: 0,
+                            shader_location: 0,
+                        },
+                        VertexAttribute {
+                            format: VertexFormat::Float32x2,
+                            offset: 8,
+                            shader_location: 1,
+                        },
+                    ],
+                }],
+                compilation_options: PipelineCompilationOptions::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: surface_format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: PipelineCompilationOptions::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Rebuild the pipeline for a new MSAA sample count.
+    pub fn set_sample_count(&mut self, device: &Device, sample_count: u32) {
+        if sample_count == self.sample_count {
+            return;
+        }
+        self.pipeline =
+            Self::create_pipeline(device, &self.bind_group_layout, self.format, sample_count);
+        self.sample_count = sample_count;
+    }
+
+    /// Update the gradient colors (`[top, bottom]`) and the pass alpha:
+    /// 1 to replace the previous contents, the trail fade to fade them.
+    pub fn update(&self, queue: &Queue, gradient: [[f32; 3]; 2], alpha: f32) {
+        let [top, bottom] = gradient;
+        let params = BackgroundParamsUniform {
+            top: [top[0], top[1], top[2], alpha],
+            bottom: [bottom[0], bottom[1], bottom[2], 0.0],
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+    }
+
+    /// Estimated GPU memory used by the background uniform buffer, in bytes.
+    pub fn estimated_bytes(&self) -> u64 {
+        self.params_buffer.size()
+    }
+}
@@ -14,7 +14,7 @@ use wgpu::{
 };
 
 use super::load_shader;
-use crate::renderer::gpu::{BrushParamsUniform, BrushRenderUniform};
+use crate::renderer::gpu::{BrushParamsUniform, BrushRenderUniform, MAX_BRUSH_POINTS};
 
 /// Compute and render pipelines for brush interaction.
 pub struct BrushPipelines {
@@ -22,7 +22,7 @@ pub struct BrushPipelines {
     pub force_pipeline: ComputePipeline,
     /// Bind group layout for brush force computation.
     pub force_bind_group_layout: BindGroupLayout,
-    /// Brush parameters uniform buffer.
+    /// Brush point array uniform buffer ([`MAX_BRUSH_POINTS`] entries).
     pub brush_buffer: Buffer,
     /// Render pipeline for brush circle indicator.
     pub circle_pipeline: RenderPipeline,
@@ -48,12 +48,12 @@ impl BrushPipelines {
         let force_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some("Brush Force Bind Group Layout"),
             entries: &[
-                // pos_type (storage, read-only)
+                // pos_type (storage, read-write for paused sculpting)
                 BindGroupLayoutEntry {
                     binding: 0,
                     visibility: ShaderStages::COMPUTE,
                     ty: BindingType::Buffer {
-                        ty: BufferBindingType::Storage { read_only: true },
+                        ty: BufferBindingType::Storage { read_only: false },
                         has_dynamic_offset: false,
                         min_binding_size: None,
                     },
@@ -113,11 +113,18 @@ impl BrushPipelines {
             is_active: 0,
             num_particles: 0,
             target_type: -1,
-            _padding: [0; 2],
+            locked_mask: 0,
+            infinite_range: 0,
+            falloff_exponent: 0.0,
+            sculpt_dt: 0.0,
+            world_width: 0.0,
+            world_height: 0.0,
+            sculpt_wrap: 0,
+            _padding: [0; 3],
         };
         let brush_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Brush Params Buffer"),
-            contents: bytemuck::bytes_of(&default_params),
+            contents: bytemuck::cast_slice(&[default_params; MAX_BRUSH_POINTS]),
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
         });
 
@@ -207,8 +214,10 @@ impl BrushPipelines {
             camera_zoom: 1.0,
             camera_offset_x: 0.0,
             camera_offset_y: 0.0,
-            _padding1: [0.0; 3],
-            _padding2: [0.0; 4],
+            line_width: 2.0,
+            fill_alpha: 0.0,
+            contrast: 1,
+            _padding: [0.0; 4],
         };
         let render_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
             label: Some("Brush Render Buffer"),
@@ -237,6 +246,11 @@ impl BrushPipelines {
         }
     }
 
+    /// Estimated GPU memory used by the brush uniform buffers, in bytes.
+    pub fn estimated_bytes(&self) -> u64 {
+        self.brush_buffer.size() + self.render_buffer.size()
+    }
+
     /// Create brush force bind group.
     pub fn create_force_bind_group(
         &self,
@@ -264,10 +278,36 @@ impl BrushPipelines {
         })
     }
 
-    /// Update brush parameters for compute.
+    /// Update all brush points for compute.
     pub fn update_brush(&self, queue: &Queue, brush: &crate::app::BrushState, num_particles: u32) {
-        let params = BrushParamsUniform::from_brush_state(brush, num_particles);
-        queue.write_buffer(&self.brush_buffer, 0, bytemuck::bytes_of(&params));
+        let points = BrushParamsUniform::points_from_brush_state(brush, num_particles);
+        queue.write_buffer(&self.brush_buffer, 0, bytemuck::cast_slice(&points));
+    }
+
+    /// Upload brush points with every slot inactive (see
+    /// [`BrushParamsUniform::inactive_points`]).
+    pub fn update_brush_inactive(
+        &self,
+        queue: &Queue,
+        brush: &crate::app::BrushState,
+        num_particles: u32,
+    ) {
+        let points = BrushParamsUniform::inactive_points(brush, num_particles);
+        queue.write_buffer(&self.brush_buffer, 0, bytemuck::cast_slice(&points));
+    }
+
+    /// Upload the mouse brush for sculpting while paused (see
+    /// [`BrushParamsUniform::sculpt_points`]).
+    pub fn update_brush_sculpt(
+        &self,
+        queue: &Queue,
+        brush: &crate::app::BrushState,
+        num_particles: u32,
+        dt: f32,
+        config: &crate::simulation::SimulationConfig,
+    ) {
+        let points = BrushParamsUniform::sculpt_points(brush, num_particles, dt, config);
+        queue.write_buffer(&self.brush_buffer, 0, bytemuck::cast_slice(&points));
     }
 
     /// Update brush render parameters for circle display.
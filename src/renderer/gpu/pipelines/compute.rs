@@ -167,6 +167,28 @@ impl ComputePipelines {
                     },
                     count: None,
                 },
+                // anisotropy
+                BindGroupLayoutEntry {
+                    binding: 7,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // type_physics
+                BindGroupLayoutEntry {
+                    binding: 8,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         })
     }
@@ -220,6 +242,17 @@ impl ComputePipelines {
                     },
                     count: None,
                 },
+                // type_physics
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         })
     }
@@ -265,6 +298,14 @@ impl ComputePipelines {
                     binding: 6,
                     resource: buffers.max_radius.as_entire_binding(),
                 },
+                BindGroupEntry {
+                    binding: 7,
+                    resource: buffers.anisotropy.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 8,
+                    resource: buffers.type_physics.as_entire_binding(),
+                },
             ],
         })
     }
@@ -275,7 +316,7 @@ impl ComputePipelines {
         device: &Device,
         pos: &Buffer,
         vel: &Buffer,
-        params: &Buffer,
+        buffers: &SimulationBuffers,
         brush_params: &Buffer,
     ) -> BindGroup {
         device.create_bind_group(&BindGroupDescriptor {
@@ -292,12 +333,16 @@ impl ComputePipelines {
                 },
                 BindGroupEntry {
                     binding: 2,
-                    resource: params.as_entire_binding(),
+                    resource: buffers.params.as_entire_binding(),
                 },
                 BindGroupEntry {
                     binding: 3,
                     resource: brush_params.as_entire_binding(),
                 },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: buffers.type_physics.as_entire_binding(),
+                },
             ],
         })
     }
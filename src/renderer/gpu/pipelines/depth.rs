@@ -0,0 +1,60 @@
+//! Depth target for the 3D particle pass.
+//!
+//! In 3D mode the particle pass depth-tests against this texture so nearer
+//! particles cover farther ones. It matches the surface size and the scene
+//! sample count, is cleared every frame, and is released while 3D is off.
+
+use wgpu::{
+    Device, Extent3d, Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+    TextureView, TextureViewDescriptor,
+};
+
+/// Format of the particle depth target.
+pub const DEPTH_FORMAT: TextureFormat = TextureFormat::Depth32Float;
+
+/// Depth texture the 3D particle pass tests against.
+#[derive(Default)]
+pub struct DepthTarget {
+    /// Depth texture and its view, created on first use and on resize.
+    target: Option<(Texture, TextureView)>,
+}
+
+impl DepthTarget {
+    /// Make sure the depth texture exists at the given size and sample
+    /// count (which must match the scene color target).
+    pub fn ensure_target(&mut self, device: &Device, width: u32, height: u32, sample_count: u32) {
+        let size = Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        };
+        if self.target.as_ref().is_some_and(|(texture, _)| {
+            texture.size() == size && texture.sample_count() == sample_count
+        }) {
+            return;
+        }
+
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("Particle Depth Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count,
+            dimension: TextureDimension::D2,
+            format: DEPTH_FORMAT,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+        self.target = Some((texture, view));
+    }
+
+    /// Drop the depth texture (when 3D is turned off).
+    pub fn release(&mut self) {
+        self.target = None;
+    }
+
+    /// View of the depth texture, if created.
+    pub fn view(&self) -> Option<&TextureView> {
+        self.target.as_ref().map(|(_, view)| view)
+    }
+}
@@ -9,16 +9,37 @@
 //! - [`render`]: Particle visualization render pipelines
 //! - [`spatial`]: Spatial hashing optimization pipelines
 //! - [`brush`]: Brush interaction pipelines
+//! - [`pick`]: Particle picking under the cursor
+//! - [`trail`]: Persistent trail texture with fade and blit passes
+//! - [`background`]: Vertical gradient background pass
+//! - [`msaa`]: Multisampled scene target for anti-aliasing
+//! - [`depth`]: Depth target for the 3D particle pass
+//! - [`vignette`]: Radial darkening post pass
 
+mod background;
 mod brush;
 mod compute;
+mod depth;
+mod msaa;
+mod pick;
 mod render;
 mod spatial;
+mod trail;
+mod vignette;
 
+pub use background::BackgroundPipeline;
 pub use brush::BrushPipelines;
 pub use compute::ComputePipelines;
-pub use render::RenderPipelines;
-pub use spatial::SpatialHashPipelines;
+pub use depth::{DEPTH_FORMAT, DepthTarget};
+pub use msaa::{MSAA_SAMPLE_COUNTS, MsaaTarget, supported_sample_count};
+pub use pick::PickPipeline;
+pub use render::{COLORMAP_SIZE, RenderPipelines};
+pub use spatial::{
+    DEFAULT_SPATIAL_WORKGROUP_SIZE, PrefixScanPass, SpatialHashPipelines, prefix_scan_passes,
+    spatial_workgroup_size,
+};
+pub use trail::TrailPipelines;
+pub use vignette::VignettePipeline;
 
 use bytemuck::{Pod, Zeroable};
 use wgpu::{Device, ShaderModuleDescriptor, ShaderSource};
@@ -78,6 +99,28 @@ impl CameraUniform {
     }
 }
 
+impl CameraUniform {
+    /// Derive a pick camera that maps a single screen pixel onto a 1×1 target.
+    ///
+    /// `world_x`/`world_y` is the world position under the cursor and the
+    /// viewport size is in pixels. The result keeps this camera's orientation
+    /// and magnifies it so the pixel fills the whole clip space.
+    pub fn for_pixel(
+        &self,
+        world_x: f32,
+        world_y: f32,
+        viewport_width: f32,
+        viewport_height: f32,
+    ) -> Self {
+        Self {
+            center_x: world_x,
+            center_y: world_y,
+            scale_x: self.scale_x * viewport_width,
+            scale_y: self.scale_y * viewport_height,
+        }
+    }
+}
+
 /// Helper to load WGSL shader source and optionally enable FP16.
 ///
 /// If `use_f16` is true:
@@ -110,3 +153,23 @@ pub(crate) fn load_shader(device: &Device, label: &str, source: &str) -> wgpu::S
         source: ShaderSource::Wgsl(std::borrow::Cow::Owned(code)),
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_camera_maps_pixel_to_clip_space() {
+        let camera = CameraUniform::with_zoom_and_offset(1600.0, 1200.0, 2.0, 0.0, 0.0);
+        let (viewport_w, viewport_h) = (800.0, 600.0);
+        // World units covered by one screen pixel
+        let pixel_w = 2.0 / (camera.scale_x * viewport_w);
+        let pick = camera.for_pixel(100.0, 50.0, viewport_w, viewport_h);
+
+        // The cursor maps to the clip-space center
+        assert_eq!((100.0 - pick.center_x) * pick.scale_x, 0.0);
+        // Half a pixel away lands on the edge of the 1×1 target
+        let edge = (100.0 + pixel_w * 0.5 - pick.center_x) * pick.scale_x;
+        assert!((edge - 1.0).abs() < 1e-4);
+    }
+}
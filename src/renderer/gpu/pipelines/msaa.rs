@@ -0,0 +1,133 @@
+//! Multisampled color target for anti-aliased particle edges.
+//!
+//! With MSAA on, the scene passes (background, glow, particles) draw into a
+//! multisampled texture that is resolved into the scene view (the surface,
+//! or the trail texture) by the last scene pass. The texture is kept between
+//! frames so trails can keep fading it. Overlays (brush circle, UI) still
+//! draw single-sampled on the surface.
+
+use wgpu::{
+    Adapter, Device, Extent3d, Texture, TextureDescriptor, TextureDimension, TextureFormat,
+    TextureUsages, TextureView, TextureViewDescriptor,
+};
+
+/// Sample counts offered for MSAA.
+pub const MSAA_SAMPLE_COUNTS: [u32; 3] = [1, 2, 4];
+
+/// Sample count to use for `requested`, given which counts the device
+/// supports. Falls back to 1 (no MSAA) when the count is not offered or not
+/// supported.
+fn resolve_sample_count(requested: u32, supported: impl Fn(u32) -> bool) -> u32 {
+    if requested <= 1 {
+        return 1;
+    }
+    if MSAA_SAMPLE_COUNTS.contains(&requested) && supported(requested) {
+        requested
+    } else {
+        log::warn!(
+            "{}x MSAA is not supported for this surface format; falling back to 1x",
+            requested
+        );
+        1
+    }
+}
+
+/// Sample count the adapter supports for render targets of `format`,
+/// falling back to 1 with a warning when `requested` is unsupported.
+pub fn supported_sample_count(adapter: &Adapter, format: TextureFormat, requested: u32) -> u32 {
+    let flags = adapter.get_texture_format_features(format).flags;
+    resolve_sample_count(requested, |count| flags.sample_count_supported(count))
+}
+
+/// Multisampled texture the scene is drawn into when MSAA is on.
+pub struct MsaaTarget {
+    /// Texture format (matches the surface).
+    format: TextureFormat,
+    /// Sample count asked for in the config, kept to detect changes.
+    requested: u32,
+    /// Sample count actually in use (1 = MSAA off).
+    sample_count: u32,
+    /// Multisampled texture and its view, created on first use and on resize.
+    target: Option<(Texture, TextureView)>,
+}
+
+impl MsaaTarget {
+    /// Create an MSAA target; `sample_count` is the supported count for
+    /// `requested` (see [`supported_sample_count`]).
+    pub fn new(format: TextureFormat, requested: u32, sample_count: u32) -> Self {
+        Self {
+            format,
+            requested,
+            sample_count,
+            target: None,
+        }
+    }
+
+    /// Sample count asked for when this target was created.
+    pub fn requested(&self) -> u32 {
+        self.requested
+    }
+
+    /// Sample count the scene pipelines must be built with.
+    pub fn sample_count(&self) -> u32 {
+        self.sample_count
+    }
+
+    /// Make sure the multisampled texture exists at the given size.
+    ///
+    /// Returns `true` when the texture was (re)created, in which case the
+    /// caller must clear it rather than fade its undefined contents. Always
+    /// `false` with MSAA off.
+    pub fn ensure_target(&mut self, device: &Device, width: u32, height: u32) -> bool {
+        if self.sample_count <= 1 {
+            return false;
+        }
+        let size = Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        };
+        if self
+            .target
+            .as_ref()
+            .is_some_and(|(texture, _)| texture.size() == size)
+        {
+            return false;
+        }
+
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("MSAA Color Texture"),
+            size,
+            mip_level_count: 1,
+            sample_count: self.sample_count,
+            dimension: TextureDimension::D2,
+            format: self.format,
+            usage: TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+        self.target = Some((texture, view));
+        true
+    }
+
+    /// View of the multisampled texture; `None` with MSAA off.
+    pub fn view(&self) -> Option<&TextureView> {
+        self.target.as_ref().map(|(_, view)| view)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sample_count_falls_back_when_unsupported() {
+        let up_to_two = |count: u32| count <= 2;
+        assert_eq!(resolve_sample_count(1, up_to_two), 1);
+        assert_eq!(resolve_sample_count(2, up_to_two), 2);
+        assert_eq!(resolve_sample_count(4, up_to_two), 1);
+        // Counts outside the offered set are never used
+        assert_eq!(resolve_sample_count(8, |_| true), 1);
+        assert_eq!(resolve_sample_count(0, |_| true), 1);
+    }
+}
@@ -0,0 +1,321 @@
+//! Particle picking pipeline.
+//!
+//! Renders particle indices into a 1×1 integer target centered on the cursor
+//! and reads back the single texel. The fragment depth is the squared distance
+//! from the sprite center, so the particle nearest to the cursor wins when
+//! several sprites overlap the pixel.
+
+use wgpu::util::DeviceExt;
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, Buffer, BufferBindingType, BufferUsages, ColorTargetState,
+    ColorWrites, CompareFunction, DepthStencilState, Device, FragmentState, FrontFace,
+    MultisampleState, PipelineCompilationOptions, PipelineLayoutDescriptor, PolygonMode,
+    PrimitiveState, PrimitiveTopology, Queue, RenderPipeline, RenderPipelineDescriptor,
+    ShaderStages, Texture, TextureFormat, TextureUsages, VertexState,
+};
+
+use super::{CameraUniform, load_shader};
+use crate::renderer::gpu::SimulationBuffers;
+
+/// Format of the particle ID target (index + 1, 0 = empty).
+const ID_FORMAT: TextureFormat = TextureFormat::R32Uint;
+/// Format of the nearest-center depth target.
+const DEPTH_FORMAT: TextureFormat = TextureFormat::Depth32Float;
+/// Readback rows must be aligned to `COPY_BYTES_PER_ROW_ALIGNMENT`.
+const READBACK_SIZE: u64 = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT as u64;
+
+/// Render pipeline and targets for picking the particle under the cursor.
+pub struct PickPipeline {
+    /// Pipeline that writes particle indices.
+    pub pipeline: RenderPipeline,
+    /// Bind group layout for particles, params and pick camera.
+    pub bind_group_layout: BindGroupLayout,
+    /// Pick camera uniform buffer.
+    pub camera_buffer: Buffer,
+    /// 1×1 particle ID target.
+    id_texture: Texture,
+    /// 1×1 depth target.
+    depth_texture: Texture,
+    /// Staging buffer for reading back the ID texel.
+    readback_buffer: Buffer,
+}
+
+impl PickPipeline {
+    /// Create the pick pipeline and its 1×1 targets.
+    pub fn new(device: &Device) -> Self {
+        let shader = load_shader(
+            device,
+            "Particle Pick Shader",
+            include_str!("../../../../shaders/particle_pick.wgsl"),
+        );
+
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Pick Bind Group Layout"),
+            entries: &[
+                // particles (storage, read-only)
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // params (uniform)
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // pick camera (uniform)
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::VERTEX,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Pick Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Particle Pick Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: PipelineCompilationOptions::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: ID_FORMAT,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: PipelineCompilationOptions::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: Some(DepthStencilState {
+                format: DEPTH_FORMAT,
+                depth_write_enabled: true,
+                depth_compare: CompareFunction::Less,
+                stencil: wgpu::StencilState::default(),
+                bias: wgpu::DepthBiasState::default(),
+            }),
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let camera = CameraUniform::new(1920.0, 1080.0, 1920.0, 1080.0);
+        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Pick Camera Buffer"),
+            contents: bytemuck::bytes_of(&camera),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let target = |label, format, usage| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d {
+                    width: 1,
+                    height: 1,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format,
+                usage,
+                view_formats: &[],
+            })
+        };
+        let id_texture = target(
+            "Pick ID Texture",
+            ID_FORMAT,
+            TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+        );
+        let depth_texture = target(
+            "Pick Depth Texture",
+            DEPTH_FORMAT,
+            TextureUsages::RENDER_ATTACHMENT,
+        );
+
+        let readback_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Pick Readback Buffer"),
+            size: READBACK_SIZE,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+
+        Self {
+            pipeline,
+            bind_group_layout,
+            camera_buffer,
+            id_texture,
+            depth_texture,
+            readback_buffer,
+        }
+    }
+
+    /// Estimated GPU memory used by the pick buffers and targets, in bytes.
+    pub fn estimated_bytes(&self) -> u64 {
+        // Two 4-byte texels plus the uniform and readback buffers
+        self.camera_buffer.size() + self.readback_buffer.size() + 8
+    }
+
+    /// Create a pick bind group for the given particle buffer.
+    pub fn create_bind_group(
+        &self,
+        device: &Device,
+        pos_type: &Buffer,
+        buffers: &SimulationBuffers,
+    ) -> BindGroup {
+        device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Pick Bind Group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: pos_type.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: buffers.params.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: self.camera_buffer.as_entire_binding(),
+                },
+            ],
+        })
+    }
+
+    /// Render particle IDs around `camera` and return the index under its center.
+    ///
+    /// `camera` should come from [`CameraUniform::for_pixel`]. Returns `None`
+    /// when no particle disk covers the pixel. Blocks until the GPU is done.
+    pub fn pick(
+        &self,
+        device: &Device,
+        queue: &Queue,
+        bind_group: &BindGroup,
+        camera: &CameraUniform,
+        num_particles: u32,
+    ) -> Option<u32> {
+        if num_particles == 0 {
+            return None;
+        }
+
+        queue.write_buffer(&self.camera_buffer, 0, bytemuck::bytes_of(camera));
+
+        let id_view = self
+            .id_texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+        let depth_view = self
+            .depth_texture
+            .create_view(&wgpu::TextureViewDescriptor::default());
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("Pick Encoder"),
+        });
+        {
+            let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Pick Pass"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view: &id_view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::TRANSPARENT),
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &depth_view,
+                    depth_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(1.0),
+                        store: wgpu::StoreOp::Discard,
+                    }),
+                    stencil_ops: None,
+                }),
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, bind_group, &[]);
+            pass.draw(0..4, 0..num_particles);
+        }
+
+        encoder.copy_texture_to_buffer(
+            wgpu::TexelCopyTextureInfo {
+                texture: &self.id_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            wgpu::TexelCopyBufferInfo {
+                buffer: &self.readback_buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT),
+                    rows_per_image: Some(1),
+                },
+            },
+            wgpu::Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+
+        let slice = self.readback_buffer.slice(..4);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        let _ = device.poll(wgpu::PollType::wait_indefinitely());
+        if receiver.recv().ok()?.is_err() {
+            log::error!("Failed to map pick buffer");
+            return None;
+        }
+
+        let id = {
+            let data = slice.get_mapped_range();
+            u32::from_le_bytes([data[0], data[1], data[2], data[3]])
+        };
+        self.readback_buffer.unmap();
+
+        // 0 means the pixel was empty
+        id.checked_sub(1)
+    }
+}
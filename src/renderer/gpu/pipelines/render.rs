@@ -7,26 +7,73 @@ use wgpu::util::DeviceExt;
 use wgpu::{
     BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
     BindGroupLayoutEntry, BindingType, BlendState, Buffer, BufferBindingType, ColorTargetState,
-    ColorWrites, Device, FragmentState, FrontFace, MultisampleState, PipelineCompilationOptions,
-    PipelineLayoutDescriptor, PolygonMode, PrimitiveState, PrimitiveTopology, Queue,
-    RenderPipeline, RenderPipelineDescriptor, ShaderStages, TextureFormat, VertexState,
+    ColorWrites, CompareFunction, DepthStencilState, Device, FragmentState, FrontFace,
+    MultisampleState, PipelineCompilationOptions, PipelineLayoutDescriptor, PolygonMode,
+    PrimitiveState, PrimitiveTopology, Queue, RenderPipeline, RenderPipelineDescriptor,
+    ShaderStages, TextureFormat, VertexState,
 };
 
-use super::{CameraUniform, load_shader};
+use super::{CameraUniform, DEPTH_FORMAT, load_shader};
 use crate::renderer::gpu::{
-    GlowParamsUniform, InfiniteParamsUniform, MirrorParamsUniform, SimulationBuffers,
+    ColorParamsUniform, GlowParamsUniform, InfiniteParamsUniform, MirrorParamsUniform,
+    SimulationBuffers,
 };
+use crate::simulation::BlendMode;
+
+/// Number of entries in the colormap lookup table.
+pub const COLORMAP_SIZE: u32 = 64;
+
+/// Additive blending, used by glow and the additive base particle pass.
+const ADDITIVE_BLENDING: BlendState = BlendState {
+    color: wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::SrcAlpha,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    },
+    alpha: wgpu::BlendComponent {
+        src_factor: wgpu::BlendFactor::One,
+        dst_factor: wgpu::BlendFactor::One,
+        operation: wgpu::BlendOperation::Add,
+    },
+};
+
+/// Depth test of the 3D particle pipelines. Particles are drawn far to
+/// near, so later (nearer or equal) fragments pass.
+fn depth_test() -> DepthStencilState {
+    DepthStencilState {
+        format: DEPTH_FORMAT,
+        depth_write_enabled: true,
+        depth_compare: CompareFunction::LessEqual,
+        stencil: wgpu::StencilState::default(),
+        bias: wgpu::DepthBiasState::default(),
+    }
+}
 
 /// Render pipelines for particle visualization.
 pub struct RenderPipelines {
     /// Pipeline for rendering particles as point sprites.
     pub particle_pipeline: RenderPipeline,
-    /// Pipeline for rendering particle glow effect.
-    pub glow_pipeline: RenderPipeline,
+    /// [`Self::particle_pipeline`] with additive blending.
+    pub particle_additive_pipeline: RenderPipeline,
+    /// Pipeline for rendering particle glow effect, built on first use by
+    /// [`RenderPipelines::ensure_glow_pipeline`].
+    pub glow_pipeline: Option<RenderPipeline>,
+    /// Color target format, kept so the glow pipeline can be built later.
+    surface_format: TextureFormat,
+    /// MSAA sample count every particle pipeline is built with.
+    sample_count: u32,
     /// Pipeline for rendering particles with mirror wrap effect.
     pub mirror_pipeline: RenderPipeline,
+    /// [`Self::mirror_pipeline`] with additive blending.
+    pub mirror_additive_pipeline: RenderPipeline,
     /// Pipeline for rendering particles with infinite wrap tiling.
     pub infinite_pipeline: RenderPipeline,
+    /// [`Self::infinite_pipeline`] with additive blending.
+    pub infinite_additive_pipeline: RenderPipeline,
+    /// Depth-tested variants of the six particle pipelines above (in field
+    /// order) for 3D mode, built on first use by
+    /// [`RenderPipelines::ensure_depth_pipelines`].
+    depth_pipelines: Option<[RenderPipeline; 6]>,
     /// Bind group layout for particle rendering.
     pub render_bind_group_layout: BindGroupLayout,
     /// Bind group layout for glow rendering.
@@ -43,11 +90,142 @@ pub struct RenderPipelines {
     pub mirror_buffer: Buffer,
     /// Infinite wrap parameters uniform buffer.
     pub infinite_buffer: Buffer,
+    /// Color mode parameters uniform buffer.
+    pub color_buffer: Buffer,
+    /// Colormap lookup table sampled by scalar color modes.
+    pub colormap_buffer: Buffer,
 }
 
 impl RenderPipelines {
     /// Create render pipelines for particle visualization.
-    pub fn new(device: &Device, surface_format: TextureFormat) -> Self {
+    pub fn new(device: &Device, surface_format: TextureFormat, sample_count: u32) -> Self {
+        // Create bind group layouts
+        let render_bind_group_layout = Self::create_render_bind_group_layout(device);
+        let glow_bind_group_layout = Self::create_glow_bind_group_layout(device);
+        let mirror_bind_group_layout = Self::create_mirror_bind_group_layout(device);
+        let infinite_bind_group_layout = Self::create_infinite_bind_group_layout(device);
+
+        let [
+            particle_pipeline,
+            particle_additive_pipeline,
+            mirror_pipeline,
+            mirror_additive_pipeline,
+            infinite_pipeline,
+            infinite_additive_pipeline,
+        ] = Self::create_particle_pipelines(
+            device,
+            [
+                &render_bind_group_layout,
+                &mirror_bind_group_layout,
+                &infinite_bind_group_layout,
+            ],
+            surface_format,
+            sample_count,
+            None,
+        );
+
+        // Create camera buffer with default values
+        let camera = CameraUniform::new(1920.0, 1080.0, 1920.0, 1080.0);
+        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Camera Buffer"),
+            contents: bytemuck::bytes_of(&camera),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Create glow buffer with default values
+        let glow_params = GlowParamsUniform {
+            glow_size: 4.0,
+            glow_intensity: 0.5,
+            glow_steepness: 2.0,
+            zoom_scale: 1.0,
+        };
+        let glow_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Glow Buffer"),
+            contents: bytemuck::bytes_of(&glow_params),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Create mirror wrap buffer with default values
+        let mirror_params = MirrorParamsUniform {
+            num_copies: 5,
+            _padding: [0; 3],
+        };
+        let mirror_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Mirror Buffer"),
+            contents: bytemuck::bytes_of(&mirror_params),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Create infinite wrap buffer with default values
+        let infinite_params = InfiniteParamsUniform {
+            start_x: -1,
+            start_y: -1,
+            num_copies_x: 3,
+            num_copies_y: 3,
+        };
+        let infinite_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Infinite Buffer"),
+            contents: bytemuck::bytes_of(&infinite_params),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        // Create color mode buffers (By Type until the config is uploaded)
+        let color_params = ColorParamsUniform {
+            mode: 0,
+            colormap_size: COLORMAP_SIZE,
+            speed_range: 100.0,
+            streak_length: 0.0,
+            hue_offset: 0.0,
+            seam_fade: 0.0,
+            depth_limit: 0.0,
+            _padding: 0.0,
+        };
+        let color_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Color Params Buffer"),
+            contents: bytemuck::bytes_of(&color_params),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        let colormap = [[1.0f32; 4]; COLORMAP_SIZE as usize];
+        let colormap_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Colormap Buffer"),
+            contents: bytemuck::cast_slice(&colormap),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        });
+
+        Self {
+            particle_pipeline,
+            particle_additive_pipeline,
+            glow_pipeline: None,
+            surface_format,
+            sample_count,
+            mirror_pipeline,
+            mirror_additive_pipeline,
+            infinite_pipeline,
+            infinite_additive_pipeline,
+            depth_pipelines: None,
+            render_bind_group_layout,
+            glow_bind_group_layout,
+            mirror_bind_group_layout,
+            infinite_bind_group_layout,
+            camera_buffer,
+            glow_buffer,
+            mirror_buffer,
+            infinite_buffer,
+            color_buffer,
+            colormap_buffer,
+        }
+    }
+
+    /// Build the standard, mirror and infinite particle pipelines (alpha and
+    /// additive variants of each) for the given bind group layouts, in
+    /// field order, with an optional depth test.
+    fn create_particle_pipelines(
+        device: &Device,
+        [render_layout, mirror_layout, infinite_layout]: [&BindGroupLayout; 3],
+        surface_format: TextureFormat,
+        sample_count: u32,
+        depth_stencil: Option<DepthStencilState>,
+    ) -> [RenderPipeline; 6] {
         // Load render shaders with FP16 support
         let render_shader = load_shader(
             device,
@@ -55,12 +233,6 @@ impl RenderPipelines {
             include_str!("../../../../shaders/particle_render.wgsl"),
         );
 
-        let glow_shader = load_shader(
-            device,
-            "Particle Glow Shader",
-            include_str!("../../../../shaders/particle_render_glow.wgsl"),
-        );
-
         let mirror_shader = load_shader(
             device,
             "Mirror Wrap Render Shader",
@@ -73,170 +245,155 @@ impl RenderPipelines {
             include_str!("../../../../shaders/particle_render_infinite.wgsl"),
         );
 
-        // Create bind group layouts
-        let render_bind_group_layout = Self::create_render_bind_group_layout(device);
-        let glow_bind_group_layout = Self::create_glow_bind_group_layout(device);
-        let mirror_bind_group_layout = Self::create_mirror_bind_group_layout(device);
-        let infinite_bind_group_layout = Self::create_infinite_bind_group_layout(device);
-
         // Create pipeline layouts
         let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("Render Pipeline Layout"),
-            bind_group_layouts: &[&render_bind_group_layout],
-            push_constant_ranges: &[],
-        });
-
-        let glow_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
-            label: Some("Glow Pipeline Layout"),
-            bind_group_layouts: &[&glow_bind_group_layout],
+            bind_group_layouts: &[render_layout],
             push_constant_ranges: &[],
         });
 
         let mirror_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("Mirror Pipeline Layout"),
-            bind_group_layouts: &[&mirror_bind_group_layout],
+            bind_group_layouts: &[mirror_layout],
             push_constant_ranges: &[],
         });
 
         let infinite_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
             label: Some("Infinite Pipeline Layout"),
-            bind_group_layouts: &[&infinite_bind_group_layout],
+            bind_group_layouts: &[infinite_layout],
             push_constant_ranges: &[],
         });
 
-        // Create particle render pipeline
-        let particle_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: Some("Particle Render Pipeline"),
-            layout: Some(&pipeline_layout),
-            vertex: VertexState {
-                module: &render_shader,
-                entry_point: Some("vs_main"),
-                buffers: &[],
-                compilation_options: PipelineCompilationOptions::default(),
-            },
-            fragment: Some(FragmentState {
-                module: &render_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(ColorTargetState {
-                    format: surface_format,
-                    blend: Some(BlendState::ALPHA_BLENDING),
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: PipelineCompilationOptions::default(),
-            }),
-            primitive: PrimitiveState {
-                topology: PrimitiveTopology::TriangleStrip,
-                strip_index_format: None,
-                front_face: FrontFace::Ccw,
-                cull_mode: None,
-                unclipped_depth: false,
-                polygon_mode: PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
+        // Create alpha and additive variants of each base particle pipeline;
+        // blend state is baked in, so the draw picks one by blend mode
+        let [particle_pipeline, particle_additive_pipeline] =
+            [BlendState::ALPHA_BLENDING, ADDITIVE_BLENDING].map(|blend| {
+                Self::create_particle_pipeline(
+                    device,
+                    "Particle Render Pipeline",
+                    &pipeline_layout,
+                    &render_shader,
+                    surface_format,
+                    sample_count,
+                    blend,
+                    depth_stencil.clone(),
+                )
+            });
+        let [mirror_pipeline, mirror_additive_pipeline] =
+            [BlendState::ALPHA_BLENDING, ADDITIVE_BLENDING].map(|blend| {
+                Self::create_particle_pipeline(
+                    device,
+                    "Mirror Wrap Render Pipeline",
+                    &mirror_pipeline_layout,
+                    &mirror_shader,
+                    surface_format,
+                    sample_count,
+                    blend,
+                    depth_stencil.clone(),
+                )
+            });
+        let [infinite_pipeline, infinite_additive_pipeline] =
+            [BlendState::ALPHA_BLENDING, ADDITIVE_BLENDING].map(|blend| {
+                Self::create_particle_pipeline(
+                    device,
+                    "Infinite Wrap Render Pipeline",
+                    &infinite_pipeline_layout,
+                    &infinite_shader,
+                    surface_format,
+                    sample_count,
+                    blend,
+                    depth_stencil.clone(),
+                )
+            });
 
-        // Create glow render pipeline with additive blending
-        let glow_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: Some("Glow Render Pipeline"),
-            layout: Some(&glow_pipeline_layout),
-            vertex: VertexState {
-                module: &glow_shader,
-                entry_point: Some("vs_main"),
-                buffers: &[],
-                compilation_options: PipelineCompilationOptions::default(),
-            },
-            fragment: Some(FragmentState {
-                module: &glow_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(ColorTargetState {
-                    format: surface_format,
-                    // Additive blending for glow effect
-                    blend: Some(BlendState {
-                        color: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::SrcAlpha,
-                            dst_factor: wgpu::BlendFactor::One,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                        alpha: wgpu::BlendComponent {
-                            src_factor: wgpu::BlendFactor::One,
-                            dst_factor: wgpu::BlendFactor::One,
-                            operation: wgpu::BlendOperation::Add,
-                        },
-                    }),
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: PipelineCompilationOptions::default(),
-            }),
-            primitive: PrimitiveState {
-                topology: PrimitiveTopology::TriangleStrip,
-                strip_index_format: None,
-                front_face: FrontFace::Ccw,
-                cull_mode: None,
-                unclipped_depth: false,
-                polygon_mode: PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
+        [
+            particle_pipeline,
+            particle_additive_pipeline,
+            mirror_pipeline,
+            mirror_additive_pipeline,
+            infinite_pipeline,
+            infinite_additive_pipeline,
+        ]
+    }
 
-        // Create mirror wrap render pipeline
-        let mirror_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: Some("Mirror Wrap Render Pipeline"),
-            layout: Some(&mirror_pipeline_layout),
-            vertex: VertexState {
-                module: &mirror_shader,
-                entry_point: Some("vs_main"),
-                buffers: &[],
-                compilation_options: PipelineCompilationOptions::default(),
-            },
-            fragment: Some(FragmentState {
-                module: &mirror_shader,
-                entry_point: Some("fs_main"),
-                targets: &[Some(ColorTargetState {
-                    format: surface_format,
-                    blend: Some(BlendState::ALPHA_BLENDING),
-                    write_mask: ColorWrites::ALL,
-                })],
-                compilation_options: PipelineCompilationOptions::default(),
-            }),
-            primitive: PrimitiveState {
-                topology: PrimitiveTopology::TriangleStrip,
-                strip_index_format: None,
-                front_face: FrontFace::Ccw,
-                cull_mode: None,
-                unclipped_depth: false,
-                polygon_mode: PolygonMode::Fill,
-                conservative: false,
-            },
-            depth_stencil: None,
-            multisample: MultisampleState::default(),
-            multiview: None,
-            cache: None,
-        });
+    /// Rebuild the particle pipelines for a new MSAA sample count. The glow
+    /// and depth-tested pipelines are dropped and rebuilt on their next use.
+    pub fn set_sample_count(&mut self, device: &Device, sample_count: u32) {
+        if sample_count == self.sample_count {
+            return;
+        }
+        [
+            self.particle_pipeline,
+            self.particle_additive_pipeline,
+            self.mirror_pipeline,
+            self.mirror_additive_pipeline,
+            self.infinite_pipeline,
+            self.infinite_additive_pipeline,
+        ] = Self::create_particle_pipelines(
+            device,
+            [
+                &self.render_bind_group_layout,
+                &self.mirror_bind_group_layout,
+                &self.infinite_bind_group_layout,
+            ],
+            self.surface_format,
+            sample_count,
+            None,
+        );
+        self.glow_pipeline = None;
+        self.depth_pipelines = None;
+        self.sample_count = sample_count;
+    }
 
-        // Create infinite wrap render pipeline
-        let infinite_pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
-            label: Some("Infinite Wrap Render Pipeline"),
-            layout: Some(&infinite_pipeline_layout),
+    /// Build the depth-tested particle pipelines if they do not exist yet.
+    ///
+    /// They are only needed in 3D mode, so they are compiled the first time
+    /// it is enabled.
+    pub fn ensure_depth_pipelines(&mut self, device: &Device) {
+        if self.depth_pipelines.is_some() {
+            return;
+        }
+        self.depth_pipelines = Some(Self::create_particle_pipelines(
+            device,
+            [
+                &self.render_bind_group_layout,
+                &self.mirror_bind_group_layout,
+                &self.infinite_bind_group_layout,
+            ],
+            self.surface_format,
+            self.sample_count,
+            Some(depth_test()),
+        ));
+    }
+
+    /// Build a particle sprite pipeline (4-vertex triangle strip per
+    /// instance) with the given blend state and optional depth test.
+    #[allow(clippy::too_many_arguments)]
+    fn create_particle_pipeline(
+        device: &Device,
+        label: &str,
+        layout: &wgpu::PipelineLayout,
+        shader: &wgpu::ShaderModule,
+        surface_format: TextureFormat,
+        sample_count: u32,
+        blend: BlendState,
+        depth_stencil: Option<DepthStencilState>,
+    ) -> RenderPipeline {
+        device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(layout),
             vertex: VertexState {
-                module: &infinite_shader,
+                module: shader,
                 entry_point: Some("vs_main"),
                 buffers: &[],
                 compilation_options: PipelineCompilationOptions::default(),
             },
             fragment: Some(FragmentState {
-                module: &infinite_shader,
+                module: shader,
                 entry_point: Some("fs_main"),
                 targets: &[Some(ColorTargetState {
                     format: surface_format,
-                    blend: Some(BlendState::ALPHA_BLENDING),
+                    blend: Some(blend),
                     write_mask: ColorWrites::ALL,
                 })],
                 compilation_options: PipelineCompilationOptions::default(),
@@ -250,75 +407,130 @@ impl RenderPipelines {
                 polygon_mode: PolygonMode::Fill,
                 conservative: false,
             },
-            depth_stencil: None,
-            multisample: MultisampleState::default(),
+            depth_stencil,
+            multisample: MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
             multiview: None,
             cache: None,
-        });
+        })
+    }
 
-        // Create camera buffer with default values
-        let camera = CameraUniform::new(1920.0, 1080.0, 1920.0, 1080.0);
-        let camera_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Camera Buffer"),
-            contents: bytemuck::bytes_of(&camera),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+    /// Depth-tested pipeline for the given blend mode, `base` being the
+    /// index of the alpha variant in [`Self::depth_pipelines`].
+    fn depth_pipeline_for(&self, base: usize, blend_mode: BlendMode) -> &RenderPipeline {
+        let pipelines = self
+            .depth_pipelines
+            .as_ref()
+            .expect("depth pipelines built by ensure_depth_pipelines");
+        &pipelines[base + usize::from(blend_mode == BlendMode::Additive)]
+    }
 
-        // Create glow buffer with default values
-        let glow_params = GlowParamsUniform {
-            glow_size: 4.0,
-            glow_intensity: 0.5,
-            glow_steepness: 2.0,
-            _padding: 0.0,
-        };
-        let glow_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Glow Buffer"),
-            contents: bytemuck::bytes_of(&glow_params),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+    /// Standard particle pipeline for the given blend mode, depth-tested
+    /// when `depth` is set.
+    pub fn particle_pipeline_for(&self, blend_mode: BlendMode, depth: bool) -> &RenderPipeline {
+        if depth {
+            return self.depth_pipeline_for(0, blend_mode);
+        }
+        match blend_mode {
+            BlendMode::Alpha => &self.particle_pipeline,
+            BlendMode::Additive => &self.particle_additive_pipeline,
+        }
+    }
 
-        // Create mirror wrap buffer with default values
-        let mirror_params = MirrorParamsUniform {
-            num_copies: 5,
-            _padding: [0; 3],
-        };
-        let mirror_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Mirror Buffer"),
-            contents: bytemuck::bytes_of(&mirror_params),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+    /// Mirror wrap particle pipeline for the given blend mode, depth-tested
+    /// when `depth` is set.
+    pub fn mirror_pipeline_for(&self, blend_mode: BlendMode, depth: bool) -> &RenderPipeline {
+        if depth {
+            return self.depth_pipeline_for(2, blend_mode);
+        }
+        match blend_mode {
+            BlendMode::Alpha => &self.mirror_pipeline,
+            BlendMode::Additive => &self.mirror_additive_pipeline,
+        }
+    }
 
-        // Create infinite wrap buffer with default values
-        let infinite_params = InfiniteParamsUniform {
-            start_x: -1,
-            start_y: -1,
-            num_copies_x: 3,
-            num_copies_y: 3,
+    /// Infinite wrap particle pipeline for the given blend mode, depth-tested
+    /// when `depth` is set.
+    pub fn infinite_pipeline_for(&self, blend_mode: BlendMode, depth: bool) -> &RenderPipeline {
+        if depth {
+            return self.depth_pipeline_for(4, blend_mode);
+        }
+        match blend_mode {
+            BlendMode::Alpha => &self.infinite_pipeline,
+            BlendMode::Additive => &self.infinite_additive_pipeline,
+        }
+    }
+
+    /// Layout entries for the color mode bindings shared by all particle
+    /// render shaders: colormap, color params, velocities and depths,
+    /// starting at `first`.
+    ///
+    /// This is the single place that decides which data the color modes can
+    /// sample; keep it in sync with `particle_color` in the shaders.
+    fn color_layout_entries(first: u32) -> [BindGroupLayoutEntry; 4] {
+        let storage = |binding| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::VERTEX,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only: true },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
         };
-        let infinite_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some("Infinite Buffer"),
-            contents: bytemuck::bytes_of(&infinite_params),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-        });
+        [
+            // colormap (storage, read-only)
+            storage(first),
+            // color params (uniform)
+            BindGroupLayoutEntry {
+                binding: first + 1,
+                visibility: ShaderStages::VERTEX,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            // velocities (storage, read-only)
+            storage(first + 2),
+            // depths (storage, read-only)
+            storage(first + 3),
+        ]
+    }
 
-        Self {
-            particle_pipeline,
-            glow_pipeline,
-            mirror_pipeline,
-            infinite_pipeline,
-            render_bind_group_layout,
-            glow_bind_group_layout,
-            mirror_bind_group_layout,
-            infinite_bind_group_layout,
-            camera_buffer,
-            glow_buffer,
-            mirror_buffer,
-            infinite_buffer,
-        }
+    /// Bind group entries matching [`Self::color_layout_entries`].
+    fn color_bind_entries<'a>(
+        &'a self,
+        first: u32,
+        velocities: &'a Buffer,
+        depths: &'a Buffer,
+    ) -> [BindGroupEntry<'a>; 4] {
+        [
+            BindGroupEntry {
+                binding: first,
+                resource: self.colormap_buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: first + 1,
+                resource: self.color_buffer.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: first + 2,
+                resource: velocities.as_entire_binding(),
+            },
+            BindGroupEntry {
+                binding: first + 3,
+                resource: depths.as_entire_binding(),
+            },
+        ]
     }
 
     /// Create bind group layout for particle rendering.
     fn create_render_bind_group_layout(device: &Device) -> BindGroupLayout {
+        let [colormap, color_params, velocities, depths] = Self::color_layout_entries(4);
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some("Render Bind Group Layout"),
             entries: &[
@@ -366,19 +578,28 @@ impl RenderPipelines {
                     },
                     count: None,
                 },
+                colormap,
+                color_params,
+                velocities,
+                depths,
             ],
         })
     }
 
     /// Create render bind group.
     ///
-    /// Takes a reference to the current particle buffer for rendering.
+    /// Takes references to the current particle, velocity and depth buffers
+    /// for rendering.
     pub fn create_render_bind_group(
         &self,
         device: &Device,
         pos_type: &Buffer,
+        velocities: &Buffer,
+        depths: &Buffer,
         buffers: &SimulationBuffers,
     ) -> BindGroup {
+        let [colormap, color_params, velocities, depths] =
+            self.color_bind_entries(4, velocities, depths);
         device.create_bind_group(&BindGroupDescriptor {
             label: Some("Render Bind Group"),
             layout: &self.render_bind_group_layout,
@@ -399,10 +620,24 @@ impl RenderPipelines {
                     binding: 3,
                     resource: self.camera_buffer.as_entire_binding(),
                 },
+                colormap,
+                color_params,
+                velocities,
+                depths,
             ],
         })
     }
 
+    /// Estimated GPU memory used by the render uniform buffers, in bytes.
+    pub fn estimated_bytes(&self) -> u64 {
+        self.camera_buffer.size()
+            + self.glow_buffer.size()
+            + self.mirror_buffer.size()
+            + self.infinite_buffer.size()
+            + self.color_buffer.size()
+            + self.colormap_buffer.size()
+    }
+
     /// Update camera uniform buffer.
     pub fn update_camera(
         &self,
@@ -435,14 +670,107 @@ impl RenderPipelines {
         queue.write_buffer(&self.camera_buffer, 0, bytemuck::bytes_of(&camera));
     }
 
+    /// Build the glow pipeline if it does not exist yet.
+    ///
+    /// Glow is off on many low-end setups, so the shader and pipeline are
+    /// only compiled the first time it is enabled.
+    pub fn ensure_glow_pipeline(&mut self, device: &Device) {
+        if self.glow_pipeline.is_some() {
+            return;
+        }
+        let glow_shader = load_shader(
+            device,
+            "Particle Glow Shader",
+            include_str!("../../../../shaders/particle_render_glow.wgsl"),
+        );
+        let glow_pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Glow Pipeline Layout"),
+            bind_group_layouts: &[&self.glow_bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let surface_format = self.surface_format;
+        let sample_count = self.sample_count;
+
+        // Create glow render pipeline with additive blending
+        self.glow_pipeline = Some(device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Glow Render Pipeline"),
+            layout: Some(&glow_pipeline_layout),
+            vertex: VertexState {
+                module: &glow_shader,
+                entry_point: Some("vs_main"),
+                buffers: &[],
+                compilation_options: PipelineCompilationOptions::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &glow_shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: surface_format,
+                    // Additive blending for glow effect
+                    blend: Some(ADDITIVE_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: PipelineCompilationOptions::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleStrip,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: PolygonMode::Fill,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+            cache: None,
+        }));
+    }
+
     /// Update glow parameters uniform buffer.
-    pub fn update_glow(&self, queue: &Queue, config: &crate::simulation::SimulationConfig) {
-        let glow_params = GlowParamsUniform::from_config(config);
+    pub fn update_glow(
+        &self,
+        queue: &Queue,
+        config: &crate::simulation::SimulationConfig,
+        zoom: f32,
+    ) {
+        let glow_params = GlowParamsUniform::from_config(config, zoom);
         queue.write_buffer(&self.glow_buffer, 0, bytemuck::bytes_of(&glow_params));
     }
 
+    /// Update color mode parameters uniform buffer.
+    pub fn update_color_mode(
+        &self,
+        queue: &Queue,
+        config: &crate::simulation::SimulationConfig,
+        hue_offset: f32,
+    ) {
+        let color_params = ColorParamsUniform::from_config(config, COLORMAP_SIZE, hue_offset);
+        queue.write_buffer(&self.color_buffer, 0, bytemuck::bytes_of(&color_params));
+    }
+
+    /// Upload a colormap; it is resampled to [`COLORMAP_SIZE`] entries.
+    pub fn update_colormap(&self, queue: &Queue, colors: &[[f32; 4]]) {
+        if colors.is_empty() {
+            return;
+        }
+        let last = (COLORMAP_SIZE - 1) as f32;
+        let lut: Vec<[f32; 4]> = (0..COLORMAP_SIZE)
+            .map(|i| {
+                let idx = (i as f32 / last * (colors.len() - 1) as f32).round() as usize;
+                colors[idx]
+            })
+            .collect();
+        queue.write_buffer(&self.colormap_buffer, 0, bytemuck::cast_slice(&lut));
+    }
+
     /// Create bind group layout for glow rendering.
     fn create_glow_bind_group_layout(device: &Device) -> BindGroupLayout {
+        let [colormap, color_params, velocities, depths] = Self::color_layout_entries(5);
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some("Glow Bind Group Layout"),
             entries: &[
@@ -501,6 +829,10 @@ impl RenderPipelines {
                     },
                     count: None,
                 },
+                colormap,
+                color_params,
+                velocities,
+                depths,
             ],
         })
     }
@@ -510,8 +842,12 @@ impl RenderPipelines {
         &self,
         device: &Device,
         pos_type: &Buffer,
+        velocities: &Buffer,
+        depths: &Buffer,
         buffers: &SimulationBuffers,
     ) -> BindGroup {
+        let [colormap, color_params, velocities, depths] =
+            self.color_bind_entries(5, velocities, depths);
         device.create_bind_group(&BindGroupDescriptor {
             label: Some("Glow Bind Group"),
             layout: &self.glow_bind_group_layout,
@@ -536,12 +872,17 @@ impl RenderPipelines {
                     binding: 4,
                     resource: self.glow_buffer.as_entire_binding(),
                 },
+                colormap,
+                color_params,
+                velocities,
+                depths,
             ],
         })
     }
 
     /// Create bind group layout for mirror wrap rendering.
     fn create_mirror_bind_group_layout(device: &Device) -> BindGroupLayout {
+        let [colormap, color_params, velocities, depths] = Self::color_layout_entries(5);
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some("Mirror Bind Group Layout"),
             entries: &[
@@ -600,6 +941,10 @@ impl RenderPipelines {
                     },
                     count: None,
                 },
+                colormap,
+                color_params,
+                velocities,
+                depths,
             ],
         })
     }
@@ -609,8 +954,12 @@ impl RenderPipelines {
         &self,
         device: &Device,
         pos_type: &Buffer,
+        velocities: &Buffer,
+        depths: &Buffer,
         buffers: &SimulationBuffers,
     ) -> BindGroup {
+        let [colormap, color_params, velocities, depths] =
+            self.color_bind_entries(5, velocities, depths);
         device.create_bind_group(&BindGroupDescriptor {
             label: Some("Mirror Bind Group"),
             layout: &self.mirror_bind_group_layout,
@@ -635,6 +984,10 @@ impl RenderPipelines {
                     binding: 4,
                     resource: self.mirror_buffer.as_entire_binding(),
                 },
+                colormap,
+                color_params,
+                velocities,
+                depths,
             ],
         })
     }
@@ -647,6 +1000,7 @@ impl RenderPipelines {
 
     /// Create bind group layout for infinite wrap rendering.
     fn create_infinite_bind_group_layout(device: &Device) -> BindGroupLayout {
+        let [colormap, color_params, velocities, depths] = Self::color_layout_entries(5);
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some("Infinite Bind Group Layout"),
             entries: &[
@@ -705,6 +1059,10 @@ impl RenderPipelines {
                     },
                     count: None,
                 },
+                colormap,
+                color_params,
+                velocities,
+                depths,
             ],
         })
     }
@@ -714,8 +1072,12 @@ impl RenderPipelines {
         &self,
         device: &Device,
         pos_type: &Buffer,
+        velocities: &Buffer,
+        depths: &Buffer,
         buffers: &SimulationBuffers,
     ) -> BindGroup {
+        let [colormap, color_params, velocities, depths] =
+            self.color_bind_entries(5, velocities, depths);
         device.create_bind_group(&BindGroupDescriptor {
             label: Some("Infinite Bind Group"),
             layout: &self.infinite_bind_group_layout,
@@ -740,6 +1102,10 @@ impl RenderPipelines {
                     binding: 4,
                     resource: self.infinite_buffer.as_entire_binding(),
                 },
+                colormap,
+                color_params,
+                velocities,
+                depths,
             ],
         })
     }
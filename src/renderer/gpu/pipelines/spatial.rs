@@ -12,7 +12,78 @@ use wgpu::{
 };
 
 use super::load_shader;
-use crate::renderer::gpu::{SimulationBuffers, SpatialHashBuffers};
+use crate::renderer::gpu::{PREFIX_SCAN_BLOCK_SIZE, SimulationBuffers, SpatialHashBuffers};
+
+/// Default workgroup size of the per-particle and per-bin spatial hash passes.
+pub const DEFAULT_SPATIAL_WORKGROUP_SIZE: u32 = 256;
+
+/// Clamp a requested spatial hash workgroup size to a power of two in
+/// 32..=256 (256 is the default `max_compute_invocations_per_workgroup`).
+pub fn spatial_workgroup_size(requested: u32) -> u32 {
+    requested.clamp(32, 256).next_power_of_two()
+}
+
+/// Load a spatial hash shader with its `WORKGROUP_SIZE` placeholder filled in.
+fn load_sized_shader(
+    device: &Device,
+    label: &str,
+    source: &str,
+    workgroup_size: u32,
+) -> wgpu::ShaderModule {
+    let source = source.replace("WORKGROUP_SIZE", &workgroup_size.to_string());
+    load_shader(device, label, &source)
+}
+
+/// One dispatch of the tiled prefix sum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrefixScanPass {
+    /// Scan each block of a level in place and record block totals.
+    Scan { level: usize, workgroups: u32 },
+    /// Add the scanned block totals of the level above into a level.
+    AddOffsets { level: usize, workgroups: u32 },
+}
+
+impl PrefixScanPass {
+    /// Level whose data this pass operates on.
+    pub fn level(self) -> usize {
+        match self {
+            Self::Scan { level, .. } | Self::AddOffsets { level, .. } => level,
+        }
+    }
+
+    /// Workgroups to dispatch (one per block).
+    pub fn workgroups(self) -> u32 {
+        match self {
+            Self::Scan { workgroups, .. } | Self::AddOffsets { workgroups, .. } => workgroups,
+        }
+    }
+}
+
+/// Dispatch order for a prefix sum with the given level sizes: scan every
+/// level bottom-up, then propagate block offsets top-down.
+///
+/// Total work is O(n) with `2 * levels - 1` dispatches, where the previous
+/// Hillis-Steele scan did O(n log n) work over `ceil(log2(n))` dispatches.
+pub fn prefix_scan_passes(levels: &[u32]) -> Vec<PrefixScanPass> {
+    let workgroups = |len: u32| len.div_ceil(PREFIX_SCAN_BLOCK_SIZE);
+    let scans = levels
+        .iter()
+        .enumerate()
+        .map(|(level, &len)| PrefixScanPass::Scan {
+            level,
+            workgroups: workgroups(len),
+        });
+    let adds = levels
+        .iter()
+        .enumerate()
+        .rev()
+        .skip(1)
+        .map(|(level, &len)| PrefixScanPass::AddOffsets {
+            level,
+            workgroups: workgroups(len),
+        });
+    scans.chain(adds).collect()
+}
 
 /// Compute pipelines for spatial hashing optimization.
 pub struct SpatialHashPipelines {
@@ -20,8 +91,10 @@ pub struct SpatialHashPipelines {
     pub clear_pipeline: ComputePipeline,
     /// Pipeline for counting particles per bin.
     pub count_pipeline: ComputePipeline,
-    /// Pipeline for prefix sum (computing bin offsets).
-    pub prefix_sum_pipeline: ComputePipeline,
+    /// Pipeline scanning 2048-element blocks of the bin counts.
+    pub prefix_scan_pipeline: ComputePipeline,
+    /// Pipeline adding scanned block totals back into each block.
+    pub prefix_add_pipeline: ComputePipeline,
     /// Pipeline for sorting particles by bin.
     pub sort_pipeline: ComputePipeline,
     /// Pipeline for binned force calculation.
@@ -36,22 +109,30 @@ pub struct SpatialHashPipelines {
     pub sort_bind_group_layout: BindGroupLayout,
     /// Bind group layout for binned force calculation.
     pub forces_bind_group_layout: BindGroupLayout,
+    /// Workgroup size of the clear, count, sort and forces passes. The prefix
+    /// scan keeps its own fixed size since its tiling depends on it.
+    pub workgroup_size: u32,
 }
 
 impl SpatialHashPipelines {
-    /// Create spatial hash pipelines.
-    pub fn new(device: &Device) -> Self {
+    /// Create spatial hash pipelines. `workgroup_size` is passed through
+    /// [`spatial_workgroup_size`] before use.
+    pub fn new(device: &Device, workgroup_size: u32) -> Self {
+        let workgroup_size = spatial_workgroup_size(workgroup_size);
+
         // Load shaders with FP16 support
-        let clear_shader = load_shader(
+        let clear_shader = load_sized_shader(
             device,
             "Bin Clear Shader",
             include_str!("../../../../shaders/bin_clear.wgsl"),
+            workgroup_size,
         );
 
-        let count_shader = load_shader(
+        let count_shader = load_sized_shader(
             device,
             "Bin Count Shader",
             include_str!("../../../../shaders/bin_count.wgsl"),
+            workgroup_size,
         );
 
         let prefix_sum_shader = load_shader(
@@ -60,16 +141,18 @@ impl SpatialHashPipelines {
             include_str!("../../../../shaders/bin_prefix_sum.wgsl"),
         );
 
-        let sort_shader = load_shader(
+        let sort_shader = load_sized_shader(
             device,
             "Bin Sort Shader",
             include_str!("../../../../shaders/bin_sort.wgsl"),
+            workgroup_size,
         );
 
-        let forces_shader = load_shader(
+        let forces_shader = load_sized_shader(
             device,
             "Binned Forces Shader",
             include_str!("../../../../shaders/particle_forces_binned.wgsl"),
+            workgroup_size,
         );
 
         // Create bind group layouts
@@ -129,11 +212,20 @@ impl SpatialHashPipelines {
             cache: None,
         });
 
-        let prefix_sum_pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
-            label: Some("Prefix Sum Pipeline"),
+        let prefix_scan_pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+            label: Some("Prefix Scan Pipeline"),
             layout: Some(&prefix_sum_pipeline_layout),
             module: &prefix_sum_shader,
-            entry_point: Some("main"),
+            entry_point: Some("scan_blocks"),
+            compilation_options: PipelineCompilationOptions::default(),
+            cache: None,
+        });
+
+        let prefix_add_pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
+            label: Some("Prefix Add Pipeline"),
+            layout: Some(&prefix_sum_pipeline_layout),
+            module: &prefix_sum_shader,
+            entry_point: Some("add_block_offsets"),
             compilation_options: PipelineCompilationOptions::default(),
             cache: None,
         });
@@ -159,7 +251,8 @@ impl SpatialHashPipelines {
         Self {
             clear_pipeline,
             count_pipeline,
-            prefix_sum_pipeline,
+            prefix_scan_pipeline,
+            prefix_add_pipeline,
             sort_pipeline,
             forces_pipeline,
             clear_bind_group_layout,
@@ -167,9 +260,15 @@ impl SpatialHashPipelines {
             prefix_sum_bind_group_layout,
             sort_bind_group_layout,
             forces_bind_group_layout,
+            workgroup_size,
         }
     }
 
+    /// Workgroups needed to cover `items` particles or bins.
+    pub fn workgroups(&self, items: u32) -> u32 {
+        items.div_ceil(self.workgroup_size)
+    }
+
     /// Create bind group layout for bin clear.
     fn create_clear_bind_group_layout(device: &Device) -> BindGroupLayout {
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
@@ -248,18 +347,18 @@ impl SpatialHashPipelines {
         device.create_bind_group_layout(&BindGroupLayoutDescriptor {
             label: Some("Prefix Sum Bind Group Layout"),
             entries: &[
-                // source (storage, read-only)
+                // data (storage, read-write, scanned in place)
                 BindGroupLayoutEntry {
                     binding: 0,
                     visibility: ShaderStages::COMPUTE,
                     ty: BindingType::Buffer {
-                        ty: BufferBindingType::Storage { read_only: true },
+                        ty: BufferBindingType::Storage { read_only: false },
                         has_dynamic_offset: false,
                         min_binding_size: None,
                     },
                     count: None,
                 },
-                // destination (storage, read-write)
+                // block_sums (storage, read-write)
                 BindGroupLayoutEntry {
                     binding: 1,
                     visibility: ShaderStages::COMPUTE,
@@ -270,17 +369,6 @@ impl SpatialHashPipelines {
                     },
                     count: None,
                 },
-                // step_size (uniform)
-                BindGroupLayoutEntry {
-                    binding: 2,
-                    visibility: ShaderStages::COMPUTE,
-                    ty: BindingType::Buffer {
-                        ty: BufferBindingType::Uniform,
-                        has_dynamic_offset: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
-                },
             ],
         })
     }
@@ -367,6 +455,28 @@ impl SpatialHashPipelines {
                     },
                     count: None,
                 },
+                // depth_in (storage, read-only)
+                BindGroupLayoutEntry {
+                    binding: 7,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // depth_out (storage, read-write)
+                BindGroupLayoutEntry {
+                    binding: 8,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: false },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         })
     }
@@ -464,6 +574,28 @@ impl SpatialHashPipelines {
                     },
                     count: None,
                 },
+                // anisotropy (storage, read-only)
+                BindGroupLayoutEntry {
+                    binding: 9,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                // type_physics (storage, read-only)
+                BindGroupLayoutEntry {
+                    binding: 10,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
             ],
         })
     }
@@ -524,32 +656,70 @@ impl SpatialHashPipelines {
         })
     }
 
-    /// Create prefix sum bind group for one pass.
-    pub fn create_prefix_sum_bind_group(
+    /// Create prefix sum bind groups, one per scan level.
+    ///
+    /// Level 0 scans `data`; each further level scans the block totals of
+    /// the level below. `block_sums` comes from `create_prefix_scan_block_sums`.
+    pub fn create_prefix_sum_bind_groups(
         &self,
         device: &Device,
-        source: &Buffer,
-        destination: &Buffer,
-        step_size_uniform: &Buffer,
-    ) -> BindGroup {
-        device.create_bind_group(&BindGroupDescriptor {
-            label: Some("Prefix Sum Bind Group"),
-            layout: &self.prefix_sum_bind_group_layout,
-            entries: &[
-                BindGroupEntry {
-                    binding: 0,
-                    resource: source.as_entire_binding(),
-                },
-                BindGroupEntry {
-                    binding: 1,
-                    resource: destination.as_entire_binding(),
-                },
-                BindGroupEntry {
-                    binding: 2,
-                    resource: step_size_uniform.as_entire_binding(),
-                },
-            ],
-        })
+        data: &Buffer,
+        block_sums: &[Buffer],
+    ) -> Vec<BindGroup> {
+        block_sums
+            .iter()
+            .enumerate()
+            .map(|(level, sums)| {
+                let level_data = if level == 0 {
+                    data
+                } else {
+                    &block_sums[level - 1]
+                };
+                device.create_bind_group(&BindGroupDescriptor {
+                    label: Some("Prefix Sum Bind Group"),
+                    layout: &self.prefix_sum_bind_group_layout,
+                    entries: &[
+                        BindGroupEntry {
+                            binding: 0,
+                            resource: level_data.as_entire_binding(),
+                        },
+                        BindGroupEntry {
+                            binding: 1,
+                            resource: sums.as_entire_binding(),
+                        },
+                    ],
+                })
+            })
+            .collect()
+    }
+
+    /// Pipeline for one prefix sum dispatch.
+    pub fn prefix_pipeline(&self, pass: PrefixScanPass) -> &ComputePipeline {
+        match pass {
+            PrefixScanPass::Scan { .. } => &self.prefix_scan_pipeline,
+            PrefixScanPass::AddOffsets { .. } => &self.prefix_add_pipeline,
+        }
+    }
+
+    /// Record a full prefix sum into `encoder`, one compute pass per dispatch.
+    ///
+    /// `levels` is from `prefix_scan_levels` and `bind_groups` from
+    /// `create_prefix_sum_bind_groups` for the same length.
+    pub fn encode_prefix_sum(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        bind_groups: &[BindGroup],
+        levels: &[u32],
+    ) {
+        for pass_info in prefix_scan_passes(levels) {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Prefix Sum Pass"),
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(self.prefix_pipeline(pass_info));
+            pass.set_bind_group(0, &bind_groups[pass_info.level()], &[]);
+            pass.dispatch_workgroups(pass_info.workgroups(), 1, 1);
+        }
     }
 
     /// Create bin sort bind group.
@@ -561,6 +731,8 @@ impl SpatialHashPipelines {
         pos_type_out: &Buffer,
         vel_in: &Buffer,
         vel_out: &Buffer,
+        depth_in: &Buffer,
+        depth_out: &Buffer,
         spatial: &SpatialHashBuffers,
         use_offset_buffer_a: bool,
         use_count_buffer_a: bool,
@@ -608,6 +780,14 @@ impl SpatialHashPipelines {
                     binding: 6,
                     resource: spatial.params.as_entire_binding(),
                 },
+                BindGroupEntry {
+                    binding: 7,
+                    resource: depth_in.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 8,
+                    resource: depth_out.as_entire_binding(),
+                },
             ],
         })
     }
@@ -657,7 +837,231 @@ impl SpatialHashPipelines {
                     binding: 8,
                     resource: sorted_pos_type.as_entire_binding(),
                 },
+                BindGroupEntry {
+                    binding: 9,
+                    resource: sim_buffers.anisotropy.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 10,
+                    resource: sim_buffers.type_physics.as_entire_binding(),
+                },
             ],
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::gpu::{create_prefix_scan_block_sums, prefix_scan_levels};
+    use crate::simulation::{InteractionMatrix, Particle, RadiusMatrix, SimulationConfig};
+    use wgpu::util::DeviceExt;
+
+    #[test]
+    fn test_prefix_scan_passes_order() {
+        let levels = prefix_scan_levels(4_300_000);
+        assert_eq!(levels, vec![4_300_000, 2100, 2]);
+        assert_eq!(
+            prefix_scan_passes(&levels),
+            vec![
+                PrefixScanPass::Scan {
+                    level: 0,
+                    workgroups: 2100
+                },
+                PrefixScanPass::Scan {
+                    level: 1,
+                    workgroups: 2
+                },
+                PrefixScanPass::Scan {
+                    level: 2,
+                    workgroups: 1
+                },
+                PrefixScanPass::AddOffsets {
+                    level: 1,
+                    workgroups: 2
+                },
+                PrefixScanPass::AddOffsets {
+                    level: 0,
+                    workgroups: 2100
+                },
+            ]
+        );
+        // A single block needs no offset pass
+        assert_eq!(prefix_scan_passes(&prefix_scan_levels(2048)).len(), 1);
+    }
+
+    #[test]
+    fn test_spatial_workgroup_size_is_clamped_power_of_two() {
+        assert_eq!(spatial_workgroup_size(64), 64);
+        assert_eq!(spatial_workgroup_size(100), 128);
+        assert_eq!(spatial_workgroup_size(1), 32);
+        assert_eq!(spatial_workgroup_size(1024), 256);
+    }
+
+    /// Runs the scan shader on a real device; skipped when no adapter exists.
+    #[test]
+    fn test_gpu_prefix_sum_is_exclusive_scan() {
+        let instance = wgpu::Instance::default();
+        let Ok(adapter) =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        else {
+            eprintln!("No GPU adapter available; skipping prefix sum test");
+            return;
+        };
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+                .expect("Failed to create device");
+        let pipelines = SpatialHashPipelines::new(&device, DEFAULT_SPATIAL_WORKGROUP_SIZE);
+
+        for num_bins in [1u32, 4, 2047, 2048, 4095, 300_000] {
+            // Same layout as the bin counts: [0, count(bin 0), count(bin 1), ...]
+            let counts: Vec<u32> = (0..num_bins).map(|i| (i * 7 + 3) % 11).collect();
+            let mut data = vec![0u32];
+            data.extend(&counts);
+            let len = data.len() as u32;
+
+            let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some("Prefix Test Data"),
+                contents: bytemuck::cast_slice(&data),
+                usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            });
+            let staging = device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Prefix Test Staging"),
+                size: buffer.size(),
+                usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            let block_sums = create_prefix_scan_block_sums(&device, len);
+            let bind_groups =
+                pipelines.create_prefix_sum_bind_groups(&device, &buffer, &block_sums);
+
+            let mut encoder = device.create_command_encoder(&Default::default());
+            pipelines.encode_prefix_sum(&mut encoder, &bind_groups, &prefix_scan_levels(len));
+            encoder.copy_buffer_to_buffer(&buffer, 0, &staging, 0, buffer.size());
+            queue.submit(Some(encoder.finish()));
+
+            staging
+                .slice(..)
+                .map_async(wgpu::MapMode::Read, |r| r.unwrap());
+            device
+                .poll(wgpu::PollType::wait_indefinitely())
+                .expect("Device poll failed");
+            let offsets: Vec<u32> =
+                bytemuck::cast_slice(&staging.slice(..).get_mapped_range()).to_vec();
+
+            // offsets[i] must be the exact sum of the counts of bins before i
+            let mut expected = 0u32;
+            for (i, &offset) in offsets.iter().enumerate() {
+                assert_eq!(offset, expected, "bins={} index={}", num_bins, i);
+                if let Some(&count) = counts.get(i) {
+                    expected += count;
+                }
+            }
+        }
+    }
+
+    /// Runs the bin sort for a few steps and checks every particle still
+    /// carries its own depth; skipped when no adapter exists.
+    #[test]
+    fn test_gpu_bin_sort_moves_depths_with_particles() {
+        let instance = wgpu::Instance::default();
+        let Ok(adapter) =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        else {
+            eprintln!("No GPU adapter available; skipping bin sort depth test");
+            return;
+        };
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+                .expect("Failed to create device");
+        let pipelines = SpatialHashPipelines::new(&device, DEFAULT_SPATIAL_WORKGROUP_SIZE);
+
+        // Reverse bin order so the sort has to move every particle; the
+        // depth records the original index
+        let num = 64;
+        let config = SimulationConfig {
+            num_particles: num,
+            num_types: 2,
+            ..Default::default()
+        };
+        let particles: Vec<Particle> = (0..num)
+            .map(|i| {
+                let x = config.world_size.x * (num - i) as f32 / (num + 1) as f32;
+                Particle {
+                    z: i as f32,
+                    ..Particle::new(x, config.world_size.y * 0.5, i % 2)
+                }
+            })
+            .collect();
+        let mut buffers = SimulationBuffers::new(
+            &device,
+            &particles,
+            &InteractionMatrix::new(2),
+            &RadiusMatrix::default_for_size(2),
+            &[[1.0; 4]; 2],
+            &config,
+        );
+        let spatial = SpatialHashBuffers::new(&device, &config, 20.0);
+
+        let clear_a = pipelines.create_clear_bind_group(&device, &spatial, true);
+        let clear_b = pipelines.create_clear_bind_group(&device, &spatial, false);
+        let prefix = pipelines.create_prefix_sum_bind_groups(
+            &device,
+            &spatial.bin_counts_a,
+            &spatial.scan_block_sums,
+        );
+        let bin_workgroups = pipelines.workgroups(spatial.total_bins_with_end());
+        let particle_workgroups = pipelines.workgroups(num);
+
+        for _ in 0..3 {
+            let count =
+                pipelines.create_count_bind_group(&device, buffers.current_pos_type(), &spatial);
+            let sort = pipelines.create_sort_bind_group(
+                &device,
+                buffers.current_pos_type(),
+                buffers.next_pos_type(),
+                buffers.current_velocities(),
+                buffers.next_velocities(),
+                buffers.current_depths(),
+                buffers.next_depths(),
+                &spatial,
+                true,
+                false,
+            );
+
+            let mut encoder = device.create_command_encoder(&Default::default());
+            for (pipeline, bind_group, workgroups) in [
+                (&pipelines.clear_pipeline, &clear_a, bin_workgroups),
+                (&pipelines.count_pipeline, &count, particle_workgroups),
+            ] {
+                let mut pass = encoder.begin_compute_pass(&Default::default());
+                pass.set_pipeline(pipeline);
+                pass.set_bind_group(0, bind_group, &[]);
+                pass.dispatch_workgroups(workgroups, 1, 1);
+            }
+            pipelines.encode_prefix_sum(&mut encoder, &prefix, &spatial.prefix_scan_levels());
+            for (pipeline, bind_group, workgroups) in [
+                (&pipelines.clear_pipeline, &clear_b, bin_workgroups),
+                (&pipelines.sort_pipeline, &sort, particle_workgroups),
+            ] {
+                let mut pass = encoder.begin_compute_pass(&Default::default());
+                pass.set_pipeline(pipeline);
+                pass.set_bind_group(0, bind_group, &[]);
+                pass.dispatch_workgroups(workgroups, 1, 1);
+            }
+            queue.submit(Some(encoder.finish()));
+            buffers.swap_buffers();
+        }
+
+        let read = buffers.read_particles(&device, &queue);
+        assert_eq!(read.len(), num as usize);
+        // The sort reordered the particles...
+        assert_ne!(read[0].z, 0.0);
+        // ...and each depth still belongs to the particle it was given to
+        for particle in &read {
+            let original = &particles[particle.z as usize];
+            assert_eq!((particle.x, particle.y), (original.x, original.y));
+            assert_eq!(particle.particle_type, original.particle_type);
+        }
+    }
+}
@@ -0,0 +1,463 @@
+//! Render pipelines for particle trails (motion blur).
+//!
+//! With trails enabled the scene is drawn into a persistent offscreen
+//! texture instead of the surface. Each frame a translucent quad of the
+//! background color fades the previous contents, particles are drawn on top,
+//! and the texture is copied to the surface before the brush and UI overlays.
+
+use wgpu::util::DeviceExt;
+use wgpu::{
+    AddressMode, BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout,
+    BindGroupLayoutDescriptor, BindGroupLayoutEntry, BindingResource, BindingType, BlendState,
+    Buffer, BufferBindingType, BufferUsages, ColorTargetState, ColorWrites, Device, Extent3d,
+    FilterMode, FragmentState, FrontFace, MultisampleState, PipelineCompilationOptions,
+    PipelineLayoutDescriptor, PolygonMode, PrimitiveState, PrimitiveTopology, Queue,
+    RenderPipeline, RenderPipelineDescriptor, Sampler, SamplerBindingType, SamplerDescriptor,
+    ShaderModuleDescriptor, ShaderSource, ShaderStages, Texture, TextureDescriptor,
+    TextureDimension, TextureFormat, TextureSampleType, TextureUsages, TextureView,
+    TextureViewDescriptor, TextureViewDimension, VertexAttribute, VertexBufferLayout, VertexFormat,
+    VertexState, VertexStepMode,
+};
+
+use crate::renderer::gpu::TrailParamsUniform;
+
+/// Offscreen texture holding the accumulated trails.
+struct TrailTarget {
+    /// Texture the scene is rendered into.
+    _texture: Texture,
+    /// Render attachment view of the texture.
+    view: TextureView,
+    /// Bind group sampling the texture for the blit pass.
+    blit_bind_group: BindGroup,
+    /// Texture size in pixels.
+    size: (u32, u32),
+}
+
+/// Fade and blit pipelines plus the persistent trail texture.
+pub struct TrailPipelines {
+    /// Pipeline drawing the translucent background quad.
+    pub fade_pipeline: RenderPipeline,
+    /// Pipeline copying the trail texture to the surface.
+    pub blit_pipeline: RenderPipeline,
+    /// Bind group for the fade pass.
+    pub fade_bind_group: BindGroup,
+    /// Fade parameters uniform buffer.
+    pub params_buffer: Buffer,
+    /// Bind group layout for the fade pass, kept to rebuild its pipeline.
+    fade_bind_group_layout: BindGroupLayout,
+    /// Bind group layout for the blit pass.
+    blit_bind_group_layout: BindGroupLayout,
+    /// Sampler for the blit pass.
+    sampler: Sampler,
+    /// Texture format (matches the surface so particle pipelines can target it).
+    format: TextureFormat,
+    /// MSAA sample count of the fade pipeline, which draws with the scene.
+    sample_count: u32,
+    /// Trail texture, created on first use and on resize.
+    target: Option<TrailTarget>,
+}
+
+impl TrailPipelines {
+    /// Create trail pipelines for the given surface format. The fade pass
+    /// is built with the scene's MSAA `sample_count`; the blit is always
+    /// single-sampled.
+    pub fn new(device: &Device, surface_format: TextureFormat, sample_count: u32) -> Self {
+        let shader = Self::create_shader(device);
+
+        let fade_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Trail Fade Bind Group Layout"),
+            entries: &[
+                // trail params (uniform)
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let blit_bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Trail Blit Bind Group Layout"),
+            entries: &[
+                // trail texture
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                // trail sampler
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+
+        let fade_pipeline = Self::create_pipeline(
+            device,
+            &shader,
+            "Trail Fade Pipeline",
+            &fade_bind_group_layout,
+            "fs_fade",
+            Some(BlendState::ALPHA_BLENDING),
+            surface_format,
+            sample_count,
+        );
+        let blit_pipeline = Self::create_pipeline(
+            device,
+            &shader,
+            "Trail Blit Pipeline",
+            &blit_bind_group_layout,
+            "fs_blit",
+            None,
+            surface_format,
+            1,
+        );
+
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Trail Params Buffer"),
+            contents: bytemuck::bytes_of(&TrailParamsUniform {
+                fade_color: [0.0, 0.0, 0.0, 1.0],
+            }),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let fade_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Trail Fade Bind Group"),
+            layout: &fade_bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: params_buffer.as_entire_binding(),
+            }],
+        });
+
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            label: Some("Trail Sampler"),
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Nearest,
+            min_filter: FilterMode::Nearest,
+            ..Default::default()
+        });
+
+        Self {
+            fade_pipeline,
+            blit_pipeline,
+            fade_bind_group,
+            params_buffer,
+            fade_bind_group_layout,
+            blit_bind_group_layout,
+            sampler,
+            format: surface_format,
+            sample_count,
+            target: None,
+        }
+    }
+
+    fn create_shader(device: &Device) -> wgpu::ShaderModule {
+        device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Trail Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../../../shaders/trail.wgsl").into()),
+        })
+    }
+
+    /// Build a fullscreen-quad pipeline running `entry_point` of the trail
+    /// shader.
+    #[allow(clippy::too_many_arguments)]
+    fn create_pipeline(
+        device: &Device,
+        shader: &wgpu::ShaderModule,
+        label: &str,
+        layout: &BindGroupLayout,
+        entry_point: &str,
+        blend: Option<BlendState>,
+        format: TextureFormat,
+        sample_count: u32,
+    ) -> RenderPipeline {
+        // Fullscreen quad vertices from `RenderBuffers`: [x, y, u, v]
+        let quad_layout = VertexBufferLayout {
+            array_stride: 16,
+            step_mode: VertexStepMode::Vertex,
+            attributes: &[
+                VertexAttribute {
+                    format: VertexFormat::Float32x2,
+                    offset: 0,
+                    shader_location: 0,
+                },
+                VertexAttribute {
+                    format: VertexFormat::Float32x2,
+                    offset: 8,
+                    shader_location: 1,
+                },
+            ],
+        };
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some(label),
+            bind_group_layouts: &[layout],
+            push_constant_ranges: &[],
+        });
+        device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some(label),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: shader,
+                entry_point: Some("vs_main"),
+                buffers: &[quad_layout],
+                compilation_options: PipelineCompilationOptions::default(),
+            },
+            fragment: Some(FragmentState {
+                module: shader,
+                entry_point: Some(entry_point),
+                targets: &[Some(ColorTargetState {
+                    format,
+                    blend,
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: PipelineCompilationOptions::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: sample_count,
+                ..Default::default()
+            },
+            multiview: None,
+            cache: None,
+        })
+    }
+
+    /// Rebuild the fade pipeline for a new MSAA sample count.
+    pub fn set_sample_count(&mut self, device: &Device, sample_count: u32) {
+        if sample_count == self.sample_count {
+            return;
+        }
+        self.fade_pipeline = Self::create_pipeline(
+            device,
+            &Self::create_shader(device),
+            "Trail Fade Pipeline",
+            &self.fade_bind_group_layout,
+            "fs_fade",
+            Some(BlendState::ALPHA_BLENDING),
+            self.format,
+            sample_count,
+        );
+        self.sample_count = sample_count;
+    }
+
+    /// Make sure the trail texture exists at the given size.
+    ///
+    /// Returns `true` when the texture was (re)created, e.g. on first use or
+    /// after a window resize, in which case the caller must clear it instead
+    /// of fading the (undefined) previous contents.
+    pub fn ensure_target(&mut self, device: &Device, width: u32, height: u32) -> bool {
+        let size = (width.max(1), height.max(1));
+        if self.target.as_ref().is_some_and(|t| t.size == size) {
+            return false;
+        }
+
+        let texture = device.create_texture(&TextureDescriptor {
+            label: Some("Trail Texture"),
+            size: Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: self.format,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&TextureViewDescriptor::default());
+        let blit_bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Trail Blit Bind Group"),
+            layout: &self.blit_bind_group_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::TextureView(&view),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::Sampler(&self.sampler),
+                },
+            ],
+        });
+
+        self.target = Some(TrailTarget {
+            _texture: texture,
+            view,
+            blit_bind_group,
+            size,
+        });
+        true
+    }
+
+    /// Drop the trail texture (when trails are turned off).
+    pub fn release(&mut self) {
+        self.target = None;
+    }
+
+    /// Render attachment view of the trail texture, if created.
+    pub fn view(&self) -> Option<&TextureView> {
+        self.target.as_ref().map(|t| &t.view)
+    }
+
+    /// Bind group sampling the trail texture, if created.
+    pub fn blit_bind_group(&self) -> Option<&BindGroup> {
+        self.target.as_ref().map(|t| &t.blit_bind_group)
+    }
+
+    /// Update the fade color and per-frame fade alpha.
+    pub fn update_fade(&self, queue: &Queue, background: [f32; 3], fade: f32) {
+        let params = TrailParamsUniform {
+            fade_color: [background[0], background[1], background[2], fade],
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+    }
+
+    /// Estimated GPU memory used by the trail uniform buffer, in bytes.
+    pub fn estimated_bytes(&self) -> u64 {
+        self.params_buffer.size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::gpu::RenderBuffers;
+
+    /// Fades a white frame halfway to black and blits it; skipped when no
+    /// adapter exists.
+    #[test]
+    fn test_gpu_trail_fade_and_blit() {
+        let instance = wgpu::Instance::default();
+        let Ok(adapter) =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+        else {
+            eprintln!("No GPU adapter available; skipping trail test");
+            return;
+        };
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+                .expect("Failed to create device");
+        let format = TextureFormat::Rgba8Unorm;
+        let mut trail = TrailPipelines::new(&device, format, 1);
+        let quad = RenderBuffers::new(&device);
+
+        assert!(trail.ensure_target(&device, 4, 4));
+        assert!(!trail.ensure_target(&device, 4, 4), "same size is reused");
+        trail.update_fade(&queue, [0.0, 0.0, 0.0], 0.5);
+
+        let output = device.create_texture(&TextureDescriptor {
+            label: Some("Trail Test Output"),
+            size: Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format,
+            usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_SRC,
+            view_formats: &[],
+        });
+        let output_view = output.create_view(&TextureViewDescriptor::default());
+        let readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Trail Test Readback"),
+            size: 256 * 4,
+            usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = device.create_command_encoder(&Default::default());
+        let pass = |encoder: &mut wgpu::CommandEncoder,
+                    view: &TextureView,
+                    load: wgpu::LoadOp<wgpu::Color>,
+                    pipeline: &RenderPipeline,
+                    bind_group: &BindGroup| {
+            let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: None,
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load,
+                        store: wgpu::StoreOp::Store,
+                    },
+                    depth_slice: None,
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            render_pass.set_pipeline(pipeline);
+            render_pass.set_bind_group(0, bind_group, &[]);
+            render_pass.set_vertex_buffer(0, quad.fullscreen_quad.slice(..));
+            render_pass.draw(0..6, 0..1);
+        };
+        pass(
+            &mut encoder,
+            trail.view().unwrap(),
+            wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+            &trail.fade_pipeline,
+            &trail.fade_bind_group,
+        );
+        pass(
+            &mut encoder,
+            &output_view,
+            wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+            &trail.blit_pipeline,
+            trail.blit_bind_group().unwrap(),
+        );
+        encoder.copy_texture_to_buffer(
+            output.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &readback,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(256),
+                    rows_per_image: Some(4),
+                },
+            },
+            output.size(),
+        );
+        queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |r| r.unwrap());
+        device.poll(wgpu::PollType::wait_indefinitely()).unwrap();
+        let data = slice.get_mapped_range();
+        let pixel = &data[..4];
+        for &c in &pixel[..3] {
+            assert!(
+                (126..=129).contains(&c),
+                "expected half fade, got {:?}",
+                pixel
+            );
+        }
+        assert_eq!(pixel[3], 255, "blit output is opaque");
+    }
+}
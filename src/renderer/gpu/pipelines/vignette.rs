@@ -0,0 +1,150 @@
+//! Render pipeline for the vignette post pass.
+//!
+//! Darkens the screen toward its corners with the fullscreen quad. It draws
+//! on the surface after the scene (and after the trail blit), so trails
+//! never accumulate it, and before the brush circle and UI.
+
+use wgpu::util::DeviceExt;
+use wgpu::{
+    BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayoutDescriptor,
+    BindGroupLayoutEntry, BindingType, BlendState, Buffer, BufferBindingType, BufferUsages,
+    ColorTargetState, ColorWrites, Device, FragmentState, FrontFace, MultisampleState,
+    PipelineCompilationOptions, PipelineLayoutDescriptor, PolygonMode, PrimitiveState,
+    PrimitiveTopology, Queue, RenderPipeline, RenderPipelineDescriptor, ShaderModuleDescriptor,
+    ShaderSource, ShaderStages, TextureFormat, VertexAttribute, VertexBufferLayout, VertexFormat,
+    VertexState, VertexStepMode,
+};
+
+use crate::renderer::gpu::VignetteParamsUniform;
+
+/// Vignette pipeline and its intensity uniform.
+pub struct VignettePipeline {
+    /// Pipeline drawing the darkening quad, alpha blended.
+    pub pipeline: RenderPipeline,
+    /// Bind group for the vignette parameters.
+    pub bind_group: BindGroup,
+    /// Vignette parameters uniform buffer.
+    params_buffer: Buffer,
+}
+
+impl VignettePipeline {
+    /// Create the vignette pipeline for the given surface format.
+    pub fn new(device: &Device, surface_format: TextureFormat) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("Vignette Bind Group Layout"),
+            entries: &[
+                // vignette params (uniform)
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+            ],
+        });
+
+        let shader = device.create_shader_module(ShaderModuleDescriptor {
+            label: Some("Vignette Shader"),
+            source: ShaderSource::Wgsl(include_str!("../../../../shaders/vignette.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+            label: Some("Vignette Pipeline Layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            label: Some("Vignette Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: VertexState {
+                module: &shader,
+                entry_point: Some("vs_main"),
+                // Fullscreen quad vertices from `RenderBuffers`: [x, y, u, v]
+                buffers: &[VertexBufferLayout {
+                    array_stride: 16,
+                    step_mode: VertexStepMode::Vertex,
+                    attributes: &[
+                        VertexAttribute {
+                            format: VertexFormat::Float32x2,
+                            offset: 0,
+                            shader_location: 0,
+                        },
+                        VertexAttribute {
+                            format: VertexFormat::Float32x2,
+                            offset: 8,
+                            shader_location: 1,
+                        },
+                    ],
+                }],
+                compilation_options: PipelineCompilationOptions::default(),
+            },
+            fragment: Some(FragmentState {
+                module: &shader,
+                entry_point: Some("fs_main"),
+                targets: &[Some(ColorTargetState {
+                    format: surface_format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+                compilation_options: PipelineCompilationOptions::default(),
+            }),
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                polygon_mode: PolygonMode::Fill,
+                unclipped_depth: false,
+                conservative: false,
+            },
+            depth_stencil: None,
+            // Drawn on the surface after the MSAA resolve
+            multisample: MultisampleState::default(),
+            multiview: None,
+            cache: None,
+        });
+
+        let params_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("Vignette Params Buffer"),
+            contents: bytemuck::bytes_of(&VignetteParamsUniform {
+                intensity: 0.0,
+                _padding: [0.0; 3],
+            }),
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&BindGroupDescriptor {
+            label: Some("Vignette Bind Group"),
+            layout: &bind_group_layout,
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: params_buffer.as_entire_binding(),
+            }],
+        });
+
+        Self {
+            pipeline,
+            bind_group,
+            params_buffer,
+        }
+    }
+
+    /// Update the darkening at the corners (0 = none, 1 = black).
+    pub fn update(&self, queue: &Queue, intensity: f32) {
+        let params = VignetteParamsUniform {
+            intensity,
+            _padding: [0.0; 3],
+        };
+        queue.write_buffer(&self.params_buffer, 0, bytemuck::bytes_of(&params));
+    }
+
+    /// Estimated GPU memory used by the vignette uniform buffer, in bytes.
+    pub fn estimated_bytes(&self) -> u64 {
+        self.params_buffer.size()
+    }
+}
@@ -0,0 +1,30 @@
+//! Blending of the base particle pass.
+
+use serde::{Deserialize, Serialize};
+
+/// How particle sprites combine with what is already drawn. Glow is always
+/// additive regardless of this setting.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum BlendMode {
+    /// Standard alpha blending; overlapping particles cover each other.
+    #[default]
+    Alpha,
+
+    /// Additive blending; overlapping particles brighten toward white.
+    Additive,
+}
+
+impl BlendMode {
+    /// Get all available blend modes.
+    pub fn all() -> &'static [BlendMode] {
+        &[BlendMode::Alpha, BlendMode::Additive]
+    }
+
+    /// Get the display name for this blend mode.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            BlendMode::Alpha => "Alpha",
+            BlendMode::Additive => "Additive",
+        }
+    }
+}
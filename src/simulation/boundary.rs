@@ -19,6 +19,10 @@ pub enum BoundaryMode {
 
     /// Infinite tiling - particles rendered multiple times.
     InfiniteWrap,
+
+    /// Particles stop at walls: the velocity into the wall is zeroed, so
+    /// mass accumulates along the edges.
+    Sticky,
 }
 
 impl BoundaryMode {
@@ -29,6 +33,7 @@ impl BoundaryMode {
             BoundaryMode::Wrap,
             BoundaryMode::MirrorWrap,
             BoundaryMode::InfiniteWrap,
+            BoundaryMode::Sticky,
         ]
     }
 
@@ -39,14 +44,86 @@ impl BoundaryMode {
             BoundaryMode::Wrap => "Wrap Around",
             BoundaryMode::MirrorWrap => "Mirror Wrap",
             BoundaryMode::InfiniteWrap => "Infinite Tiling",
+            BoundaryMode::Sticky => "Sticky (Absorb)",
+        }
+    }
+
+    /// Shader selector (0 = Repel, 1 = Wrap, 2 = Mirror, 3 = Infinite,
+    /// 4 = Sticky).
+    pub fn shader_id(&self) -> u32 {
+        match self {
+            BoundaryMode::Repel => 0,
+            BoundaryMode::Wrap => 1,
+            BoundaryMode::MirrorWrap => 2,
+            BoundaryMode::InfiniteWrap => 3,
+            BoundaryMode::Sticky => 4,
         }
     }
 }
 
+/// Defines the shape of the world boundary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum WorldShape {
+    /// Axis-aligned rectangle covering the whole world.
+    #[default]
+    Rectangle,
+
+    /// Circle inscribed in the world rectangle.
+    ///
+    /// Repel mode bounces particles radially off the edge; the wrap modes
+    /// teleport them to the opposite side of the circle.
+    Circle,
+}
+
+impl WorldShape {
+    /// Get all available world shapes.
+    pub fn all() -> &'static [WorldShape] {
+        &[WorldShape::Rectangle, WorldShape::Circle]
+    }
+
+    /// Get the display name for this shape.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            WorldShape::Rectangle => "Rectangle",
+            WorldShape::Circle => "Circle",
+        }
+    }
+
+    /// Shader selector (0 = Rectangle, 1 = Circle).
+    pub fn shader_id(&self) -> u32 {
+        match self {
+            WorldShape::Rectangle => 0,
+            WorldShape::Circle => 1,
+        }
+    }
+}
+
+/// Center and radius of the circle inscribed in the world.
+pub fn world_circle(world_size: glam::Vec2) -> (glam::Vec2, f32) {
+    (world_size * 0.5, world_size.x.min(world_size.y) * 0.5)
+}
+
+/// Whether neighbor distances wrap around the world edges.
+///
+/// Only rectangular worlds are toroidal; a circular world never wraps
+/// distances even in the wrap modes.
+pub fn uses_wrapped_distance(config: &SimulationConfig) -> bool {
+    config.world_shape == WorldShape::Rectangle
+        && matches!(
+            config.boundary_mode,
+            BoundaryMode::Wrap | BoundaryMode::MirrorWrap | BoundaryMode::InfiniteWrap
+        )
+}
+
 /// Apply boundary conditions to a single particle.
 pub fn apply_boundary(particle: &mut Particle, config: &SimulationConfig) {
+    if config.world_shape == WorldShape::Circle {
+        apply_circle_boundary(particle, config);
+        return;
+    }
     match config.boundary_mode {
         BoundaryMode::Repel => apply_repel_boundary(particle, config),
+        BoundaryMode::Sticky => apply_sticky_boundary(particle, config),
         BoundaryMode::Wrap | BoundaryMode::MirrorWrap | BoundaryMode::InfiniteWrap => {
             apply_wrap_boundary(particle, config);
         }
@@ -85,6 +162,30 @@ fn apply_repel_boundary(particle: &mut Particle, config: &SimulationConfig) {
     }
 }
 
+/// Apply sticky (absorbing) boundary conditions.
+///
+/// Particles that hit an edge are clamped to it and lose the velocity
+/// component into that wall; at a corner both components are zeroed.
+fn apply_sticky_boundary(particle: &mut Particle, config: &SimulationConfig) {
+    let margin = config.particle_size * 2.0;
+
+    if particle.x < margin {
+        particle.x = margin;
+        particle.vx = particle.vx.max(0.0);
+    } else if particle.x > config.world_size.x - margin {
+        particle.x = config.world_size.x - margin;
+        particle.vx = particle.vx.min(0.0);
+    }
+
+    if particle.y < margin {
+        particle.y = margin;
+        particle.vy = particle.vy.max(0.0);
+    } else if particle.y > config.world_size.y - margin {
+        particle.y = config.world_size.y - margin;
+        particle.vy = particle.vy.min(0.0);
+    }
+}
+
 /// Apply wrap-around boundary conditions.
 ///
 /// Particles that exit one side appear on the opposite side.
@@ -104,6 +205,68 @@ fn apply_wrap_boundary(particle: &mut Particle, config: &SimulationConfig) {
     }
 }
 
+/// Apply boundary conditions for a circular world.
+///
+/// In Repel mode particles outside the circle are placed back on the edge
+/// and their outward velocity is reflected; Sticky mode removes the outward
+/// velocity instead. In the wrap modes they reappear on the opposite side
+/// of the circle.
+fn apply_circle_boundary(particle: &mut Particle, config: &SimulationConfig) {
+    let (center, radius) = world_circle(config.world_size);
+    let limit = (radius - config.particle_size * 2.0).max(0.0);
+    let offset = glam::Vec2::new(particle.x, particle.y) - center;
+    let dist = offset.length();
+    if dist <= limit || dist == 0.0 {
+        return;
+    }
+    let dir = offset / dist;
+
+    match config.boundary_mode {
+        BoundaryMode::Repel => {
+            let pos = center + dir * limit;
+            particle.x = pos.x;
+            particle.y = pos.y;
+            let vel = glam::Vec2::new(particle.vx, particle.vy);
+            let outward = vel.dot(dir);
+            if outward > 0.0 {
+                let vel = vel - dir * outward * 2.0;
+                particle.vx = vel.x;
+                particle.vy = vel.y;
+            }
+        }
+        BoundaryMode::Sticky => {
+            let pos = center + dir * limit;
+            particle.x = pos.x;
+            particle.y = pos.y;
+            let vel = glam::Vec2::new(particle.vx, particle.vy);
+            let outward = vel.dot(dir);
+            if outward > 0.0 {
+                let vel = vel - dir * outward;
+                particle.vx = vel.x;
+                particle.vy = vel.y;
+            }
+        }
+        BoundaryMode::Wrap | BoundaryMode::MirrorWrap | BoundaryMode::InfiniteWrap => {
+            let pos = center - dir * limit;
+            particle.x = pos.x;
+            particle.y = pos.y;
+        }
+    }
+}
+
+/// Scale particle positions from a world of size `from` onto a world of
+/// size `to`, keeping each particle at the same relative location.
+pub fn rescale_positions(particles: &mut [Particle], from: glam::Vec2, to: glam::Vec2) {
+    if from.x <= 0.0 || from.y <= 0.0 {
+        return;
+    }
+    let scale = to / from;
+    for particle in particles {
+        particle.x *= scale.x;
+        particle.y *= scale.y;
+    }
+}
+
 /// Calculate the shortest distance between two particles considering wrapping.
 ///
 /// Returns the delta vector from `from` to `to` using the shortest path,
@@ -137,6 +300,32 @@ pub fn wrapped_delta(
     delta
 }
 
+/// Index of the particle nearest to `pos` and its distance.
+///
+/// In the wrap modes `pos` may lie in any drawn copy of the world (mirror or
+/// infinite tiles); it is folded back into the world and distances use the
+/// nearest image, so the instance visually closest to `pos` decides.
+pub fn nearest_particle(
+    particles: &[Particle],
+    pos: glam::Vec2,
+    config: &SimulationConfig,
+) -> Option<(usize, f32)> {
+    let wrap = uses_wrapped_distance(config);
+    let pos = if wrap {
+        pos.rem_euclid(config.world_size)
+    } else {
+        pos
+    };
+    particles
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            let delta = wrapped_delta(pos, p.position(), config.world_size, wrap);
+            (i, delta.length())
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -162,6 +351,28 @@ mod tests {
         assert!(p.vx >= 0.0); // Velocity should be reversed
     }
 
+    #[test]
+    fn test_sticky_boundary() {
+        let mut config = test_config();
+        config.boundary_mode = BoundaryMode::Sticky;
+
+        // Only the velocity into the wall is removed
+        let mut p = Particle::new(-10.0, 50.0, 0);
+        p.vx = -5.0;
+        p.vy = 2.0;
+        apply_boundary(&mut p, &config);
+        assert_eq!((p.x, p.vx, p.vy), (4.0, 0.0, 2.0));
+
+        // A corner hit zeroes both components
+        let mut p = Particle::new(105.0, 110.0, 0);
+        p.vx = 3.0;
+        p.vy = 4.0;
+        apply_boundary(&mut p, &config);
+        assert_eq!((p.x, p.y), (96.0, 96.0));
+        assert_eq!((p.vx, p.vy), (0.0, 0.0));
+        assert!(!uses_wrapped_distance(&config));
+    }
+
     #[test]
     fn test_wrap_boundary() {
         let mut config = test_config();
@@ -173,6 +384,56 @@ mod tests {
         assert!(p.x >= 0.0 && p.x < 100.0);
     }
 
+    #[test]
+    fn test_circle_boundary() {
+        let mut config = test_config();
+        config.world_shape = WorldShape::Circle;
+        config.boundary_mode = BoundaryMode::Repel;
+
+        // Corner of the square is outside the inscribed circle
+        let mut p = Particle::new(95.0, 95.0, 0);
+        p.vx = 3.0;
+        p.vy = 3.0;
+        apply_boundary(&mut p, &config);
+        let (center, radius) = world_circle(config.world_size);
+        let dist = glam::Vec2::new(p.x, p.y).distance(center);
+        assert!(dist <= radius);
+        assert!(p.vx < 0.0 && p.vy < 0.0);
+
+        // Wrap mode sends the particle to the opposite side
+        config.boundary_mode = BoundaryMode::Wrap;
+        let mut p = Particle::new(95.0, 95.0, 0);
+        apply_boundary(&mut p, &config);
+        assert!(p.x < 50.0 && p.y < 50.0);
+        assert!(!uses_wrapped_distance(&config));
+    }
+
+    #[test]
+    fn test_nearest_particle_uses_nearest_image() {
+        let mut config = test_config();
+        let particles = vec![Particle::new(95.0, 50.0, 0), Particle::new(40.0, 50.0, 1)];
+
+        // Left of the world: the wrapped image of particle 0 is closest
+        config.boundary_mode = BoundaryMode::Wrap;
+        let (index, distance) =
+            nearest_particle(&particles, glam::Vec2::new(-2.0, 50.0), &config).unwrap();
+        assert_eq!(index, 0);
+        assert!((distance - 3.0).abs() < 1e-4);
+
+        // Two tiles over in infinite wrap folds back the same way
+        config.boundary_mode = BoundaryMode::InfiniteWrap;
+        let (index, _) =
+            nearest_particle(&particles, glam::Vec2::new(238.0, 250.0), &config).unwrap();
+        assert_eq!(index, 1);
+
+        // Repel walls don't wrap
+        config.boundary_mode = BoundaryMode::Repel;
+        let (index, _) =
+            nearest_particle(&particles, glam::Vec2::new(-2.0, 50.0), &config).unwrap();
+        assert_eq!(index, 1);
+        assert!(nearest_particle(&[], glam::Vec2::ZERO, &config).is_none());
+    }
+
     #[test]
     fn test_wrapped_delta() {
         let world = glam::Vec2::new(100.0, 100.0);
@@ -196,4 +457,18 @@ mod tests {
         );
         assert!((delta.x - 20.0).abs() < 0.001); // Should go right through boundary
     }
+
+    #[test]
+    fn test_rescale_positions() {
+        let mut particles = vec![Particle::new(50.0, 25.0, 0), Particle::new(100.0, 100.0, 1)];
+        rescale_positions(
+            &mut particles,
+            glam::Vec2::new(100.0, 100.0),
+            glam::Vec2::new(300.0, 50.0),
+        );
+        assert!((particles[0].x - 150.0).abs() < 0.001);
+        assert!((particles[0].y - 12.5).abs() < 0.001);
+        assert!((particles[1].x - 300.0).abs() < 0.001);
+        assert!((particles[1].y - 50.0).abs() < 0.001);
+    }
 }
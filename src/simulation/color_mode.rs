@@ -0,0 +1,58 @@
+//! Particle coloring modes for rendering.
+
+use serde::{Deserialize, Serialize};
+
+/// Defines how the render shaders choose each particle's color.
+///
+/// Every mode is selected by a single `mode` value in the color uniform, and
+/// all data a mode samples is bound in one place
+/// (`RenderPipelines::color_bind_entries`). New scalar modes (divergence,
+/// bin density, ...) add a variant here, a branch in `particle_color` in the
+/// render shaders, and their buffer to that binding set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ColorMode {
+    /// Color by particle type using the type palette.
+    #[default]
+    ByType,
+
+    /// Color by speed, sampling the colormap from slow to fast.
+    BySpeed,
+
+    /// Color by heading, mapping the velocity angle around the hue wheel.
+    ByDirection,
+}
+
+impl ColorMode {
+    /// Get all available color modes.
+    pub fn all() -> &'static [ColorMode] {
+        &[
+            ColorMode::ByType,
+            ColorMode::BySpeed,
+            ColorMode::ByDirection,
+        ]
+    }
+
+    /// Get the display name for this mode.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ColorMode::ByType => "By Type",
+            ColorMode::BySpeed => "By Speed",
+            ColorMode::ByDirection => "By Direction",
+        }
+    }
+
+    /// Mode selector written to the color uniform (matches `COLOR_MODE_*` in
+    /// the render shaders).
+    pub fn shader_id(&self) -> u32 {
+        match self {
+            ColorMode::ByType => 0,
+            ColorMode::BySpeed => 1,
+            ColorMode::ByDirection => 2,
+        }
+    }
+
+    /// Whether this mode samples the colormap instead of the type palette.
+    pub fn uses_colormap(&self) -> bool {
+        matches!(self, ColorMode::BySpeed)
+    }
+}
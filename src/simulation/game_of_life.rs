@@ -415,9 +415,11 @@ mod tests {
 
     #[test]
     fn test_blinker_oscillation() {
-        let mut config = GameOfLifeConfig::default();
-        config.width = 5;
-        config.height = 5;
+        let config = GameOfLifeConfig {
+            width: 5,
+            height: 5,
+            ..Default::default()
+        };
         let mut game = GameOfLife::new(config);
 
         // Create a blinker (vertical line)
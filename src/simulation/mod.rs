@@ -1,59 +1,136 @@
 //! Simulation module containing core physics and data structures.
 
+mod blend_mode;
 mod boundary;
+mod color_mode;
 mod game_of_life;
+mod headless;
 mod particle;
+mod particle_shape;
 mod physics;
 mod spatial_hash;
 
-pub use boundary::BoundaryMode;
+pub use blend_mode::BlendMode;
+pub use boundary::{
+    BoundaryMode, WorldShape, nearest_particle, rescale_positions, uses_wrapped_distance,
+    world_circle,
+};
+pub use color_mode::ColorMode;
 pub use game_of_life::GameOfLife;
+pub use headless::Simulation;
 pub use particle::{
-    InteractionMatrix, Particle, ParticlePosType, ParticlePosTypeHalf, ParticleVel,
-    ParticleVelHalf, RadiusMatrix,
+    InteractionMatrix, PARTICLE_TYPE_MASK, Particle, ParticlePosType, ParticlePosTypeHalf,
+    ParticleVel, ParticleVelHalf, RadiusMatrix, STATIC_PARTICLE_FLAG,
+};
+pub use particle_shape::ParticleShape;
+pub use physics::{
+    PhysicsEngine, StepParity, TrajectoryDivergence, VelocityClampMode, advance_particles,
+    compute_forces_cpu, pair_force, step_parity, trajectory_divergence,
 };
-pub use physics::{PhysicsEngine, advance_particles, compute_forces_cpu};
 pub use spatial_hash::SpatialHash;
 
 use serde::{Deserialize, Serialize};
 
+use crate::generators::colors::PaletteType;
+
 /// Configuration for the particle life simulation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SimulationConfig {
     /// Number of particles in the simulation (16 - 1,048,576).
     pub num_particles: u32,
 
-    /// Number of particle types/species (1 - 16).
+    /// Number of particle types/species (1 - [`MAX_NUM_TYPES`]).
     pub num_types: u32,
 
     /// Force scaling factor (0.1 - 10.0). Higher values reduce force magnitude.
     pub force_factor: f32,
 
+    /// Multiplier on interaction matrix values (0.0 - 2.0), applied in the
+    /// force pass without modifying the stored matrix. Unlike `force_factor`
+    /// it leaves close-range repulsion untouched.
+    #[serde(default = "default_matrix_strength")]
+    pub matrix_strength: f32,
+
     /// Friction coefficient (0.0 - 1.0). Applied each frame to slow particles.
     pub friction: f32,
 
+    /// Per-type friction overrides indexed by particle type. Empty (or a
+    /// missing entry) falls back to `friction`.
+    #[serde(default)]
+    pub type_friction: Vec<f32>,
+
+    /// Per-type force factor overrides indexed by particle type. Empty (or a
+    /// missing entry) falls back to `force_factor`.
+    #[serde(default)]
+    pub type_force_factor: Vec<f32>,
+
+    /// Optional display names indexed by particle type. Metadata only; an
+    /// empty (or missing) entry shows as `Type N`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub type_names: Vec<String>,
+
     /// Repulsion strength at close range (0.01 - 4.0).
     pub repel_strength: f32,
 
+    /// Radius of the repulsion core as a fraction of each pair's min radius
+    /// (see [`SimulationConfig::repel_core_fraction`]). Attraction flips to
+    /// repulsion at this distance.
+    #[serde(default = "default_repel_radius")]
+    pub repel_radius: f32,
+
     /// Maximum velocity magnitude. Particles are clamped to this speed.
     pub max_velocity: f32,
 
+    /// How speed is limited to `max_velocity`.
+    #[serde(default)]
+    pub velocity_clamp: VelocityClampMode,
+
+    /// Torque on a particle's orientation from the tangential component of
+    /// its velocity (0.0 = spin disabled). Spin is visual only.
+    #[serde(default)]
+    pub spin_coupling: f32,
+
+    /// Rotational friction (0.0 - 1.0) applied to spin each step.
+    #[serde(default = "default_spin_friction")]
+    pub spin_friction: f32,
+
+    /// Thermostat target for the kinetic temperature (mean `0.5 * |v|^2`).
+    /// `None` leaves velocities alone.
+    #[serde(default)]
+    pub target_temperature: Option<f32>,
+
     /// Boundary handling mode.
     pub boundary_mode: BoundaryMode,
 
+    /// Shape of the world boundary (rectangle or inscribed circle).
+    #[serde(default)]
+    pub world_shape: WorldShape,
+
     /// Wall repulsion strength for Repel boundary mode (0.0 - 100.0).
     pub wall_repel_strength: f32,
 
     /// Number of mirror copies for MirrorWrap mode (5 or 9).
     pub mirror_wrap_count: u32,
 
+    /// Fixed simulation timestep in seconds. `None` uses the (capped)
+    /// frame time, so simulation speed follows the display rate.
+    #[serde(default)]
+    pub fixed_dt: Option<f32>,
+
+    /// Physics steps per rendered frame when `fixed_dt` is set (1 - 16).
+    #[serde(default = "default_substeps")]
+    pub substeps: u32,
+
     /// World size in pixels.
     pub world_size: glam::Vec2,
 
-    /// Enable 3D simulation with depth.
+    /// Enable 3D depth rendering. Each particle gets a fixed random depth
+    /// at spawn; sprites shrink and dim with depth and nearer ones cover
+    /// farther ones. Physics stays planar.
     pub enable_3d: bool,
 
-    /// Maximum depth for 3D mode.
+    /// Maximum depth for 3D mode; particles at this depth draw at half size
+    /// and brightness.
     pub depth_limit: f32,
 
     /// Particle render size in pixels.
@@ -71,6 +148,70 @@ pub struct SimulationConfig {
     /// Glow falloff steepness (1.0 - 4.0). Higher = sharper edge.
     pub glow_steepness: f32,
 
+    /// Divide the glow size by the camera zoom so the halo keeps the same
+    /// on-screen size at every zoom level.
+    #[serde(default)]
+    pub glow_zoom_compensate: bool,
+
+    /// Keep the previous frame and fade it toward the background instead
+    /// of clearing, leaving motion trails.
+    #[serde(default)]
+    pub enable_trails: bool,
+
+    /// Per-frame fade alpha for trails (0.01 - 1.0). Lower = longer trails.
+    #[serde(default = "default_trail_fade")]
+    pub trail_fade: f32,
+
+    /// Darkening toward the screen corners (0 = off, 1 = black corners).
+    #[serde(default)]
+    pub vignette: f32,
+
+    /// How particles are colored.
+    #[serde(default)]
+    pub color_mode: ColorMode,
+
+    /// Sprite shape particles are drawn with.
+    #[serde(default)]
+    pub particle_shape: ParticleShape,
+
+    /// Blending of the base particle pass.
+    #[serde(default)]
+    pub blend_mode: BlendMode,
+
+    /// Colormap sampled by scalar color modes (e.g. speed).
+    #[serde(default = "default_colormap")]
+    pub colormap: PaletteType,
+
+    /// Speed mapped to the top of the colormap in By Speed mode.
+    #[serde(default = "default_color_speed_range")]
+    pub color_speed_range: f32,
+
+    /// Rotate type colors' hue over time.
+    #[serde(default)]
+    pub animate_palette: bool,
+
+    /// Hue rotation speed in degrees per second.
+    #[serde(default = "default_palette_hue_speed")]
+    pub palette_hue_speed: f32,
+
+    /// Random hue/value shift (0-1) applied to generated type colors to
+    /// separate similar neighbors; 0 keeps the palette as generated.
+    #[serde(default)]
+    pub palette_jitter: f32,
+
+    /// Fraction of each world axis faded out near the edges in Wrap mode
+    /// (0.0 - 0.2, 0 = off), hiding the wrap seam.
+    #[serde(default)]
+    pub seam_fade: f32,
+
+    /// Render particles as streaks along their velocity.
+    #[serde(default)]
+    pub velocity_streaks: bool,
+
+    /// Streak length per unit of speed (seconds of travel shown).
+    #[serde(default = "default_streak_length")]
+    pub streak_length: f32,
+
     /// Use spatial hashing for force calculation optimization.
     pub use_spatial_hash: bool,
 
@@ -86,11 +227,77 @@ pub struct SimulationConfig {
     #[serde(default)]
     pub neighbor_budget: u32,
 
+    /// When the neighbor budget is hit, sample neighbors spread across each
+    /// bin from a per-frame hashed offset instead of taking the first ones.
+    #[serde(default)]
+    pub fair_neighbor_budget: bool,
+
     /// Background color [r, g, b] in 0.0-1.0 range.
     pub background_color: [f32; 3],
+
+    /// Optional vertical gradient `[top, bottom]` drawn instead of the solid
+    /// `background_color`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub background_gradient: Option<[[f32; 3]; 2]>,
+
+    /// Seed for rule, color and position generation. `None` draws from the
+    /// thread RNG, so every regeneration differs between runs.
+    #[serde(default)]
+    pub seed: Option<u64>,
+}
+
+/// Default value for matrix_strength (used by serde).
+fn default_matrix_strength() -> f32 {
+    1.0
+}
+
+/// Default value for spin_friction (used by serde).
+fn default_spin_friction() -> f32 {
+    0.1
+}
+
+fn default_repel_radius() -> f32 {
+    1.0
+}
+
+/// Largest supported number of particle types. Matrices, colors and per-type
+/// GPU buffers are all sized from `num_types`, so this is a UI and validation
+/// limit rather than a shader one.
+pub const MAX_NUM_TYPES: u32 = 32;
+
+/// Smallest repulsion core fraction; keeps the core radius away from zero,
+/// which the repulsion falloff divides by.
+pub const MIN_REPEL_RADIUS: f32 = 0.05;
+
+/// Default value for colormap (used by serde).
+fn default_colormap() -> PaletteType {
+    PaletteType::HeatmapClassic
+}
+
+/// Default value for color_speed_range (used by serde).
+fn default_color_speed_range() -> f32 {
+    100.0
+}
+
+/// Default value for palette_hue_speed (used by serde).
+fn default_palette_hue_speed() -> f32 {
+    30.0
+}
+
+/// Default value for streak_length (used by serde).
+fn default_streak_length() -> f32 {
+    0.05
 }
 
 /// Default value for max_bin_density (used by serde).
+fn default_trail_fade() -> f32 {
+    0.1
+}
+
+fn default_substeps() -> u32 {
+    1
+}
+
 fn default_max_bin_density() -> f32 {
     5000.0
 }
@@ -101,12 +308,24 @@ impl Default for SimulationConfig {
             num_particles: 64_000,
             num_types: 7,
             force_factor: 1.0,
+            matrix_strength: default_matrix_strength(),
             friction: 0.3,
+            type_friction: Vec::new(),
+            type_force_factor: Vec::new(),
+            type_names: Vec::new(),
             repel_strength: 3.0, // Increased to discourage clustering
+            repel_radius: default_repel_radius(),
             max_velocity: 500.0,
+            velocity_clamp: VelocityClampMode::default(),
+            spin_coupling: 0.0,
+            spin_friction: default_spin_friction(),
+            target_temperature: None,
             boundary_mode: BoundaryMode::Wrap,
+            world_shape: WorldShape::Rectangle,
             wall_repel_strength: 100.0,
             mirror_wrap_count: 5,
+            fixed_dt: None,
+            substeps: default_substeps(),
             world_size: glam::Vec2::new(1920.0, 1080.0),
             enable_3d: false,
             depth_limit: 420.0,
@@ -115,17 +334,103 @@ impl Default for SimulationConfig {
             glow_intensity: 0.35,
             glow_size: 4.0,
             glow_steepness: 2.0,
+            glow_zoom_compensate: false,
+            enable_trails: false,
+            trail_fade: default_trail_fade(),
+            vignette: 0.0,
+            color_mode: ColorMode::ByType,
+            particle_shape: ParticleShape::default(),
+            blend_mode: BlendMode::default(),
+            colormap: default_colormap(),
+            color_speed_range: default_color_speed_range(),
+            animate_palette: false,
+            palette_hue_speed: default_palette_hue_speed(),
+            palette_jitter: 0.0,
+            seam_fade: 0.0,
+            velocity_streaks: false,
+            streak_length: default_streak_length(),
             // Spatial hash enabled for debugging
             use_spatial_hash: true,
             spatial_hash_cell_size: 64.0,
             background_color: [0.0, 0.0, 0.0], // Black
+            background_gradient: None,
             max_bin_density: 5000.0,
+            fair_neighbor_budget: false,
             neighbor_budget: 0, // 0 = unlimited (default), set non-zero to cap iterations in dense clusters
+            seed: None,
         }
     }
 }
 
 impl SimulationConfig {
+    /// Physics steps to run per rendered frame: `substeps` with a fixed
+    /// timestep, otherwise one.
+    pub fn substeps_per_frame(&self) -> u32 {
+        if self.fixed_dt.is_some() {
+            self.substeps.clamp(1, 16)
+        } else {
+            1
+        }
+    }
+
+    /// `repel_radius` clamped to [`MIN_REPEL_RADIUS`]..=1, so the core never
+    /// reaches zero or extends past the min radius.
+    pub fn repel_core_fraction(&self) -> f32 {
+        if self.repel_radius.is_finite() {
+            self.repel_radius.clamp(MIN_REPEL_RADIUS, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    /// Friction for a particle type, falling back to the global value.
+    #[inline]
+    pub fn friction_for(&self, particle_type: usize) -> f32 {
+        self.type_friction
+            .get(particle_type)
+            .copied()
+            .unwrap_or(self.friction)
+    }
+
+    /// Force factor for a particle type, falling back to the global value.
+    #[inline]
+    pub fn force_factor_for(&self, particle_type: usize) -> f32 {
+        self.type_force_factor
+            .get(particle_type)
+            .copied()
+            .unwrap_or(self.force_factor)
+    }
+
+    /// Display name for a particle type, falling back to `Type N`.
+    pub fn type_name(&self, particle_type: usize) -> String {
+        match self.type_names.get(particle_type).map(|n| n.trim()) {
+            Some(name) if !name.is_empty() => name.to_string(),
+            _ => format!("Type {particle_type}"),
+        }
+    }
+
+    /// Resize non-empty per-type overrides and names to `num_types`, padding
+    /// new types with the global values (or unnamed). Empty lists stay empty.
+    pub fn resize_type_overrides(&mut self) {
+        let n = self.num_types as usize;
+        if !self.type_friction.is_empty() {
+            self.type_friction.resize(n, self.friction);
+        }
+        if !self.type_force_factor.is_empty() {
+            self.type_force_factor.resize(n, self.force_factor);
+        }
+        if !self.type_names.is_empty() {
+            self.type_names.resize(n, String::new());
+        }
+    }
+
+    /// Per-type `[friction, force_factor]` packed for the GPU.
+    pub fn type_physics_gpu_data(&self) -> Vec<[f32; 2]> {
+        (0..(self.num_types as usize).max(1))
+            .map(|t| [self.friction_for(t), self.force_factor_for(t)])
+            .collect()
+    }
+
     /// Create a configuration suitable for GPU rendering.
     pub fn gpu_defaults() -> Self {
         Self::default()
@@ -136,8 +441,8 @@ impl SimulationConfig {
         if self.num_particles == 0 {
             return Err("num_particles must be greater than 0".to_string());
         }
-        if self.num_types == 0 || self.num_types > 16 {
-            return Err("num_types must be between 1 and 16".to_string());
+        if self.num_types == 0 || self.num_types > MAX_NUM_TYPES {
+            return Err(format!("num_types must be between 1 and {MAX_NUM_TYPES}"));
         }
         if self.force_factor <= 0.0 {
             return Err("force_factor must be positive".to_string());
@@ -148,9 +453,56 @@ impl SimulationConfig {
         if self.repel_strength < 0.0 {
             return Err("repel_strength must be non-negative".to_string());
         }
+        if !(0.0..=1.0).contains(&self.repel_radius) {
+            return Err("repel_radius must be between 0.0 and 1.0".to_string());
+        }
+        if self.target_temperature.is_some_and(|t| t <= 0.0) {
+            return Err("target_temperature must be positive".to_string());
+        }
         if self.world_size.x <= 0.0 || self.world_size.y <= 0.0 {
             return Err("world_size must have positive dimensions".to_string());
         }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_num_types_limit() {
+        let mut config = SimulationConfig {
+            num_types: MAX_NUM_TYPES,
+            ..Default::default()
+        };
+        assert!(config.validate().is_ok());
+        config.num_types = MAX_NUM_TYPES + 1;
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn test_type_names_fall_back_and_follow_num_types() {
+        let mut config = SimulationConfig {
+            num_types: 3,
+            type_names: vec!["Predator".to_string(), "  ".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(config.type_name(0), "Predator");
+        assert_eq!(config.type_name(1), "Type 1");
+        assert_eq!(config.type_name(2), "Type 2");
+
+        config.resize_type_overrides();
+        assert_eq!(config.type_names.len(), 3);
+        config.num_types = 1;
+        config.resize_type_overrides();
+        assert_eq!(config.type_names, vec!["Predator".to_string()]);
+
+        // Unnamed configs stay empty and omit the field when saved
+        let mut unnamed = SimulationConfig::default();
+        unnamed.resize_type_overrides();
+        assert!(unnamed.type_names.is_empty());
+        let json = serde_json::to_string(&unnamed).unwrap();
+        assert!(!json.contains("type_names"));
+    }
+}
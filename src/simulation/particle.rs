@@ -23,8 +23,13 @@ pub struct Particle {
     pub vy: f32,
     /// Particle type/species index (0 to num_types-1).
     pub particle_type: u32,
-    /// Padding after particle_type to align _padding2 to 16 bytes.
-    pub _padding1: [u32; 3],
+    /// Non-zero for a static particle: it exerts forces but never moves.
+    pub is_static: u32,
+    /// Depth below the screen plane in 3D mode (0 = nearest). Set at spawn
+    /// and never simulated: physics stays planar.
+    pub z: f32,
+    /// Padding after z to align _padding2 to 16 bytes.
+    pub _padding1: u32,
     /// Additional padding to match WGSL vec3<u32> at 16-byte alignment.
     /// In WGSL storage buffers, vec3<u32> requires 16-byte alignment,
     /// so it starts at offset 32, making total struct size 48 bytes.
@@ -39,7 +44,9 @@ impl Default for Particle {
             vx: 0.0,
             vy: 0.0,
             particle_type: 0,
-            _padding1: [0; 3],
+            is_static: 0,
+            z: 0.0,
+            _padding1: 0,
             _padding2: [0; 4],
         }
     }
@@ -54,7 +61,9 @@ impl Particle {
             vx: 0.0,
             vy: 0.0,
             particle_type,
-            _padding1: [0; 3],
+            is_static: 0,
+            z: 0.0,
+            _padding1: 0,
             _padding2: [0; 4],
         }
     }
@@ -67,7 +76,9 @@ impl Particle {
             vx,
             vy,
             particle_type,
-            _padding1: [0; 3],
+            is_static: 0,
+            z: 0.0,
+            _padding1: 0,
             _padding2: [0; 4],
         }
     }
@@ -103,6 +114,38 @@ impl Particle {
     pub fn speed(&self) -> f32 {
         self.velocity().length()
     }
+
+    /// Whether this particle is pinned in place.
+    #[inline]
+    pub fn is_static(&self) -> bool {
+        self.is_static != 0
+    }
+
+    /// Pin or release this particle; pinning also stops it.
+    pub fn set_static(&mut self, is_static: bool) {
+        self.is_static = u32::from(is_static);
+        if is_static {
+            self.vx = 0.0;
+            self.vy = 0.0;
+        }
+    }
+}
+
+/// High bit of the GPU `particle_type` word, set for static particles.
+/// Shaders mask it off with [`PARTICLE_TYPE_MASK`] before using the type.
+pub const STATIC_PARTICLE_FLAG: u32 = 1 << 31;
+
+/// Mask selecting the type index from a GPU `particle_type` word.
+pub const PARTICLE_TYPE_MASK: u32 = !STATIC_PARTICLE_FLAG;
+
+/// Pack a particle's type and static flag into one GPU `particle_type` word.
+#[inline]
+fn pack_type(p: &Particle) -> u32 {
+    if p.is_static() {
+        p.particle_type | STATIC_PARTICLE_FLAG
+    } else {
+        p.particle_type
+    }
 }
 
 /// Interaction matrix defining attraction/repulsion between particle types.
@@ -161,6 +204,35 @@ impl InteractionMatrix {
         self.data[from_type * self.size + to_type] = value;
     }
 
+    /// Set every self-interaction (diagonal) entry to the same value.
+    pub fn set_diagonal(&mut self, value: f32) {
+        for i in 0..self.size {
+            self.set(i, i, value);
+        }
+    }
+
+    /// Re-roll self-interaction (diagonal) entries uniformly in `[min, max]`,
+    /// leaving cross-type values untouched.
+    pub fn randomize_diagonal<R: rand::Rng + ?Sized>(&mut self, rng: &mut R, min: f32, max: f32) {
+        let (lo, hi) = if min <= max { (min, max) } else { (max, min) };
+        for i in 0..self.size {
+            let value = lo + rng.random::<f32>() * (hi - lo);
+            self.set(i, i, value);
+        }
+    }
+
+    /// Nudge every entry by Gaussian noise with standard deviation `sigma`,
+    /// clamping the result to [-1, 1].
+    pub fn mutate<R: rand::Rng + ?Sized>(&mut self, sigma: f32, rng: &mut R) {
+        for val in &mut self.data {
+            // Box-Muller; 1 - u keeps the log argument in (0, 1]
+            let u1 = 1.0 - rng.random::<f32>();
+            let u2 = rng.random::<f32>();
+            let noise = (-2.0 * u1.ln()).sqrt() * (std::f32::consts::TAU * u2).cos();
+            *val = (*val + noise * sigma).clamp(-1.0, 1.0);
+        }
+    }
+
     /// Make the matrix symmetric (average of m[i][j] and m[j][i]).
     pub fn symmetrize(&mut self) {
         for i in 0..self.size {
@@ -172,6 +244,23 @@ impl InteractionMatrix {
         }
     }
 
+    /// Swap rows and columns (m[i][j] <-> m[j][i]), so each type reacts to
+    /// others the way they used to react to it.
+    pub fn transpose(&mut self) {
+        for i in 0..self.size {
+            for j in i + 1..self.size {
+                let upper = self.get(i, j);
+                self.set(i, j, self.get(j, i));
+                self.set(j, i, upper);
+            }
+        }
+    }
+
+    /// Negate every value, turning attraction into repulsion and vice versa.
+    pub fn invert(&mut self) {
+        self.scale(-1.0);
+    }
+
     /// Make the matrix anti-symmetric (m[i][j] = -m[j][i]).
     pub fn anti_symmetrize(&mut self) {
         for i in 0..self.size {
@@ -185,6 +274,76 @@ impl InteractionMatrix {
         }
     }
 
+    /// Make the matrix invariant under a cyclic shift of all types by `period`
+    /// (m[i][j] = m[(i+period)%n][(j+period)%n]).
+    ///
+    /// Each entry is replaced by the average over its orbit of shifted
+    /// counterparts, so repeated application is a no-op.
+    pub fn cyclic_symmetrize(&mut self, period: usize) {
+        let n = self.size;
+        if n == 0 || period.is_multiple_of(n) {
+            return;
+        }
+
+        let mut visited = vec![false; n * n];
+        let mut orbit = Vec::with_capacity(n);
+        for start in 0..n * n {
+            if visited[start] {
+                continue;
+            }
+
+            // Follow the shift until it cycles back to the start
+            orbit.clear();
+            let (mut i, mut j) = (start / n, start % n);
+            while !visited[i * n + j] {
+                visited[i * n + j] = true;
+                orbit.push(i * n + j);
+                i = (i + period) % n;
+                j = (j + period) % n;
+            }
+
+            let avg = orbit.iter().map(|&k| self.data[k]).sum::<f32>() / orbit.len() as f32;
+            for &k in &orbit {
+                self.data[k] = avg;
+            }
+        }
+    }
+
+    /// Scale each row so its absolute values sum to `target`, keeping signs.
+    /// Balances how strongly each type reacts to all others. All-zero rows
+    /// are left untouched.
+    pub fn normalize_rows(&mut self, target: f32) {
+        for i in 0..self.size {
+            let sum: f32 = (0..self.size).map(|j| self.get(i, j).abs()).sum();
+            if sum > f32::EPSILON {
+                for j in 0..self.size {
+                    self.set(i, j, self.get(i, j) * target / sum);
+                }
+            }
+        }
+    }
+
+    /// Scale each column so its absolute values sum to `target`, keeping
+    /// signs. Balances how strongly each type influences all others.
+    /// All-zero columns are left untouched.
+    pub fn normalize_cols(&mut self, target: f32) {
+        for j in 0..self.size {
+            let sum: f32 = (0..self.size).map(|i| self.get(i, j).abs()).sum();
+            if sum > f32::EPSILON {
+                for i in 0..self.size {
+                    self.set(i, j, self.get(i, j) * target / sum);
+                }
+            }
+        }
+    }
+
+    /// Multiply every value by `factor`.
+    pub fn scale(&mut self, factor: f32) {
+        for val in &mut self.data {
+            *val *= factor;
+        }
+    }
+
     /// Clamp all values to the given range.
     pub fn clamp(&mut self, min: f32, max: f32) {
         for val in &mut self.data {
@@ -192,6 +351,41 @@ impl InteractionMatrix {
         }
     }
 
+    /// Copy into a `size` x `size` matrix, truncating extra types and
+    /// zero-filling new ones.
+    pub fn resized(&self, size: usize) -> Self {
+        let mut matrix = Self::new(size);
+        let keep = self.size.min(size);
+        for i in 0..keep {
+            for j in 0..keep {
+                matrix.set(i, j, self.get(i, j));
+            }
+        }
+        matrix
+    }
+
+    /// Serialize the matrix alone (`size` and `data`) as compact JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("matrix serialization cannot fail")
+    }
+
+    /// Parse a matrix written by [`Self::to_json`], rejecting inconsistent
+    /// dimensions and out-of-range values.
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        let matrix: Self =
+            serde_json::from_str(s.trim()).map_err(|e| format!("Invalid matrix JSON: {e}"))?;
+        if matrix.data.len() != matrix.size * matrix.size {
+            return Err(format!(
+                "Matrix has {} values, expected {} for size {}",
+                matrix.data.len(),
+                matrix.size * matrix.size,
+                matrix.size
+            ));
+        }
+        matrix.validate()?;
+        Ok(matrix)
+    }
+
     /// Validate that all values are within expected bounds.
     pub fn validate(&self) -> Result<(), String> {
         for (i, &val) in self.data.iter().enumerate() {
@@ -219,6 +413,12 @@ impl InteractionMatrix {
 /// - Below min_radius: Repulsion force is applied
 /// - Between min and max: Attraction/repulsion from InteractionMatrix
 /// - Above max_radius: No interaction
+///
+/// Each type may also have an elliptical (anisotropic) interaction shape given
+/// by an orientation and an aspect ratio. The ellipse keeps `max_radius` along
+/// its major axis and is squashed by `aspect` across it, so it never exceeds
+/// the circular radius and spatial hash sizing stays valid. Empty vectors
+/// (e.g. from older presets) mean isotropic.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RadiusMatrix {
     /// Minimum interaction distances (flattened NxN).
@@ -227,8 +427,20 @@ pub struct RadiusMatrix {
     pub max_radius: Vec<f32>,
     /// Number of particle types.
     pub size: usize,
+    /// Per-type ellipse orientation in radians (major axis angle).
+    #[serde(default)]
+    pub orientation: Vec<f32>,
+    /// Per-type ellipse aspect ratio (1.0 = circular).
+    #[serde(default)]
+    pub aspect: Vec<f32>,
 }
 
+/// Default min radius as a fraction of the shorter world side (30 px at 1080).
+pub const DEFAULT_MIN_RADIUS_FRACTION: f32 = 30.0 / 1080.0;
+
+/// Default max radius as a fraction of the shorter world side (80 px at 1080).
+pub const DEFAULT_MAX_RADIUS_FRACTION: f32 = 80.0 / 1080.0;
+
 impl RadiusMatrix {
     /// Create new radius matrices with uniform values.
     pub fn new(size: usize, min_radius: f32, max_radius: f32) -> Self {
@@ -236,6 +448,8 @@ impl RadiusMatrix {
             min_radius: vec![min_radius; size * size],
             max_radius: vec![max_radius; size * size],
             size,
+            orientation: vec![0.0; size],
+            aspect: vec![1.0; size],
         }
     }
 
@@ -244,6 +458,25 @@ impl RadiusMatrix {
         Self::new(size, 30.0, 80.0)
     }
 
+    /// Create radius matrices with the default radii expressed as fractions
+    /// of the shorter world side, so the look doesn't depend on world scale.
+    /// Matches [`Self::default_for_size`] for the default 1920x1080 world.
+    pub fn default_for_world(size: usize, world_size: glam::Vec2) -> Self {
+        let side = world_size.min_element().max(1.0);
+        Self::new(
+            size,
+            side * DEFAULT_MIN_RADIUS_FRACTION,
+            side * DEFAULT_MAX_RADIUS_FRACTION,
+        )
+    }
+
+    /// Multiply every min and max radius by `factor`.
+    pub fn scale(&mut self, factor: f32) {
+        for r in self.min_radius.iter_mut().chain(self.max_radius.iter_mut()) {
+            *r *= factor;
+        }
+    }
+
     /// Get the minimum radius for interaction between two types.
     #[inline]
     pub fn get_min(&self, from_type: usize, to_type: usize) -> f32 {
@@ -263,6 +496,18 @@ impl RadiusMatrix {
         self.max_radius[idx] = max;
     }
 
+    /// Set the minimum radius for a type pair, capped at its maximum.
+    pub fn set_min(&mut self, from_type: usize, to_type: usize, min: f32) {
+        let idx = from_type * self.size + to_type;
+        self.min_radius[idx] = min.clamp(0.0, self.max_radius[idx]);
+    }
+
+    /// Set the maximum radius for a type pair, kept at or above its minimum.
+    pub fn set_max(&mut self, from_type: usize, to_type: usize, max: f32) {
+        let idx = from_type * self.size + to_type;
+        self.max_radius[idx] = max.max(self.min_radius[idx]);
+    }
+
     /// Set the same radius range for all type pairs.
     pub fn set_uniform(&mut self, min: f32, max: f32) {
         for val in &mut self.min_radius {
@@ -273,6 +518,58 @@ impl RadiusMatrix {
         }
     }
 
+    /// Get the anisotropy `(orientation, aspect)` for a type.
+    ///
+    /// Falls back to isotropic `(0.0, 1.0)` when no value is stored.
+    #[inline]
+    pub fn get_anisotropy(&self, particle_type: usize) -> (f32, f32) {
+        let orientation = self.orientation.get(particle_type).copied().unwrap_or(0.0);
+        let aspect = self.aspect.get(particle_type).copied().unwrap_or(1.0);
+        (orientation, aspect.max(1.0))
+    }
+
+    /// Set the anisotropy for a type (aspect is clamped to >= 1).
+    pub fn set_anisotropy(&mut self, particle_type: usize, orientation: f32, aspect: f32) {
+        self.orientation.resize(self.size, 0.0);
+        self.aspect.resize(self.size, 1.0);
+        self.orientation[particle_type] = orientation;
+        self.aspect[particle_type] = aspect.max(1.0);
+    }
+
+    /// Reset all types to circular interaction radii.
+    pub fn clear_anisotropy(&mut self) {
+        self.orientation = vec![0.0; self.size];
+        self.aspect = vec![1.0; self.size];
+    }
+
+    /// Effective distance used for radius tests from `from_type`'s point of view.
+    ///
+    /// `delta` is rotated into the type's local frame and the cross-axis
+    /// component is stretched by the aspect ratio, so the result is always
+    /// `>= delta.length()`.
+    #[inline]
+    pub fn effective_distance(&self, from_type: usize, delta: glam::Vec2) -> f32 {
+        let (orientation, aspect) = self.get_anisotropy(from_type);
+        if aspect <= 1.0 {
+            return delta.length();
+        }
+        let (sin, cos) = orientation.sin_cos();
+        let along = delta.x * cos + delta.y * sin;
+        let across = (delta.y * cos - delta.x * sin) * aspect;
+        (along * along + across * across).sqrt()
+    }
+
+    /// Per-type anisotropy packed as `[cos, sin, aspect, 0]` for the GPU.
+    pub fn anisotropy_gpu_data(&self) -> Vec<[f32; 4]> {
+        (0..self.size.max(1))
+            .map(|t| {
+                let (orientation, aspect) = self.get_anisotropy(t);
+                let (sin, cos) = orientation.sin_cos();
+                [cos, sin, aspect, 0.0]
+            })
+            .collect()
+    }
+
     /// Get the maximum radius value in the matrix (for spatial hash sizing).
     pub fn max_interaction_radius(&self) -> f32 {
         self.max_radius.iter().copied().fold(0.0, f32::max)
@@ -308,14 +605,18 @@ impl RadiusMatrix {
 }
 
 /// Structure for Position and Type (SoA layout).
-/// aligned to 16 bytes to match WGSL vec2<f32> + u32 + pad.
+/// aligned to 16 bytes to match WGSL vec2<f32> + u32 + u32.
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 #[repr(C, align(16))]
 pub struct ParticlePosType {
     pub x: f32,
     pub y: f32,
+    /// Type index, with [`STATIC_PARTICLE_FLAG`] set for static particles.
     pub particle_type: u32,
-    pub _padding: u32,
+    /// Spin state packed as two f16 values (orientation in radians, angular
+    /// velocity in rad/s), matching WGSL `pack2x16float`. GPU-only: uploads
+    /// from CPU particles reset it to zero.
+    pub spin: u32,
 }
 
 impl From<&Particle> for ParticlePosType {
@@ -323,12 +624,26 @@ impl From<&Particle> for ParticlePosType {
         Self {
             x: p.x,
             y: p.y,
-            particle_type: p.particle_type,
-            _padding: 0,
+            particle_type: pack_type(p),
+            spin: 0,
         }
     }
 }
 
+impl ParticlePosType {
+    /// Type index with the static flag masked off.
+    #[inline]
+    pub fn type_index(&self) -> u32 {
+        self.particle_type & PARTICLE_TYPE_MASK
+    }
+
+    /// Whether the particle is pinned in place.
+    #[inline]
+    pub fn is_static(&self) -> bool {
+        self.particle_type & STATIC_PARTICLE_FLAG != 0
+    }
+}
+
 /// Structure for Velocity (SoA layout).
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 #[repr(C)] // size 8, align 4. Matches vec2<f32> array stride 8.
@@ -359,7 +674,7 @@ impl From<&Particle> for ParticlePosTypeHalf {
         Self {
             x: half::f16::from_f32(p.x),
             y: half::f16::from_f32(p.y),
-            particle_type: p.particle_type,
+            particle_type: pack_type(p),
         }
     }
 }
@@ -397,6 +712,30 @@ mod tests {
         assert_eq!(p.vy, 0.0);
     }
 
+    #[test]
+    fn test_static_flag_round_trip() {
+        let mut p = Particle::with_velocity(1.0, 2.0, 3.0, 4.0, 5);
+        p.set_static(true);
+        assert_eq!(p.speed(), 0.0);
+
+        let packed = ParticlePosType::from(&p);
+        assert!(packed.is_static());
+        assert_eq!(packed.type_index(), 5);
+        assert!(!ParticlePosType::from(&Particle::new(0.0, 0.0, 5)).is_static());
+    }
+
+    #[test]
+    fn test_f16_velocity_negation_is_exact() {
+        for v in [0.1f32, -3.7, 123.456, 1e-3] {
+            // Velocity as read back from f16 storage, then negated
+            let read = half::f16::from_f32(v).to_f32();
+            let stored = ParticleVelHalf::from(&Particle::with_velocity(0.0, 0.0, read, -read, 0));
+            let negated = ParticleVelHalf::from(&Particle::with_velocity(0.0, 0.0, -read, read, 0));
+            assert_eq!(negated.vx, -stored.vx);
+            assert_eq!(negated.vy, -stored.vy);
+        }
+    }
+
     #[test]
     fn test_particle_velocity() {
         let p = Particle::with_velocity(0.0, 0.0, 3.0, 4.0, 0);
@@ -425,6 +764,191 @@ mod tests {
         assert_eq!(m.get(1, 0), 0.0);
     }
 
+    #[test]
+    fn test_matrix_transpose_and_invert_are_involutions() {
+        let mut m = InteractionMatrix::new(3);
+        for i in 0..3 {
+            for j in 0..3 {
+                m.set(i, j, (i * 3 + j) as f32 / 10.0 - 0.4);
+            }
+        }
+
+        let mut t = m.clone();
+        t.transpose();
+        assert_eq!(t.get(0, 2), m.get(2, 0));
+        assert_eq!(t.get(1, 1), m.get(1, 1));
+        t.transpose();
+        assert_eq!(t.data, m.data);
+
+        let mut n = m.clone();
+        n.invert();
+        assert_eq!(n.get(0, 1), -m.get(0, 1));
+        n.invert();
+        assert_eq!(n.data, m.data);
+
+        // Symmetrizing is idempotent
+        let mut s = m.clone();
+        s.symmetrize();
+        let once = s.data.clone();
+        s.symmetrize();
+        assert_eq!(s.data, once);
+    }
+
+    #[test]
+    fn test_matrix_anti_symmetrize() {
+        let mut m = InteractionMatrix::new(3);
+        for i in 0..3 {
+            for j in 0..3 {
+                m.set(i, j, (i * 3 + j) as f32 / 10.0 - 0.4);
+            }
+        }
+
+        m.anti_symmetrize();
+        for i in 0..3 {
+            assert_eq!(m.get(i, i), 0.0);
+            for j in 0..3 {
+                assert_eq!(m.get(i, j), -m.get(j, i));
+            }
+        }
+        // m[0][1] = 0.1 - 0.4, m[1][0] = 0.3 - 0.4: half their difference
+        assert!((m.get(0, 1) + 0.1).abs() < 1e-6);
+
+        // Already anti-symmetric matrices are unchanged
+        let once = m.data.clone();
+        m.anti_symmetrize();
+        assert_eq!(m.data, once);
+    }
+
+    #[test]
+    fn test_matrix_cyclic_symmetrize() {
+        let n = 6;
+        let mut m = InteractionMatrix::new(n);
+        for i in 0..n {
+            for j in 0..n {
+                m.set(i, j, ((i * 7 + j * 3) % 11) as f32 / 10.0 - 0.5);
+            }
+        }
+
+        for period in [1, 2, 3] {
+            let mut c = m.clone();
+            c.cyclic_symmetrize(period);
+            for i in 0..n {
+                for j in 0..n {
+                    let shifted = c.get((i + period) % n, (j + period) % n);
+                    assert!((c.get(i, j) - shifted).abs() < 1e-6);
+                }
+            }
+        }
+
+        // A shift by the full size leaves the matrix unchanged
+        let mut c = m.clone();
+        c.cyclic_symmetrize(n);
+        assert_eq!(c.data, m.data);
+    }
+
+    #[test]
+    fn test_matrix_normalize_rows_and_cols() {
+        let mut matrix = InteractionMatrix::new(3);
+        matrix.set(0, 0, 0.5);
+        matrix.set(0, 1, -1.0);
+        matrix.set(0, 2, 0.5);
+        matrix.set(2, 0, 0.25);
+
+        let mut rows = matrix.clone();
+        rows.normalize_rows(1.0);
+        assert_eq!(rows.get(0, 0), 0.25);
+        assert_eq!(rows.get(0, 1), -0.5);
+        assert_eq!(rows.get(0, 2), 0.25);
+        assert_eq!(rows.get(2, 0), 1.0);
+        // The all-zero row stays zero
+        assert!((0..3).all(|j| rows.get(1, j) == 0.0));
+
+        let mut cols = matrix.clone();
+        cols.normalize_cols(1.5);
+        assert_eq!(cols.get(0, 0), 1.0);
+        assert_eq!(cols.get(2, 0), 0.5);
+        assert_eq!(cols.get(0, 1), -1.5);
+        assert_eq!(cols.get(0, 2), 1.5);
+    }
+
+    #[test]
+    fn test_matrix_mutate() {
+        use rand::SeedableRng;
+        let mut rng = rand::rngs::StdRng::seed_from_u64(7);
+
+        // Small noise around zero: stays in range and averages out
+        let mut m = InteractionMatrix::new(16);
+        m.mutate(0.1, &mut rng);
+        assert!(m.data.iter().all(|v| (-1.0..=1.0).contains(v)));
+        assert!(m.data.iter().any(|&v| v != 0.0));
+        let mean = m.data.iter().sum::<f32>() / m.data.len() as f32;
+        assert!(mean.abs() < 0.03, "mean change {}", mean);
+
+        // Large noise at the edge is clamped
+        let mut m = InteractionMatrix::filled(8, 1.0);
+        m.mutate(0.5, &mut rng);
+        assert!(m.data.iter().all(|v| (-1.0..=1.0).contains(v)));
+    }
+
+    #[test]
+    fn test_matrix_randomize_diagonal() {
+        let mut m = InteractionMatrix::filled(4, 0.9);
+        let mut rng = rand::rng();
+        m.randomize_diagonal(&mut rng, -0.3, 0.6);
+
+        for i in 0..4 {
+            for j in 0..4 {
+                if i == j {
+                    assert!((-0.3..=0.6).contains(&m.get(i, j)));
+                } else {
+                    assert_eq!(m.get(i, j), 0.9);
+                }
+            }
+        }
+
+        m.set_diagonal(-0.5);
+        assert!((0..4).all(|i| m.get(i, i) == -0.5));
+        assert_eq!(m.get(0, 1), 0.9);
+    }
+
+    #[test]
+    fn test_matrix_json_round_trip_and_resize() {
+        let mut m = InteractionMatrix::new(3);
+        m.set(0, 2, 0.5);
+        m.set(2, 1, -0.75);
+        let parsed = InteractionMatrix::from_json(&m.to_json()).unwrap();
+        assert_eq!(parsed.size, 3);
+        assert_eq!(parsed.data, m.data);
+
+        assert!(InteractionMatrix::from_json(r#"{"size": 2, "data": [0.1]}"#).is_err());
+        assert!(InteractionMatrix::from_json("not json").is_err());
+
+        let grown = m.resized(4);
+        assert_eq!(grown.get(2, 1), -0.75);
+        assert_eq!(grown.get(3, 3), 0.0);
+        let shrunk = m.resized(2);
+        assert_eq!(shrunk.data, vec![0.0; 4]);
+    }
+
+    #[test]
+    fn test_radius_matrix_anisotropy() {
+        let mut r = RadiusMatrix::new(2, 30.0, 80.0);
+        let delta = glam::Vec2::new(0.0, 10.0);
+        assert!((r.effective_distance(0, delta) - 10.0).abs() < 1e-5);
+
+        // Major axis along +x: distance along x unchanged, across (y) stretched
+        r.set_anisotropy(0, 0.0, 2.0);
+        assert!((r.effective_distance(0, glam::Vec2::new(10.0, 0.0)) - 10.0).abs() < 1e-5);
+        assert!((r.effective_distance(0, delta) - 20.0).abs() < 1e-4);
+        // Other types stay isotropic
+        assert!((r.effective_distance(1, delta) - 10.0).abs() < 1e-5);
+
+        // Missing data (older presets) falls back to isotropic
+        r.orientation.clear();
+        r.aspect.clear();
+        assert_eq!(r.get_anisotropy(0), (0.0, 1.0));
+    }
+
     #[test]
     fn test_radius_matrix_validation() {
         let mut r = RadiusMatrix::new(2, 30.0, 80.0);
@@ -432,5 +956,25 @@ mod tests {
 
         r.set(0, 0, 100.0, 50.0); // max < min
         assert!(r.validate().is_err());
+
+        // Per-cell setters never let min cross max
+        let mut r = RadiusMatrix::new(2, 30.0, 80.0);
+        r.set_min(0, 1, 120.0);
+        assert_eq!(r.get_min(0, 1), 80.0);
+        r.set_max(1, 0, 10.0);
+        assert_eq!(r.get_max(1, 0), 30.0);
+        assert!(r.validate().is_ok());
+    }
+
+    #[test]
+    fn test_radius_matrix_relative_to_world() {
+        let r = RadiusMatrix::default_for_world(3, glam::Vec2::new(1920.0, 1080.0));
+        let d = RadiusMatrix::default_for_size(3);
+        assert!((r.get_min(0, 1) - d.get_min(0, 1)).abs() < 1e-3);
+        assert!((r.get_max(2, 2) - d.get_max(2, 2)).abs() < 1e-3);
+
+        // Doubling the shorter side doubles the radii
+        let r = RadiusMatrix::default_for_world(3, glam::Vec2::new(4000.0, 2160.0));
+        assert!((r.get_max(1, 0) - 160.0).abs() < 1e-3);
     }
 }
@@ -0,0 +1,54 @@
+//! Particle sprite shapes for rendering.
+
+use serde::{Deserialize, Serialize};
+
+/// Shape of each particle sprite, chosen in the render shaders' fragment
+/// stage. Glow keeps its own falloff regardless of the shape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum ParticleShape {
+    /// Solid disk with a one-pixel anti-aliased edge.
+    #[default]
+    HardDisk,
+
+    /// Disk whose alpha fades smoothly from the middle to the edge.
+    SoftDisk,
+
+    /// Filled square covering the whole sprite.
+    Square,
+
+    /// Gaussian dot, brightest at the center.
+    Gaussian,
+}
+
+impl ParticleShape {
+    /// Get all available shapes.
+    pub fn all() -> &'static [ParticleShape] {
+        &[
+            ParticleShape::HardDisk,
+            ParticleShape::SoftDisk,
+            ParticleShape::Square,
+            ParticleShape::Gaussian,
+        ]
+    }
+
+    /// Get the display name for this shape.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            ParticleShape::HardDisk => "Hard Disk",
+            ParticleShape::SoftDisk => "Soft Disk",
+            ParticleShape::Square => "Square",
+            ParticleShape::Gaussian => "Gaussian",
+        }
+    }
+
+    /// Shape selector written to the sim params uniform (matches `SHAPE_*`
+    /// in the render shaders).
+    pub fn shader_id(&self) -> u32 {
+        match self {
+            ParticleShape::HardDisk => 0,
+            ParticleShape::SoftDisk => 1,
+            ParticleShape::Square => 2,
+            ParticleShape::Gaussian => 3,
+        }
+    }
+}
@@ -2,14 +2,62 @@
 
 use glam::Vec2;
 use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use super::{
     SimulationConfig,
-    boundary::{BoundaryMode, apply_boundary, wrapped_delta},
+    boundary::{apply_boundary, uses_wrapped_distance, wrapped_delta},
     particle::{InteractionMatrix, Particle, RadiusMatrix},
     spatial_hash::SpatialHash,
 };
 
+/// How particle speed is limited to `max_velocity`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum VelocityClampMode {
+    /// Rescale to exactly `max_velocity` once exceeded.
+    #[default]
+    Hard,
+
+    /// Map speed through `max_velocity * tanh(speed / max_velocity)`, which
+    /// approaches the cap smoothly and leaves slow particles nearly untouched.
+    SoftTanh,
+}
+
+impl VelocityClampMode {
+    /// Get all available clamp modes.
+    pub fn all() -> &'static [VelocityClampMode] {
+        &[VelocityClampMode::Hard, VelocityClampMode::SoftTanh]
+    }
+
+    /// Get the display name for this mode.
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            VelocityClampMode::Hard => "Hard",
+            VelocityClampMode::SoftTanh => "Soft (tanh)",
+        }
+    }
+
+    /// Shader selector (0 = Hard, 1 = SoftTanh).
+    pub fn shader_id(&self) -> u32 {
+        match self {
+            VelocityClampMode::Hard => 0,
+            VelocityClampMode::SoftTanh => 1,
+        }
+    }
+
+    /// Limit `velocity` to `max_velocity` using this mode.
+    pub fn apply(&self, velocity: Vec2, max_velocity: f32) -> Vec2 {
+        let speed = velocity.length();
+        match self {
+            VelocityClampMode::Hard if speed > max_velocity => velocity * (max_velocity / speed),
+            VelocityClampMode::SoftTanh if speed > 0.0 && max_velocity > 0.0 => {
+                velocity * (max_velocity * (speed / max_velocity).tanh() / speed)
+            }
+            _ => velocity,
+        }
+    }
+}
+
 /// Physics engine that computes forces and advances the simulation.
 pub struct PhysicsEngine {
     /// Cached force vectors for each particle.
@@ -75,6 +123,35 @@ impl PhysicsEngine {
     }
 }
 
+/// Signed force one particle feels toward another at distance `dist`,
+/// before division by the force factor.
+///
+/// Positive values attract and negative values repel. Below the repulsion
+/// core (`min_r` scaled by [`SimulationConfig::repel_core_fraction`]) the
+/// pair always repels, growing linearly toward `repel_strength`; between the
+/// core and `max_r` the matrix value (scaled by `matrix_strength`) falls off
+/// linearly to zero. Beyond `max_r` there is no force.
+pub fn pair_force(
+    dist: f32,
+    min_r: f32,
+    max_r: f32,
+    attraction: f32,
+    config: &SimulationConfig,
+) -> f32 {
+    let min_r = min_r * config.repel_core_fraction();
+    if dist > max_r {
+        0.0
+    } else if dist < min_r {
+        // Close range repulsion
+        -config.repel_strength * (min_r - dist) / min_r
+    } else {
+        // Attraction/repulsion based on interaction matrix, with linear
+        // falloff from min to max radius
+        let t = (dist - min_r) / (max_r - min_r);
+        attraction * config.matrix_strength * (1.0 - t)
+    }
+}
+
 /// Compute forces between all particles using brute force O(n²).
 ///
 /// This is the CPU fallback when spatial hashing is disabled or unavailable.
@@ -85,10 +162,7 @@ pub fn compute_forces_cpu(
     radius_matrix: &RadiusMatrix,
     config: &SimulationConfig,
 ) -> Vec<Vec2> {
-    let use_wrap = matches!(
-        config.boundary_mode,
-        BoundaryMode::Wrap | BoundaryMode::MirrorWrap | BoundaryMode::InfiniteWrap
-    );
+    let use_wrap = uses_wrapped_distance(config);
 
     particles
         .par_iter()
@@ -121,23 +195,19 @@ pub fn compute_forces_cpu(
                     continue; // Avoid division by zero
                 }
 
+                // Anisotropic types test radii in their local (elliptical) frame
+                let dist_eff = radius_matrix.effective_distance(p_type, delta);
+                if dist_eff > max_r {
+                    continue;
+                }
+
                 let min_r = radius_matrix.get_min(p_type, q_type);
                 let direction = delta / dist;
-
-                if dist < min_r {
-                    // Close range repulsion
-                    let repel_strength = config.repel_strength * (min_r - dist) / min_r;
-                    force -= direction * repel_strength;
-                } else {
-                    // Attraction/repulsion based on interaction matrix
-                    let strength = interaction_matrix.get(p_type, q_type);
-                    // Linear falloff from min to max radius
-                    let t = (dist - min_r) / (max_r - min_r);
-                    force += direction * strength * (1.0 - t);
-                }
+                let attraction = interaction_matrix.get(p_type, q_type);
+                force += direction * pair_force(dist_eff, min_r, max_r, attraction, config);
             }
 
-            force / config.force_factor
+            force / config.force_factor_for(p_type)
         })
         .collect()
 }
@@ -151,10 +221,7 @@ fn compute_forces_spatial(
     config: &SimulationConfig,
     spatial_hash: &SpatialHash,
 ) {
-    let use_wrap = matches!(
-        config.boundary_mode,
-        BoundaryMode::Wrap | BoundaryMode::MirrorWrap | BoundaryMode::InfiniteWrap
-    );
+    let use_wrap = uses_wrapped_distance(config);
 
     forces.par_iter_mut().enumerate().for_each(|(i, force)| {
         *force = Vec2::ZERO;
@@ -190,20 +257,18 @@ fn compute_forces_spatial(
                 continue;
             }
 
+            let dist_eff = radius_matrix.effective_distance(p_type, delta);
+            if dist_eff > max_r {
+                continue;
+            }
+
             let min_r = radius_matrix.get_min(p_type, q_type);
             let direction = delta / dist;
-
-            if dist < min_r {
-                let repel_strength = config.repel_strength * (min_r - dist) / min_r;
-                *force -= direction * repel_strength;
-            } else {
-                let strength = interaction_matrix.get(p_type, q_type);
-                let t = (dist - min_r) / (max_r - min_r);
-                *force += direction * strength * (1.0 - t);
-            }
+            let attraction = interaction_matrix.get(p_type, q_type);
+            *force += direction * pair_force(dist_eff, min_r, max_r, attraction, config);
         }
 
-        *force /= config.force_factor;
+        *force /= config.force_factor_for(p_type);
     });
 }
 
@@ -215,6 +280,8 @@ fn compute_forces_spatial(
 /// 3. Velocity clamping
 /// 4. Position update
 /// 5. Boundary handling
+///
+/// Static particles are skipped and stay at rest.
 pub fn advance_particles(
     particles: &mut [Particle],
     forces: &[Vec2],
@@ -225,8 +292,14 @@ pub fn advance_particles(
         .par_iter_mut()
         .zip(forces.par_iter())
         .for_each(|(p, &force)| {
+            if p.is_static() {
+                p.vx = 0.0;
+                p.vy = 0.0;
+                return;
+            }
+
             // Apply friction (damping)
-            let friction_factor = 1.0 - config.friction;
+            let friction_factor = 1.0 - config.friction_for(p.particle_type as usize);
             p.vx *= friction_factor;
             p.vy *= friction_factor;
 
@@ -235,12 +308,11 @@ pub fn advance_particles(
             p.vy += force.y * dt;
 
             // Clamp velocity magnitude
-            let speed = p.speed();
-            if speed > config.max_velocity {
-                let scale = config.max_velocity / speed;
-                p.vx *= scale;
-                p.vy *= scale;
-            }
+            let v = config
+                .velocity_clamp
+                .apply(Vec2::new(p.vx, p.vy), config.max_velocity);
+            p.vx = v.x;
+            p.vy = v.y;
 
             // Update position
             p.x += p.vx * dt;
@@ -251,6 +323,129 @@ pub fn advance_particles(
         });
 }
 
+/// Position divergence between a reference and an observed set of particles.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TrajectoryDivergence {
+    /// Largest matched position difference.
+    pub max: f32,
+    /// Mean matched position difference.
+    pub mean: f32,
+    /// Observed particles with no reference particle of the same type within
+    /// the search radius.
+    pub unmatched: usize,
+}
+
+/// Measure how far `observed` particles are from `reference` particles.
+///
+/// The GPU reorders particles by spatial bin every step, so indices don't
+/// line up between the two sets. Each observed particle is instead matched to
+/// the nearest reference particle of the same type within `search_radius`.
+/// The result is a lower bound on the true per-particle divergence, which is
+/// exact as long as particles move less than their spacing in one step.
+pub fn trajectory_divergence(
+    reference: &[Particle],
+    observed: &[Particle],
+    config: &SimulationConfig,
+    search_radius: f32,
+) -> TrajectoryDivergence {
+    if reference.is_empty() || observed.is_empty() {
+        return TrajectoryDivergence::default();
+    }
+
+    let use_wrap = uses_wrapped_distance(config);
+    let hash = SpatialHash::build(reference, search_radius, config.world_size);
+    let (sum, max, matched) = observed
+        .par_iter()
+        .map(|p| nearest_match(&hash, reference, p, config, search_radius, use_wrap))
+        .map(|m| m.map_or((0.0, 0.0, 0usize), |(_, d)| (d as f64, d, 1)))
+        .reduce(
+            || (0.0, 0.0, 0),
+            |a, b| (a.0 + b.0, a.1.max(b.1), a.2 + b.2),
+        );
+
+    TrajectoryDivergence {
+        max,
+        mean: if matched > 0 {
+            (sum / matched as f64) as f32
+        } else {
+            0.0
+        },
+        unmatched: observed.len() - matched,
+    }
+}
+
+/// Index of and distance to the nearest reference particle of the same type
+/// as `p` within `search_radius`.
+fn nearest_match(
+    hash: &SpatialHash,
+    reference: &[Particle],
+    p: &Particle,
+    config: &SimulationConfig,
+    search_radius: f32,
+    use_wrap: bool,
+) -> Option<(usize, f32)> {
+    let pos = p.position();
+    hash.query_radius(pos, search_radius, config.world_size, use_wrap)
+        .into_iter()
+        .filter(|&j| reference[j].particle_type == p.particle_type)
+        .map(|j| {
+            let d = wrapped_delta(pos, reference[j].position(), config.world_size, use_wrap);
+            (j, d.length())
+        })
+        .filter(|&(_, d)| d <= search_radius)
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Worst per-particle difference between a CPU and a GPU step taken from the
+/// same starting state.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StepParity {
+    /// Largest position difference.
+    pub max_position: f32,
+    /// Largest velocity difference.
+    pub max_velocity: f32,
+    /// Observed particles with no reference particle of the same type within
+    /// the search radius.
+    pub unmatched: usize,
+}
+
+/// Compare one step's `observed` (GPU) particles with the `reference` (CPU)
+/// particles, pairing them like [`trajectory_divergence`] since the GPU
+/// reorders particles.
+pub fn step_parity(
+    reference: &[Particle],
+    observed: &[Particle],
+    config: &SimulationConfig,
+    search_radius: f32,
+) -> StepParity {
+    if reference.is_empty() || observed.is_empty() {
+        return StepParity::default();
+    }
+
+    let use_wrap = uses_wrapped_distance(config);
+    let hash = SpatialHash::build(reference, search_radius, config.world_size);
+    observed
+        .par_iter()
+        .map(
+            |p| match nearest_match(&hash, reference, p, config, search_radius, use_wrap) {
+                Some((j, d)) => StepParity {
+                    max_position: d,
+                    max_velocity: (p.velocity() - reference[j].velocity()).length(),
+                    unmatched: 0,
+                },
+                None => StepParity {
+                    unmatched: 1,
+                    ..Default::default()
+                },
+            },
+        )
+        .reduce(StepParity::default, |a, b| StepParity {
+            max_position: a.max_position.max(b.max_position),
+            max_velocity: a.max_velocity.max(b.max_velocity),
+            unmatched: a.unmatched + b.unmatched,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -270,6 +465,57 @@ mod tests {
         RadiusMatrix::new(2, 5.0, 50.0)
     }
 
+    #[test]
+    fn test_trajectory_divergence_ignores_order() {
+        let config = SimulationConfig {
+            world_size: glam::Vec2::new(100.0, 100.0),
+            boundary_mode: crate::simulation::BoundaryMode::Repel,
+            ..Default::default()
+        };
+        let reference = vec![
+            Particle::new(10.0, 10.0, 0),
+            Particle::new(50.0, 50.0, 1),
+            Particle::new(80.0, 20.0, 0),
+        ];
+        // Same particles, shuffled, one nudged by 3 units
+        let observed = vec![
+            Particle::new(80.0, 20.0, 0),
+            Particle::new(13.0, 10.0, 0),
+            Particle::new(50.0, 50.0, 1),
+        ];
+        let div = trajectory_divergence(&reference, &observed, &config, 10.0);
+        assert_eq!(div.unmatched, 0);
+        assert!((div.max - 3.0).abs() < 1e-4);
+        assert!((div.mean - 1.0).abs() < 1e-4);
+
+        // Type mismatch leaves a particle unmatched
+        let observed = vec![Particle::new(10.0, 10.0, 1)];
+        let div = trajectory_divergence(&reference, &observed, &config, 10.0);
+        assert_eq!(div.unmatched, 1);
+    }
+
+    #[test]
+    fn test_step_parity_pairs_velocities() {
+        let config = SimulationConfig {
+            world_size: glam::Vec2::new(100.0, 100.0),
+            boundary_mode: crate::simulation::BoundaryMode::Repel,
+            ..Default::default()
+        };
+        let reference = vec![
+            Particle::with_velocity(10.0, 10.0, 1.0, 0.0, 0),
+            Particle::with_velocity(50.0, 50.0, 0.0, 2.0, 1),
+        ];
+        // Reordered; the second particle is off by 0.5 in position and 1 in velocity
+        let observed = vec![
+            Particle::with_velocity(50.5, 50.0, 0.0, 3.0, 1),
+            Particle::with_velocity(10.0, 10.0, 1.0, 0.0, 0),
+        ];
+        let parity = step_parity(&reference, &observed, &config, 5.0);
+        assert!((parity.max_position - 0.5).abs() < 1e-5);
+        assert!((parity.max_velocity - 1.0).abs() < 1e-5);
+        assert_eq!(parity.unmatched, 0);
+    }
+
     #[test]
     fn test_force_calculation() {
         let particles = make_test_particles();
@@ -291,6 +537,98 @@ mod tests {
         assert!(forces[1].x > 0.0);
     }
 
+    #[test]
+    fn test_pair_force_curve() {
+        let config = SimulationConfig {
+            repel_strength: 2.0,
+            matrix_strength: 1.0,
+            ..Default::default()
+        };
+
+        // Full repulsion at contact, none at the min radius
+        assert!((pair_force(0.0, 10.0, 50.0, 1.0, &config) + 2.0).abs() < 1e-6);
+        assert!((pair_force(10.0, 10.0, 50.0, 1.0, &config) - 1.0).abs() < 1e-6);
+        // Matrix value falls off linearly to zero at the max radius
+        assert!((pair_force(30.0, 10.0, 50.0, -0.5, &config) + 0.25).abs() < 1e-6);
+        assert_eq!(pair_force(50.0, 10.0, 50.0, 1.0, &config), 0.0);
+        assert_eq!(pair_force(60.0, 10.0, 50.0, 1.0, &config), 0.0);
+    }
+
+    #[test]
+    fn test_repel_radius_moves_crossover() {
+        let config = SimulationConfig {
+            repel_strength: 2.0,
+            repel_radius: 0.5,
+            ..Default::default()
+        };
+        // Core is now 5: repulsion stops there and attraction starts
+        assert!((pair_force(0.0, 10.0, 50.0, 1.0, &config) + 2.0).abs() < 1e-6);
+        assert!(pair_force(4.0, 10.0, 50.0, 1.0, &config) < 0.0);
+        assert!(pair_force(7.0, 10.0, 50.0, 1.0, &config) > 0.0);
+
+        // A zero fraction is clamped instead of dividing by zero
+        let config = SimulationConfig {
+            repel_radius: 0.0,
+            ..config
+        };
+        assert!(pair_force(0.1, 10.0, 50.0, 1.0, &config).is_finite());
+    }
+
+    #[test]
+    fn test_forces_attract_across_wrap_seam() {
+        let particles = vec![Particle::new(2.0, 50.0, 0), Particle::new(98.0, 50.0, 1)];
+        let mut matrix = InteractionMatrix::new(2);
+        matrix.set(0, 1, 1.0);
+        matrix.set(1, 0, 1.0);
+        let config = SimulationConfig {
+            force_factor: 1.0,
+            world_size: glam::Vec2::new(100.0, 100.0),
+            boundary_mode: crate::simulation::BoundaryMode::Wrap,
+            ..Default::default()
+        };
+
+        // 4 units apart through the seam, 96 through the interior
+        let forces = compute_forces_cpu(
+            &particles,
+            &matrix,
+            &RadiusMatrix::new(2, 1.0, 20.0),
+            &config,
+        );
+        assert!(
+            forces[0].x < 0.0,
+            "left particle should pull toward the left edge"
+        );
+        assert!(
+            forces[1].x > 0.0,
+            "right particle should pull toward the right edge"
+        );
+    }
+
+    #[test]
+    fn test_soft_velocity_clamp() {
+        let soft = VelocityClampMode::SoftTanh;
+
+        // Slow drift is nearly unaffected
+        let slow = soft.apply(Vec2::new(1.0, 0.0), 100.0);
+        assert!((slow.x - 1.0).abs() < 1e-3);
+
+        // Fast particles settle at the cap, keeping direction
+        let fast = soft.apply(Vec2::new(0.0, 1000.0), 100.0);
+        assert!(fast.y > 99.0 && fast.y <= 100.0);
+        assert_eq!(fast.x, 0.0);
+
+        // No discontinuity at the cap, unlike the hard clamp
+        let below = soft.apply(Vec2::new(99.9, 0.0), 100.0).x;
+        let above = soft.apply(Vec2::new(100.1, 0.0), 100.0).x;
+        assert!((above - below).abs() < 0.1);
+        assert_eq!(
+            VelocityClampMode::Hard
+                .apply(Vec2::new(200.0, 0.0), 100.0)
+                .x,
+            100.0
+        );
+    }
+
     #[test]
     fn test_particle_advancement() {
         let mut particles = vec![Particle::with_velocity(50.0, 50.0, 1.0, 0.0, 0)];
@@ -307,6 +645,27 @@ mod tests {
         assert!((particles[0].x - 51.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_static_particle_holds_still_but_exerts_force() {
+        let mut particles = make_test_particles();
+        particles[1].set_static(true);
+        let config = SimulationConfig {
+            force_factor: 1.0,
+            friction: 0.0,
+            max_velocity: 100.0,
+            world_size: glam::Vec2::new(100.0, 100.0),
+            ..Default::default()
+        };
+
+        let forces =
+            compute_forces_cpu(&particles, &make_test_matrix(), &make_test_radii(), &config);
+        advance_particles(&mut particles, &forces, &config, 1.0);
+
+        // The free particle is pulled toward the static one, which stays put
+        assert!(particles[0].x > 50.0);
+        assert_eq!((particles[1].x, particles[1].vx), (60.0, 0.0));
+    }
+
     #[test]
     fn test_friction_damping() {
         let mut particles = vec![Particle::with_velocity(50.0, 50.0, 10.0, 0.0, 0)];
@@ -324,6 +683,29 @@ mod tests {
         assert!((particles[0].vx - 5.0).abs() < 0.001);
     }
 
+    #[test]
+    fn test_per_type_friction_override() {
+        let mut particles = vec![
+            Particle::with_velocity(20.0, 50.0, 10.0, 0.0, 0),
+            Particle::with_velocity(80.0, 50.0, 10.0, 0.0, 1),
+        ];
+        let forces = vec![Vec2::ZERO; 2];
+        let config = SimulationConfig {
+            num_types: 2,
+            friction: 0.5,
+            type_friction: vec![0.0],
+            max_velocity: 100.0,
+            world_size: glam::Vec2::new(100.0, 100.0),
+            ..Default::default()
+        };
+
+        advance_particles(&mut particles, &forces, &config, 1.0);
+
+        // Type 0 uses its override; type 1 has no entry and falls back
+        assert!((particles[0].vx - 10.0).abs() < 0.001);
+        assert!((particles[1].vx - 5.0).abs() < 0.001);
+    }
+
     #[test]
     fn test_velocity_clamping() {
         let mut particles = vec![Particle::with_velocity(50.0, 50.0, 100.0, 0.0, 0)];
@@ -0,0 +1,312 @@
+//! Disk-backed recording of raw particle states.
+//!
+//! Unlike video capture, this logs particle positions and types every frame
+//! (or every K frames) so runs can be replayed in the app or analyzed with
+//! external tools.
+//!
+//! # File Format
+//!
+//! All values are little-endian.
+//!
+//! ```text
+//! Header (24 bytes)
+//!   magic      [u8; 8]  b"PLSTATE\0"
+//!   version    u32      1
+//!   num_types  u32
+//!   world_w    f32
+//!   world_h    f32
+//! Frame (repeated until EOF)
+//!   step       u64      simulation step the frame was captured at
+//!   count      u32      number of particles N
+//!   records    N × { x: f32, y: f32, type: u8 }   (9 bytes each)
+//! ```
+//!
+//! Frames are length-prefixed by `count`, so readers can skip frames without
+//! decoding them. Particle order follows the GPU buffer, which the spatial
+//! hash re-sorts every step; particles are not tracked by identity.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+
+use crate::simulation::{Particle, ParticlePosType};
+
+/// File magic identifying a particle state recording.
+pub const STATE_MAGIC: &[u8; 8] = b"PLSTATE\0";
+/// Current file format version.
+pub const STATE_VERSION: u32 = 1;
+/// File extension used for state recordings.
+pub const STATE_EXTENSION: &str = "plstate";
+
+const HEADER_BYTES: u64 = 24;
+const FRAME_HEADER_BYTES: u64 = 12;
+const RECORD_BYTES: u64 = 9;
+
+/// Header describing a state recording.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StateHeader {
+    /// Number of particle types.
+    pub num_types: u32,
+    /// World width.
+    pub world_width: f32,
+    /// World height.
+    pub world_height: f32,
+}
+
+/// Streams particle frames to a state recording file.
+pub struct StateRecorder {
+    writer: BufWriter<File>,
+    path: PathBuf,
+    frames_written: u64,
+    bytes_written: u64,
+    scratch: Vec<u8>,
+}
+
+impl StateRecorder {
+    /// Estimated file bytes per recorded frame for `num_particles`.
+    pub fn estimate_bytes_per_frame(num_particles: u32) -> u64 {
+        FRAME_HEADER_BYTES + u64::from(num_particles) * RECORD_BYTES
+    }
+
+    /// Create a new recording and write its header.
+    pub fn create(path: impl Into<PathBuf>, header: StateHeader) -> Result<Self> {
+        let path = path.into();
+        let file = File::create(&path)
+            .with_context(|| format!("Failed to create state recording {}", path.display()))?;
+        let mut writer = BufWriter::new(file);
+
+        writer.write_all(STATE_MAGIC)?;
+        writer.write_all(&STATE_VERSION.to_le_bytes())?;
+        writer.write_all(&header.num_types.to_le_bytes())?;
+        writer.write_all(&header.world_width.to_le_bytes())?;
+        writer.write_all(&header.world_height.to_le_bytes())?;
+
+        Ok(Self {
+            writer,
+            path,
+            frames_written: 0,
+            bytes_written: HEADER_BYTES,
+            scratch: Vec::new(),
+        })
+    }
+
+    /// Append one frame of particle positions and types.
+    pub fn write_frame(&mut self, step: u64, particles: &[ParticlePosType]) -> Result<()> {
+        self.scratch.clear();
+        self.scratch
+            .reserve(FRAME_HEADER_BYTES as usize + particles.len() * RECORD_BYTES as usize);
+        self.scratch.extend_from_slice(&step.to_le_bytes());
+        self.scratch
+            .extend_from_slice(&(particles.len() as u32).to_le_bytes());
+        for p in particles {
+            self.scratch.extend_from_slice(&p.x.to_le_bytes());
+            self.scratch.extend_from_slice(&p.y.to_le_bytes());
+            self.scratch.push(p.type_index().min(u8::MAX as u32) as u8);
+        }
+
+        self.writer
+            .write_all(&self.scratch)
+            .with_context(|| format!("Failed to write frame to {}", self.path.display()))?;
+        self.frames_written += 1;
+        self.bytes_written += self.scratch.len() as u64;
+        Ok(())
+    }
+
+    /// Flush buffered frames to disk and return the file path.
+    pub fn finish(mut self) -> Result<PathBuf> {
+        self.writer
+            .flush()
+            .with_context(|| format!("Failed to flush {}", self.path.display()))?;
+        Ok(self.path)
+    }
+
+    /// Path of the recording file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of frames written so far.
+    pub fn frames_written(&self) -> u64 {
+        self.frames_written
+    }
+
+    /// Number of bytes written so far (including the header).
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+}
+
+/// Reads frames back from a state recording for playback.
+pub struct StatePlayer {
+    reader: BufReader<File>,
+    path: PathBuf,
+    header: StateHeader,
+    /// Byte offset and step of each frame.
+    frames: Vec<(u64, u64)>,
+}
+
+impl StatePlayer {
+    /// Open a recording and index its frames.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let file = File::open(&path)
+            .with_context(|| format!("Failed to open state recording {}", path.display()))?;
+        let file_len = file.metadata()?.len();
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0u8; 8];
+        reader
+            .read_exact(&mut magic)
+            .context("State recording is too short")?;
+        if &magic != STATE_MAGIC {
+            bail!("{} is not a particle state recording", path.display());
+        }
+        let version = read_u32(&mut reader)?;
+        if version != STATE_VERSION {
+            bail!("Unsupported state recording version {}", version);
+        }
+        let header = StateHeader {
+            num_types: read_u32(&mut reader)?,
+            world_width: read_f32(&mut reader)?,
+            world_height: read_f32(&mut reader)?,
+        };
+
+        // Walk the length prefixes; a truncated trailing frame (e.g. from a
+        // crash mid-write) is ignored.
+        let mut frames = Vec::new();
+        let mut offset = HEADER_BYTES;
+        while offset + FRAME_HEADER_BYTES <= file_len {
+            reader.seek(SeekFrom::Start(offset))?;
+            let step = read_u64(&mut reader)?;
+            let count = u64::from(read_u32(&mut reader)?);
+            let end = offset + FRAME_HEADER_BYTES + count * RECORD_BYTES;
+            if end > file_len {
+                break;
+            }
+            frames.push((offset, step));
+            offset = end;
+        }
+
+        Ok(Self {
+            reader,
+            path,
+            header,
+            frames,
+        })
+    }
+
+    /// Recording header.
+    pub fn header(&self) -> StateHeader {
+        self.header
+    }
+
+    /// Path of the recording file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of complete frames in the recording.
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Simulation step a frame was captured at.
+    pub fn frame_step(&self, index: usize) -> Option<u64> {
+        self.frames.get(index).map(|&(_, step)| step)
+    }
+
+    /// Decode a frame into particles (velocities are zero).
+    pub fn read_frame(&mut self, index: usize) -> Result<Vec<Particle>> {
+        let Some(&(offset, _)) = self.frames.get(index) else {
+            bail!(
+                "Frame {} out of range ({} frames)",
+                index,
+                self.frames.len()
+            );
+        };
+        self.reader
+            .seek(SeekFrom::Start(offset + FRAME_HEADER_BYTES - 4))?;
+        let count = read_u32(&mut self.reader)? as usize;
+
+        let mut data = vec![0u8; count * RECORD_BYTES as usize];
+        self.reader
+            .read_exact(&mut data)
+            .with_context(|| format!("Failed to read frame {}", index))?;
+
+        Ok(data
+            .chunks_exact(RECORD_BYTES as usize)
+            .map(|r| {
+                let x = f32::from_le_bytes([r[0], r[1], r[2], r[3]]);
+                let y = f32::from_le_bytes([r[4], r[5], r[6], r[7]]);
+                Particle::new(x, y, u32::from(r[8]))
+            })
+            .collect())
+    }
+}
+
+fn read_u32(reader: &mut impl Read) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64(reader: &mut impl Read) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn read_f32(reader: &mut impl Read) -> Result<f32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(f32::from_le_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_recording_roundtrip() {
+        let path = std::env::temp_dir().join(format!(
+            "par_particle_life_state_{}.{}",
+            std::process::id(),
+            STATE_EXTENSION
+        ));
+        let header = StateHeader {
+            num_types: 3,
+            world_width: 800.0,
+            world_height: 600.0,
+        };
+
+        let frame = |offset: f32| -> Vec<ParticlePosType> {
+            (0..4)
+                .map(|i| ParticlePosType::from(&Particle::new(i as f32 + offset, 2.0, i % 3)))
+                .collect()
+        };
+
+        let mut recorder = StateRecorder::create(&path, header).unwrap();
+        recorder.write_frame(0, &frame(0.0)).unwrap();
+        recorder.write_frame(5, &frame(10.0)).unwrap();
+        assert_eq!(
+            recorder.bytes_written(),
+            HEADER_BYTES + 2 * StateRecorder::estimate_bytes_per_frame(4)
+        );
+        let path = recorder.finish().unwrap();
+
+        let mut player = StatePlayer::open(&path).unwrap();
+        assert_eq!(player.header(), header);
+        assert_eq!(player.frame_count(), 2);
+        assert_eq!(player.frame_step(1), Some(5));
+
+        let particles = player.read_frame(1).unwrap();
+        assert_eq!(particles.len(), 4);
+        assert_eq!(particles[3].x, 13.0);
+        assert_eq!(particles[3].particle_type, 0);
+        assert!(player.read_frame(2).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
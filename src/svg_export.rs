@@ -0,0 +1,160 @@
+//! Vector (SVG) export of particle positions.
+//!
+//! Each particle becomes a `<circle>` at its world coordinate, colored by type
+//! and sized by `particle_size`, over a background `<rect>`. The `viewBox`
+//! matches the world size so the output keeps the simulation's proportions at
+//! any scale.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::generators::colors::Color;
+use crate::simulation::{Particle, SimulationConfig};
+
+/// Particle count above which SVG files become unwieldy and export should be
+/// capped.
+pub const SVG_PARTICLE_WARN_THRESHOLD: usize = 100_000;
+
+/// Write particles as SVG to `writer`.
+///
+/// When `max_particles` is set and smaller than `particles.len()`, particles
+/// are subsampled evenly across the buffer. Returns the number of circles
+/// written.
+pub fn write_svg(
+    writer: &mut impl Write,
+    particles: &[Particle],
+    colors: &[Color],
+    config: &SimulationConfig,
+    max_particles: Option<usize>,
+) -> std::io::Result<usize> {
+    let width = config.world_size.x;
+    let height = config.world_size.y;
+    let radius = config.particle_size;
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {w} {h}" width="{w}" height="{h}">"#,
+        w = width,
+        h = height
+    )?;
+    let fill = match &config.background_gradient {
+        Some([top, bottom]) => {
+            writeln!(
+                writer,
+                r#"<defs><linearGradient id="bg" x1="0" y1="0" x2="0" y2="1"><stop offset="0" stop-color="{}"/><stop offset="1" stop-color="{}"/></linearGradient></defs>"#,
+                hex_color(top),
+                hex_color(bottom)
+            )?;
+            "url(#bg)".to_string()
+        }
+        None => hex_color(&config.background_color),
+    };
+    writeln!(
+        writer,
+        r#"<rect width="{}" height="{}" fill="{}"/>"#,
+        width, height, fill
+    )?;
+
+    // Group circles by type so each fill is written once
+    let stride = match max_particles {
+        Some(max) if max > 0 && max < particles.len() => particles.len() as f64 / max as f64,
+        _ => 1.0,
+    };
+    let count = (particles.len() as f64 / stride).round() as usize;
+    let mut written = 0;
+    for (type_idx, color) in colors.iter().enumerate() {
+        writeln!(
+            writer,
+            r#"<g fill="{}" fill-opacity="{}">"#,
+            hex_color(&[color[0], color[1], color[2]]),
+            color[3]
+        )?;
+        for i in 0..count {
+            let p = &particles[((i as f64 * stride) as usize).min(particles.len() - 1)];
+            if p.particle_type as usize != type_idx {
+                continue;
+            }
+            writeln!(
+                writer,
+                r#"<circle cx="{:.2}" cy="{:.2}" r="{}"/>"#,
+                p.x, p.y, radius
+            )?;
+            written += 1;
+        }
+        writeln!(writer, "</g>")?;
+    }
+    writeln!(writer, "</svg>")?;
+    Ok(written)
+}
+
+/// Export particles to an SVG file. Returns the number of circles written.
+pub fn export_svg(
+    path: &Path,
+    particles: &[Particle],
+    colors: &[Color],
+    config: &SimulationConfig,
+    max_particles: Option<usize>,
+) -> Result<usize> {
+    let file =
+        File::create(path).with_context(|| format!("Failed to create SVG {}", path.display()))?;
+    let mut writer = BufWriter::new(file);
+    let written = write_svg(&mut writer, particles, colors, config, max_particles)
+        .with_context(|| format!("Failed to write SVG {}", path.display()))?;
+    writer.flush()?;
+    Ok(written)
+}
+
+/// Format an RGB color in 0.0-1.0 range as `#rrggbb`.
+fn hex_color(rgb: &[f32; 3]) -> String {
+    let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    format!(
+        "#{:02x}{:02x}{:02x}",
+        channel(rgb[0]),
+        channel(rgb[1]),
+        channel(rgb[2])
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_svg_subsamples() {
+        let config = SimulationConfig {
+            world_size: glam::Vec2::new(200.0, 100.0),
+            particle_size: 1.5,
+            ..SimulationConfig::default()
+        };
+        let colors = vec![[1.0, 0.0, 0.0, 1.0], [0.0, 0.0, 1.0, 1.0]];
+        let particles: Vec<Particle> = (0..10)
+            .map(|i| Particle::new(i as f32, 5.0, i % 2))
+            .collect();
+
+        let mut out = Vec::new();
+        let written = write_svg(&mut out, &particles, &colors, &config, None).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert_eq!(written, 10);
+        assert!(svg.contains(r#"viewBox="0 0 200 100""#));
+        assert!(svg.contains(r##"fill="#ff0000""##));
+        assert_eq!(svg.matches("<circle").count(), 10);
+        assert!(svg.contains(r#"r="1.5""#));
+
+        let mut out = Vec::new();
+        let written = write_svg(&mut out, &particles, &colors, &config, Some(5)).unwrap();
+        assert_eq!(written, 5);
+
+        let config = SimulationConfig {
+            background_gradient: Some([[0.0, 0.0, 1.0], [0.0, 0.0, 0.0]]),
+            ..config
+        };
+        let mut out = Vec::new();
+        write_svg(&mut out, &particles, &colors, &config, None).unwrap();
+        let svg = String::from_utf8(out).unwrap();
+        assert!(svg.contains(r##"<stop offset="0" stop-color="#0000ff"/>"##));
+        assert!(svg.contains(r#"fill="url(#bg)""#));
+    }
+}
@@ -1,7 +1,8 @@
 //! Video recording module using ffmpeg for encoding.
 //!
 //! Supports MP4, WebM, and GIF output formats with real-time encoding
-//! via a background thread that pipes frames to ffmpeg.
+//! via a background thread that pipes frames to ffmpeg, plus lossless
+//! numbered PNG sequences written by the same thread without ffmpeg.
 
 use crossbeam_channel::{Receiver, Sender, bounded};
 use std::io::Write;
@@ -19,6 +20,8 @@ pub enum VideoFormat {
     WebM,
     /// Animated GIF (limited colors, large files).
     GIF,
+    /// Lossless numbered PNG frames in a folder (no ffmpeg needed).
+    PngSequence,
 }
 
 impl VideoFormat {
@@ -28,12 +31,28 @@ impl VideoFormat {
             VideoFormat::MP4 => "mp4",
             VideoFormat::WebM => "webm",
             VideoFormat::GIF => "gif",
+            VideoFormat::PngSequence => "png",
         }
     }
 
     /// Get all available formats.
     pub fn all() -> &'static [VideoFormat] {
-        &[VideoFormat::MP4, VideoFormat::WebM, VideoFormat::GIF]
+        &[
+            VideoFormat::MP4,
+            VideoFormat::WebM,
+            VideoFormat::GIF,
+            VideoFormat::PngSequence,
+        ]
+    }
+
+    /// Whether this format is encoded by ffmpeg.
+    pub fn uses_ffmpeg(&self) -> bool {
+        !matches!(self, VideoFormat::PngSequence)
+    }
+
+    /// Whether the output is a folder of frames rather than a single file.
+    pub fn is_sequence(&self) -> bool {
+        matches!(self, VideoFormat::PngSequence)
     }
 
     /// Get the display name for this format.
@@ -42,6 +61,24 @@ impl VideoFormat {
             VideoFormat::MP4 => "MP4 (H.264)",
             VideoFormat::WebM => "WebM (VP9)",
             VideoFormat::GIF => "GIF",
+            VideoFormat::PngSequence => "PNG Sequence",
+        }
+    }
+}
+
+impl std::str::FromStr for VideoFormat {
+    type Err = String;
+
+    /// Parse a single-file format from its extension (`mp4`, `webm`, `gif`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "mp4" => Ok(VideoFormat::MP4),
+            "webm" => Ok(VideoFormat::WebM),
+            "gif" => Ok(VideoFormat::GIF),
+            _ => Err(format!(
+                "unknown video format '{}' (use mp4, webm or gif)",
+                s
+            )),
         }
     }
 }
@@ -80,14 +117,18 @@ impl VideoRecorder {
 
     /// Start recording to the specified filename.
     ///
-    /// Returns an error if already recording or if ffmpeg is not available.
+    /// For [`VideoFormat::PngSequence`] `filename` is the folder to create
+    /// for the frames. Returns an error if already recording, if ffmpeg is
+    /// needed but not available, or if the folder can't be created.
     pub fn start_recording(&mut self, filename: String) -> Result<(), String> {
         if self.is_recording {
             return Err("Already recording".to_string());
         }
 
-        // Check if ffmpeg is available
-        if !self.check_ffmpeg_available() {
+        if self.format.is_sequence() {
+            std::fs::create_dir_all(&filename)
+                .map_err(|e| format!("Failed to create folder {}: {}", filename, e))?;
+        } else if !self.check_ffmpeg_available() {
             return Err("ffmpeg not found. Please install ffmpeg to record videos.".to_string());
         }
 
@@ -113,9 +154,12 @@ impl VideoRecorder {
         let format = self.format;
 
         let encoder_thread = thread::spawn(move || {
-            if let Err(e) =
+            let result = if format.is_sequence() {
+                Self::png_thread_main(width, height, receiver, &filename)
+            } else {
                 Self::encoder_thread_main(width, height, fps, format, receiver, &filename)
-            {
+            };
+            if let Err(e) = result {
                 log::error!("Video encoder error: {}", e);
             }
         });
@@ -155,15 +199,21 @@ impl VideoRecorder {
     /// Add a frame to the recording.
     ///
     /// The frame data should be RGBA format with dimensions matching the recorder.
-    /// If the frame buffer is full, the frame will be dropped.
+    /// If the frame buffer is full, the frame will be dropped, except for PNG
+    /// sequences, which wait for the writer so no frame is lost.
     pub fn add_frame(&mut self, frame_data: Vec<u8>) -> Result<(), String> {
         if !self.is_recording {
             return Err("Not recording".to_string());
         }
 
         if let Some(sender) = &self.frame_sender {
-            // Try to send the frame, drop if channel is full (skip frame)
-            if sender.try_send(frame_data).is_ok() {
+            if self.format.is_sequence() {
+                sender
+                    .send(frame_data)
+                    .map_err(|_| "PNG writer stopped".to_string())?;
+                self.frame_count += 1;
+            } else if sender.try_send(frame_data).is_ok() {
+                // Try to send the frame, drop if channel is full (skip frame)
                 self.frame_count += 1;
             } else {
                 log::warn!("Frame buffer full, skipping frame");
@@ -245,6 +295,9 @@ impl VideoRecorder {
 
         // Add format-specific encoding options
         match format {
+            VideoFormat::PngSequence => {
+                return Err("PNG sequences are not encoded by ffmpeg".to_string());
+            }
             VideoFormat::MP4 => {
                 cmd.args([
                     "-c:v", "libx264", "-pix_fmt", "yuv420p", "-preset", "medium", "-crf",
@@ -315,6 +368,7 @@ impl VideoRecorder {
             VideoFormat::MP4 => "MP4 video",
             VideoFormat::WebM => "WebM video",
             VideoFormat::GIF => "GIF animation",
+            VideoFormat::PngSequence => "PNG sequence",
         };
 
         log::info!(
@@ -326,6 +380,37 @@ impl VideoRecorder {
 
         Ok(())
     }
+
+    /// Main function for the PNG sequence writer thread.
+    ///
+    /// Writes each frame as `frame_000001.png`, `frame_000002.png`, ... into
+    /// the `folder` created by [`Self::start_recording`].
+    fn png_thread_main(
+        width: u32,
+        height: u32,
+        receiver: Receiver<Vec<u8>>,
+        folder: &str,
+    ) -> Result<(), String> {
+        let folder = std::path::Path::new(folder);
+        let mut frame_count = 0;
+        while let Ok(frame_data) = receiver.recv() {
+            frame_count += 1;
+            let image = image::RgbaImage::from_raw(width, height, frame_data)
+                .ok_or_else(|| format!("Frame {} has the wrong size", frame_count))?;
+            let path = folder.join(format!("frame_{:06}.png", frame_count));
+            image
+                .save(&path)
+                .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        }
+
+        log::info!(
+            "PNG sequence complete: {} frames written to {}",
+            frame_count,
+            folder.display()
+        );
+
+        Ok(())
+    }
 }
 
 impl Drop for VideoRecorder {
@@ -335,3 +420,45 @@ impl Drop for VideoRecorder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_png_sequence_writes_numbered_frames() {
+        let folder = std::env::temp_dir().join(format!(
+            "par_particle_life_png_sequence_{}",
+            std::process::id()
+        ));
+        let mut recorder = VideoRecorder::new(2, 2, 30, VideoFormat::PngSequence);
+        recorder
+            .start_recording(folder.display().to_string())
+            .unwrap();
+        recorder.add_frame(vec![255; 16]).unwrap();
+        recorder.add_frame(vec![0; 16]).unwrap();
+        assert_eq!(
+            recorder.stop_recording().unwrap(),
+            folder.display().to_string()
+        );
+
+        let first = image::open(folder.join("frame_000001.png"))
+            .unwrap()
+            .to_rgba8();
+        let second = image::open(folder.join("frame_000002.png"))
+            .unwrap()
+            .to_rgba8();
+        assert_eq!(first.as_raw(), &vec![255; 16]);
+        assert_eq!(second.as_raw(), &vec![0; 16]);
+        assert!(!folder.join("frame_000003.png").exists());
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn test_video_format_from_str() {
+        assert_eq!("mp4".parse::<VideoFormat>(), Ok(VideoFormat::MP4));
+        assert_eq!("WebM".parse::<VideoFormat>(), Ok(VideoFormat::WebM));
+        assert_eq!("gif".parse::<VideoFormat>(), Ok(VideoFormat::GIF));
+        assert!("png".parse::<VideoFormat>().is_err());
+    }
+}